@@ -1,4 +1,4 @@
-import React, { useState, useEffect, useMemo, useRef } from 'react';
+import React, { useState, useEffect, useRef } from 'react';
 import {
     Users,
     UserPlus,
@@ -15,10 +15,30 @@ import AccountListView from './components/AccountListView';
 import ImportView from './components/ImportView';
 import LoginPage from './components/LoginPage';
 import EditModal from './components/EditModal';
+import usePagination from './hooks/usePagination';
 import { normalizePhoneNumber } from './utils/phoneUtils';
+
+// 列表列 key 与后端排序字段的映射（2FA 密钥为密文，不支持排序）
+const SORT_FIELD_MAP = {
+    id: 'id',
+    email: 'email',
+    recovery: 'recovery',
+    phone: 'phone',
+    groupName: 'group_name',
+    remark: 'remark',
+    status: 'status',
+    regYear: 'reg_year',
+    country: 'country',
+    createdAt: 'created_at',
+};
+
 const App = () => {
     const [view, setView] = useState('list');
     const [accounts, setAccounts] = useState([]);
+    const [accountTotal, setAccountTotal] = useState(0);
+    const [resultAccountIds, setResultAccountIds] = useState([]);
+    const [allGroups, setAllGroups] = useState([]);
+    const [sortConfig, setSortConfig] = useState({ key: null, direction: null });
     const [search, setSearch] = useState('');
     const [debouncedSearch, setDebouncedSearch] = useState('');
     const [soldStatusFilter, setSoldStatusFilter] = useState('all');
@@ -28,6 +48,10 @@ const App = () => {
     const [importing, setImporting] = useState(false);
     const notificationTimerRef = useRef(null);
     const latestLoadRequestIdRef = useRef(0);
+    const latestIdsRequestIdRef = useRef(0);
+
+    // 服务端分页：只维护页码，当前页数据由 loadAccounts 查询
+    const pagination = usePagination(accountTotal, 10);
 
     // Modals state
     const [editingAccount, setEditingAccount] = useState(null);
@@ -95,23 +119,61 @@ const App = () => {
             setLoading(false);
             return;
         }
-        loadAccounts(debouncedSearch, soldStatusFilter);
-    }, [authChecking, isLoggedIn, debouncedSearch, soldStatusFilter]);
+        loadAccounts();
+    }, [
+        authChecking,
+        isLoggedIn,
+        debouncedSearch,
+        soldStatusFilter,
+        groupFilter,
+        pagination.currentPage,
+        pagination.pageSize,
+        sortConfig,
+    ]);
+
+    // 标签列表由后端汇总（列表只有当前页，无法在前端统计）
+    useEffect(() => {
+        if (authChecking || !isLoggedIn) return;
+        loadAccountGroups();
+    }, [authChecking, isLoggedIn]);
+
+    // 当前筛选结果的全部账号 ID，用于跨页全选与按筛选结果导出
+    useEffect(() => {
+        if (authChecking || !isLoggedIn) return;
+        loadResultAccountIds();
+    }, [authChecking, isLoggedIn, debouncedSearch, soldStatusFilter, groupFilter, accountTotal]);
+
+    const buildAccountFilter = ({
+        search: searchValue = debouncedSearch,
+        soldStatus: soldStatusFilterValue = soldStatusFilter,
+        group: groupValue = groupFilter,
+    } = {}) => ({
+        search: searchValue || undefined,
+        soldStatus: soldStatusFilterValue === 'all' ? undefined : soldStatusFilterValue,
+        group: groupValue || undefined,
+    });
 
-    const loadAccounts = async (searchValue = search, soldStatusFilterValue = soldStatusFilter) => {
+    const loadAccounts = async (overrides = {}) => {
         const requestId = ++latestLoadRequestIdRef.current;
         try {
             setLoading(true);
-            const soldStatus = soldStatusFilterValue === 'all' ? null : soldStatusFilterValue;
-            const data = await api.getAccounts(searchValue, soldStatus);
+            const page = await api.getAccounts({
+                ...buildAccountFilter(overrides),
+                page: overrides.page ?? pagination.currentPage,
+                pageSize: pagination.pageSize,
+                sortField: sortConfig.key ? SORT_FIELD_MAP[sortConfig.key] : undefined,
+                sortDirection: sortConfig.direction || undefined,
+            });
             if (requestId !== latestLoadRequestIdRef.current) return;
-            setAccounts(data);
+            setAccounts(page.items);
+            setAccountTotal(page.total);
         } catch (error) {
             if (requestId !== latestLoadRequestIdRef.current) return;
             const message = String(error?.message || error || '');
             if (message.includes('未登录') || message.toLowerCase().includes('unauthorized')) {
                 setIsLoggedIn(false);
                 setAccounts([]);
+                setAccountTotal(0);
                 return;
             }
             console.error('加载账号失败:', error);
@@ -122,13 +184,41 @@ const App = () => {
         }
     };
 
-    // 获取所有唯一的标签
-    const allGroups = useMemo(() => {
-        const groups = accounts
-            .map(acc => acc.groupName)
-            .filter(g => g && g.trim() !== '');
-        return [...new Set(groups)].sort();
-    }, [accounts]);
+    const loadAccountGroups = async () => {
+        const result = await api.getAccountGroups();
+        if (result.success) {
+            setAllGroups(result.data);
+        }
+    };
+
+    // 行内编辑标签后把新标签并入筛选列表，避免每次编辑都重新汇总
+    const mergeAccountGroups = (groupName) => {
+        const tags = String(groupName || '')
+            .split(/[,，\s]+/)
+            .map(tag => tag.trim())
+            .filter(Boolean);
+        if (tags.length === 0) return;
+        setAllGroups(prev => [...new Set([...prev, ...tags])].sort());
+    };
+
+    const loadResultAccountIds = async () => {
+        const requestId = ++latestIdsRequestIdRef.current;
+        const result = await api.getAccountIds(buildAccountFilter());
+        if (requestId !== latestIdsRequestIdRef.current) return;
+        setResultAccountIds(result.success ? result.data : []);
+    };
+
+    // 表头排序：同一列在升序 / 降序间切换，排序由后端完成
+    const handleSortChange = (key) => {
+        if (!SORT_FIELD_MAP[key]) return;
+        setSortConfig(prev => {
+            if (prev.key === key) {
+                return { key, direction: prev.direction === 'asc' ? 'desc' : 'asc' };
+            }
+            return { key, direction: 'asc' };
+        });
+        pagination.resetPage();
+    };
 
 
     // --- Helpers ---
@@ -165,6 +255,8 @@ const App = () => {
         await api.logout();
         setIsLoggedIn(false);
         setAccounts([]);
+        setAccountTotal(0);
+        setResultAccountIds([]);
         setView('list');
         setSearch('');
         setDebouncedSearch('');
@@ -183,6 +275,7 @@ const App = () => {
 
             if (successCount > 0) {
                 await loadAccounts();
+                await loadAccountGroups();
                 showNotification(`已撤回 ${successCount} 个账号的删除`);
             } else {
                 showNotification('撤回失败', 'error');
@@ -240,7 +333,8 @@ const App = () => {
                 if (accountToDelete) {
                     setDeletedAccounts([accountToDelete]);
                 }
-                setAccounts(prevAccounts => prevAccounts.filter(acc => acc.id !== id));
+                // 重新查询当前页，让后续账号补位并更新总数
+                await loadAccounts();
                 // 使用 setTimeout 确保状态更新后再显示通知
                 setTimeout(() => showNotification('账号已删除'), 0);
             } else {
@@ -295,7 +389,9 @@ const App = () => {
                 setSearch('');
                 setSoldStatusFilter('all');
                 setGroupFilter(null);
-                await loadAccounts('', 'all');
+                pagination.resetPage();
+                await loadAccounts({ search: '', soldStatus: 'all', group: null, page: 1 });
+                await loadAccountGroups();
 
                 // 显示导入结果 - 使用API返回的属性名
                 const successCount = result.successCount || 0;
@@ -323,8 +419,8 @@ const App = () => {
     // 行内编辑保存
     const handleInlineEdit = async (id, field, value) => {
         try {
+            // 批量编辑的选中项可能不在当前页，找不到时只提交更新
             const account = accounts.find(acc => acc.id === id);
-            if (!account) return false;
 
             const editableFields = new Set(['email', 'password', 'recovery', 'phone', 'secret', 'groupName', 'remark', 'regYear', 'country']);
             if (!editableFields.has(field)) {
@@ -344,6 +440,9 @@ const App = () => {
                     prevAccounts.map(acc => (acc.id === id ? updatedAccount : acc))
                 );
                 showNotification(field + ' 已更新');
+                if (field === 'groupName') {
+                    mergeAccountGroups(normalizedValue);
+                }
 
                 // 当 secret 字段更新时，2FA 验证码会由 AccountListView 内部自动更新
                 return true;
@@ -398,6 +497,7 @@ const App = () => {
             if (result.success) {
                 showNotification(`已导入 ${result.successCount} 个测试账号`);
                 await loadAccounts();
+                await loadAccountGroups();
             } else {
                 showNotification(`导入失败：${result.failedCount} 个`, 'error');
             }
@@ -411,9 +511,6 @@ const App = () => {
     const handleBatchDelete = async (ids) => {
         const idsArray = Array.from(ids);
 
-        // 先保存要删除的账号用于撤回
-        const accountsToDelete = accounts.filter(acc => idsArray.includes(acc.id));
-
         const deletePromises = idsArray.map(id =>
             api.deleteAccount(id)
                 .then(result => ({ id, success: result.success }))
@@ -430,14 +527,11 @@ const App = () => {
             .filter(r => r.status === 'fulfilled' && r.value.success)
             .map(r => r.value.id);
 
-        // 更新 accounts 状态，移除已删除的账号
+        // 重新查询当前页，移除已删除的账号
         if (successfulIds.length > 0) {
-            // 保存成功删除的账号用于撤回
-            const deletedItems = accountsToDelete.filter(acc => successfulIds.includes(acc.id));
-            setDeletedAccounts(deletedItems);
-            setAccounts(prevAccounts =>
-                prevAccounts.filter(acc => !successfulIds.includes(acc.id))
-            );
+            // 保存成功删除的账号 ID 用于撤回（选中项可能跨页，不一定在当前页数据中）
+            setDeletedAccounts(successfulIds.map(id => ({ id })));
+            await loadAccounts();
             // 使用 setTimeout 确保状态更新后再显示通知
             setTimeout(() => showNotification(`已删除 ${successfulIds.length} 个账号`), 0);
         }
@@ -450,23 +544,6 @@ const App = () => {
         return { successfulIds, failedCount };
     };
 
-    // 账号已由后端筛选搜索和出售状态，前端额外过滤标签
-    const filteredAccounts = useMemo(() => {
-        if (!groupFilter) return accounts;
-
-        // 使用 Set 替代 array.includes() 以提高性能
-        const filterSet = new Set([groupFilter]);
-
-        return accounts.filter(acc => {
-            const tags = String(acc.groupName || '')
-                .split(/[,，\s]+/)
-                .map(tag => tag.trim())
-                .filter(Boolean);
-            // 使用 some + Set.has 替代 includes
-            return tags.some(tag => filterSet.has(tag));
-        });
-    }, [accounts, groupFilter]);
-
     const globalFontStyle = {
         fontFamily: '"Times New Roman", Times, serif',
     };
@@ -565,7 +642,12 @@ const App = () => {
             <main className="max-w-[1600px] mx-auto px-4 py-8">
                 {view === 'list' ? (
                     <AccountListView
-                        accounts={filteredAccounts}
+                        accounts={accounts}
+                        totalAccounts={accountTotal}
+                        resultAccountIds={resultAccountIds}
+                        pagination={pagination}
+                        sortConfig={sortConfig}
+                        onSortChange={handleSortChange}
                         search={search}
                         setSearch={setSearch}
                         soldStatusFilter={soldStatusFilter}
@@ -580,7 +662,10 @@ const App = () => {
                         onDelete={handleDelete}
                         onBatchDelete={handleBatchDelete}
                         onInlineEdit={handleInlineEdit}
-                        onRefreshAccounts={() => loadAccounts()}
+                        onRefreshAccounts={async () => {
+                            await loadAccounts();
+                            await loadAccountGroups();
+                        }}
                         loading={loading}
                         darkMode={darkMode}
                     />
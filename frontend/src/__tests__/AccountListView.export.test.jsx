@@ -21,6 +21,7 @@ vi.mock('../services/api', () => ({
   default: {
     exportAccountsText: vi.fn(),
     exportDatabaseSql: vi.fn(),
+    generateTotpForAccounts: vi.fn(async () => ({ success: true, data: [] })),
   },
 }));
 
@@ -53,6 +54,21 @@ const mockAccounts = [
 
 const createDefaultProps = (overrides = {}) => ({
   accounts: mockAccounts,
+  totalAccounts: mockAccounts.length,
+  resultAccountIds: mockAccounts.map(account => account.id),
+  pagination: {
+    currentPage: 1,
+    pageSize: 10,
+    totalPages: 1,
+    totalItems: mockAccounts.length,
+    goToPage: vi.fn(),
+    changePageSize: vi.fn(),
+    resetPage: vi.fn(),
+    hasNextPage: false,
+    hasPrevPage: false,
+  },
+  sortConfig: { key: null, direction: null },
+  onSortChange: vi.fn(),
   search: '',
   setSearch: vi.fn(),
   soldStatusFilter: 'all',
@@ -110,20 +126,21 @@ describe('AccountListView 导出弹窗与参数', () => {
     });
   });
 
-  it('标签筛选模式通过 accountIds 传递当前筛选结果', async () => {
+  it('标签筛选模式通过 accountIds 传递当前筛选结果（含其他分页）', async () => {
     const user = userEvent.setup();
-    const props = createDefaultProps({ groupFilter: '组A' });
+    // 当前页只有 2 条，筛选结果共 3 条
+    const props = createDefaultProps({ groupFilter: '组A', totalAccounts: 3, resultAccountIds: [3, 2, 1] });
 
     render(<AccountListView {...props} />);
 
     await user.click(screen.getByRole('button', { name: '导出账号' }));
-    expect(screen.getByText('将导出当前筛选结果，共 2 条')).toBeInTheDocument();
+    expect(screen.getByText('将导出当前筛选结果，共 3 条')).toBeInTheDocument();
 
     await user.click(screen.getByRole('button', { name: '导出' }));
 
     await waitFor(() => {
       expect(api.exportAccountsText).toHaveBeenCalledWith(
-        [1, 2],
+        [3, 2, 1],
         null,
         null,
         expect.any(Object),
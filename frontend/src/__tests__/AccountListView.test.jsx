@@ -2,6 +2,7 @@ import { render, screen, fireEvent, waitFor } from '@testing-library/react';
 import userEvent from '@testing-library/user-event';
 import { describe, it, expect, vi, beforeEach } from 'vitest';
 import AccountListView from '../components/AccountListView';
+import api from '../services/api';
 import { CLICK_COPY_DELAY_MS } from '../hooks/useInlineEdit';
 
 // Mock HistoryDrawer to avoid API calls and state issues
@@ -18,13 +19,21 @@ vi.mock('../components/HistoryDrawer', () => ({
   }
 }));
 
+vi.mock('../services/api', () => ({
+  default: {
+    revealAccountSecret: vi.fn(),
+    generateTotpForAccounts: vi.fn(),
+  },
+}));
+
+// 列表为脱敏数据：password / secret 只有占位符
 const mockAccounts = [
   {
     id: 1,
     email: 'test1@gmail.com',
-    password: 'password1',
+    password: '******',
     recovery: 'recovery1@gmail.com',
-    secret: 'JBSWY3DPEHPK3PXP',
+    secret: '******',
     status: 'pro',
     soldStatus: 'unsold',
     remark: '测试账号1',
@@ -33,9 +42,9 @@ const mockAccounts = [
   {
     id: 2,
     email: 'test2@gmail.com',
-    password: 'password2',
+    password: '******',
     recovery: 'recovery2@gmail.com',
-    secret: 'JBSWY3DPEHPK3PXQ',
+    secret: '******',
     status: 'inactive',
     soldStatus: 'sold',
     remark: '测试账号2',
@@ -48,18 +57,43 @@ const DOUBLE_CLICK_SETTLE_MS = CLICK_COPY_DELAY_MS + 120;
 const buildMockAccount = (id) => ({
   id,
   email: `test${id}@gmail.com`,
-  password: `password${id}`,
+  password: '******',
   recovery: `recovery${id}@gmail.com`,
-  secret: 'JBSWY3DPEHPK3PXP',
+  secret: '******',
   status: id % 2 === 0 ? 'inactive' : 'pro',
   soldStatus: id % 3 === 0 ? 'sold' : 'unsold',
   remark: `测试账号${id}`,
   createdAt: '2024-01-01',
 });
 
+// 服务端分页：列表只收到当前页数据，页码状态由 App 中的 usePagination 提供
+const buildPagination = (totalItems, overrides = {}) => {
+  const pageSize = overrides.pageSize ?? 10;
+  const currentPage = overrides.currentPage ?? 1;
+  const totalPages = Math.max(1, Math.ceil(totalItems / pageSize));
+  return {
+    currentPage,
+    pageSize,
+    totalPages,
+    totalItems,
+    goToPage: vi.fn(),
+    nextPage: vi.fn(),
+    prevPage: vi.fn(),
+    changePageSize: vi.fn(),
+    resetPage: vi.fn(),
+    hasNextPage: currentPage < totalPages,
+    hasPrevPage: currentPage > 1,
+  };
+};
+
 describe('AccountListView 组件', () => {
   const defaultProps = {
     accounts: mockAccounts,
+    totalAccounts: mockAccounts.length,
+    resultAccountIds: mockAccounts.map(account => account.id),
+    pagination: buildPagination(mockAccounts.length),
+    sortConfig: { key: null, direction: null },
+    onSortChange: vi.fn(),
     search: '',
     setSearch: vi.fn(),
     soldStatusFilter: 'all',
@@ -82,6 +116,8 @@ describe('AccountListView 组件', () => {
 
   beforeEach(() => {
     vi.clearAllMocks();
+    api.revealAccountSecret.mockImplementation(async (id, field) => ({ success: true, data: `${field}${id}` }));
+    api.generateTotpForAccounts.mockResolvedValue({ success: true, data: [] });
   });
 
   describe('基础渲染', () => {
@@ -264,89 +300,36 @@ describe('AccountListView 组件', () => {
   });
 
   describe('排序功能', () => {
-    it('点击表头可在升序和降序之间切换', async () => {
+    it('点击表头交给后端排序，不在前端重排当前页', async () => {
       const user = userEvent.setup();
-      const sortableAccounts = [
-        {
-          id: 10,
-          email: 'zeta@example.com',
-          password: 'password-z',
-          recovery: '',
-          secret: '',
-          status: 'inactive',
-          soldStatus: 'unsold',
-          remark: '',
-          createdAt: '2024-01-02',
-        },
-        {
-          id: 11,
-          email: 'alpha@example.com',
-          password: 'password-a',
-          recovery: '',
-          secret: '',
-          status: 'inactive',
-          soldStatus: 'unsold',
-          remark: '',
-          createdAt: '2024-01-01',
-        },
-      ];
-
-      render(<AccountListView {...defaultProps} accounts={sortableAccounts} />);
+      const onSortChange = vi.fn();
+      render(<AccountListView {...defaultProps} onSortChange={onSortChange} />);
 
       const getOrder = () => screen
-        .getAllByText(/(zeta|alpha)@example\.com/)
+        .getAllByText(/test[12]@gmail\.com/)
         .map((el) => el.textContent);
 
-      expect(getOrder()).toEqual(['zeta@example.com', 'alpha@example.com']);
-
-      const accountSortButton = screen.getByRole('button', { name: '账号' });
-      await user.click(accountSortButton);
-      expect(getOrder()).toEqual(['alpha@example.com', 'zeta@example.com']);
+      await user.click(screen.getByRole('button', { name: '账号' }));
+      expect(onSortChange).toHaveBeenCalledWith('email');
+      expect(getOrder()).toEqual(['test1@gmail.com', 'test2@gmail.com']);
 
-      await user.click(accountSortButton);
-      expect(getOrder()).toEqual(['zeta@example.com', 'alpha@example.com']);
+      await user.click(screen.getByRole('button', { name: '备注' }));
+      expect(onSortChange).toHaveBeenLastCalledWith('remark');
     });
 
-    it('空值会跟随排序方向切换（升序在后，降序在前）', async () => {
-      const user = userEvent.setup();
-      const sortableAccounts = [
-        {
-          id: 20,
-          email: 'has-remark@example.com',
-          password: 'password-a',
-          recovery: '',
-          secret: '',
-          status: 'inactive',
-          soldStatus: 'unsold',
-          remark: '有备注',
-          createdAt: '2024-01-02',
-        },
-        {
-          id: 21,
-          email: 'empty-remark@example.com',
-          password: 'password-b',
-          recovery: '',
-          secret: '',
-          status: 'inactive',
-          soldStatus: 'unsold',
-          remark: '',
-          createdAt: '2024-01-01',
-        },
-      ];
-
-      render(<AccountListView {...defaultProps} accounts={sortableAccounts} />);
-
-      const getOrder = () => screen
-        .getAllByText(/(has-remark|empty-remark)@example\.com/)
-        .map((el) => el.textContent);
+    it('2FA 列不可排序（密钥为密文，后端无法排序）', () => {
+      render(<AccountListView {...defaultProps} />);
 
-      const remarkSortButton = screen.getByRole('button', { name: '备注' });
+      expect(screen.getByText('2FA')).toBeInTheDocument();
+      expect(screen.queryByRole('button', { name: '2FA' })).not.toBeInTheDocument();
+    });
 
-      await user.click(remarkSortButton); // asc: 空值应在后
-      expect(getOrder()).toEqual(['has-remark@example.com', 'empty-remark@example.com']);
+    it('按当前排序配置显示排序方向', () => {
+      render(<AccountListView {...defaultProps} sortConfig={{ key: 'email', direction: 'asc' }} />);
 
-      await user.click(remarkSortButton); // desc: 空值应在前
-      expect(getOrder()).toEqual(['empty-remark@example.com', 'has-remark@example.com']);
+      const accountSortButton = screen.getByRole('button', { name: '账号' });
+      expect(accountSortButton.querySelector('.text-blue-500')).not.toBeNull();
+      expect(screen.getByRole('button', { name: '备注' }).querySelector('.text-blue-500')).toBeNull();
     });
   });
 
@@ -419,17 +402,47 @@ describe('AccountListView 组件', () => {
       });
     });
 
-    it('点击密码单元格复制', async () => {
+    it('点击密码单元格按需获取明文后复制', async () => {
       const user = userEvent.setup();
       render(<AccountListView {...defaultProps} />);
 
-      // 点击密码文本来复制
-      const passwordElement = screen.getByText('password1');
-      await user.click(passwordElement);
+      // 列表只显示占位符，单击时才向后端获取单个字段明文
+      const passwordElements = screen.getAllByTitle('单击查看并复制密码，双击编辑');
+      expect(passwordElements[0]).toHaveTextContent('******');
+      await user.click(passwordElements[0]);
 
       await waitFor(() => {
         expect(defaultProps.copyToClipboard).toHaveBeenCalledWith('password1', '密码');
       });
+      expect(api.revealAccountSecret).toHaveBeenCalledTimes(1);
+      expect(api.revealAccountSecret).toHaveBeenCalledWith(1, 'password');
+    });
+
+    it('点击2FA密钥单元格按需获取明文后复制', async () => {
+      const user = userEvent.setup();
+      render(<AccountListView {...defaultProps} />);
+
+      await user.click(screen.getAllByTitle('单击查看并复制2FA密钥，双击编辑')[0]);
+
+      await waitFor(() => {
+        expect(defaultProps.copyToClipboard).toHaveBeenCalledWith('secret1', '2FA密钥');
+      });
+      expect(api.revealAccountSecret).toHaveBeenCalledWith(1, 'secret');
+    });
+
+    it('获取明文失败时不复制占位符', async () => {
+      const user = userEvent.setup();
+      const alertSpy = vi.spyOn(window, 'alert').mockImplementation(() => {});
+      api.revealAccountSecret.mockResolvedValue({ success: false, message: '权限不足', data: null });
+      render(<AccountListView {...defaultProps} />);
+
+      await user.click(screen.getAllByTitle('单击查看并复制密码，双击编辑')[0]);
+
+      await waitFor(() => {
+        expect(alertSpy).toHaveBeenCalledWith('获取明文失败: 权限不足');
+      });
+      expect(defaultProps.copyToClipboard).not.toHaveBeenCalled();
+      alertSpy.mockRestore();
     });
 
     it('点击恢复邮箱单元格复制', async () => {
@@ -497,12 +510,18 @@ describe('AccountListView 组件', () => {
       const copyAllButtons = screen.getAllByTitle('复制全部信息');
       await user.click(copyAllButtons[0]);
 
-      expect(defaultProps.copyToClipboard).toHaveBeenCalled();
+      await waitFor(() => {
+        expect(defaultProps.copyToClipboard).toHaveBeenCalled();
+      });
       const callArgs = defaultProps.copyToClipboard.mock.calls.at(-1);
       expect(callArgs[0]).toContain('test1@gmail.com');
       expect(callArgs[0]).toContain('password1');
       expect(callArgs[0]).toContain('recovery1@gmail.com');
+      expect(callArgs[0]).toContain('https://2fa.run/2fa/secret1');
+      expect(callArgs[0]).not.toContain('******');
       expect(callArgs[1]).toBe('全部信息');
+      expect(api.revealAccountSecret).toHaveBeenCalledWith(1, 'password');
+      expect(api.revealAccountSecret).toHaveBeenCalledWith(1, 'secret');
     });
 
     it('手机号列支持分开复制手机号和国别+手机号', async () => {
@@ -682,15 +701,39 @@ describe('AccountListView 组件', () => {
   });
 
   describe('分页功能', () => {
-    it('显示分页组件（账号数量大于10时）', () => {
-      const manyAccounts = Array.from({ length: 15 }, (_, i) => buildMockAccount(i + 1));
+    it('按后端返回的总数显示分页，翻页交给分页状态处理', async () => {
+      const user = userEvent.setup();
+      const pageAccounts = Array.from({ length: 10 }, (_, i) => buildMockAccount(i + 1));
+      const pagination = buildPagination(25);
 
-      render(<AccountListView {...defaultProps} accounts={manyAccounts} />);
+      render(
+        <AccountListView
+          {...defaultProps}
+          accounts={pageAccounts}
+          totalAccounts={25}
+          pagination={pagination}
+        />
+      );
 
-      // 应该只显示前10个账号
       expect(screen.getByText('test1@gmail.com')).toBeInTheDocument();
       expect(screen.getByText('test10@gmail.com')).toBeInTheDocument();
-      expect(screen.queryByText('test11@gmail.com')).not.toBeInTheDocument();
+      expect(screen.getByText('25')).toBeInTheDocument();
+
+      await user.click(screen.getByTitle('下一页'));
+      expect(pagination.goToPage).toHaveBeenCalledWith(2);
+    });
+
+    it('序号按当前页偏移计算', () => {
+      render(
+        <AccountListView
+          {...defaultProps}
+          accounts={[buildMockAccount(11), buildMockAccount(12)]}
+          totalAccounts={12}
+          pagination={buildPagination(12, { currentPage: 2 })}
+        />
+      );
+
+      expect(screen.getByText('11')).toBeInTheDocument();
     });
   });
 
@@ -698,12 +741,16 @@ describe('AccountListView 组件', () => {
     it('选中后可一键全选当前结果全部数据（跨分页）并执行批量删除', async () => {
       const user = userEvent.setup();
       const onBatchDelete = vi.fn().mockResolvedValue({ successfulIds: [], failedCount: 0 });
-      const manyAccounts = Array.from({ length: 15 }, (_, i) => buildMockAccount(i + 1));
+      const pageAccounts = Array.from({ length: 10 }, (_, i) => buildMockAccount(i + 1));
+      const allIds = Array.from({ length: 15 }, (_, i) => i + 1);
 
       render(
         <AccountListView
           {...defaultProps}
-          accounts={manyAccounts}
+          accounts={pageAccounts}
+          totalAccounts={15}
+          resultAccountIds={allIds}
+          pagination={buildPagination(15)}
           onBatchDelete={onBatchDelete}
         />
       );
@@ -736,6 +783,8 @@ describe('AccountListView 组件', () => {
         <AccountListView
           {...defaultProps}
           accounts={firstResult}
+          totalAccounts={2}
+          resultAccountIds={[1, 2]}
           onBatchDelete={onBatchDelete}
         />
       );
@@ -748,6 +797,8 @@ describe('AccountListView 组件', () => {
         <AccountListView
           {...defaultProps}
           accounts={secondResult}
+          totalAccounts={1}
+          resultAccountIds={[1]}
           onBatchDelete={onBatchDelete}
         />
       );
@@ -0,0 +1,77 @@
+import { renderHook, waitFor } from '@testing-library/react';
+import { describe, it, expect, vi, beforeEach } from 'vitest';
+import useTwoFA from '../hooks/useTwoFA';
+import api from '../services/api';
+
+vi.mock('../services/api', () => ({
+  default: {
+    generateTotpForAccounts: vi.fn(),
+  },
+}));
+
+describe('useTwoFA Hook', () => {
+  // 列表为脱敏数据：secret 只有占位符
+  const mockAccounts = [
+    { id: 1, email: 'test1@gmail.com', secret: '******' },
+    { id: 2, email: 'test2@gmail.com', secret: '' },
+    { id: 3, email: 'test3@gmail.com', secret: '******' },
+  ];
+
+  beforeEach(() => {
+    vi.clearAllMocks();
+  });
+
+  it('只为设置了 2FA 的账号向后端批量获取验证码', async () => {
+    api.generateTotpForAccounts.mockResolvedValue({
+      success: true,
+      data: [
+        { accountId: 1, code: '123456', remaining: 20 },
+        { accountId: 3, code: '654321', remaining: 20 },
+      ],
+    });
+
+    const { result } = renderHook(() => useTwoFA(mockAccounts));
+
+    await waitFor(() => {
+      expect(result.current.twoFACodes[1]).toEqual({ code: '123456', expiry: 20 });
+    });
+    expect(result.current.twoFACodes[3]).toEqual({ code: '654321', expiry: 20 });
+    expect(result.current.twoFACodes[2]).toBeUndefined();
+    expect(api.generateTotpForAccounts).toHaveBeenCalledWith([1, 3]);
+  });
+
+  it('生成失败的账号不显示验证码', async () => {
+    api.generateTotpForAccounts.mockResolvedValue({
+      success: true,
+      data: [
+        { accountId: 1, code: '123456', remaining: 20 },
+        { accountId: 3, error: '无效的 2FA 密钥' },
+      ],
+    });
+
+    const { result } = renderHook(() => useTwoFA(mockAccounts));
+
+    await waitFor(() => {
+      expect(result.current.twoFACodes[1]).toBeDefined();
+    });
+    expect(result.current.twoFACodes[3]).toBeUndefined();
+  });
+
+  it('请求失败（如只读用户无权限）时不显示验证码', async () => {
+    api.generateTotpForAccounts.mockResolvedValue({ success: false, message: '权限不足', data: [] });
+
+    const { result } = renderHook(() => useTwoFA(mockAccounts));
+
+    await waitFor(() => {
+      expect(api.generateTotpForAccounts).toHaveBeenCalledTimes(1);
+    });
+    expect(result.current.twoFACodes).toEqual({});
+  });
+
+  it('没有设置 2FA 的账号时不请求后端', () => {
+    const { result } = renderHook(() => useTwoFA([{ id: 2, email: 'test2@gmail.com', secret: '' }]));
+
+    expect(api.generateTotpForAccounts).not.toHaveBeenCalled();
+    expect(result.current.twoFACodes).toEqual({});
+  });
+});
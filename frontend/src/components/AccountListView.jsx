@@ -11,7 +11,6 @@ import {
 import { save } from '@tauri-apps/plugin-dialog';
 import { writeTextFile } from '@tauri-apps/plugin-fs';
 import Pagination from './Pagination';
-import usePagination from '../hooks/usePagination';
 import useInlineEdit from '../hooks/useInlineEdit';
 import useAccountSelection from '../hooks/useAccountSelection';
 import useTwoFA from '../hooks/useTwoFA';
@@ -22,6 +21,7 @@ import AccountTable from './AccountTable';
 import api from '../services/api';
 
 const REUSABLE_INLINE_FIELDS = ['recovery', 'phone', 'groupName', 'remark', 'regYear', 'country'];
+const EMPTY_RECYCLE_DRAWER = Object.freeze({ isOpen: false, loading: false, accounts: [], total: 0, page: 1, pageSize: 20 });
 const DEFAULT_EXPORT_CATEGORY_LABEL_TEMPLATE = '{index}. {groupField}: {groupValue}（共 {count} 条）';
 const DEFAULT_EXPORT_CONFIG = Object.freeze({
     separator: '----',
@@ -112,6 +112,11 @@ const normalizeExportConfig = (rawConfig) => {
  */
 const AccountListView = ({
     accounts,
+    totalAccounts = accounts.length,
+    resultAccountIds = [],
+    pagination,
+    sortConfig,
+    onSortChange,
     search,
     setSearch,
     soldStatusFilter,
@@ -132,87 +137,10 @@ const AccountListView = ({
     onSearchChange,
     darkMode
 }) => {
-    const [sortConfig, setSortConfig] = useState({ key: null, direction: null });
     const [inlineRecentValuesByField, setInlineRecentValuesByField] = useState(() => buildEmptyRecentValues());
 
-    const sortedAccounts = useMemo(() => {
-        if (!sortConfig.key || !sortConfig.direction) return accounts;
-
-        const isEmpty = (value) => value === null || value === undefined || String(value).trim() === '';
-        const compareNullableText = (a, b, direction) => {
-            const aEmpty = isEmpty(a);
-            const bEmpty = isEmpty(b);
-            if (aEmpty && bEmpty) return 0;
-            if (aEmpty) return direction === 'asc' ? 1 : -1;
-            if (bEmpty) return direction === 'asc' ? -1 : 1;
-            const result = String(a).localeCompare(String(b), 'zh-CN', { numeric: true, sensitivity: 'base' });
-            return direction === 'asc' ? result : -result;
-        };
-
-        const direction = sortConfig.direction;
-        const sorted = [...accounts].sort((a, b) => {
-            switch (sortConfig.key) {
-                case 'id': {
-                    const aId = Number(a.id) || 0;
-                    const bId = Number(b.id) || 0;
-                    return direction === 'asc' ? aId - bId : bId - aId;
-                }
-                case 'email':
-                case 'recovery':
-                case 'secret':
-                case 'phone':
-                case 'groupName':
-                case 'remark':
-                case 'country': {
-                    return compareNullableText(a[sortConfig.key], b[sortConfig.key], direction);
-                }
-                case 'regYear': {
-                    const aYear = Number.parseInt(a.regYear, 10);
-                    const bYear = Number.parseInt(b.regYear, 10);
-                    const aValid = Number.isFinite(aYear) ? aYear : null;
-                    const bValid = Number.isFinite(bYear) ? bYear : null;
-                    if (aValid === null && bValid === null) return 0;
-                    if (aValid === null) return direction === 'asc' ? 1 : -1;
-                    if (bValid === null) return direction === 'asc' ? -1 : 1;
-                    return direction === 'asc' ? aValid - bValid : bValid - aValid;
-                }
-                case 'status': {
-                    const statusRank = { inactive: 0, pro: 1 };
-                    const soldRank = { unsold: 0, sold: 1 };
-                    const aStatus = statusRank[a.status] ?? -1;
-                    const bStatus = statusRank[b.status] ?? -1;
-                    if (aStatus !== bStatus) {
-                        return direction === 'asc' ? aStatus - bStatus : bStatus - aStatus;
-                    }
-
-                    const aSold = soldRank[a.soldStatus] ?? -1;
-                    const bSold = soldRank[b.soldStatus] ?? -1;
-                    return direction === 'asc' ? aSold - bSold : bSold - aSold;
-                }
-                case 'createdAt': {
-                    const aTs = Date.parse(a.createdAt || '');
-                    const bTs = Date.parse(b.createdAt || '');
-                    const aValid = Number.isNaN(aTs) ? null : aTs;
-                    const bValid = Number.isNaN(bTs) ? null : bTs;
-                    if (aValid === null && bValid === null) {
-                        return compareNullableText(a.createdAt, b.createdAt, direction);
-                    }
-                    if (aValid === null) return direction === 'asc' ? 1 : -1;
-                    if (bValid === null) return direction === 'asc' ? -1 : 1;
-                    return direction === 'asc' ? aValid - bValid : bValid - aValid;
-                }
-                default:
-                    return 0;
-            }
-        });
-
-        return sorted;
-    }, [accounts, sortConfig]);
-
-    const pagination = usePagination(sortedAccounts, 10);
-
     // 2FA 验证码：只处理当前页可见账号
-    const { twoFACodes: visibleTwoFACodes } = useTwoFA(pagination.paginatedData);
+    const { twoFACodes: visibleTwoFACodes } = useTwoFA(accounts);
     const twoFACodes = externalTwoFACodes ?? visibleTwoFACodes;
 
     // 可复用列的最近 5 条值（用于行内编辑自动补全）
@@ -220,7 +148,7 @@ const AccountListView = ({
         const byField = Object.fromEntries(REUSABLE_INLINE_FIELDS.map(field => [field, []]));
         const seenByField = Object.fromEntries(REUSABLE_INLINE_FIELDS.map(field => [field, new Set()]));
 
-        // accounts 默认后端按 id DESC 返回，直接线性扫描可避免每次 n log n 排序
+        // accounts 为后端返回的当前页数据，直接线性扫描即可
         for (const account of accounts) {
             for (const field of REUSABLE_INLINE_FIELDS) {
                 if (byField[field].length >= 5) continue;
@@ -312,17 +240,15 @@ const AccountListView = ({
 
     // 导出对话框状态
     const [exportDialog, setExportDialog] = useState({ isOpen: false, mode: 'all' });
-    const [recycleDrawer, setRecycleDrawer] = useState({ isOpen: false, loading: false, accounts: [] });
+    const [recycleDrawer, setRecycleDrawer] = useState(EMPTY_RECYCLE_DRAWER);
 
     // 行内编辑 Hook
     const inlineEdit = useInlineEdit({ onInlineEdit: handleInlineEditWithRecent, allGroups, recentValuesByField });
 
     // 多选 Hook
     const selection = useAccountSelection();
-    const currentResultAccountIds = useMemo(
-        () => sortedAccounts.map(account => account.id),
-        [sortedAccounts]
-    );
+    // 列表只有当前页数据，当前筛选结果的全部 ID 由后端查询
+    const currentResultAccountIds = resultAccountIds;
 
     const normalizedSearchKeyword = (search || '').trim();
     const hasSoldStatusFilter = soldStatusFilter !== 'all';
@@ -342,7 +268,7 @@ const AccountListView = ({
         }
         if (mode === 'filtered') {
             if (hasGroupNameFilter) {
-                return { accountIds: accounts.map(a => a.id), searchParam: null, soldStatusParam: null };
+                return { accountIds: currentResultAccountIds, searchParam: null, soldStatusParam: null };
             }
             return {
                 accountIds: null,
@@ -355,8 +281,8 @@ const AccountListView = ({
 
     const exportScopeCounts = {
         selected: selectedAccountIds.length,
-        filtered: accounts.length,
-        all: accounts.length,
+        filtered: totalAccounts,
+        all: totalAccounts,
     };
 
     const previewAccountsForExport = exportDialog.mode === 'selected'
@@ -377,16 +303,6 @@ const AccountListView = ({
         selection.selectedIds.size < currentResultAccountIds.length
     );
 
-    const handleSortChange = (key) => {
-        setSortConfig(prev => {
-            if (prev.key === key) {
-                return { key, direction: prev.direction === 'asc' ? 'desc' : 'asc' };
-            }
-            return { key, direction: 'asc' };
-        });
-        pagination.resetPage();
-    };
-
     // 搜索时重置到第一页
     const handleSearchChange = (e) => {
         setSearch(e.target.value);
@@ -398,11 +314,29 @@ const AccountListView = ({
     const openHistoryDrawer = (account) => setHistoryDrawer({ isOpen: true, account });
     const closeHistoryDrawer = () => setHistoryDrawer({ isOpen: false, account: null });
 
+    // 列表只有脱敏占位符，明文按字段向后端单独获取（后端记录每次查看）
+    const revealSecret = async (accountId, field) => {
+        const result = await api.revealAccountSecret(accountId, field);
+        if (!result.success) {
+            alert('获取明文失败: ' + result.message);
+            return null;
+        }
+        return result.data;
+    };
+
+    const revealAndCopy = async (accountId, field, label) => {
+        const value = await revealSecret(accountId, field);
+        if (value) copyToClipboard(value, label);
+    };
+
     // 复制全部信息
-    const copyAllInfo = (acc) => {
-        const cleanSecret = acc.secret ? acc.secret.replace(/\s/g, '') : '';
+    const copyAllInfo = async (acc) => {
+        const password = await revealSecret(acc.id, 'password');
+        if (password === null) return;
+        const secret = acc.secret ? await revealSecret(acc.id, 'secret') : '';
+        const cleanSecret = secret ? secret.replace(/\s/g, '') : '';
         const fullInfo = `邮箱账号：${acc.email}
-密码：${acc.password}
+密码：${password}
 恢复邮箱：${acc.recovery}
 手机号：${acc.phone || '无'}
 注册年份：${acc.regYear || '无'}
@@ -521,12 +455,26 @@ const AccountListView = ({
         }
     };
 
-    const loadDeletedAccounts = async () => {
+    const loadDeletedAccounts = async (page = recycleDrawer.page, pageSize = recycleDrawer.pageSize) => {
         setRecycleDrawer(prev => ({ ...prev, loading: true }));
         try {
-            const result = await api.getDeletedAccounts();
+            const result = await api.getDeletedAccounts(page, pageSize);
             if (result.success) {
-                setRecycleDrawer(prev => ({ ...prev, accounts: result.data || [], loading: false }));
+                const total = result.total || 0;
+                const lastPage = Math.max(1, Math.ceil(total / pageSize));
+                // 删除 / 恢复后当前页可能已不存在，回退到最后一页
+                if (page > lastPage) {
+                    await loadDeletedAccounts(lastPage, pageSize);
+                    return;
+                }
+                setRecycleDrawer(prev => ({
+                    ...prev,
+                    accounts: result.data || [],
+                    total,
+                    page,
+                    pageSize,
+                    loading: false
+                }));
             } else {
                 setRecycleDrawer(prev => ({ ...prev, loading: false }));
                 alert(result.message || '加载回收站失败');
@@ -538,14 +486,16 @@ const AccountListView = ({
     };
 
     const openRecycleDrawer = async () => {
-        setRecycleDrawer(prev => ({ ...prev, isOpen: true }));
-        await loadDeletedAccounts();
+        setRecycleDrawer({ ...EMPTY_RECYCLE_DRAWER, isOpen: true });
+        await loadDeletedAccounts(1, EMPTY_RECYCLE_DRAWER.pageSize);
     };
 
     const closeRecycleDrawer = () => {
-        setRecycleDrawer({ isOpen: false, loading: false, accounts: [] });
+        setRecycleDrawer(EMPTY_RECYCLE_DRAWER);
     };
 
+    const recycleTotalPages = Math.max(1, Math.ceil(recycleDrawer.total / recycleDrawer.pageSize));
+
     const handleRestoreDeleted = async (id) => {
         const result = await api.restoreAccount(id);
         if (!result.success) {
@@ -696,17 +646,17 @@ const AccountListView = ({
 
                 {/* 表格 */}
                 <AccountTable
-                    paginatedData={pagination.paginatedData}
+                    paginatedData={accounts}
                     pagination={pagination}
                     loading={loading}
                     darkMode={darkMode}
                     sortConfig={sortConfig}
-                    onSortChange={handleSortChange}
+                    onSortChange={onSortChange}
                     selectedIds={selection.selectedIds}
                     onToggleSelectAll={selection.toggleSelectAll}
                     onToggleCheckbox={selection.toggleCheckbox}
-                    onRowMouseDown={(e, accId) => selection.handleRowMouseDown(e, accId, pagination.paginatedData, inlineEdit.editingCell)}
-                    onRowMouseEnter={(accId) => selection.handleRowMouseEnter(accId, pagination.paginatedData)}
+                    onRowMouseDown={(e, accId) => selection.handleRowMouseDown(e, accId, accounts, inlineEdit.editingCell)}
+                    onRowMouseEnter={(accId) => selection.handleRowMouseEnter(accId, accounts)}
                     editingCell={inlineEdit.editingCell}
                     editValue={inlineEdit.editValue}
                     setEditValue={inlineEdit.setEditValue}
@@ -714,6 +664,7 @@ const AccountListView = ({
                     showSuggestions={inlineEdit.showSuggestions}
                     filteredSuggestions={inlineEdit.filteredSuggestions}
                     onCellClick={(value, label) => inlineEdit.handleCellClick(value, label, copyToClipboard)}
+                    onSecretClick={(accountId, field, label) => inlineEdit.handleCellClick(field, label, () => revealAndCopy(accountId, field, label))}
                     onCellDoubleClick={inlineEdit.handleCellDoubleClick}
                     onEditableInputBlur={inlineEdit.handleEditableInputBlur}
                     onKeyDown={inlineEdit.handleKeyDown}
@@ -770,7 +721,7 @@ const AccountListView = ({
                                 <Trash2 size={18} />
                                 <h3 className="text-lg font-semibold">回收站</h3>
                                 <span className={`text-xs px-2 py-0.5 rounded-full ${darkMode ? 'bg-slate-700 text-slate-300' : 'bg-slate-100 text-slate-500'}`}>
-                                    {recycleDrawer.accounts.length} 条
+                                    {recycleDrawer.total} 条
                                 </span>
                             </div>
                             <button
@@ -816,9 +767,21 @@ const AccountListView = ({
                                 </div>
                             ))}
                         </div>
+                        {recycleDrawer.total > 0 && (
+                            <Pagination
+                                currentPage={recycleDrawer.page}
+                                totalPages={recycleTotalPages}
+                                totalItems={recycleDrawer.total}
+                                pageSize={recycleDrawer.pageSize}
+                                onPageChange={(page) => loadDeletedAccounts(page)}
+                                onPageSizeChange={(pageSize) => loadDeletedAccounts(1, pageSize)}
+                                hasNextPage={recycleDrawer.page < recycleTotalPages}
+                                hasPrevPage={recycleDrawer.page > 1}
+                            />
+                        )}
                         <div className={`px-6 py-4 border-t flex justify-between items-center ${darkMode ? 'border-slate-700' : 'border-slate-200'}`}>
                             <button
-                                onClick={loadDeletedAccounts}
+                                onClick={() => loadDeletedAccounts()}
                                 className={`px-3 py-1.5 text-sm rounded-lg ${darkMode ? 'bg-slate-700 hover:bg-slate-600' : 'bg-slate-100 hover:bg-slate-200'}`}
                             >
                                 刷新
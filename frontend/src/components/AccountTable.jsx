@@ -57,6 +57,7 @@ const AccountTable = ({
     showSuggestions,
     filteredSuggestions,
     onCellClick,
+    onSecretClick,
     onCellDoubleClick,
     onEditableInputBlur,
     onKeyDown,
@@ -299,13 +300,13 @@ const AccountTable = ({
             <div className="flex flex-col gap-1">
                 <button
                     type="button"
-                    onClick={() => onCellClick(acc.secret, '2FA密钥')}
+                    onClick={() => onSecretClick(acc.id, 'secret', '2FA密钥')}
                     onDoubleClick={(e) => onCellDoubleClick(e, acc.id, 'secret', acc.secret)}
                     onMouseDown={(e) => e.stopPropagation()}
                     className={`w-full h-7 text-left text-xs font-mono cursor-pointer rounded-md px-1.5 transition-colors select-none truncate flex items-center ${darkMode
                         ? 'text-slate-300 hover:text-blue-200 hover:bg-slate-700/70'
                         : 'text-slate-600 hover:text-blue-600 hover:bg-blue-50'}`}
-                    title="单击复制2FA密钥，双击编辑"
+                    title="单击查看并复制2FA密钥，双击编辑"
                 >
                     {acc.secret}
                 </button>
@@ -366,12 +367,12 @@ const AccountTable = ({
         return (
             <button
                 type="button"
-                onClick={() => onCellClick(textValue, label)}
+                onClick={() => (variant === 'password' ? onSecretClick(acc.id, field, label) : onCellClick(textValue, label))}
                 onDoubleClick={(e) => onCellDoubleClick(e, acc.id, field, textValue)}
                 onMouseDown={(e) => e.stopPropagation()}
                 className={`w-full h-7 text-left cursor-pointer rounded-md px-1.5 transition-colors select-none truncate flex items-center ${variantClass}`}
                 style={{ maxWidth }}
-                title={`单击复制，双击编辑：${textValue}`}
+                title={variant === 'password' ? `单击查看并复制${label}，双击编辑` : `单击复制，双击编辑：${textValue}`}
             >
                 {textValue}
             </button>
@@ -412,7 +413,7 @@ const AccountTable = ({
                             {renderSortableHeader('序号', 'id', 'px-2 py-2.5 text-center w-[44px]', 'center')}
                             {renderSortableHeader('账号', 'email', 'px-3 py-2.5 w-[200px]')}
                             {renderSortableHeader('恢复', 'recovery', 'px-3 py-2.5 w-[120px]')}
-                            {renderSortableHeader('2FA', 'secret', 'px-2 py-2.5 w-[90px]')}
+                            <th className="px-2 py-2.5 w-[90px]">2FA</th>
                             {renderSortableHeader('手机', 'phone', 'px-2 py-2.5 w-[90px]')}
                             {renderSortableHeader('标签', 'groupName', 'px-2 py-2.5 w-[90px]')}
                             {renderSortableHeader('备注', 'remark', 'px-3 py-2.5 w-[100px]')}
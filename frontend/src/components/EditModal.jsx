@@ -22,7 +22,7 @@ const EditModal = ({ account, onClose, onSubmit }) => {
                         </div>
                         <div>
                             <label className="block text-sm font-medium text-slate-500 mb-1">登录密码</label>
-                            <input name="password" defaultValue={account.password} required
+                            <input name="password" defaultValue={account.password} title="保留占位符 ****** 表示不修改" required
                                 className="w-full px-4 py-3 bg-slate-50 border border-slate-200 rounded-xl focus:ring-2 focus:ring-blue-500 outline-none transition-all" />
                         </div>
                         <div>
@@ -37,7 +37,7 @@ const EditModal = ({ account, onClose, onSubmit }) => {
                         </div>
                         <div>
                             <label className="block text-sm font-medium text-slate-500 mb-1">2FA 密钥</label>
-                            <input name="secret" defaultValue={account.secret}
+                            <input name="secret" defaultValue={account.secret} title="保留占位符 ****** 表示不修改"
                                 className="w-full px-4 py-3 bg-slate-50 border border-slate-200 rounded-xl focus:ring-2 focus:ring-blue-500 outline-none transition-all" />
                         </div>
                         <div className="grid grid-cols-2 gap-4">
@@ -8,6 +8,7 @@ import api from '../services/api';
  */
 const HistoryDrawer = ({ isOpen, onClose, account, darkMode }) => {
     const [history, setHistory] = useState([]);
+    const [reveals, setReveals] = useState([]);
     const [loading, setLoading] = useState(false);
     const [expandedFields, setExpandedFields] = useState({});
 
@@ -19,6 +20,16 @@ const HistoryDrawer = ({ isOpen, onClose, account, darkMode }) => {
         sold_status: { name: '售出状态', icon: History, color: 'purple', gradient: 'from-purple-500 to-pink-600' }
     };
 
+    // 查看渠道映射
+    const revealChannels = {
+        reveal: '单独查看',
+        list: '列表',
+        detail: '详情',
+        recycle_bin: '回收站',
+        qrcode: '二维码',
+        migration: '迁移码'
+    };
+
     // 售出状态值映射
     const soldStatusLabels = {
         'sold': '已售出',
@@ -36,10 +47,15 @@ const HistoryDrawer = ({ isOpen, onClose, account, darkMode }) => {
     const loadHistory = async () => {
         setLoading(true);
         try {
-            const result = await api.getAccountHistory(account.id);
+            const [result, revealResult] = await Promise.all([
+                api.getAccountHistory(account.id),
+                api.getSecretReveals(account.id)
+            ]);
             if (result.success) {
                 setHistory(result.data);
             }
+            // 查看记录仅管理员可读，无权限时不显示
+            setReveals(revealResult.success ? revealResult.data : []);
         } catch (err) {
             console.error('加载历史记录失败:', err);
         } finally {
@@ -186,6 +202,9 @@ const HistoryDrawer = ({ isOpen, onClose, account, darkMode }) => {
                                                                 <Clock size={12} />
                                                                 <span className="font-medium">{date}</span>
                                                                 <span className={`px-1.5 py-0.5 rounded ${darkMode ? 'bg-slate-700' : 'bg-slate-200'}`}>{time}</span>
+                                                                {record.actorUsername && (
+                                                                    <span className="ml-auto font-medium">{record.actorUsername}</span>
+                                                                )}
                                                             </div>
 
                                                             {/* 修改内容 - 优化显示 */}
@@ -221,6 +240,30 @@ const HistoryDrawer = ({ isOpen, onClose, account, darkMode }) => {
                             })}
                         </div>
                     )}
+
+                    {/* 敏感字段查看记录（仅管理员可见） */}
+                    {!loading && reveals.length > 0 && (
+                        <div className={`mt-4 rounded-2xl shadow-md p-4 ${darkMode ? 'bg-slate-700/50' : 'bg-white'}`}>
+                            <p className={`font-bold mb-3 ${darkMode ? 'text-slate-200' : 'text-slate-700'}`}>
+                                查看记录
+                            </p>
+                            <div className="space-y-2">
+                                {reveals.map((reveal) => {
+                                    const { date, time } = formatTime(reveal.revealedAt);
+                                    return (
+                                        <div
+                                            key={reveal.id}
+                                            className={`flex items-center justify-between text-xs p-2 rounded-lg ${darkMode ? 'bg-slate-800/70 text-slate-300' : 'bg-slate-50 text-slate-600'}`}
+                                        >
+                                            <span>{date} {time}</span>
+                                            <span>{fieldNames[reveal.fieldName]?.name || reveal.fieldName} · {revealChannels[reveal.channel] || reveal.channel}</span>
+                                            <span className="font-medium">{reveal.actorUsername || '(未知)'}</span>
+                                        </div>
+                                    );
+                                })}
+                            </div>
+                        </div>
+                    )}
                 </div>
 
                 {/* 底部信息 */}
@@ -1,34 +1,28 @@
 import { useState, useMemo, useEffect } from 'react';
 
 /**
- * 分页逻辑 Hook
- * @param {Array} data - 数据数组
+ * 分页逻辑 Hook（服务端分页）
+ * 只维护当前页与每页条数，数据由调用方按 currentPage / pageSize 向后端分页查询
+ * @param {number} totalItems - 后端返回的匹配总数
  * @param {number} initialPageSize - 初始每页条数，默认10
  * @returns {Object} 分页状态和方法
  */
-const usePagination = (data, initialPageSize = 10) => {
+const usePagination = (totalItems, initialPageSize = 10) => {
     const [currentPage, setCurrentPage] = useState(1);
     const [pageSize, setPageSize] = useState(initialPageSize);
 
     // 计算总页数
     const totalPages = useMemo(() => {
-        return Math.max(1, Math.ceil(data.length / pageSize));
-    }, [data.length, pageSize]);
+        return Math.max(1, Math.ceil(totalItems / pageSize));
+    }, [totalItems, pageSize]);
 
-    // 当数据或每页条数变化时，确保当前页有效
+    // 当总数或每页条数变化时（如删除后），确保当前页有效
     useEffect(() => {
         if (currentPage > totalPages) {
             setCurrentPage(Math.max(1, totalPages));
         }
     }, [totalPages, currentPage]);
 
-    // 获取当前页的数据
-    const paginatedData = useMemo(() => {
-        const startIndex = (currentPage - 1) * pageSize;
-        const endIndex = startIndex + pageSize;
-        return data.slice(startIndex, endIndex);
-    }, [data, currentPage, pageSize]);
-
     // 跳转到指定页
     const goToPage = (page) => {
         const targetPage = Math.max(1, Math.min(page, totalPages));
@@ -64,8 +58,7 @@ const usePagination = (data, initialPageSize = 10) => {
         currentPage,
         pageSize,
         totalPages,
-        totalItems: data.length,
-        paginatedData,
+        totalItems,
         goToPage,
         nextPage,
         prevPage,
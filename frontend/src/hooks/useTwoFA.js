@@ -1,119 +1,85 @@
-import { useState, useEffect, useCallback, useRef } from 'react';
-import { generateSync } from 'otplib';
-
-/**
- * 2FA 验证码管理 Hook
- * 隔离每秒定时器，避免触发父组件重渲染
- *
- * @param {Array} accounts - 所有账号列表
- * @param {Array} visibleAccountIds - 当前可见页的账号 ID 列表（用于增量计算）
- */
-const useTwoFA = (accounts, visibleAccountIds = null) => {
-    const [twoFACodes, setTwoFACodes] = useState({});
-    const codeCache = useRef(new Map()); // 缓存 30 秒内的计算结果
+import { useState, useEffect, useRef } from 'react';
+import api from '../services/api';
 
-    const getAccountsToProcess = useCallback((accountList) => {
-        if (!Array.isArray(visibleAccountIds)) {
-            return accountList;
+// 构建验证码映射（生成失败的账号不显示验证码）
+const buildCodeMap = (results) => {
+    const codes = {};
+    (Array.isArray(results) ? results : []).forEach(item => {
+        if (item?.code) {
+            codes[item.accountId] = { code: item.code, expiry: Number(item.remaining) || 0 };
         }
-        const visibleIdSet = new Set(visibleAccountIds);
-        return accountList.filter(acc => visibleIdSet.has(acc.id));
-    }, [visibleAccountIds]);
-
-    const generate2FACodes = useCallback((accountsList) => {
-        const now = Math.floor(Date.now() / 1000);
-        const remaining = 30 - (now % 30);
-
-        // 如果即将过期（剩余 < 5 秒），强制重新生成
-        const forceRegenerate = remaining < 5;
-
-        const newCodes = {};
-        accountsList.forEach(acc => {
-            if (!acc.secret) return;
-            const cleanSecret = acc.secret.replace(/\s/g, '').toUpperCase();
-            // 过滤明显无效的 secret，避免重复报错阻塞
-            if (cleanSecret.length < 16 || !/^[A-Z2-7]+$/.test(cleanSecret)) return;
+    });
+    return codes;
+};
 
-            const cacheKey = `${acc.id}_${cleanSecret}_${Math.floor(now / 30)}`; // 30 秒一个缓存周期
-
-            // 检查缓存
-            if (!forceRegenerate && codeCache.current.has(cacheKey)) {
-                newCodes[acc.id] = codeCache.current.get(cacheKey);
-                return;
-            }
+/**
+ * 2FA 验证码管理 Hook
+ * 验证码由后端按账号 ID 批量生成，前端不持有 2FA 密钥明文；
+ * 隔离每秒定时器，避免触发父组件重渲染
+ *
+ * @param {Array} accounts - 当前可见页的账号列表（仅处理设置了 2FA 的账号）
+ */
+const useTwoFA = (accounts) => {
+    const [twoFACodes, setTwoFACodes] = useState({});
+    const codesRef = useRef({});
 
-            try {
-                const code = generateSync({ secret: cleanSecret });
-                const result = { code, expiry: remaining };
-                newCodes[acc.id] = result;
-
-                // 缓存结果（30 秒有效）
-                codeCache.current.set(cacheKey, result);
-
-                // 清理旧缓存（保留最近 100 个）
-                if (codeCache.current.size > 100) {
-                    const entries = Array.from(codeCache.current.entries());
-                    entries.slice(0, 50).forEach(([key]) => {
-                        codeCache.current.delete(key);
-                    });
-                }
-            } catch {
-                // secret 解析失败时静默跳过，避免每次循环打印错误日志
-            }
-        });
+    // 列表为脱敏数据：secret 有占位符即表示设置了 2FA
+    const idsKey = accounts
+        .filter(acc => Boolean(acc.secret))
+        .map(acc => acc.id)
+        .join(',');
 
-        return newCodes;
-    }, []);
-
-    const generateAll2FACodes = useCallback((accountsList) => {
-        setTwoFACodes(generate2FACodes(accountsList));
-    }, [generate2FACodes]);
-
     useEffect(() => {
-        if (accounts.length === 0) {
-            setTwoFACodes({});
-            return;
-        }
+        const ids = idsKey ? idsKey.split(',').map(Number) : [];
+        const applyCodes = (codes) => {
+            codesRef.current = codes;
+            setTwoFACodes(codes);
+        };
 
-        // 增量计算：只计算可见页，且仅处理有 secret 的账号
-        const accountsToProcess = getAccountsToProcess(accounts).filter(acc => Boolean(acc.secret));
-        if (accountsToProcess.length === 0) {
-            setTwoFACodes({});
+        if (ids.length === 0) {
+            applyCodes({});
             return;
         }
 
-        generateAll2FACodes(accountsToProcess);
+        let cancelled = false;
+        let fetching = false;
+        const fetchCodes = async () => {
+            if (fetching) return;
+            fetching = true;
+            const result = await api.generateTotpForAccounts(ids);
+            fetching = false;
+            if (cancelled) return;
+            // 无权限（只读用户）或请求失败时不显示验证码
+            applyCodes(result.success ? buildCodeMap(result.data) : {});
+        };
+
+        fetchCodes();
 
+        // 本地按秒倒计时，任一验证码到期时重新向后端获取
         const timer = setInterval(() => {
-            setTwoFACodes(prev => {
-                const now = Math.floor(Date.now() / 1000);
-                const remaining = 30 - (now % 30);
-
-                // 每 30 秒重新生成一次代码
-                if (remaining === 30) {
-                    const accountsToRegenerate = getAccountsToProcess(accounts).filter(acc => Boolean(acc.secret));
-                    return generate2FACodes(accountsToRegenerate);
-                }
-
-                // 仅更新过期时间，不重新计算代码
-                const updated = {};
-                let changed = false;
-                for (const id in prev) {
-                    if (prev[id].expiry !== remaining) {
-                        updated[id] = { ...prev[id], expiry: remaining };
-                        changed = true;
-                    } else {
-                        updated[id] = prev[id];
-                    }
-                }
-                return changed ? updated : prev;
-            });
+            const next = {};
+            let expired = false;
+            for (const id in codesRef.current) {
+                const expiry = codesRef.current[id].expiry - 1;
+                if (expiry <= 0) expired = true;
+                next[id] = { ...codesRef.current[id], expiry };
+            }
+            if (expired) {
+                fetchCodes();
+                return;
+            }
+            if (Object.keys(next).length > 0) {
+                applyCodes(next);
+            }
         }, 1000);
 
-        return () => clearInterval(timer);
-    }, [accounts, generateAll2FACodes, generate2FACodes, getAccountsToProcess]);
-
-    return { twoFACodes, generateAll2FACodes };
-};
-
-export default useTwoFA;
+        return () => {
+            cancelled = true;
+            clearInterval(timer);
+        };
+    }, [idsKey]);
+
+    return { twoFACodes };
+};
+
+export default useTwoFA;
@@ -1,13 +1,33 @@
 import type {
   ApiAdapter,
   Account,
+  AccountFilter,
   AccountInput,
+  AccountListQuery,
+  AccountPage,
+  AccountTotpResult,
   BatchImportResult,
+  CsvImportConfig,
+  ImportOptions,
+  ImportParseResult,
+  ImportRowResult,
+  PasswordImportResult,
+  PasswordManagerSource,
+  SecretField,
+  SecretReveal,
+  TotpParams,
   TotpResult,
   ApiResponse,
   LoginResult,
   CheckAuthResult,
   ExportConfig,
+  ExportFileSummary,
+  ExportPreset,
+  SessionInfo,
+  SessionUser,
+  User,
+  UserRole,
+  UserUpdate,
   BackupInfo,
 } from '../types';
 import { snakeToCamel, camelToSnake } from '../utils';
@@ -148,10 +168,10 @@ export class HttpAdapter implements ApiAdapter {
     return this.unwrapData<T>(payload);
   }
 
-  async login(password: string): Promise<LoginResult> {
+  async login(password: string, username?: string, deviceLabel?: string): Promise<LoginResult> {
     const result = await this.requestData<Record<string, unknown>>('/auth/login', {
       method: 'POST',
-      body: JSON.stringify({ password }),
+      body: JSON.stringify({ username: username || undefined, password, device_label: deviceLabel || undefined }),
     });
     const token =
       typeof result?.session_token === 'string'
@@ -172,6 +192,9 @@ export class HttpAdapter implements ApiAdapter {
         typeof result?.expires_at_epoch_secs === 'number'
           ? result.expires_at_epoch_secs
           : (typeof result?.expiresAtEpochSecs === 'number' ? result.expiresAtEpochSecs : undefined),
+      setupRequired: Boolean(result?.setup_required ?? result?.setupRequired),
+      user: result?.user ? (result.user as SessionUser) : undefined,
+      locked: Boolean(result?.locked),
     };
   }
 
@@ -182,9 +205,10 @@ export class HttpAdapter implements ApiAdapter {
         ? result.session_token
         : (typeof result?.sessionToken === 'string' ? result.sessionToken : undefined);
     const success = Boolean(result?.success);
+    const locked = Boolean(result?.locked);
     if (success && token) {
       this.saveSessionToken(token);
-    } else if (!success) {
+    } else if (!success && !locked) {
       this.saveSessionToken(null);
     }
     return {
@@ -196,6 +220,9 @@ export class HttpAdapter implements ApiAdapter {
         typeof result?.expires_at_epoch_secs === 'number'
           ? result.expires_at_epoch_secs
           : (typeof result?.expiresAtEpochSecs === 'number' ? result.expiresAtEpochSecs : undefined),
+      setupRequired: Boolean(result?.setup_required ?? result?.setupRequired),
+      user: result?.user ? (result.user as SessionUser) : undefined,
+      locked,
     };
   }
 
@@ -207,18 +234,139 @@ export class HttpAdapter implements ApiAdapter {
     }
   }
 
-  async getAccounts(search?: string, soldStatus?: string): Promise<Account[]> {
-    const list = await this.requestData<any[]>(
+  async setupAdminPassword(password: string, username?: string): Promise<LoginResult> {
+    const result = await this.requestData<Record<string, unknown>>('/auth/setup', {
+      method: 'POST',
+      body: JSON.stringify({ username: username || undefined, password }),
+    });
+    const token = typeof result?.session_token === 'string' ? result.session_token : undefined;
+    if (token) {
+      this.saveSessionToken(token);
+    }
+    return {
+      success: Boolean(result?.success),
+      banned: false,
+      message: typeof result?.message === 'string' ? result.message : undefined,
+      sessionToken: token,
+      expiresAtEpochSecs: typeof result?.expires_at_epoch_secs === 'number' ? result.expires_at_epoch_secs : undefined,
+      user: result?.user ? (result.user as SessionUser) : undefined,
+    };
+  }
+
+  async changeAdminPassword(currentPassword: string, newPassword: string): Promise<void> {
+    await this.requestData('/auth/change-password', {
+      method: 'POST',
+      body: JSON.stringify({ current_password: currentPassword, new_password: newPassword }),
+    });
+  }
+
+  async lock(): Promise<void> {
+    await this.requestData('/auth/lock', { method: 'POST' });
+  }
+
+  async unlock(password: string): Promise<CheckAuthResult> {
+    const result = await this.requestData<Record<string, unknown>>('/auth/unlock', {
+      method: 'POST',
+      body: JSON.stringify({ password }),
+    });
+    return {
+      success: Boolean(result?.success),
+      banned: Boolean(result?.banned),
+      message: typeof result?.message === 'string' ? result.message : undefined,
+      sessionToken: typeof result?.session_token === 'string' ? result.session_token : undefined,
+      expiresAtEpochSecs: typeof result?.expires_at_epoch_secs === 'number' ? result.expires_at_epoch_secs : undefined,
+      user: result?.user ? (result.user as SessionUser) : undefined,
+      locked: Boolean(result?.locked),
+    };
+  }
+
+  async listSessions(): Promise<SessionInfo[]> {
+    const result = await this.requestData<any[]>('/auth/sessions', { method: 'GET' });
+    const rows = Array.isArray(result) ? result : [];
+    return rows.map(item => snakeToCamel<SessionInfo>(item));
+  }
+
+  async revokeSession(sessionId: string): Promise<void> {
+    await this.requestData(`/auth/sessions/${encodeURIComponent(sessionId)}`, { method: 'DELETE' });
+  }
+
+  async revokeAllSessions(includeCurrent = false): Promise<number> {
+    const result = await this.requestData<number>('/auth/sessions/revoke-all', {
+      method: 'POST',
+      body: JSON.stringify({ include_current: includeCurrent }),
+    });
+    if (includeCurrent) {
+      this.saveSessionToken(null);
+    }
+    return Number(result || 0);
+  }
+
+  async listUsers(): Promise<User[]> {
+    const result = await this.requestData<any[]>('/users', { method: 'GET' });
+    const rows = Array.isArray(result) ? result : [];
+    return rows.map(item => snakeToCamel<User>(item));
+  }
+
+  async createUser(username: string, password: string, role: UserRole): Promise<User> {
+    const result = await this.requestData<any>('/users', {
+      method: 'POST',
+      body: JSON.stringify({ username, password, role }),
+    });
+    return snakeToCamel<User>(result);
+  }
+
+  async updateUser(id: number, update: UserUpdate): Promise<User> {
+    const result = await this.requestData<any>(`/users/${id}`, {
+      method: 'PUT',
+      body: JSON.stringify(update),
+    });
+    return snakeToCamel<User>(result);
+  }
+
+  async deleteUser(id: number): Promise<void> {
+    await this.requestData(`/users/${id}`, { method: 'DELETE' });
+  }
+
+  async getAccounts(query: AccountListQuery = {}): Promise<AccountPage> {
+    const result = await this.requestData<any>(
       '/accounts',
       { method: 'GET' },
       {
-        search: search || undefined,
-        sold_status: soldStatus || undefined,
+        search: query.search || undefined,
+        sold_status: query.soldStatus || undefined,
+        group: query.group || undefined,
+        page: query.page ? String(query.page) : undefined,
+        page_size: query.pageSize ? String(query.pageSize) : undefined,
+        sort_field: query.sortField || undefined,
+        sort_direction: query.sortDirection || undefined,
       }
     );
 
-    const accounts = Array.isArray(list) ? list : [];
-    return accounts.map(item => snakeToCamel<Account>(item));
+    const items = Array.isArray(result?.items) ? result.items : [];
+    return {
+      items: items.map((item: any) => snakeToCamel<Account>(item)),
+      total: Number(result?.total || 0),
+      page: Number(result?.page || query.page || 1),
+      pageSize: Number(result?.page_size || query.pageSize || 0),
+    };
+  }
+
+  async getAccountGroups(): Promise<string[]> {
+    const result = await this.requestData<string[]>('/accounts/groups', { method: 'GET' });
+    return Array.isArray(result) ? result : [];
+  }
+
+  async getAccountIds(filter: AccountFilter = {}): Promise<number[]> {
+    const result = await this.requestData<number[]>(
+      '/accounts/ids',
+      { method: 'GET' },
+      {
+        search: filter.search || undefined,
+        sold_status: filter.soldStatus || undefined,
+        group: filter.group || undefined,
+      }
+    );
+    return Array.isArray(result) ? result.map(Number) : [];
   }
 
   async createAccount(account: AccountInput): Promise<Account> {
@@ -231,7 +379,7 @@ export class HttpAdapter implements ApiAdapter {
   }
 
   async updateAccount(id: number, account: Partial<AccountInput>): Promise<Account> {
-    // 后端 PUT 要求完整 AccountInput，需要先获取当前数据再合并
+    // 后端 PUT 要求完整 AccountInput：详情为脱敏数据，未修改的 password/secret 以占位符回传，后端保留原值
     const currentAccount = await this.requestData<any>('/accounts/' + id, { method: 'GET' });
     const current = snakeToCamel<Account>(currentAccount);
 
@@ -273,16 +421,33 @@ export class HttpAdapter implements ApiAdapter {
     return snakeToCamel<Account>(result);
   }
 
-  async batchImport(accounts: AccountInput[]): Promise<BatchImportResult> {
+  async parseImportText(text: string): Promise<ImportParseResult> {
+    const result = await this.requestData<Record<string, unknown>>('/import/parse', {
+      method: 'POST',
+      body: JSON.stringify({ text }),
+    });
+    const data = snakeToCamel<ImportParseResult>(result || {});
+    return {
+      accounts: Array.isArray(data.accounts) ? data.accounts : [],
+      warnings: Array.isArray(data.warnings) ? data.warnings : [],
+      detectedFormats: Array.isArray(data.detectedFormats) ? data.detectedFormats : [],
+    };
+  }
+
+  async batchImport(accounts: AccountInput[], options?: ImportOptions): Promise<BatchImportResult> {
     const payload = {
       accounts: accounts.map(item => camelToSnake<Record<string, unknown>>(item as Record<string, unknown>)),
+      options: options ? camelToSnake<Record<string, unknown>>(options as Record<string, unknown>) : undefined,
     };
 
     const result = await this.requestData<Record<string, unknown>>('/accounts/batch-import', {
       method: 'POST',
       body: JSON.stringify(payload),
     });
+    return this.normalizeBatchImportResult(result);
+  }
 
+  private normalizeBatchImportResult(result: Record<string, unknown> | null): BatchImportResult {
     const normalized = snakeToCamel<Record<string, unknown>>(result || {});
     const successCount = Number(normalized.successCount ?? 0);
     const failCount = Number(normalized.failedCount ?? normalized.failCount ?? 0);
@@ -290,19 +455,96 @@ export class HttpAdapter implements ApiAdapter {
     return {
       successCount: Number.isFinite(successCount) ? successCount : 0,
       failCount: Number.isFinite(failCount) ? failCount : 0,
+      skippedCount: Number(normalized.skippedCount ?? 0),
+      dryRun: Boolean(normalized.dryRun),
+      rows: Array.isArray(normalized.rows) ? (normalized.rows as ImportRowResult[]) : [],
     };
   }
 
-  async generateTotp(secret: string): Promise<TotpResult> {
+  async batchImportCsv(
+    content: string,
+    config?: CsvImportConfig,
+    options?: ImportOptions,
+  ): Promise<BatchImportResult> {
+    const payload = {
+      content,
+      config: config ? camelToSnake<Record<string, unknown>>(config as Record<string, unknown>) : undefined,
+      options: options ? camelToSnake<Record<string, unknown>>(options as Record<string, unknown>) : undefined,
+    };
+    const result = await this.requestData<Record<string, unknown>>('/accounts/batch-import/csv', {
+      method: 'POST',
+      body: JSON.stringify(payload),
+    });
+    return this.normalizeBatchImportResult(result);
+  }
+
+  async batchImportJson(content: string, options?: ImportOptions): Promise<BatchImportResult> {
+    const payload = {
+      content,
+      options: options ? camelToSnake<Record<string, unknown>>(options as Record<string, unknown>) : undefined,
+    };
+    const result = await this.requestData<Record<string, unknown>>('/accounts/batch-import/json', {
+      method: 'POST',
+      body: JSON.stringify(payload),
+    });
+    return this.normalizeBatchImportResult(result);
+  }
+
+  async importPasswordManager(
+    source: PasswordManagerSource,
+    content: string,
+    options?: ImportOptions,
+  ): Promise<PasswordImportResult> {
+    const payload = {
+      source,
+      content,
+      options: options ? camelToSnake<Record<string, unknown>>(options as Record<string, unknown>) : undefined,
+    };
+    const result = await this.requestData<Record<string, unknown>>('/import/password-manager', {
+      method: 'POST',
+      body: JSON.stringify(payload),
+    });
+    const data = snakeToCamel<Record<string, unknown>>(result || {});
+    return {
+      ...this.normalizeBatchImportResult(result),
+      source,
+      totalEntries: Number(data.totalEntries ?? 0),
+      ignoredCount: Number(data.ignoredCount ?? 0),
+    };
+  }
+
+  async generateTotp(secret: string, params?: TotpParams): Promise<TotpResult> {
     const result = await this.requestData<Record<string, unknown>>('/totp/generate', {
       method: 'POST',
-      body: JSON.stringify({ secret }),
+      body: JSON.stringify({ secret, params: params ?? null }),
     });
+    return this.normalizeTotpResult(result);
+  }
+
+  async generateTotpForAccount(accountId: number): Promise<TotpResult> {
+    const result = await this.requestData<Record<string, unknown>>(
+      '/accounts/' + accountId + '/totp',
+      { method: 'GET' }
+    );
+    return this.normalizeTotpResult(result);
+  }
+
+  async generateTotpForAccounts(accountIds: number[]): Promise<AccountTotpResult[]> {
+    const list = await this.requestData<any[]>('/totp/generate-batch', {
+      method: 'POST',
+      body: JSON.stringify({ ids: accountIds }),
+    });
+    const results = Array.isArray(list) ? list : [];
+    return results.map(item => snakeToCamel<AccountTotpResult>(item));
+  }
 
+  private normalizeTotpResult(result: Record<string, unknown> | null): TotpResult {
     const normalized = snakeToCamel<Record<string, unknown>>(result || {});
     return {
       code: String(normalized.code ?? ''),
       remaining: Number(normalized.remaining ?? 0),
+      previousCode: normalized.previousCode ? String(normalized.previousCode) : undefined,
+      nextCode: normalized.nextCode ? String(normalized.nextCode) : undefined,
     };
   }
 
@@ -315,6 +557,23 @@ export class HttpAdapter implements ApiAdapter {
     return rows.map(item => snakeToCamel(item));
   }
 
+  async getSecretReveals(accountId: number): Promise<SecretReveal[]> {
+    const reveals = await this.requestData<any[]>('/accounts/' + accountId + '/reveals', {
+      method: 'GET',
+    });
+
+    const rows = Array.isArray(reveals) ? reveals : [];
+    return rows.map(item => snakeToCamel<SecretReveal>(item));
+  }
+
+  async revealAccountSecret(accountId: number, field: SecretField): Promise<string | null> {
+    const value = await this.requestData<string | null>('/accounts/' + accountId + '/reveal', {
+      method: 'POST',
+      body: JSON.stringify({ field }),
+    });
+    return value ?? null;
+  }
+
   async exportDatabaseSql(): Promise<string> {
     throw new Error('HTTP 模式不支持数据库导出功能，请使用 Tauri 桌面模式');
   }
@@ -323,20 +582,68 @@ export class HttpAdapter implements ApiAdapter {
     accountIds: number[] | null,
     search: string | null,
     soldStatus: string | null,
-    config: ExportConfig,
+    config: ExportConfig | string,
   ): Promise<string> {
     throw new Error('HTTP 模式不支持账号导出功能，请使用 Tauri 桌面模式');
   }
 
+  async exportAccountsToFile(
+    accountIds: number[] | null,
+    search: string | null,
+    soldStatus: string | null,
+    config: ExportConfig | string,
+    path: string,
+  ): Promise<ExportFileSummary> {
+    throw new Error('HTTP 模式不支持导出到文件，请使用 Tauri 桌面模式');
+  }
+
+  async exportDatabaseSqlToFile(path: string): Promise<ExportFileSummary> {
+    throw new Error('HTTP 模式不支持导出到文件，请使用 Tauri 桌面模式');
+  }
+
+  async listExportPresets(): Promise<ExportPreset[]> {
+    const result = await this.requestData<any[]>('/export-presets', { method: 'GET' });
+    const rows = Array.isArray(result) ? result : [];
+    return rows.map(item => snakeToCamel<ExportPreset>(item));
+  }
+
+  async createExportPreset(name: string, config: ExportConfig): Promise<ExportPreset> {
+    const result = await this.requestData<any>('/export-presets', {
+      method: 'POST',
+      body: JSON.stringify({ name, config: camelToSnake(config as unknown as Record<string, unknown>) }),
+    });
+    return snakeToCamel<ExportPreset>(result);
+  }
+
+  async updateExportPreset(id: number, name: string, config: ExportConfig): Promise<ExportPreset> {
+    const result = await this.requestData<any>(`/export-presets/${id}`, {
+      method: 'PUT',
+      body: JSON.stringify({ name, config: camelToSnake(config as unknown as Record<string, unknown>) }),
+    });
+    return snakeToCamel<ExportPreset>(result);
+  }
+
+  async deleteExportPreset(id: number): Promise<void> {
+    await this.requestData(`/export-presets/${id}`, { method: 'DELETE' });
+  }
+
   async deleteAllAccounts(): Promise<number> {
     const result = await this.requestData<number>('/accounts/delete-all', { method: 'POST' });
     return Number(result || 0);
   }
 
-  async getDeletedAccounts(): Promise<Account[]> {
-    const list = await this.requestData<any[]>('/accounts/deleted', { method: 'GET' });
-    const accounts = Array.isArray(list) ? list : [];
-    return accounts.map(item => snakeToCamel<Account>(item));
+  async getDeletedAccounts(page = 1, pageSize = 20): Promise<AccountPage> {
+    const result = await this.requestData<any>('/accounts/deleted', { method: 'GET' }, {
+      page: String(page),
+      page_size: String(pageSize),
+    });
+    const items = Array.isArray(result?.items) ? result.items : [];
+    return {
+      items: items.map((item: any) => snakeToCamel<Account>(item)),
+      total: Number(result?.total || 0),
+      page: Number(result?.page || page),
+      pageSize: Number(result?.page_size || pageSize),
+    };
   }
 
   async restoreAccount(id: number): Promise<Account> {
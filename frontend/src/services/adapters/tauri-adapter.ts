@@ -2,12 +2,32 @@ import { invoke } from '@tauri-apps/api/core';
 import type {
   ApiAdapter,
   Account,
+  AccountFilter,
   AccountInput,
+  AccountListQuery,
+  AccountPage,
+  AccountTotpResult,
   BatchImportResult,
+  CsvImportConfig,
+  ImportOptions,
+  ImportParseResult,
+  ImportRowResult,
+  PasswordImportResult,
+  PasswordManagerSource,
+  SecretField,
+  SecretReveal,
+  TotpParams,
   TotpResult,
   LoginResult,
   CheckAuthResult,
   ExportConfig,
+  ExportFileSummary,
+  ExportPreset,
+  SessionInfo,
+  SessionUser,
+  User,
+  UserRole,
+  UserUpdate,
   BackupInfo,
 } from '../types';
 import { snakeToCamel, camelToSnake } from '../utils';
@@ -85,9 +105,13 @@ export class TauriAdapter implements ApiAdapter {
     return snakeToCamel<Record<string, unknown>>(payload as Record<string, unknown>);
   }
 
-  async login(password: string): Promise<LoginResult> {
+  async login(password: string, username?: string, deviceLabel?: string): Promise<LoginResult> {
     try {
-      const payload = await this.invokeWithSnake<unknown>('login', { password });
+      const payload = await this.invokeWithSnake<unknown>('login', {
+        username: username || null,
+        password,
+        deviceLabel: deviceLabel || null,
+      });
       const auth = this.normalizeAuthPayload(payload);
       const success = Boolean(auth.success);
       const token = typeof auth.sessionToken === 'string' ? auth.sessionToken : undefined;
@@ -102,6 +126,9 @@ export class TauriAdapter implements ApiAdapter {
         banned: Boolean(auth.banned),
         sessionToken: token,
         expiresAtEpochSecs: typeof auth.expiresAtEpochSecs === 'number' ? auth.expiresAtEpochSecs : undefined,
+        setupRequired: Boolean(auth.setupRequired),
+        user: auth.user ? (auth.user as SessionUser) : undefined,
+        locked: Boolean(auth.locked),
       };
     } catch (error) {
       const message = error instanceof Error ? error.message : String(error);
@@ -121,10 +148,11 @@ export class TauriAdapter implements ApiAdapter {
       });
       const auth = this.normalizeAuthPayload(payload);
       const success = Boolean(auth.success);
+      const locked = Boolean(auth.locked);
       const token = typeof auth.sessionToken === 'string' ? auth.sessionToken : undefined;
       if (success && token) {
         this.saveSessionToken(token);
-      } else if (!success) {
+      } else if (!success && !locked) {
         this.saveSessionToken(null);
       }
       return {
@@ -133,6 +161,9 @@ export class TauriAdapter implements ApiAdapter {
         message: typeof auth.message === 'string' ? auth.message : undefined,
         sessionToken: token,
         expiresAtEpochSecs: typeof auth.expiresAtEpochSecs === 'number' ? auth.expiresAtEpochSecs : undefined,
+        setupRequired: Boolean(auth.setupRequired),
+        user: auth.user ? (auth.user as SessionUser) : undefined,
+        locked,
       };
     } catch (error) {
       this.saveSessionToken(null);
@@ -154,7 +185,90 @@ export class TauriAdapter implements ApiAdapter {
     }
   }
 
+  async setupAdminPassword(password: string, username?: string): Promise<LoginResult> {
+    const payload = await this.invokeWithSnake<unknown>('setup_admin_password', { username: username || null, password });
+    const auth = this.normalizeAuthPayload(payload);
+    const token = typeof auth.sessionToken === 'string' ? auth.sessionToken : undefined;
+    if (token) {
+      this.saveSessionToken(token);
+    }
+    return {
+      success: Boolean(auth.success),
+      message: typeof auth.message === 'string' ? auth.message : undefined,
+      banned: false,
+      sessionToken: token,
+      expiresAtEpochSecs: typeof auth.expiresAtEpochSecs === 'number' ? auth.expiresAtEpochSecs : undefined,
+      user: auth.user ? (auth.user as SessionUser) : undefined,
+    };
+  }
+
+  async changeAdminPassword(currentPassword: string, newPassword: string): Promise<void> {
+    await this.invokeAuthed('change_admin_password', { currentPassword, newPassword });
+  }
+
+  async lock(): Promise<void> {
+    await this.invokeAuthed('lock');
+  }
+
+  async unlock(password: string): Promise<CheckAuthResult> {
+    const payload = await this.invokeAuthed<unknown>('unlock', { password });
+    const auth = this.normalizeAuthPayload(payload);
+    return {
+      success: Boolean(auth.success),
+      banned: Boolean(auth.banned),
+      message: typeof auth.message === 'string' ? auth.message : undefined,
+      sessionToken: typeof auth.sessionToken === 'string' ? auth.sessionToken : undefined,
+      expiresAtEpochSecs: typeof auth.expiresAtEpochSecs === 'number' ? auth.expiresAtEpochSecs : undefined,
+      user: auth.user ? (auth.user as SessionUser) : undefined,
+      locked: Boolean(auth.locked),
+    };
+  }
+
+  async listSessions(): Promise<SessionInfo[]> {
+    const list = await this.invokeAuthed<any[]>('list_sessions');
+    const sessions = Array.isArray(list) ? list : [];
+    return sessions.map(item => snakeToCamel<SessionInfo>(item));
+  }
+
+  async revokeSession(sessionId: string): Promise<void> {
+    await this.invokeAuthed('revoke_session', { sessionId });
+  }
+
+  async revokeAllSessions(includeCurrent = false): Promise<number> {
+    const count = await this.invokeAuthed<number>('revoke_all_sessions', { includeCurrent });
+    if (includeCurrent) {
+      this.saveSessionToken(null);
+    }
+    return Number(count || 0);
+  }
+
+  async listUsers(): Promise<User[]> {
+    const list = await this.invokeAuthed<any[]>('list_users');
+    const users = Array.isArray(list) ? list : [];
+    return users.map(item => snakeToCamel<User>(item));
+  }
+
+  async createUser(username: string, password: string, role: UserRole): Promise<User> {
+    const result = await this.invokeAuthed<any>('create_user', { username, password, role });
+    return snakeToCamel<User>(result);
+  }
+
+  async updateUser(id: number, update: UserUpdate): Promise<User> {
+    const result = await this.invokeAuthed<any>('update_user', {
+      id,
+      role: update.role ?? null,
+      disabled: update.disabled ?? null,
+      password: update.password || null,
+    });
+    return snakeToCamel<User>(result);
+  }
+
+  async deleteUser(id: number): Promise<void> {
+    await this.invokeAuthed('delete_user', { id });
+  }
+
   private async buildCompleteAccountPayload(id: number, account: Partial<AccountInput>): Promise<Record<string, unknown>> {
+    // 详情为脱敏数据：未修改的 password/secret 以占位符回传，后端保留原值
     const raw = await this.invokeAuthed<any>('get_account_by_id', { id });
     const currentAccount = snakeToCamel<Account>(raw);
 
@@ -180,13 +294,40 @@ export class TauriAdapter implements ApiAdapter {
     };
   }
 
-  async getAccounts(search?: string, soldStatus?: string): Promise<Account[]> {
-    const accounts = await this.invokeAuthed<any[]>('get_accounts', {
-      search: search || null,
-      soldStatus: soldStatus || null,
+  async getAccounts(query: AccountListQuery = {}): Promise<AccountPage> {
+    // 列表为脱敏数据，明文通过 revealAccountSecret 按需获取
+    const result = await this.invokeAuthed<any>('get_accounts', {
+      search: query.search || null,
+      soldStatus: query.soldStatus || null,
+      group: query.group || null,
+      pageQuery: {
+        page: query.page ?? null,
+        pageSize: query.pageSize ?? null,
+        sortField: query.sortField || null,
+        sortDirection: query.sortDirection || null,
+      },
     });
-    const accountList = Array.isArray(accounts) ? accounts : [];
-    return accountList.map(item => snakeToCamel<Account>(item));
+    const items = Array.isArray(result?.items) ? result.items : [];
+    return {
+      items: items.map((item: any) => snakeToCamel<Account>(item)),
+      total: Number(result?.total || 0),
+      page: Number(result?.page || query.page || 1),
+      pageSize: Number(result?.page_size || query.pageSize || 0),
+    };
+  }
+
+  async getAccountGroups(): Promise<string[]> {
+    const result = await this.invokeAuthed<string[]>('get_account_groups');
+    return Array.isArray(result) ? result : [];
+  }
+
+  async getAccountIds(filter: AccountFilter = {}): Promise<number[]> {
+    const result = await this.invokeAuthed<number[]>('get_account_ids', {
+      search: filter.search || null,
+      soldStatus: filter.soldStatus || null,
+      group: filter.group || null,
+    });
+    return Array.isArray(result) ? result.map(Number) : [];
   }
 
   async createAccount(account: AccountInput): Promise<Account> {
@@ -214,8 +355,22 @@ export class TauriAdapter implements ApiAdapter {
     return snakeToCamel<Account>(result);
   }
 
-  async batchImport(accounts: AccountInput[]): Promise<BatchImportResult> {
-    const result = await this.invokeAuthed<any>('batch_import', { accounts });
+  async parseImportText(text: string): Promise<ImportParseResult> {
+    const result = await this.invokeAuthed<any>('parse_import_text', { text });
+    const data = snakeToCamel<ImportParseResult>((result || {}) as Record<string, unknown>);
+    return {
+      accounts: Array.isArray(data.accounts) ? data.accounts : [],
+      warnings: Array.isArray(data.warnings) ? data.warnings : [],
+      detectedFormats: Array.isArray(data.detectedFormats) ? data.detectedFormats : [],
+    };
+  }
+
+  async batchImport(accounts: AccountInput[], options?: ImportOptions): Promise<BatchImportResult> {
+    const result = await this.invokeAuthed<any>('batch_import', { accounts, options });
+    return this.normalizeBatchImportResult(result);
+  }
+
+  private normalizeBatchImportResult(result: unknown): BatchImportResult {
     const data = snakeToCamel<Record<string, unknown>>((result || {}) as Record<string, unknown>);
 
     const successCount = Number(data.successCount ?? 0);
@@ -224,15 +379,64 @@ export class TauriAdapter implements ApiAdapter {
     return {
       successCount: Number.isFinite(successCount) ? successCount : 0,
       failCount: Number.isFinite(failCount) ? failCount : 0,
+      skippedCount: Number(data.skippedCount ?? 0),
+      dryRun: Boolean(data.dryRun),
+      rows: Array.isArray(data.rows) ? (data.rows as ImportRowResult[]) : [],
+    };
+  }
+
+  async batchImportCsv(
+    content: string,
+    config?: CsvImportConfig,
+    options?: ImportOptions,
+  ): Promise<BatchImportResult> {
+    const result = await this.invokeAuthed<any>('batch_import_csv', { content, config, options });
+    return this.normalizeBatchImportResult(result);
+  }
+
+  async batchImportJson(content: string, options?: ImportOptions): Promise<BatchImportResult> {
+    const result = await this.invokeAuthed<any>('batch_import_json', { content, options });
+    return this.normalizeBatchImportResult(result);
+  }
+
+  async importPasswordManager(
+    source: PasswordManagerSource,
+    content: string,
+    options?: ImportOptions,
+  ): Promise<PasswordImportResult> {
+    const result = await this.invokeAuthed<any>('import_password_manager', { source, content, options });
+    const data = snakeToCamel<Record<string, unknown>>((result || {}) as Record<string, unknown>);
+    return {
+      ...this.normalizeBatchImportResult(result),
+      source,
+      totalEntries: Number(data.totalEntries ?? 0),
+      ignoredCount: Number(data.ignoredCount ?? 0),
     };
   }
 
-  async generateTotp(secret: string): Promise<TotpResult> {
-    const result = await this.invokeAuthed<any>('generate_totp', { secret });
+  async generateTotp(secret: string, params?: TotpParams): Promise<TotpResult> {
+    const result = await this.invokeAuthed<any>('generate_totp', { secret, params: params ?? null });
+    return this.normalizeTotpResult(result);
+  }
+
+  async generateTotpForAccount(accountId: number): Promise<TotpResult> {
+    const result = await this.invokeAuthed<any>('generate_totp_for_account', { id: accountId });
+    return this.normalizeTotpResult(result);
+  }
+
+  async generateTotpForAccounts(accountIds: number[]): Promise<AccountTotpResult[]> {
+    const list = await this.invokeAuthed<any[]>('generate_totp_for_accounts', { ids: accountIds });
+    const results = Array.isArray(list) ? list : [];
+    return results.map(item => snakeToCamel<AccountTotpResult>(item));
+  }
+
+  private normalizeTotpResult(result: any): TotpResult {
     const data = snakeToCamel<Record<string, unknown>>((result || {}) as Record<string, unknown>);
     return {
       code: String(data.code ?? ''),
       remaining: Number(data.remaining ?? 0),
+      previousCode: data.previousCode ? String(data.previousCode) : undefined,
+      nextCode: data.nextCode ? String(data.nextCode) : undefined,
     };
   }
 
@@ -242,6 +446,17 @@ export class TauriAdapter implements ApiAdapter {
     return historyList.map(item => snakeToCamel(item));
   }
 
+  async getSecretReveals(accountId: number): Promise<SecretReveal[]> {
+    const reveals = await this.invokeAuthed<any[]>('get_secret_reveals', { accountId });
+    const revealList = Array.isArray(reveals) ? reveals : [];
+    return revealList.map(item => snakeToCamel<SecretReveal>(item));
+  }
+
+  async revealAccountSecret(accountId: number, field: SecretField): Promise<string | null> {
+    const value = await this.invokeAuthed<string | null>('reveal_account_secret', { id: accountId, field });
+    return value ?? null;
+  }
+
   async exportDatabaseSql(): Promise<string> {
     return await this.invokeAuthed<string>('export_database_sql');
   }
@@ -250,24 +465,73 @@ export class TauriAdapter implements ApiAdapter {
     accountIds: number[] | null,
     search: string | null,
     soldStatus: string | null,
-    config: ExportConfig,
+    config: ExportConfig | string,
   ): Promise<string> {
     return await this.invokeAuthed<string>('export_accounts_text', {
       accountIds: accountIds || null,
       search: search || null,
       soldStatus: soldStatus || null,
-      config,
+      ...(typeof config === 'string' ? { preset: config } : { config }),
     });
   }
 
+  async exportAccountsToFile(
+    accountIds: number[] | null,
+    search: string | null,
+    soldStatus: string | null,
+    config: ExportConfig | string,
+    path: string,
+  ): Promise<ExportFileSummary> {
+    const result = await this.invokeAuthed<any>('export_accounts_to_file', {
+      accountIds: accountIds || null,
+      search: search || null,
+      soldStatus: soldStatus || null,
+      ...(typeof config === 'string' ? { preset: config } : { config }),
+      path,
+    });
+    return snakeToCamel<ExportFileSummary>(result);
+  }
+
+  async exportDatabaseSqlToFile(path: string): Promise<ExportFileSummary> {
+    const result = await this.invokeAuthed<any>('export_database_sql_to_file', { path });
+    return snakeToCamel<ExportFileSummary>(result);
+  }
+
+  async listExportPresets(): Promise<ExportPreset[]> {
+    const list = await this.invokeAuthed<any[]>('list_export_presets');
+    const presets = Array.isArray(list) ? list : [];
+    return presets.map(item => snakeToCamel<ExportPreset>(item));
+  }
+
+  async createExportPreset(name: string, config: ExportConfig): Promise<ExportPreset> {
+    const result = await this.invokeAuthed<any>('create_export_preset', { name, config });
+    return snakeToCamel<ExportPreset>(result);
+  }
+
+  async updateExportPreset(id: number, name: string, config: ExportConfig): Promise<ExportPreset> {
+    const result = await this.invokeAuthed<any>('update_export_preset', { id, name, config });
+    return snakeToCamel<ExportPreset>(result);
+  }
+
+  async deleteExportPreset(id: number): Promise<void> {
+    await this.invokeAuthed('delete_export_preset', { id });
+  }
+
   async deleteAllAccounts(): Promise<number> {
     return await this.invokeAuthed<number>('delete_all_accounts');
   }
 
-  async getDeletedAccounts(): Promise<Account[]> {
-    const accounts = await this.invokeAuthed<any[]>('get_deleted_accounts');
-    const accountList = Array.isArray(accounts) ? accounts : [];
-    return accountList.map(item => snakeToCamel<Account>(item));
+  async getDeletedAccounts(page = 1, pageSize = 20): Promise<AccountPage> {
+    const result = await this.invokeAuthed<any>('get_deleted_accounts', {
+      pageQuery: { page, pageSize },
+    });
+    const items = Array.isArray(result?.items) ? result.items : [];
+    return {
+      items: items.map((item: any) => snakeToCamel<Account>(item)),
+      total: Number(result?.total || 0),
+      page: Number(result?.page || page),
+      pageSize: Number(result?.page_size || pageSize),
+    };
   }
 
   async restoreAccount(id: number): Promise<Account> {
@@ -58,25 +58,50 @@ const api = {
         }
     },
 
-    // 获取所有账号
-    async getAccounts(search = '', soldStatus = null) {
+    // 分页获取账号列表，返回 { items, total, page, pageSize }
+    async getAccounts(query = {}) {
         try {
-            const accounts = await adapter.getAccounts(search || undefined, soldStatus || undefined);
-            return accounts;
+            return await adapter.getAccounts(query);
         } catch (error) {
             console.error('Failed to get accounts:', error);
             throw error;
         }
     },
 
-    // 获取回收站账号
-    async getDeletedAccounts() {
+    // 获取全部标签（用于标签筛选）
+    async getAccountGroups() {
         try {
-            const accounts = await adapter.getDeletedAccounts();
-            return { success: true, data: accounts };
+            const groups = await adapter.getAccountGroups();
+            return { success: true, data: groups };
+        } catch (error) {
+            return { success: false, message: getErrorMessage(error), data: [] };
+        }
+    },
+
+    // 获取符合筛选条件的全部账号 ID（跨页全选、按筛选结果导出）
+    async getAccountIds(filter = {}) {
+        try {
+            const ids = await adapter.getAccountIds(filter);
+            return { success: true, data: ids };
+        } catch (error) {
+            return { success: false, message: getErrorMessage(error), data: [] };
+        }
+    },
+
+    // 分页获取回收站账号
+    async getDeletedAccounts(page = 1, pageSize = 20) {
+        try {
+            const result = await adapter.getDeletedAccounts(page, pageSize);
+            return {
+                success: true,
+                data: result.items,
+                total: result.total,
+                page: result.page,
+                pageSize: result.pageSize
+            };
         } catch (error) {
             console.error('Failed to get deleted accounts:', error);
-            return { success: false, data: [], message: getErrorMessage(error) };
+            return { success: false, data: [], total: 0, message: getErrorMessage(error) };
         }
     },
 
@@ -186,19 +211,8 @@ const api = {
     // 获取 2FA 验证码
     async get2FACode(id) {
         try {
-            // 首先获取账号信息以获得 secret
-            const accounts = await adapter.getAccounts();
-            const account = accounts.find(acc => acc.id === id);
-
-            if (!account || !account.secret) {
-                return {
-                    success: false,
-                    message: 'Account not found or secret not configured'
-                };
-            }
-
-            // 使用 secret 生成 TOTP
-            const result = await adapter.generateTotp(account.secret);
+            // 由后端按账号存储的密钥与 TOTP 参数（算法/位数/周期）生成
+            const result = await adapter.generateTotpForAccount(id);
 
             return {
                 success: true,
@@ -211,6 +225,16 @@ const api = {
         }
     },
 
+    // 批量获取账号 2FA 验证码（后端按已存储的密钥生成，前端不接触密钥明文）
+    async generateTotpForAccounts(ids) {
+        try {
+            const results = await adapter.generateTotpForAccounts(ids);
+            return { success: true, data: results };
+        } catch (error) {
+            return { success: false, message: getErrorMessage(error), data: [] };
+        }
+    },
+
     // 获取账号修改历史记录
     async getAccountHistory(id) {
         try {
@@ -222,6 +246,26 @@ const api = {
         }
     },
 
+    // 获取敏感字段查看记录（仅管理员可用）
+    async getSecretReveals(id) {
+        try {
+            const reveals = await adapter.getSecretReveals(id);
+            return { success: true, data: reveals };
+        } catch (error) {
+            return { success: false, message: getErrorMessage(error), data: [] };
+        }
+    },
+
+    // 按需查看单个敏感字段（password / secret），后端会记录查看
+    async revealAccountSecret(id, field) {
+        try {
+            const value = await adapter.revealAccountSecret(id, field);
+            return { success: true, data: value };
+        } catch (error) {
+            return { success: false, message: getErrorMessage(error), data: null };
+        }
+    },
+
     // 导出数据库为 SQL
     async exportDatabaseSql() {
         try {
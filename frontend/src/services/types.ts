@@ -1,5 +1,11 @@
 // frontend/src/services/types.ts
 
+export interface TotpParams {
+  algorithm: 'SHA1' | 'SHA256' | 'SHA512';
+  digits: number;
+  period: number;
+}
+
 export interface Account {
   id: number;
   email: string;
@@ -16,8 +22,44 @@ export interface Account {
   createdAt: string;
   updatedAt: string;
   deletedAt?: string | null;
+  totp?: TotpParams;
 }
 
+export interface AccountPage {
+  items: Account[];
+  total: number;
+  page: number;
+  pageSize: number;
+}
+
+/** 账号列表筛选条件（搜索 + 出售状态 + 标签） */
+export interface AccountFilter {
+  search?: string;
+  soldStatus?: string;
+  group?: string;
+}
+
+/** 账号列表分页查询（服务端分页与排序） */
+export interface AccountListQuery extends AccountFilter {
+  page?: number;
+  pageSize?: number;
+  sortField?: string;
+  sortDirection?: 'asc' | 'desc';
+}
+
+/** 可按需查看的敏感字段 */
+export type SecretField = 'password' | 'secret';
+
+export interface SecretReveal {
+  id: number;
+  accountId: number;
+  fieldName: string;
+  channel: string;
+  actorUserId?: number | null;
+  actorUsername?: string | null;
+  revealedAt: string;
+}
+
 export interface AccountInput {
   email: string;
   password: string;
@@ -28,6 +70,39 @@ export interface AccountInput {
   country?: string;
   groupName?: string;
   remark?: string;
+  totp?: TotpParams;
+}
+
+export type UserRole = 'viewer' | 'operator' | 'admin';
+
+export interface SessionUser {
+  id: number;
+  username: string;
+  role: UserRole;
+}
+
+export interface User extends SessionUser {
+  disabled: boolean;
+  createdAt: string;
+  updatedAt: string;
+}
+
+export interface UserUpdate {
+  role?: UserRole;
+  disabled?: boolean;
+  password?: string;
+}
+
+export interface SessionInfo {
+  id: string;
+  userId: number;
+  username: string;
+  deviceLabel?: string;
+  ip?: string;
+  createdAtEpochSecs: number;
+  expiresAtEpochSecs: number;
+  lastSeenEpochSecs: number;
+  current: boolean;
 }
 
 export interface LoginResult {
@@ -36,6 +111,9 @@ export interface LoginResult {
   banned?: boolean;
   sessionToken?: string;
   expiresAtEpochSecs?: number;
+  setupRequired?: boolean;
+  user?: SessionUser;
+  locked?: boolean;
 }
 
 export interface CheckAuthResult {
@@ -44,16 +122,97 @@ export interface CheckAuthResult {
   message?: string;
   sessionToken?: string;
   expiresAtEpochSecs?: number;
+  setupRequired?: boolean;
+  user?: SessionUser;
+  locked?: boolean;
+}
+
+export type ImportDuplicateMode = 'skip' | 'overwrite' | 'merge';
+
+export type ImportRecycledMode = 'insert' | 'restore' | 'skip';
+
+export interface ImportOptions {
+  duplicateMode?: ImportDuplicateMode;
+  recycledMode?: ImportRecycledMode;
+  dryRun?: boolean;
+}
+
+export type ImportRowStatus =
+  | 'inserted'
+  | 'updated'
+  | 'restored'
+  | 'skipped_duplicate'
+  | 'skipped_deleted'
+  | 'failed';
+
+export interface ImportRowResult {
+  index: number;
+  email: string;
+  status: ImportRowStatus;
+  accountId: number | null;
+  conflict: 'active' | 'recycled' | null;
+  failure: 'invalid_secret' | 'invalid_phone' | 'other' | null;
+  reason: string | null;
+}
+
+export interface CsvColumnMapping {
+  /** 表头名或从 0 开始的列序号 */
+  column: string;
+  /** 目标字段；为空表示忽略该列 */
+  field: ExportDataField | '';
+}
+
+export interface CsvImportConfig {
+  delimiter?: string;
+  hasHeader?: boolean;
+  /** 为空时按表头名自动匹配 */
+  mapping?: CsvColumnMapping[];
 }
 
 export interface BatchImportResult {
   successCount: number;
   failCount: number;
+  skippedCount?: number;
+  dryRun?: boolean;
+  rows?: ImportRowResult[];
+}
+
+export interface ImportLineWarning {
+  line: number;
+  content: string;
+  message: string;
+}
+
+export interface ImportParseResult {
+  accounts: AccountInput[];
+  warnings: ImportLineWarning[];
+  detectedFormats: string[];
+}
+
+export type PasswordManagerSource = 'bitwarden' | 'keepass' | 'chrome';
+
+export interface PasswordImportResult extends BatchImportResult {
+  source: PasswordManagerSource;
+  totalEntries: number;
+  /** 非 Google 域名、被忽略的登录项数 */
+  ignoredCount: number;
 }
 
 export interface TotpResult {
   code: string;
   remaining: number;
+  previousCode?: string;
+  nextCode?: string;
+}
+
+/** 批量生成结果：单个账号失败时 `error` 有值，不影响其他账号 */
+export interface AccountTotpResult {
+  accountId: number;
+  code?: string | null;
+  remaining?: number | null;
+  previousCode?: string | null;
+  nextCode?: string | null;
+  error?: string | null;
 }
 
 export interface BackupInfo {
@@ -80,7 +239,8 @@ export type ExportDataField =
   | 'group_name'
   | 'remark'
   | 'status'
-  | 'sold_status';
+  | 'sold_status'
+  | 'otpauth_uri';
 
 export type ExportSortDirection = 'asc' | 'desc';
 
@@ -115,6 +275,9 @@ export interface ExportCategorySortConfig {
   direction: ExportSortDirection;
 }
 
+/** xlsx 时 exportAccountsText 返回 base64 编码的工作簿 */
+export type ExportFormat = 'text' | 'csv' | 'json' | 'jsonl' | 'xlsx';
+
 export interface ExportConfig {
   separator: string;
   fields: ExportDataField[];
@@ -122,14 +285,46 @@ export interface ExportConfig {
   accountOrder: ExportAccountOrderConfig;
   categorySort: ExportCategorySortConfig;
   categoryLabelTemplate: string;
+  /** 行模板，例如 `{email}|{password}{?secret}|2FA: {secret}{/secret}`；设置后取代 fields + separator */
+  lineTemplate?: string;
+  format?: ExportFormat;
+}
+
+/** 导出到文件的结果摘要 */
+export interface ExportFileSummary {
+  path: string;
+  rows: number;
+  bytes: number;
+  sha256: string;
+}
+
+export interface ExportPreset {
+  id: number;
+  name: string;
+  config: ExportConfig;
+  createdAt: string;
+  updatedAt: string;
 }
 
 export interface ApiAdapter {
-  login(password: string): Promise<LoginResult>;
+  login(password: string, username?: string, deviceLabel?: string): Promise<LoginResult>;
   checkAuth(): Promise<CheckAuthResult>;
   logout(): Promise<void>;
-  getAccounts(search?: string, soldStatus?: string): Promise<Account[]>;
-  getDeletedAccounts(): Promise<Account[]>;
+  setupAdminPassword(password: string, username?: string): Promise<LoginResult>;
+  changeAdminPassword(currentPassword: string, newPassword: string): Promise<void>;
+  lock(): Promise<void>;
+  unlock(password: string): Promise<CheckAuthResult>;
+  listSessions(): Promise<SessionInfo[]>;
+  revokeSession(sessionId: string): Promise<void>;
+  revokeAllSessions(includeCurrent?: boolean): Promise<number>;
+  listUsers(): Promise<User[]>;
+  createUser(username: string, password: string, role: UserRole): Promise<User>;
+  updateUser(id: number, update: UserUpdate): Promise<User>;
+  deleteUser(id: number): Promise<void>;
+  getAccounts(query?: AccountListQuery): Promise<AccountPage>;
+  getAccountGroups(): Promise<string[]>;
+  getAccountIds(filter?: AccountFilter): Promise<number[]>;
+  getDeletedAccounts(page?: number, pageSize?: number): Promise<AccountPage>;
   createAccount(account: AccountInput): Promise<Account>;
   updateAccount(id: number, account: Partial<AccountInput>): Promise<Account>;
   deleteAccount(id: number): Promise<void>;
@@ -139,9 +334,28 @@ export interface ApiAdapter {
   deleteAllAccounts(): Promise<number>;
   toggleStatus(id: number): Promise<Account>;
   toggleSoldStatus(id: number): Promise<Account>;
-  batchImport(accounts: AccountInput[]): Promise<BatchImportResult>;
-  generateTotp(secret: string): Promise<TotpResult>;
+  parseImportText(text: string): Promise<ImportParseResult>;
+  batchImport(accounts: AccountInput[], options?: ImportOptions): Promise<BatchImportResult>;
+  batchImportCsv(
+    content: string,
+    config?: CsvImportConfig,
+    options?: ImportOptions,
+  ): Promise<BatchImportResult>;
+  batchImportJson(content: string, options?: ImportOptions): Promise<BatchImportResult>;
+  importPasswordManager(
+    source: PasswordManagerSource,
+    content: string,
+    options?: ImportOptions,
+  ): Promise<PasswordImportResult>;
+  generateTotp(secret: string, params?: TotpParams): Promise<TotpResult>;
+  /** 由后端按账号已存储的密钥与参数生成验证码 */
+  generateTotpForAccount(accountId: number): Promise<TotpResult>;
+  generateTotpForAccounts(accountIds: number[]): Promise<AccountTotpResult[]>;
   getAccountHistory(accountId: number): Promise<any[]>;
+  /** 敏感字段查看记录（仅管理员） */
+  getSecretReveals(accountId: number): Promise<SecretReveal[]>;
+  /** 按需解密单个敏感字段（password / secret），后端记录查看 */
+  revealAccountSecret(accountId: number, field: SecretField): Promise<string | null>;
   createBackup(reason?: string): Promise<string>;
   listBackups(): Promise<BackupInfo[]>;
   restoreBackup(backupName: string): Promise<void>;
@@ -150,6 +364,20 @@ export interface ApiAdapter {
     accountIds: number[] | null,
     search: string | null,
     soldStatus: string | null,
-    config: ExportConfig,
+    /** 导出配置，或已保存的预设名称 */
+    config: ExportConfig | string,
   ): Promise<string>;
+  /** 直接写入 path（数据目录或允许的导出目录内），仅桌面模式可用 */
+  exportAccountsToFile(
+    accountIds: number[] | null,
+    search: string | null,
+    soldStatus: string | null,
+    config: ExportConfig | string,
+    path: string,
+  ): Promise<ExportFileSummary>;
+  exportDatabaseSqlToFile(path: string): Promise<ExportFileSummary>;
+  listExportPresets(): Promise<ExportPreset[]>;
+  createExportPreset(name: string, config: ExportConfig): Promise<ExportPreset>;
+  updateExportPreset(id: number, name: string, config: ExportConfig): Promise<ExportPreset>;
+  deleteExportPreset(id: number): Promise<void>;
 }
@@ -1,17 +1,172 @@
+use crate::database::{self, AuditActor, StoredSession, User, UserRole};
+use crate::key_manager::{self, LockReason};
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
 use rand::{distributions::Alphanumeric, Rng};
-use std::sync::{Mutex, OnceLock};
-use subtle::ConstantTimeEq;
+use rusqlite::Connection;
+use sha2::{Digest, Sha256};
+use std::sync::OnceLock;
 
-const MAX_FAILED_ATTEMPTS: u8 = 3;
-const BAN_DURATION_SECS: i64 = 24 * 60 * 60;
-const SESSION_TTL_SECS: i64 = 7 * 24 * 60 * 60;
+const MIN_PASSWORD_CHARS: usize = 8;
+/// 管理员密码的初始化 / 覆盖来源；日常登录校验数据库中的 Argon2id 哈希
+pub const ADMIN_PASSWORD_ENV: &str = "GOOGLE_MANAGER_ADMIN_PASSWORD";
+/// 环境变量管理的管理员用户名（默认 `admin`）
+pub const ADMIN_USERNAME_ENV: &str = "GOOGLE_MANAGER_ADMIN_USERNAME";
+const DEFAULT_ADMIN_USERNAME: &str = "admin";
+/// 连续登录失败多少次后封禁
+pub const MAX_FAILED_ATTEMPTS_ENV: &str = "GOOGLE_MANAGER_MAX_FAILED_ATTEMPTS";
+/// 封禁时长（秒）
+pub const BAN_DURATION_ENV: &str = "GOOGLE_MANAGER_BAN_DURATION_SECS";
+/// 会话最长有效期（秒），无论是否活跃
+pub const SESSION_TTL_ENV: &str = "GOOGLE_MANAGER_SESSION_TTL_SECS";
+/// 空闲超时（秒）：超过该时间没有请求的会话失效，每次请求顺延
+pub const IDLE_TIMEOUT_ENV: &str = "GOOGLE_MANAGER_IDLE_TIMEOUT_SECS";
+/// 设备标签上限（字符数），超出部分截断
+const DEVICE_LABEL_MAX_CHARS: usize = 64;
 
-#[derive(Default)]
-struct AuthState {
-    failed_attempts: u8,
-    banned_until_epoch_secs: Option<i64>,
-    session_token: Option<String>,
-    session_expires_epoch_secs: Option<i64>,
+/// 登录锁定阈值、会话时长与环境变量管理的管理员
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthPolicy {
+    pub max_failed_attempts: u32,
+    pub ban_duration_secs: i64,
+    pub session_ttl_secs: i64,
+    pub idle_timeout_secs: i64,
+    /// 密码由 `GOOGLE_MANAGER_ADMIN_PASSWORD` 管理的用户名（未设置该变量时为空）
+    pub env_admin_username: Option<String>,
+}
+
+impl Default for AuthPolicy {
+    fn default() -> Self {
+        Self {
+            max_failed_attempts: 3,
+            ban_duration_secs: 24 * 60 * 60,
+            session_ttl_secs: 7 * 24 * 60 * 60,
+            idle_timeout_secs: 30 * 60,
+            env_admin_username: None,
+        }
+    }
+}
+
+impl AuthPolicy {
+    /// 读取环境变量覆盖默认值；未设置或不是正整数时使用默认值
+    pub fn from_env() -> Self {
+        Self::from_lookup(|name| std::env::var(name).ok())
+    }
+
+    fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Self {
+        let defaults = Self::default();
+        Self {
+            max_failed_attempts: lookup_positive(&lookup, MAX_FAILED_ATTEMPTS_ENV)
+                .unwrap_or(defaults.max_failed_attempts),
+            ban_duration_secs: lookup_positive(&lookup, BAN_DURATION_ENV)
+                .unwrap_or(defaults.ban_duration_secs),
+            session_ttl_secs: lookup_positive(&lookup, SESSION_TTL_ENV)
+                .unwrap_or(defaults.session_ttl_secs),
+            idle_timeout_secs: lookup_positive(&lookup, IDLE_TIMEOUT_ENV)
+                .unwrap_or(defaults.idle_timeout_secs),
+            env_admin_username: non_empty(lookup(ADMIN_PASSWORD_ENV))
+                .map(|_| admin_username(lookup(ADMIN_USERNAME_ENV))),
+        }
+    }
+
+    /// 该用户的密码由环境变量管理（每次启动都会被覆盖），不允许在界面中修改
+    fn is_env_managed(&self, username: &str) -> bool {
+        self.env_admin_username
+            .as_deref()
+            .is_some_and(|managed| managed.eq_ignore_ascii_case(username))
+    }
+
+    /// 最近活动早于（含）该时间的会话视为空闲超时
+    fn idle_cutoff(&self, now: i64) -> i64 {
+        now - self.idle_timeout_secs
+    }
+
+    /// 会话在当前时间点的实际失效时间（空闲超时与最长有效期取较早者）
+    fn session_expiry(&self, last_seen_at: i64, expires_at: i64) -> i64 {
+        (last_seen_at + self.idle_timeout_secs).min(expires_at)
+    }
+
+    fn ban_message(&self) -> String {
+        format!(
+            "密码错误次数过多，已封禁 {}",
+            describe_duration(self.ban_duration_secs)
+        )
+    }
+}
+
+fn lookup_positive<T: std::str::FromStr + PartialOrd + Default>(
+    lookup: &impl Fn(&str) -> Option<String>,
+    name: &str,
+) -> Option<T> {
+    let value = lookup(name)?;
+    match value.trim().parse::<T>() {
+        Ok(parsed) if parsed > T::default() => Some(parsed),
+        _ => {
+            log::warn!("环境变量 {} 必须是正整数，已使用默认值: {}", name, value);
+            None
+        }
+    }
+}
+
+fn describe_duration(secs: i64) -> String {
+    if secs % 3600 == 0 {
+        format!("{} 小时", secs / 3600)
+    } else if secs % 60 == 0 {
+        format!("{} 分钟", secs / 60)
+    } else {
+        format!("{} 秒", secs)
+    }
+}
+
+/// 登录时记录的客户端信息
+#[derive(Debug, Clone, Default)]
+pub struct SessionClient {
+    pub device_label: Option<String>,
+    /// 仅 HTTP 模式记录对端 IP
+    pub ip: Option<String>,
+}
+
+/// 活动会话信息（`expires_at_epoch_secs` 为按最近活动计算的实际失效时间）
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct SessionInfo {
+    pub id: String,
+    pub user_id: i64,
+    pub username: String,
+    pub device_label: Option<String>,
+    pub ip: Option<String>,
+    pub created_at_epoch_secs: i64,
+    pub expires_at_epoch_secs: i64,
+    pub last_seen_epoch_secs: i64,
+    /// 是否为发起请求的会话
+    pub current: bool,
+}
+
+/// 当前会话对应的用户
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SessionUser {
+    pub id: i64,
+    pub username: String,
+    pub role: UserRole,
+}
+
+impl From<&User> for SessionUser {
+    fn from(user: &User) -> Self {
+        Self {
+            id: user.id,
+            username: user.username.clone(),
+            role: user.role,
+        }
+    }
+}
+
+impl SessionUser {
+    /// 写入审计记录时的操作人
+    pub fn audit_actor(&self) -> AuditActor<'_> {
+        AuditActor {
+            user_id: self.id,
+            username: &self.username,
+        }
+    }
 }
 
 #[derive(serde::Serialize, serde::Deserialize, Clone)]
@@ -21,202 +176,634 @@ pub struct AuthResult {
     pub message: String,
     pub session_token: Option<String>,
     pub expires_at_epoch_secs: Option<i64>,
+    /// 尚未创建任何用户，需要先走初始化设置流程
+    #[serde(default)]
+    pub setup_required: bool,
+    #[serde(default)]
+    pub user: Option<SessionUser>,
+    /// 会话有效但保险库已锁定，需要输入密码解锁
+    #[serde(default)]
+    pub locked: bool,
 }
 
-fn state() -> &'static Mutex<AuthState> {
-    static STATE: OnceLock<Mutex<AuthState>> = OnceLock::new();
-    STATE.get_or_init(|| Mutex::new(AuthState::default()))
+impl AuthResult {
+    fn denied(message: impl Into<String>, banned: bool) -> Self {
+        Self {
+            success: false,
+            banned,
+            message: message.into(),
+            session_token: None,
+            expires_at_epoch_secs: None,
+            setup_required: false,
+            user: None,
+            locked: false,
+        }
+    }
+
+    fn setup_required() -> Self {
+        Self {
+            setup_required: true,
+            ..Self::denied("尚未创建管理员账号，请先完成初始化设置", false)
+        }
+    }
 }
 
 fn now_epoch_secs() -> i64 {
     chrono::Utc::now().timestamp()
 }
 
-fn admin_password() -> Result<String, String> {
-    let value = std::env::var("GOOGLE_MANAGER_ADMIN_PASSWORD")
-        .map_err(|_| "系统未配置 GOOGLE_MANAGER_ADMIN_PASSWORD，禁止登录".to_string())?;
-    let trimmed = value.trim();
-    if trimmed.is_empty() {
-        return Err("GOOGLE_MANAGER_ADMIN_PASSWORD 不能为空，禁止登录".to_string());
+fn non_empty(value: Option<String>) -> Option<String> {
+    value
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+fn admin_username(value: Option<String>) -> String {
+    non_empty(value).unwrap_or_else(|| DEFAULT_ADMIN_USERNAME.to_string())
+}
+
+fn hash_password(password: &str) -> Result<String, String> {
+    let salt = SaltString::generate(&mut rand::thread_rng());
+    Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| format!("生成密码哈希失败: {}", e))
+}
+
+fn verify_password(password: &str, password_hash: &str) -> Result<bool, String> {
+    let parsed = PasswordHash::new(password_hash).map_err(|e| format!("密码哈希无效: {}", e))?;
+    Ok(Argon2::default()
+        .verify_password(password.as_bytes(), &parsed)
+        .is_ok())
+}
+
+/// 用户名不存在时用于校验的占位哈希，使登录耗时与用户存在时一致，避免据此枚举用户名
+fn dummy_password_hash() -> &'static str {
+    static HASH: OnceLock<String> = OnceLock::new();
+    HASH.get_or_init(|| hash_password(&random_token(32)).unwrap_or_default())
+}
+
+fn validate_new_password(password: &str) -> Result<(), String> {
+    if password.trim().is_empty() {
+        return Err("密码不能为空".to_string());
+    }
+    if password.chars().count() < MIN_PASSWORD_CHARS {
+        return Err(format!("密码至少需要 {} 个字符", MIN_PASSWORD_CHARS));
+    }
+    Ok(())
+}
+
+/// 启动时同步环境变量中的管理员密码：对应用户不存在时创建管理员，
+/// 已存在但密码不一致时以环境变量为准，并恢复为启用的管理员（用于忘记密码后的重置）
+pub fn bootstrap_admin_credential(conn: &Connection) -> Result<(), String> {
+    let Some(password) = non_empty(std::env::var(ADMIN_PASSWORD_ENV).ok()) else {
+        return Ok(());
+    };
+    let username = admin_username(std::env::var(ADMIN_USERNAME_ENV).ok());
+    sync_admin_credential(conn, &username, &password)
+}
+
+fn sync_admin_credential(conn: &Connection, username: &str, password: &str) -> Result<(), String> {
+    let Some((user, stored)) = database::find_user_credential(conn, username)? else {
+        database::create_user(conn, username, &hash_password(password)?, UserRole::Admin)?;
+        return Ok(());
+    };
+    if !verify_password(password, &stored).unwrap_or(false) {
+        log::warn!(
+            "用户 {} 的密码已被 {} 覆盖",
+            user.username,
+            ADMIN_PASSWORD_ENV
+        );
+        database::set_user_password_hash(conn, user.id, &hash_password(password)?)?;
+    }
+    if user.role != UserRole::Admin || user.disabled {
+        database::update_user(conn, user.id, Some(UserRole::Admin), Some(false))?;
     }
-    Ok(trimmed.to_string())
+    Ok(())
 }
 
-fn new_session_token() -> String {
+fn random_token(len: usize) -> String {
     rand::thread_rng()
         .sample_iter(&Alphanumeric)
-        .take(64)
+        .take(len)
         .map(char::from)
         .collect()
 }
 
-fn clear_session(auth: &mut AuthState) {
-    auth.session_token = None;
-    auth.session_expires_epoch_secs = None;
+/// 数据库中只保存会话令牌的 SHA-256（令牌本身是 64 位随机串，无需加盐）
+fn hash_token(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.trim().as_bytes()))
 }
 
-fn sync_expired_state(auth: &mut AuthState, now: i64) {
-    if let Some(until) = auth.banned_until_epoch_secs {
-        if until <= now {
-            auth.banned_until_epoch_secs = None;
-        }
+fn provided_token(session_token: Option<&str>) -> Option<&str> {
+    session_token.map(str::trim).filter(|v| !v.is_empty())
+}
+
+fn normalize_device_label(label: Option<&str>) -> Option<String> {
+    label
+        .map(str::trim)
+        .filter(|label| !label.is_empty())
+        .map(|label| label.chars().take(DEVICE_LABEL_MAX_CHARS).collect())
+}
+
+/// 登录失败按「用户名 + 客户端 IP」计数（桌面端没有 IP，只按用户名），
+/// 不存在的用户名也单独计数，不会连累真实账号
+fn login_lockout_key(username: &str, client: &SessionClient) -> String {
+    let username = username.trim().to_lowercase();
+    match client.ip.as_deref() {
+        Some(ip) => format!("login:{}@{}", username, ip),
+        None => format!("login:{}", username),
+    }
+}
+
+/// 已登录用户的密码校验失败（解锁等）按用户计数，封禁时只注销该用户的会话
+fn user_lockout_key(user_id: i64) -> String {
+    format!("user:{}", user_id)
+}
+
+fn is_banned(conn: &Connection, key: &str, now: i64) -> Result<bool, String> {
+    let (_, banned_until) = database::get_lockout(conn, key)?;
+    Ok(banned_until.is_some_and(|until| until > now))
+}
+
+fn start_session(
+    conn: &Connection,
+    policy: &AuthPolicy,
+    now: i64,
+    user: &User,
+    client: &SessionClient,
+    message: &str,
+) -> Result<AuthResult, String> {
+    database::delete_expired_sessions(conn, now, policy.idle_cutoff(now))?;
+    // 空闲自动锁定随登录恢复；主动锁定必须由操作员及以上角色解锁，登录不会解除
+    let locked = key_manager::resume_idle_lock();
+
+    let token = random_token(64);
+    let session = StoredSession {
+        id: random_token(16),
+        token_hash: hash_token(&token),
+        user: user.clone(),
+        device_label: normalize_device_label(client.device_label.as_deref()),
+        ip: client.ip.clone(),
+        created_at: now,
+        expires_at: now + policy.session_ttl_secs,
+        last_seen_at: now,
+    };
+    database::insert_session(conn, &session)?;
+    Ok(AuthResult {
+        success: true,
+        banned: false,
+        message: if locked {
+            key_manager::VAULT_LOCKED_MESSAGE.to_string()
+        } else {
+            message.to_string()
+        },
+        session_token: Some(token),
+        expires_at_epoch_secs: Some(policy.session_expiry(now, session.expires_at)),
+        setup_required: false,
+        user: Some(SessionUser::from(user)),
+        locked,
+    })
+}
+
+pub fn check_auth(
+    conn: &Connection,
+    policy: &AuthPolicy,
+    session_token: Option<&str>,
+) -> Result<AuthResult, String> {
+    if database::count_users(conn)? == 0 {
+        return Ok(AuthResult::setup_required());
     }
-    if let Some(expires_at) = auth.session_expires_epoch_secs {
-        if expires_at <= now {
-            clear_session(auth);
+    check_session(conn, policy, session_token)
+}
+
+fn find_active_session(
+    conn: &Connection,
+    policy: &AuthPolicy,
+    now: i64,
+    session_token: Option<&str>,
+) -> Result<Result<(String, StoredSession), AuthResult>, String> {
+    let session = match provided_token(session_token) {
+        Some(token) => {
+            database::find_session(conn, &hash_token(token), now, policy.idle_cutoff(now))?
+                .map(|session| (token.to_string(), session))
         }
+        None => None,
+    };
+    Ok(session.ok_or_else(|| AuthResult::denied("未登录或会话已失效，请重新登录", false)))
+}
+
+/// 校验会话；有效且未锁定时顺延空闲超时。锁定期间不顺延，超时后需要重新登录
+fn check_session(
+    conn: &Connection,
+    policy: &AuthPolicy,
+    session_token: Option<&str>,
+) -> Result<AuthResult, String> {
+    let now = now_epoch_secs();
+    let (token, session) = match find_active_session(conn, policy, now, session_token)? {
+        Ok(found) => found,
+        Err(denied) => return Ok(denied),
+    };
+    let locked = key_manager::is_master_key_locked();
+    let last_seen_at = if locked {
+        session.last_seen_at
+    } else {
+        database::touch_session(conn, &session.token_hash, now)?;
+        now
+    };
+    Ok(AuthResult {
+        success: !locked,
+        banned: false,
+        message: if locked {
+            key_manager::VAULT_LOCKED_MESSAGE.to_string()
+        } else {
+            "已登录".to_string()
+        },
+        session_token: Some(token),
+        expires_at_epoch_secs: Some(policy.session_expiry(last_seen_at, session.expires_at)),
+        setup_required: false,
+        user: Some(SessionUser::from(&session.user)),
+        locked,
+    })
+}
+
+/// 校验会话有效，返回当前用户
+pub fn session_user(
+    conn: &Connection,
+    policy: &AuthPolicy,
+    session_token: Option<&str>,
+) -> Result<SessionUser, String> {
+    let result = check_session(conn, policy, session_token)?;
+    match result.user {
+        Some(user) if result.success => Ok(user),
+        _ => Err(result.message),
     }
 }
 
-pub fn check_auth(session_token: Option<&str>) -> Result<AuthResult, String> {
-    if let Err(message) = admin_password() {
-        return Ok(AuthResult {
-            success: false,
-            banned: false,
-            message,
-            session_token: None,
-            expires_at_epoch_secs: None,
-        });
+/// 校验用户角色满足 `required`
+pub fn ensure_role(user: &SessionUser, required: UserRole) -> Result<(), String> {
+    if user.role >= required {
+        return Ok(());
     }
+    Err(format!(
+        "权限不足：该操作需要{}权限（当前用户 {} 为{}）",
+        required.label(),
+        user.username,
+        user.role.label()
+    ))
+}
 
+/// 校验会话有效且角色满足 `required`，返回当前用户
+pub fn require_role(
+    conn: &Connection,
+    policy: &AuthPolicy,
+    session_token: Option<&str>,
+    required: UserRole,
+) -> Result<SessionUser, String> {
+    let user = session_user(conn, policy, session_token)?;
+    ensure_role(&user, required)?;
+    Ok(user)
+}
+
+pub fn login(
+    conn: &Connection,
+    policy: &AuthPolicy,
+    username: &str,
+    password: &str,
+    client: &SessionClient,
+) -> Result<AuthResult, String> {
+    if database::count_users(conn)? == 0 {
+        return Ok(AuthResult::setup_required());
+    }
     let now = now_epoch_secs();
-    let mut auth = state().lock().map_err(|e| e.to_string())?;
-    sync_expired_state(&mut auth, now);
+    let lockout_key = login_lockout_key(username, client);
+    if is_banned(conn, &lockout_key, now)? {
+        return Ok(AuthResult::denied(policy.ban_message(), true));
+    }
 
-    if let Some(until) = auth.banned_until_epoch_secs {
-        if until > now {
-            return Ok(AuthResult {
-                success: false,
-                banned: true,
-                message: "账号已被封禁，请稍后再试".to_string(),
-                session_token: None,
-                expires_at_epoch_secs: None,
-            });
+    match database::find_user_credential(conn, username)? {
+        Some((user, password_hash)) => {
+            if verify_password(password, &password_hash)? {
+                if user.disabled {
+                    return Ok(AuthResult::denied("该用户已被禁用", false));
+                }
+                database::clear_lockout(conn, &lockout_key)?;
+                return start_session(conn, policy, now, &user, client, "登录成功");
+            }
+        }
+        None => {
+            let _ = verify_password(password, dummy_password_hash());
         }
     }
 
-    let provided = session_token.map(str::trim).filter(|v| !v.is_empty());
-    let active = auth.session_token.clone();
-    let expires_at = auth.session_expires_epoch_secs;
+    record_failed_attempt(conn, policy, now, &lockout_key, "用户名或密码错误")
+}
 
-    match (provided, active, expires_at) {
-        (Some(input), Some(current), Some(expires_at)) if input == current => Ok(AuthResult {
-            success: true,
-            banned: false,
-            message: "已登录".to_string(),
-            session_token: Some(current),
-            expires_at_epoch_secs: Some(expires_at),
-        }),
-        _ => Ok(AuthResult {
-            success: false,
-            banned: false,
-            message: "未登录或会话已失效，请重新登录".to_string(),
-            session_token: None,
-            expires_at_epoch_secs: None,
-        }),
+/// 为锁定键记录一次密码错误，达到阈值时封禁该键；超过封禁时长未再出错的计数自动清零
+fn record_failed_attempt(
+    conn: &Connection,
+    policy: &AuthPolicy,
+    now: i64,
+    key: &str,
+    message: &str,
+) -> Result<AuthResult, String> {
+    database::prune_lockouts(conn, now, now - policy.ban_duration_secs)?;
+    let (failed_attempts, _) = database::get_lockout(conn, key)?;
+    let failed_attempts = failed_attempts.saturating_add(1);
+    if failed_attempts >= policy.max_failed_attempts {
+        database::set_lockout(conn, key, 0, Some(now + policy.ban_duration_secs), now)?;
+        return Ok(AuthResult::denied(policy.ban_message(), true));
+    }
+    database::set_lockout(conn, key, failed_attempts, None, now)?;
+
+    Ok(AuthResult::denied(
+        format!(
+            "{}，还可尝试 {} 次",
+            message,
+            policy.max_failed_attempts - failed_attempts
+        ),
+        false,
+    ))
+}
+
+/// 记录已登录用户的一次密码校验失败（解锁、修改密码共用计数），封禁时注销该用户的全部会话
+fn record_user_failure(
+    conn: &Connection,
+    policy: &AuthPolicy,
+    now: i64,
+    user_id: i64,
+    message: &str,
+) -> Result<AuthResult, String> {
+    let result = record_failed_attempt(conn, policy, now, &user_lockout_key(user_id), message)?;
+    if result.banned {
+        database::delete_user_sessions(conn, user_id, None)?;
     }
+    Ok(result)
 }
 
-pub fn require_auth(session_token: Option<&str>) -> Result<(), String> {
-    let result = check_auth(session_token)?;
-    if result.success {
-        return Ok(());
+/// 校验会话有效（不要求保险库已解锁），返回当前用户
+pub fn active_session_user(
+    conn: &Connection,
+    policy: &AuthPolicy,
+    session_token: Option<&str>,
+) -> Result<SessionUser, String> {
+    match find_active_session(conn, policy, now_epoch_secs(), session_token)? {
+        Ok((_, session)) => Ok(SessionUser::from(&session.user)),
+        Err(denied) => Err(denied.message),
     }
-    Err(result.message)
 }
 
-pub fn login(password: &str) -> Result<AuthResult, String> {
-    let configured_password = match admin_password() {
-        Ok(value) => value,
-        Err(message) => {
+/// 锁定保险库：清除内存中的主密钥，所有会话都需要输入密码解锁后才能继续操作。
+/// 保险库为进程全局状态，只读用户无权锁定，也无权解除主动锁定
+pub fn lock(user: &SessionUser) -> Result<(), String> {
+    ensure_role(user, UserRole::Operator)?;
+    key_manager::lock_master_key(LockReason::Explicit);
+    log::info!("保险库已被用户 {} 锁定", user.username);
+    Ok(())
+}
+
+/// 使用当前会话用户的密码解锁保险库；失败按该用户计数，达到阈值时封禁并注销该用户的全部会话。
+/// 主动锁定只能由操作员及以上角色解锁
+pub fn unlock(
+    conn: &Connection,
+    policy: &AuthPolicy,
+    session_token: Option<&str>,
+    password: &str,
+) -> Result<AuthResult, String> {
+    let now = now_epoch_secs();
+    let (_, session) = match find_active_session(conn, policy, now, session_token)? {
+        Ok(found) => found,
+        Err(denied) => return Ok(denied),
+    };
+    let lockout_key = user_lockout_key(session.user.id);
+    if is_banned(conn, &lockout_key, now)? {
+        database::delete_user_sessions(conn, session.user.id, None)?;
+        return Ok(AuthResult::denied(policy.ban_message(), true));
+    }
+    if key_manager::lock_reason() == Some(LockReason::Explicit) {
+        if let Err(e) = ensure_role(&SessionUser::from(&session.user), UserRole::Operator) {
             return Ok(AuthResult {
-                success: false,
-                banned: false,
-                message,
-                session_token: None,
-                expires_at_epoch_secs: None,
+                locked: true,
+                ..AuthResult::denied(e, false)
             });
         }
-    };
+    }
+    let (_, password_hash) = database::find_user_credential(conn, &session.user.username)?
+        .ok_or_else(|| "用户不存在".to_string())?;
+    if !verify_password(password, &password_hash)? {
+        let mut result = record_user_failure(conn, policy, now, session.user.id, "密码错误")?;
+        result.locked = !result.banned && key_manager::is_master_key_locked();
+        return Ok(result);
+    }
+    database::clear_lockout(conn, &lockout_key)?;
+    key_manager::unlock_master_key();
+    check_session(conn, policy, session_token)
+}
 
+/// 清理过期 / 空闲超时的会话；没有任何活跃会话时自动锁定保险库
+pub fn enforce_idle_lock(conn: &Connection, policy: &AuthPolicy) -> Result<(), String> {
     let now = now_epoch_secs();
-    let mut auth = state().lock().map_err(|e| e.to_string())?;
-    sync_expired_state(&mut auth, now);
+    database::delete_expired_sessions(conn, now, policy.idle_cutoff(now))?;
+    if key_manager::is_master_key_locked() {
+        return Ok(());
+    }
+    if database::list_sessions(conn, None, now, policy.idle_cutoff(now))?.is_empty() {
+        key_manager::lock_master_key(LockReason::Idle);
+        log::info!("所有会话均已空闲超时，保险库已自动锁定");
+    }
+    Ok(())
+}
 
-    if let Some(until) = auth.banned_until_epoch_secs {
-        if until > now {
-            return Ok(AuthResult {
-                success: false,
-                banned: true,
-                message: "密码错误次数过多，已封禁 24 小时".to_string(),
-                session_token: None,
-                expires_at_epoch_secs: None,
-            });
-        }
+/// 首次运行创建管理员账号（仅在尚无任何用户时允许），成功后直接登录
+pub fn setup_admin_password(
+    conn: &Connection,
+    policy: &AuthPolicy,
+    username: &str,
+    password: &str,
+    client: &SessionClient,
+) -> Result<AuthResult, String> {
+    if database::count_users(conn)? > 0 {
+        return Err("管理员账号已创建，请直接登录".to_string());
     }
+    validate_new_password(password)?;
+    let user = database::create_user(conn, username, &hash_password(password)?, UserRole::Admin)?;
+    start_session(
+        conn,
+        policy,
+        now_epoch_secs(),
+        &user,
+        client,
+        "管理员账号创建成功",
+    )
+}
 
-    if password
-        .as_bytes()
-        .ct_eq(configured_password.as_bytes())
-        .into()
+/// 修改当前登录用户的密码，需要有效会话并验证当前密码；当前密码错误与解锁失败共用按用户的封禁计数
+pub fn change_admin_password(
+    conn: &Connection,
+    policy: &AuthPolicy,
+    session_token: Option<&str>,
+    current_password: &str,
+    new_password: &str,
+) -> Result<(), String> {
+    let user = session_user(conn, policy, session_token)?;
+    if policy.is_env_managed(&user.username) {
+        return Err(format!(
+            "用户 {} 的密码由环境变量 {} 管理，请修改环境变量后重启",
+            user.username, ADMIN_PASSWORD_ENV
+        ));
+    }
+    let now = now_epoch_secs();
+    let lockout_key = user_lockout_key(user.id);
+    if is_banned(conn, &lockout_key, now)? {
+        database::delete_user_sessions(conn, user.id, None)?;
+        return Err(policy.ban_message());
+    }
+    let (_, password_hash) = database::find_user_credential(conn, &user.username)?
+        .ok_or_else(|| "用户不存在".to_string())?;
+    if !verify_password(current_password, &password_hash)? {
+        return Err(record_user_failure(conn, policy, now, user.id, "当前密码错误")?.message);
+    }
+    database::clear_lockout(conn, &lockout_key)?;
+    validate_new_password(new_password)?;
+    if current_password == new_password {
+        return Err("新密码不能与当前密码相同".to_string());
+    }
+    database::set_user_password_hash(conn, user.id, &hash_password(new_password)?)
+}
+
+/// 修改后系统中必须仍有至少一个启用的管理员
+fn ensure_admin_remains(conn: &Connection, target: &User) -> Result<(), String> {
+    if target.role == UserRole::Admin
+        && !target.disabled
+        && database::count_active_admins(conn)? <= 1
     {
-        auth.failed_attempts = 0;
-        auth.banned_until_epoch_secs = None;
-        let token = new_session_token();
-        let expires_at = now + SESSION_TTL_SECS;
-        auth.session_token = Some(token.clone());
-        auth.session_expires_epoch_secs = Some(expires_at);
-        return Ok(AuthResult {
-            success: true,
-            banned: false,
-            message: "登录成功".to_string(),
-            session_token: Some(token),
-            expires_at_epoch_secs: Some(expires_at),
-        });
+        return Err("至少需要保留一个启用的管理员".to_string());
     }
+    Ok(())
+}
 
-    auth.failed_attempts = auth.failed_attempts.saturating_add(1);
-    if auth.failed_attempts >= MAX_FAILED_ATTEMPTS {
-        auth.failed_attempts = 0;
-        auth.banned_until_epoch_secs = Some(now + BAN_DURATION_SECS);
-        clear_session(&mut auth);
-        return Ok(AuthResult {
-            success: false,
-            banned: true,
-            message: "密码错误次数过多，已封禁 24 小时".to_string(),
-            session_token: None,
-            expires_at_epoch_secs: None,
-        });
+/// 创建用户（管理员操作）
+pub fn create_user(
+    conn: &Connection,
+    username: &str,
+    password: &str,
+    role: UserRole,
+) -> Result<User, String> {
+    validate_new_password(password)?;
+    database::create_user(conn, username, &hash_password(password)?, role)
+}
+
+/// 修改用户角色、禁用状态或重置密码（管理员操作）；禁用时注销该用户的全部会话，
+/// 角色变化在下一次请求时即生效
+pub fn update_user(
+    conn: &Connection,
+    policy: &AuthPolicy,
+    actor: &SessionUser,
+    id: i64,
+    role: Option<UserRole>,
+    disabled: Option<bool>,
+    password: Option<&str>,
+) -> Result<User, String> {
+    let target = database::get_user(conn, id)?;
+    let demoted = role.is_some_and(|role| role != UserRole::Admin);
+    if demoted || disabled == Some(true) {
+        if target.id == actor.id {
+            return Err("不能禁用自己或降低自己的角色".to_string());
+        }
+        ensure_admin_remains(conn, &target)?;
+    }
+    if password.is_some() && policy.is_env_managed(&target.username) {
+        return Err(format!(
+            "用户 {} 的密码由环境变量 {} 管理",
+            target.username, ADMIN_PASSWORD_ENV
+        ));
     }
 
-    Ok(AuthResult {
-        success: false,
-        banned: false,
-        message: format!(
-            "密码错误，还可尝试 {} 次",
-            MAX_FAILED_ATTEMPTS - auth.failed_attempts
-        ),
-        session_token: None,
-        expires_at_epoch_secs: None,
-    })
+    if let Some(password) = password {
+        validate_new_password(password)?;
+        database::set_user_password_hash(conn, id, &hash_password(password)?)?;
+    }
+    let user = database::update_user(conn, id, role, disabled)?;
+    if user.disabled {
+        database::delete_user_sessions(conn, id, None)?;
+    }
+    Ok(user)
+}
+
+/// 删除用户及其会话（管理员操作，不能删除自己）
+pub fn delete_user(conn: &Connection, actor: &SessionUser, id: i64) -> Result<(), String> {
+    if id == actor.id {
+        return Err("不能删除当前登录的用户".to_string());
+    }
+    let target = database::get_user(conn, id)?;
+    ensure_admin_remains(conn, &target)?;
+    database::delete_user_sessions(conn, id, None)?;
+    database::delete_user(conn, id)
 }
 
-pub fn logout(session_token: Option<&str>) -> Result<(), String> {
-    let mut auth = state().lock().map_err(|e| e.to_string())?;
-    let provided = session_token.map(str::trim).filter(|v| !v.is_empty());
-    if provided.is_none() {
-        clear_session(&mut auth);
+pub fn logout(conn: &Connection, session_token: Option<&str>) -> Result<(), String> {
+    let Some(token) = provided_token(session_token) else {
+        return Ok(());
+    };
+    if database::delete_session_by_token_hash(conn, &hash_token(token))? > 0 {
         return Ok(());
     }
+    Err("会话无效，退出失败".to_string())
+}
 
-    if let (Some(input), Some(current)) = (provided, auth.session_token.as_deref()) {
-        if input == current {
-            clear_session(&mut auth);
-            return Ok(());
-        }
+/// 列出活动会话：管理员可查看所有用户的会话，其他用户只能查看自己的会话
+pub fn list_sessions(
+    conn: &Connection,
+    policy: &AuthPolicy,
+    actor: &SessionUser,
+    session_token: Option<&str>,
+) -> Result<Vec<SessionInfo>, String> {
+    let scope = (actor.role != UserRole::Admin).then_some(actor.id);
+    let current = provided_token(session_token).map(hash_token);
+    let now = now_epoch_secs();
+    let sessions = database::list_sessions(conn, scope, now, policy.idle_cutoff(now))?;
+    Ok(sessions
+        .into_iter()
+        .map(|session| SessionInfo {
+            current: current.as_deref() == Some(session.token_hash.as_str()),
+            id: session.id,
+            user_id: session.user.id,
+            username: session.user.username,
+            device_label: session.device_label,
+            ip: session.ip,
+            created_at_epoch_secs: session.created_at,
+            expires_at_epoch_secs: policy.session_expiry(session.last_seen_at, session.expires_at),
+            last_seen_epoch_secs: session.last_seen_at,
+        })
+        .collect())
+}
+
+/// 吊销指定会话：管理员可吊销任意会话，其他用户只能吊销自己的会话
+pub fn revoke_session(
+    conn: &Connection,
+    actor: &SessionUser,
+    session_id: &str,
+) -> Result<(), String> {
+    let scope = (actor.role != UserRole::Admin).then_some(actor.id);
+    if database::delete_session(conn, session_id.trim(), scope)? == 0 {
+        return Err("会话不存在或已失效".to_string());
     }
+    Ok(())
+}
 
-    Err("会话无效，退出失败".to_string())
+/// 吊销当前用户的全部会话（`include_current` 为 false 时保留发起请求的会话），返回吊销数量
+pub fn revoke_all_sessions(
+    conn: &Connection,
+    actor: &SessionUser,
+    session_token: Option<&str>,
+    include_current: bool,
+) -> Result<usize, String> {
+    let keep = if include_current {
+        None
+    } else {
+        provided_token(session_token).map(hash_token)
+    };
+    database::delete_user_sessions(conn, actor.id, keep.as_deref())
 }
 
 #[cfg(test)]
@@ -231,72 +818,717 @@ mod tests {
             .expect("test lock poisoned")
     }
 
-    fn reset_state() {
-        let mut auth = state().lock().expect("auth lock poisoned");
-        auth.failed_attempts = 0;
-        auth.banned_until_epoch_secs = None;
-        auth.session_token = None;
-        auth.session_expires_epoch_secs = None;
+    fn policy() -> AuthPolicy {
+        AuthPolicy::default()
+    }
+
+    fn setup_conn(password: Option<&str>) -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        database::create_auth_tables(&conn).unwrap();
+        if let Some(password) = password {
+            create_user(&conn, "admin", password, UserRole::Admin).unwrap();
+        }
+        conn
     }
 
     #[test]
     fn check_auth_without_session_should_fail() {
         let _guard = test_guard();
-        std::env::set_var("GOOGLE_MANAGER_ADMIN_PASSWORD", "test-pass-123");
-        reset_state();
+        let conn = setup_conn(Some("test-pass-123"));
 
-        let result = check_auth(None).unwrap();
+        let result = check_auth(&conn, &policy(), None).unwrap();
         assert!(!result.success);
         assert!(!result.banned);
+        assert!(!result.setup_required);
         assert!(result.message.contains("未登录"));
     }
 
     #[test]
-    fn login_without_config_should_fail_closed() {
+    fn login_without_credential_should_require_setup() {
         let _guard = test_guard();
-        std::env::remove_var("GOOGLE_MANAGER_ADMIN_PASSWORD");
-        reset_state();
+        let conn = setup_conn(None);
 
-        let result = login("anything").unwrap();
+        let result = login(
+            &conn,
+            &policy(),
+            "admin",
+            "anything",
+            &SessionClient::default(),
+        )
+        .unwrap();
         assert!(!result.success);
         assert!(!result.banned);
-        assert!(result.message.contains("GOOGLE_MANAGER_ADMIN_PASSWORD"));
+        assert!(result.setup_required);
+        assert!(check_auth(&conn, &policy(), None).unwrap().setup_required);
     }
 
     #[test]
-    fn require_auth_should_reject_invalid_token() {
+    fn require_role_should_reject_invalid_token() {
         let _guard = test_guard();
-        std::env::set_var("GOOGLE_MANAGER_ADMIN_PASSWORD", "test-pass-123");
-        reset_state();
+        let conn = setup_conn(Some("test-pass-123"));
 
-        let login_result = login("test-pass-123").unwrap();
+        let login_result = login(
+            &conn,
+            &policy(),
+            "admin",
+            "test-pass-123",
+            &SessionClient::default(),
+        )
+        .unwrap();
         assert!(login_result.success);
+        assert_eq!(login_result.user.unwrap().role, UserRole::Admin);
         let valid_token = login_result.session_token.unwrap();
 
-        assert!(require_auth(Some(&valid_token)).is_ok());
-        assert!(require_auth(Some("invalid-token")).is_err());
+        assert!(require_role(&conn, &policy(), Some(&valid_token), UserRole::Admin).is_ok());
+        assert!(require_role(&conn, &policy(), Some("invalid-token"), UserRole::Viewer).is_err());
     }
 
     #[test]
     fn login_should_ban_after_three_failures() {
         let _guard = test_guard();
-        std::env::set_var("GOOGLE_MANAGER_ADMIN_PASSWORD", "test-pass-123");
-        reset_state();
+        let conn = setup_conn(Some("test-pass-123"));
+
+        let client = SessionClient {
+            device_label: None,
+            ip: Some("203.0.113.7".to_string()),
+        };
 
-        let r1 = login("wrong").unwrap();
+        // 不存在的用户名单独计数，不会封禁真实账号
+        for _ in 0..2 {
+            assert!(
+                !login(&conn, &policy(), "nobody", "test-pass-123", &client)
+                    .unwrap()
+                    .banned
+            );
+        }
+        assert!(
+            login(&conn, &policy(), "nobody", "test-pass-123", &client)
+                .unwrap()
+                .banned
+        );
+        assert!(
+            login(&conn, &policy(), "admin", "test-pass-123", &client)
+                .unwrap()
+                .success
+        );
+
+        let r1 = login(&conn, &policy(), "admin", "wrong", &client).unwrap();
         assert!(!r1.success);
         assert!(!r1.banned);
-
-        let r2 = login("wrong").unwrap();
-        assert!(!r2.success);
+        let r2 = login(&conn, &policy(), "Admin", "wrong", &client).unwrap();
         assert!(!r2.banned);
-
-        let r3 = login("wrong").unwrap();
+        let r3 = login(&conn, &policy(), "admin", "wrong", &client).unwrap();
         assert!(!r3.success);
         assert!(r3.banned);
+        assert!(
+            login(&conn, &policy(), "admin", "test-pass-123", &client)
+                .unwrap()
+                .banned
+        );
 
-        let status = check_auth(None).unwrap();
+        // 封禁只针对该用户名 + IP，其他来源与会话校验不受影响
+        let other_ip = SessionClient {
+            device_label: None,
+            ip: Some("198.51.100.9".to_string()),
+        };
+        assert!(
+            login(&conn, &policy(), "admin", "test-pass-123", &other_ip)
+                .unwrap()
+                .success
+        );
+        let status = check_auth(&conn, &policy(), None).unwrap();
         assert!(!status.success);
-        assert!(status.banned);
+        assert!(!status.banned);
+    }
+
+    #[test]
+    fn setup_stores_argon2id_hash_only_once() {
+        let _guard = test_guard();
+        let conn = setup_conn(None);
+
+        assert!(setup_admin_password(
+            &conn,
+            &policy(),
+            "owner",
+            "short",
+            &SessionClient::default()
+        )
+        .is_err());
+        let result = setup_admin_password(
+            &conn,
+            &policy(),
+            "owner",
+            "first-pass-1",
+            &SessionClient::default(),
+        )
+        .unwrap();
+        assert!(result.success);
+        assert!(require_role(
+            &conn,
+            &policy(),
+            result.session_token.as_deref(),
+            UserRole::Admin
+        )
+        .is_ok());
+
+        let (user, stored) = database::find_user_credential(&conn, "OWNER")
+            .unwrap()
+            .unwrap();
+        assert_eq!(user.role, UserRole::Admin);
+        assert!(stored.starts_with("$argon2id$"));
+        assert!(!stored.contains("first-pass-1"));
+        assert!(setup_admin_password(
+            &conn,
+            &policy(),
+            "other",
+            "second-pass-2",
+            &SessionClient::default()
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn change_password_requires_session_and_current_password() {
+        let _guard = test_guard();
+        let conn = setup_conn(Some("old-pass-123"));
+        let token = login(
+            &conn,
+            &policy(),
+            "admin",
+            "old-pass-123",
+            &SessionClient::default(),
+        )
+        .unwrap()
+        .session_token;
+
+        assert!(
+            change_admin_password(&conn, &policy(), None, "old-pass-123", "new-pass-456").is_err()
+        );
+        assert!(
+            change_admin_password(&conn, &policy(), token.as_deref(), "wrong", "new-pass-456")
+                .unwrap_err()
+                .starts_with("当前密码错误")
+        );
+        change_admin_password(
+            &conn,
+            &policy(),
+            token.as_deref(),
+            "old-pass-123",
+            "new-pass-456",
+        )
+        .unwrap();
+
+        assert!(
+            !login(
+                &conn,
+                &policy(),
+                "admin",
+                "old-pass-123",
+                &SessionClient::default()
+            )
+            .unwrap()
+            .success
+        );
+        assert!(
+            login(
+                &conn,
+                &policy(),
+                "admin",
+                "new-pass-456",
+                &SessionClient::default()
+            )
+            .unwrap()
+            .success
+        );
+    }
+
+    #[test]
+    fn change_password_failures_ban_and_revoke_user_sessions() {
+        let _guard = test_guard();
+        let conn = setup_conn(Some("admin-pass-1"));
+        create_user(&conn, "bob", "bob-pass-12", UserRole::Operator).unwrap();
+        let token = login(
+            &conn,
+            &policy(),
+            "bob",
+            "bob-pass-12",
+            &SessionClient::default(),
+        )
+        .unwrap()
+        .session_token;
+        let admin_token = login(
+            &conn,
+            &policy(),
+            "admin",
+            "admin-pass-1",
+            &SessionClient::default(),
+        )
+        .unwrap()
+        .session_token;
+
+        let first = change_admin_password(
+            &conn,
+            &policy(),
+            token.as_deref(),
+            "guess-1",
+            "new-pass-456",
+        );
+        assert!(first.unwrap_err().contains("还可尝试 2 次"));
+        // 与解锁失败共用按用户的计数
+        assert!(
+            !unlock(&conn, &policy(), token.as_deref(), "guess-2")
+                .unwrap()
+                .banned
+        );
+        let banned = change_admin_password(
+            &conn,
+            &policy(),
+            token.as_deref(),
+            "guess-3",
+            "new-pass-456",
+        );
+        assert!(banned.unwrap_err().contains("封禁"));
+        assert!(session_user(&conn, &policy(), token.as_deref()).is_err());
+        assert!(session_user(&conn, &policy(), admin_token.as_deref()).is_ok());
+    }
+
+    #[test]
+    fn login_with_unknown_username_still_verifies_a_hash() {
+        let _guard = test_guard();
+        let conn = setup_conn(Some("admin-pass-1"));
+
+        let result = login(
+            &conn,
+            &policy(),
+            "nobody",
+            "admin-pass-1",
+            &SessionClient::default(),
+        )
+        .unwrap();
+        assert!(!result.success);
+        assert!(result.message.contains("用户名或密码错误"));
+        assert!(dummy_password_hash().starts_with("$argon2id$"));
+    }
+
+    #[test]
+    fn env_password_bootstraps_and_overrides_stored_hash() {
+        let _guard = test_guard();
+        let conn = setup_conn(None);
+
+        sync_admin_credential(&conn, "admin", "env-pass-123").unwrap();
+        let (_, first) = database::find_user_credential(&conn, "admin")
+            .unwrap()
+            .unwrap();
+        sync_admin_credential(&conn, "admin", "env-pass-123").unwrap();
+        let (_, second) = database::find_user_credential(&conn, "admin")
+            .unwrap()
+            .unwrap();
+        assert_eq!(first, second);
+
+        sync_admin_credential(&conn, "admin", "env-pass-456").unwrap();
+        let env_policy = AuthPolicy::from_lookup(|name| match name {
+            ADMIN_PASSWORD_ENV => Some("env-pass-456".to_string()),
+            _ => None,
+        });
+        assert_eq!(env_policy.env_admin_username.as_deref(), Some("admin"));
+        let token = login(
+            &conn,
+            &env_policy,
+            "admin",
+            "env-pass-456",
+            &SessionClient::default(),
+        )
+        .unwrap()
+        .session_token;
+        assert!(token.is_some());
+        assert!(change_admin_password(
+            &conn,
+            &env_policy,
+            token.as_deref(),
+            "env-pass-456",
+            "other-pass-789"
+        )
+        .unwrap_err()
+        .contains(ADMIN_PASSWORD_ENV));
+    }
+
+    #[test]
+    fn roles_limit_operations_and_changes_apply_to_session() {
+        let _guard = test_guard();
+        let conn = setup_conn(Some("admin-pass-1"));
+        let admin = database::find_user_credential(&conn, "admin")
+            .unwrap()
+            .unwrap()
+            .0;
+        let actor = SessionUser::from(&admin);
+        let viewer = create_user(&conn, "alice", "alice-pass-1", UserRole::Viewer).unwrap();
+
+        let token = login(
+            &conn,
+            &policy(),
+            "alice",
+            "alice-pass-1",
+            &SessionClient::default(),
+        )
+        .unwrap()
+        .session_token;
+        assert!(require_role(&conn, &policy(), token.as_deref(), UserRole::Viewer).is_ok());
+        assert!(
+            require_role(&conn, &policy(), token.as_deref(), UserRole::Operator)
+                .unwrap_err()
+                .contains("权限不足")
+        );
+
+        update_user(
+            &conn,
+            &policy(),
+            &actor,
+            viewer.id,
+            Some(UserRole::Operator),
+            None,
+            None,
+        )
+        .unwrap();
+        assert!(require_role(&conn, &policy(), token.as_deref(), UserRole::Operator).is_ok());
+        assert!(require_role(&conn, &policy(), token.as_deref(), UserRole::Admin).is_err());
+
+        update_user(&conn, &policy(), &actor, viewer.id, None, Some(true), None).unwrap();
+        assert!(require_role(&conn, &policy(), token.as_deref(), UserRole::Viewer).is_err());
+        assert!(
+            !login(
+                &conn,
+                &policy(),
+                "alice",
+                "alice-pass-1",
+                &SessionClient::default()
+            )
+            .unwrap()
+            .success
+        );
+
+        // 唯一的管理员不能被降级、禁用或删除
+        assert!(update_user(
+            &conn,
+            &policy(),
+            &actor,
+            admin.id,
+            Some(UserRole::Viewer),
+            None,
+            None
+        )
+        .is_err());
+        assert!(delete_user(&conn, &actor, admin.id).is_err());
+        delete_user(&conn, &actor, viewer.id).unwrap();
+        assert_eq!(database::list_users(&conn).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn concurrent_sessions_can_be_listed_and_revoked() {
+        let _guard = test_guard();
+        let conn = setup_conn(Some("admin-pass-1"));
+        create_user(&conn, "bob", "bob-pass-12", UserRole::Operator).unwrap();
+        let desktop = SessionClient {
+            device_label: Some("  桌面端  ".to_string()),
+            ip: None,
+        };
+        let browser = SessionClient {
+            device_label: Some("Firefox".to_string()),
+            ip: Some("192.168.1.20".to_string()),
+        };
+
+        let admin_desktop = login(&conn, &policy(), "admin", "admin-pass-1", &desktop).unwrap();
+        let admin_browser = login(&conn, &policy(), "admin", "admin-pass-1", &browser).unwrap();
+        let bob = login(&conn, &policy(), "bob", "bob-pass-12", &browser).unwrap();
+        let admin_token = admin_desktop.session_token.as_deref();
+        let bob_token = bob.session_token.as_deref();
+        // 新登录不会挤掉已有会话
+        assert!(session_user(&conn, &policy(), admin_token).is_ok());
+        assert!(session_user(&conn, &policy(), admin_browser.session_token.as_deref()).is_ok());
+
+        let admin = session_user(&conn, &policy(), admin_token).unwrap();
+        let bob_user = session_user(&conn, &policy(), bob_token).unwrap();
+        assert_eq!(
+            list_sessions(&conn, &policy(), &admin, admin_token)
+                .unwrap()
+                .len(),
+            3
+        );
+        let own = list_sessions(&conn, &policy(), &bob_user, bob_token).unwrap();
+        assert_eq!(own.len(), 1);
+        assert!(own[0].current);
+        assert_eq!(own[0].ip.as_deref(), Some("192.168.1.20"));
+
+        let desktop_info = list_sessions(&conn, &policy(), &admin, admin_token)
+            .unwrap()
+            .into_iter()
+            .find(|session| session.current)
+            .unwrap();
+        assert_eq!(desktop_info.device_label.as_deref(), Some("桌面端"));
+        // 非管理员不能吊销别人的会话
+        assert!(revoke_session(&conn, &bob_user, &desktop_info.id).is_err());
+
+        assert_eq!(
+            revoke_all_sessions(&conn, &admin, admin_token, false).unwrap(),
+            1
+        );
+        assert!(session_user(&conn, &policy(), admin_browser.session_token.as_deref()).is_err());
+        assert!(session_user(&conn, &policy(), admin_token).is_ok());
+
+        let bob_session = list_sessions(&conn, &policy(), &bob_user, bob_token)
+            .unwrap()
+            .remove(0);
+        revoke_session(&conn, &admin, &bob_session.id).unwrap();
+        assert!(session_user(&conn, &policy(), bob_token).is_err());
+
+        logout(&conn, admin_token).unwrap();
+        assert!(session_user(&conn, &policy(), admin_token).is_err());
+    }
+
+    #[test]
+    fn sessions_and_ban_state_persist_in_database() {
+        let _guard = test_guard();
+        let policy = AuthPolicy {
+            max_failed_attempts: 2,
+            ..AuthPolicy::default()
+        };
+        let conn = setup_conn(Some("admin-pass-1"));
+
+        let token = login(
+            &conn,
+            &policy,
+            "admin",
+            "admin-pass-1",
+            &SessionClient::default(),
+        )
+        .unwrap()
+        .session_token
+        .unwrap();
+        // 数据库只保存令牌哈希，内存中不保留任何会话状态
+        let stored = database::list_sessions(&conn, None, now_epoch_secs(), 0).unwrap();
+        assert_eq!(stored.len(), 1);
+        assert_ne!(stored[0].token_hash, token);
+        assert_eq!(stored[0].token_hash, hash_token(&token));
+        assert!(session_user(&conn, &policy, Some(&token)).is_ok());
+
+        assert!(
+            !login(&conn, &policy, "admin", "wrong", &SessionClient::default())
+                .unwrap()
+                .banned
+        );
+        assert_eq!(database::get_lockout(&conn, "login:admin").unwrap().0, 1);
+        let banned = login(&conn, &policy, "admin", "wrong", &SessionClient::default()).unwrap();
+        assert!(banned.banned);
+        assert!(database::get_lockout(&conn, "login:admin")
+            .unwrap()
+            .1
+            .is_some());
+        // 登录封禁不影响已有会话，但正确密码也无法再登录
+        assert!(session_user(&conn, &policy, Some(&token)).is_ok());
+        assert!(
+            login(
+                &conn,
+                &policy,
+                "admin",
+                "admin-pass-1",
+                &SessionClient::default()
+            )
+            .unwrap()
+            .banned
+        );
+
+        let now = now_epoch_secs();
+        database::set_lockout(&conn, "login:admin", 0, Some(now - 1), now).unwrap();
+        assert!(
+            login(
+                &conn,
+                &policy,
+                "admin",
+                "admin-pass-1",
+                &SessionClient::default()
+            )
+            .unwrap()
+            .success
+        );
+    }
+
+    #[test]
+    fn idle_timeout_slides_on_each_authenticated_call() {
+        let _guard = test_guard();
+        let conn = setup_conn(Some("admin-pass-1"));
+        let token = login(
+            &conn,
+            &policy(),
+            "admin",
+            "admin-pass-1",
+            &SessionClient::default(),
+        )
+        .unwrap()
+        .session_token
+        .unwrap();
+        let token_hash = hash_token(&token);
+        let idle = AuthPolicy::default().idle_timeout_secs;
+
+        // 接近空闲超时的请求会把失效时间顺延
+        let now = now_epoch_secs();
+        database::touch_session(&conn, &token_hash, now - idle + 5).unwrap();
+        let result = check_auth(&conn, &policy(), Some(&token)).unwrap();
+        assert!(result.success);
+        assert!(result.expires_at_epoch_secs.unwrap() >= now + idle);
+        let stored = database::list_sessions(&conn, None, now, 0).unwrap();
+        assert!(stored[0].last_seen_at >= now);
+
+        // 超过空闲超时的会话失效并在清理时删除
+        database::touch_session(&conn, &token_hash, now - idle - 1).unwrap();
+        assert!(session_user(&conn, &policy(), Some(&token)).is_err());
+        assert_eq!(
+            database::delete_expired_sessions(&conn, now, now - idle).unwrap(),
+            1
+        );
+    }
+
+    #[test]
+    fn unlock_requires_session_password_and_counts_failures() {
+        let _guard = test_guard();
+        let conn = setup_conn(Some("admin-pass-1"));
+        create_user(&conn, "bob", "bob-pass-12", UserRole::Operator).unwrap();
+        let token = login(
+            &conn,
+            &policy(),
+            "bob",
+            "bob-pass-12",
+            &SessionClient::default(),
+        )
+        .unwrap()
+        .session_token;
+
+        let bob = database::find_user_credential(&conn, "bob")
+            .unwrap()
+            .unwrap()
+            .0;
+        let admin_token = login(
+            &conn,
+            &policy(),
+            "admin",
+            "admin-pass-1",
+            &SessionClient::default(),
+        )
+        .unwrap()
+        .session_token;
+
+        assert!(
+            !unlock(&conn, &policy(), None, "bob-pass-12")
+                .unwrap()
+                .success
+        );
+        // 只能用会话所属用户的密码解锁，失败只计入该用户
+        let wrong = unlock(&conn, &policy(), token.as_deref(), "admin-pass-1").unwrap();
+        assert!(!wrong.success);
+        assert!(wrong.message.contains("还可尝试 2 次"));
+        assert_eq!(
+            database::get_lockout(&conn, &user_lockout_key(bob.id))
+                .unwrap()
+                .0,
+            1
+        );
+
+        let unlocked = unlock(&conn, &policy(), token.as_deref(), "bob-pass-12").unwrap();
+        assert!(unlocked.success);
+        assert!(!unlocked.locked);
+        assert_eq!(unlocked.user.unwrap().username, "bob");
+        assert_eq!(
+            database::get_lockout(&conn, &user_lockout_key(bob.id))
+                .unwrap()
+                .0,
+            0
+        );
+
+        // 连续失败达到阈值时只注销该用户的会话，其他用户与登录不受影响
+        for _ in 0..2 {
+            assert!(
+                !unlock(&conn, &policy(), token.as_deref(), "wrong-pass")
+                    .unwrap()
+                    .banned
+            );
+        }
+        assert!(
+            unlock(&conn, &policy(), token.as_deref(), "wrong-pass")
+                .unwrap()
+                .banned
+        );
+        assert!(active_session_user(&conn, &policy(), token.as_deref()).is_err());
+        assert!(active_session_user(&conn, &policy(), admin_token.as_deref()).is_ok());
+        assert!(
+            login(
+                &conn,
+                &policy(),
+                "admin",
+                "admin-pass-1",
+                &SessionClient::default()
+            )
+            .unwrap()
+            .success
+        );
+    }
+
+    #[test]
+    fn lock_requires_operator() {
+        let _guard = test_guard();
+        let conn = setup_conn(Some("admin-pass-1"));
+        create_user(&conn, "viewer", "viewer-pass-1", UserRole::Viewer).unwrap();
+        let token = login(
+            &conn,
+            &policy(),
+            "viewer",
+            "viewer-pass-1",
+            &SessionClient::default(),
+        )
+        .unwrap()
+        .session_token;
+
+        let viewer = active_session_user(&conn, &policy(), token.as_deref()).unwrap();
+        assert!(lock(&viewer).unwrap_err().contains("权限不足"));
+        assert!(!key_manager::is_master_key_locked());
+    }
+
+    #[test]
+    fn policy_reads_env_and_ignores_invalid_values() {
+        let _guard = test_guard();
+        let policy = AuthPolicy::from_lookup(|name| match name {
+            BAN_DURATION_ENV => Some("1800".to_string()),
+            SESSION_TTL_ENV => Some("0".to_string()),
+            _ => None,
+        });
+
+        assert_eq!(policy.ban_duration_secs, 1800);
+        assert_eq!(
+            policy.session_ttl_secs,
+            AuthPolicy::default().session_ttl_secs
+        );
+        assert!(policy.ban_message().contains("30 分钟"));
+        assert!(AuthPolicy::default().ban_message().contains("24 小时"));
+    }
+
+    #[test]
+    fn legacy_admin_credential_is_migrated_to_admin_user() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE admin_credential (id INTEGER PRIMARY KEY CHECK (id = 1), password_hash TEXT NOT NULL, updated_at TEXT);",
+        )
+        .unwrap();
+        let hash = hash_password("legacy-pass-1").unwrap();
+        conn.execute(
+            "INSERT INTO admin_credential (id, password_hash) VALUES (1, ?1)",
+            [&hash],
+        )
+        .unwrap();
+
+        database::create_auth_tables(&conn).unwrap();
+        let (user, stored) = database::find_user_credential(&conn, "admin")
+            .unwrap()
+            .unwrap();
+        assert_eq!(user.role, UserRole::Admin);
+        assert_eq!(stored, hash);
+        database::create_auth_tables(&conn).unwrap();
+        assert_eq!(database::count_users(&conn).unwrap(), 1);
     }
 }
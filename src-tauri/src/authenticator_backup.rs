@@ -0,0 +1,673 @@
+//! 身份验证器备份导入：Aegis、2FAS、andOTP（含加密备份）
+//!
+//! 备份条目统一转换为 `MigrationEntry`，复用迁移码的按邮箱 / 标签匹配与密钥写入逻辑。
+
+use crate::database::AuditActor;
+use crate::migration::{self, MigrationEntry, MigrationMatch};
+use crate::totp::{TotpParams, DEFAULT_PERIOD};
+use aes_gcm::{
+    aead::{Aead, KeyInit},
+    Aes256Gcm, Nonce,
+};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+/// 2FAS 加密备份的 PBKDF2 迭代次数
+const TWOFAS_PBKDF2_ROUNDS: u32 = 10_000;
+/// andOTP 加密备份头部长度：迭代次数(4) + 盐(12) + IV(12)
+const ANDOTP_HEADER_LEN: usize = 4 + 12 + 12;
+/// AES-GCM 认证标签长度
+const GCM_TAG_LEN: usize = 16;
+// 备份文件中 KDF 参数的上限，防止恶意文件让派生耗尽内存或 CPU
+/// Aegis scrypt：log2(n) ≤ 20（默认 15）
+const MAX_AEGIS_SCRYPT_LOG_N: u32 = 20;
+/// Aegis scrypt：r·p ≤ 64（默认 8·1）
+const MAX_AEGIS_SCRYPT_RP: u64 = 64;
+/// andOTP PBKDF2 迭代次数上限（默认 14 万~16 万）
+const MAX_ANDOTP_PBKDF2_ITERATIONS: u32 = 5_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuthenticatorBackupSource {
+    /// Aegis 导出的 JSON（明文或密码加密）
+    Aegis,
+    /// 2FAS 导出的 `.2fas` 文件（明文或密码加密）
+    #[serde(rename = "2fas", alias = "two_fas")]
+    TwoFas,
+    /// andOTP 明文 JSON，或 Base64 编码的 `.json.aes` 加密备份
+    Andotp,
+}
+
+impl AuthenticatorBackupSource {
+    fn display_name(self) -> &'static str {
+        match self {
+            Self::Aegis => "Aegis",
+            Self::TwoFas => "2FAS",
+            Self::Andotp => "andOTP",
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct AegisFile {
+    header: Option<AegisHeader>,
+    db: serde_json::Value,
+}
+
+#[derive(Deserialize)]
+struct AegisHeader {
+    slots: Option<Vec<AegisSlot>>,
+    params: Option<AegisKeyParams>,
+}
+
+#[derive(Deserialize)]
+struct AegisSlot {
+    #[serde(rename = "type")]
+    slot_type: u32,
+    key: String,
+    key_params: AegisKeyParams,
+    #[serde(default)]
+    n: u64,
+    #[serde(default)]
+    r: u32,
+    #[serde(default)]
+    p: u32,
+    #[serde(default)]
+    salt: String,
+}
+
+#[derive(Deserialize)]
+struct AegisKeyParams {
+    nonce: String,
+    tag: String,
+}
+
+#[derive(Deserialize)]
+struct AegisDb {
+    entries: Vec<AegisEntry>,
+}
+
+#[derive(Deserialize)]
+struct AegisEntry {
+    #[serde(rename = "type")]
+    otp_type: String,
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    issuer: String,
+    info: AegisInfo,
+}
+
+#[derive(Deserialize)]
+struct AegisInfo {
+    secret: String,
+    algo: Option<String>,
+    digits: Option<u32>,
+    period: Option<u32>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TwoFasFile {
+    #[serde(default)]
+    services: Vec<TwoFasService>,
+    services_encrypted: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct TwoFasService {
+    #[serde(default)]
+    name: String,
+    secret: String,
+    #[serde(default)]
+    otp: TwoFasOtp,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TwoFasOtp {
+    #[serde(default)]
+    label: String,
+    #[serde(default)]
+    account: String,
+    #[serde(default)]
+    issuer: String,
+    digits: Option<u32>,
+    period: Option<u32>,
+    algorithm: Option<String>,
+    token_type: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct AndotpEntry {
+    secret: String,
+    #[serde(default)]
+    issuer: String,
+    #[serde(default)]
+    label: String,
+    digits: Option<u32>,
+    period: Option<u32>,
+    algorithm: Option<String>,
+    #[serde(rename = "type")]
+    otp_type: Option<String>,
+}
+
+fn decode_hex(value: &str, field: &str) -> Result<Vec<u8>, String> {
+    value
+        .trim()
+        .as_bytes()
+        .chunks(2)
+        .map(|pair| {
+            std::str::from_utf8(pair)
+                .ok()
+                .filter(|pair| pair.len() == 2)
+                .and_then(|pair| u8::from_str_radix(pair, 16).ok())
+                .ok_or_else(|| format!("{} 不是合法的十六进制", field))
+        })
+        .collect()
+}
+
+fn decode_base64(value: &str, field: &str) -> Result<Vec<u8>, String> {
+    STANDARD
+        .decode(value.trim())
+        .map_err(|e| format!("{} Base64 解码失败: {}", field, e))
+}
+
+fn require_password(password: Option<&str>) -> Result<&str, String> {
+    password
+        .filter(|p| !p.is_empty())
+        .ok_or_else(|| "该备份已加密，请提供备份密码".to_string())
+}
+
+/// AES-256-GCM 解密，`ciphertext` 末尾带 16 字节认证标签
+fn aes_gcm_decrypt(key: &[u8], nonce: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, String> {
+    if nonce.len() != 12 {
+        return Err("备份中的 nonce 长度非法".to_string());
+    }
+    let cipher = Aes256Gcm::new_from_slice(key).map_err(|_| "备份密钥长度非法".to_string())?;
+    cipher
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| "备份密码错误或文件已损坏".to_string())
+}
+
+fn parse_json<T: serde::de::DeserializeOwned>(bytes: &[u8], source: &str) -> Result<T, String> {
+    serde_json::from_slice(bytes).map_err(|e| format!("{} 备份解析失败: {}", source, e))
+}
+
+/// 统一整理为迁移条目；`otp_type` 转为小写，缺省参数按 TOTP 默认值补全
+fn backup_entry(
+    label: &str,
+    account: &str,
+    issuer: &str,
+    secret: &str,
+    otp_type: Option<&str>,
+    params: (Option<&str>, Option<u32>, Option<u32>),
+) -> MigrationEntry {
+    let (algorithm, digits, period) = params;
+    let email = Some(account.trim())
+        .filter(|account| !account.is_empty())
+        .map(str::to_string)
+        .unwrap_or_else(|| migration::label_account(label));
+    MigrationEntry {
+        name: if label.trim().is_empty() {
+            email.clone()
+        } else {
+            label.trim().to_string()
+        },
+        issuer: issuer.trim().to_string(),
+        email,
+        secret: secret
+            .replace(' ', "")
+            .trim_end_matches('=')
+            .to_ascii_uppercase(),
+        otp_type: otp_type.unwrap_or("totp").trim().to_ascii_lowercase(),
+        params: TotpParams {
+            algorithm: algorithm.unwrap_or("SHA1").trim().to_ascii_uppercase(),
+            digits: digits.unwrap_or(6),
+            period: period.unwrap_or(DEFAULT_PERIOD),
+        },
+    }
+}
+
+/// 用密码槽解出 Aegis 主密钥（scrypt 派生槽密钥后 AES-GCM 解密）
+fn decrypt_aegis_master_key(slots: &[AegisSlot], password: &str) -> Result<Vec<u8>, String> {
+    let mut password_slots = slots.iter().filter(|slot| slot.slot_type == 1).peekable();
+    if password_slots.peek().is_none() {
+        return Err("Aegis 备份中没有密码槽，无法用密码解密".to_string());
+    }
+    for slot in password_slots {
+        if !slot.n.is_power_of_two() || slot.n < 2 {
+            return Err(format!("Aegis 密码槽的 scrypt 参数 n 非法: {}", slot.n));
+        }
+        if slot.n.trailing_zeros() > MAX_AEGIS_SCRYPT_LOG_N
+            || slot.r as u64 * slot.p as u64 > MAX_AEGIS_SCRYPT_RP
+        {
+            return Err(format!(
+                "Aegis 密码槽的 scrypt 参数超出上限: n={}, r={}, p={}",
+                slot.n, slot.r, slot.p
+            ));
+        }
+        let params = scrypt::Params::new(slot.n.trailing_zeros() as u8, slot.r, slot.p, 32)
+            .map_err(|e| format!("Aegis 密码槽的 scrypt 参数非法: {}", e))?;
+        let mut derived = [0u8; 32];
+        scrypt::scrypt(
+            password.as_bytes(),
+            &decode_hex(&slot.salt, "salt")?,
+            &params,
+            &mut derived,
+        )
+        .map_err(|e| format!("scrypt 派生失败: {}", e))?;
+        let mut sealed = decode_hex(&slot.key, "key")?;
+        sealed.extend(decode_hex(&slot.key_params.tag, "tag")?);
+        let nonce = decode_hex(&slot.key_params.nonce, "nonce")?;
+        if let Ok(master_key) = aes_gcm_decrypt(&derived, &nonce, &sealed) {
+            return Ok(master_key);
+        }
+    }
+    Err("备份密码错误或文件已损坏".to_string())
+}
+
+fn parse_aegis(content: &str, password: Option<&str>) -> Result<Vec<MigrationEntry>, String> {
+    let file: AegisFile = parse_json(content.as_bytes(), "Aegis")?;
+    let db: AegisDb = match (&file.db, file.header) {
+        (serde_json::Value::String(encrypted), Some(header)) => {
+            let slots = header.slots.unwrap_or_default();
+            let params = header
+                .params
+                .ok_or_else(|| "Aegis 加密备份缺少 header.params".to_string())?;
+            let master_key = decrypt_aegis_master_key(&slots, require_password(password)?)?;
+            let mut sealed = decode_base64(encrypted, "db")?;
+            sealed.extend(decode_hex(&params.tag, "tag")?);
+            let plain =
+                aes_gcm_decrypt(&master_key, &decode_hex(&params.nonce, "nonce")?, &sealed)?;
+            parse_json(&plain, "Aegis")?
+        }
+        (serde_json::Value::String(_), None) => return Err("Aegis 加密备份缺少 header".to_string()),
+        (value, _) => serde_json::from_value(value.clone())
+            .map_err(|e| format!("Aegis 备份解析失败: {}", e))?,
+    };
+    Ok(db
+        .entries
+        .into_iter()
+        .map(|entry| {
+            backup_entry(
+                &entry.name,
+                "",
+                &entry.issuer,
+                &entry.info.secret,
+                Some(&entry.otp_type),
+                (
+                    entry.info.algo.as_deref(),
+                    entry.info.digits,
+                    entry.info.period,
+                ),
+            )
+        })
+        .collect())
+}
+
+/// 解密 2FAS 的 `servicesEncrypted`（`密文+标签:盐:IV`，均为 Base64）
+fn decrypt_twofas_services(encrypted: &str, password: &str) -> Result<Vec<TwoFasService>, String> {
+    let mut parts = encrypted.split(':');
+    let (Some(data), Some(salt), Some(iv)) = (parts.next(), parts.next(), parts.next()) else {
+        return Err("2FAS 加密数据格式非法".to_string());
+    };
+    let mut key = [0u8; 32];
+    pbkdf2::pbkdf2_hmac::<Sha256>(
+        password.as_bytes(),
+        &decode_base64(salt, "salt")?,
+        TWOFAS_PBKDF2_ROUNDS,
+        &mut key,
+    );
+    let plain = aes_gcm_decrypt(
+        &key,
+        &decode_base64(iv, "iv")?,
+        &decode_base64(data, "servicesEncrypted")?,
+    )?;
+    parse_json(&plain, "2FAS")
+}
+
+fn parse_twofas(content: &str, password: Option<&str>) -> Result<Vec<MigrationEntry>, String> {
+    let file: TwoFasFile = parse_json(content.as_bytes(), "2FAS")?;
+    let services = match file.services_encrypted.filter(|s| !s.trim().is_empty()) {
+        Some(encrypted) => decrypt_twofas_services(&encrypted, require_password(password)?)?,
+        None => file.services,
+    };
+    Ok(services
+        .into_iter()
+        .map(|service| {
+            let otp = service.otp;
+            let issuer = if otp.issuer.trim().is_empty() {
+                &service.name
+            } else {
+                &otp.issuer
+            };
+            backup_entry(
+                &otp.label,
+                &otp.account,
+                issuer,
+                &service.secret,
+                otp.token_type.as_deref(),
+                (otp.algorithm.as_deref(), otp.digits, otp.period),
+            )
+        })
+        .collect())
+}
+
+/// 解密 andOTP 加密备份：迭代次数(大端 4 字节) + 盐 + IV + 密文+标签，PBKDF2-HMAC-SHA1 派生密钥
+fn decrypt_andotp(encoded: &str, password: &str) -> Result<Vec<u8>, String> {
+    let bytes = decode_base64(encoded, "andOTP 加密备份")?;
+    if bytes.len() < ANDOTP_HEADER_LEN + GCM_TAG_LEN {
+        return Err("andOTP 加密备份被截断".to_string());
+    }
+    let iterations = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+    if iterations == 0 {
+        return Err("andOTP 加密备份的迭代次数非法".to_string());
+    }
+    if iterations > MAX_ANDOTP_PBKDF2_ITERATIONS {
+        return Err(format!("andOTP 加密备份的迭代次数超出上限: {}", iterations));
+    }
+    let mut key = [0u8; 32];
+    pbkdf2::pbkdf2_hmac::<sha1::Sha1>(password.as_bytes(), &bytes[4..16], iterations, &mut key);
+    aes_gcm_decrypt(
+        &key,
+        &bytes[16..ANDOTP_HEADER_LEN],
+        &bytes[ANDOTP_HEADER_LEN..],
+    )
+}
+
+fn parse_andotp(content: &str, password: Option<&str>) -> Result<Vec<MigrationEntry>, String> {
+    let trimmed = content.trim();
+    let entries: Vec<AndotpEntry> = if trimmed.starts_with('[') {
+        parse_json(trimmed.as_bytes(), "andOTP")?
+    } else {
+        parse_json(
+            &decrypt_andotp(trimmed, require_password(password)?)?,
+            "andOTP",
+        )?
+    };
+    Ok(entries
+        .into_iter()
+        .map(|entry| {
+            backup_entry(
+                &entry.label,
+                "",
+                &entry.issuer,
+                &entry.secret,
+                entry.otp_type.as_deref(),
+                (entry.algorithm.as_deref(), entry.digits, entry.period),
+            )
+        })
+        .collect())
+}
+
+/// 解析（必要时解密）身份验证器备份，返回其中的全部条目
+pub fn decode_authenticator_backup(
+    source: AuthenticatorBackupSource,
+    content: &str,
+    password: Option<&str>,
+) -> Result<Vec<MigrationEntry>, String> {
+    let entries = match source {
+        AuthenticatorBackupSource::Aegis => parse_aegis(content, password)?,
+        AuthenticatorBackupSource::TwoFas => parse_twofas(content, password)?,
+        AuthenticatorBackupSource::Andotp => parse_andotp(content, password)?,
+    };
+    if entries.is_empty() {
+        return Err(format!("{} 备份中没有任何条目", source.display_name()));
+    }
+    Ok(entries)
+}
+
+/// 解析备份并按邮箱 / 标签匹配账号；`apply` 为 true 时把密钥与参数写入匹配到的账号
+pub fn import_authenticator_backup(
+    conn: &Connection,
+    source: AuthenticatorBackupSource,
+    content: &str,
+    password: Option<&str>,
+    apply: bool,
+    actor: AuditActor,
+) -> Result<Vec<MigrationMatch>, String> {
+    let entries = decode_authenticator_backup(source, content, password)?;
+    let mut matches = migration::match_migration_entries(conn, entries)?;
+    if apply {
+        migration::apply_migration_matches(conn, &mut matches, actor);
+    }
+    Ok(matches)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aes_gcm::aead::generic_array::GenericArray;
+
+    const SECRET: &str = "JBSWY3DPEHPK3PXPJBSWY3DPEHPK3PXP";
+    const PASSWORD: &str = "backup-pass";
+
+    fn to_hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    /// 返回（密文, 标签）
+    fn seal(key: &[u8], nonce: &[u8], plain: &[u8]) -> (Vec<u8>, Vec<u8>) {
+        let cipher = Aes256Gcm::new(GenericArray::from_slice(key));
+        let mut sealed = cipher.encrypt(Nonce::from_slice(nonce), plain).unwrap();
+        let tag = sealed.split_off(sealed.len() - GCM_TAG_LEN);
+        (sealed, tag)
+    }
+
+    fn aegis_db() -> serde_json::Value {
+        serde_json::json!({
+            "version": 2,
+            "entries": [
+                {
+                    "type": "totp",
+                    "name": "alice@gmail.com",
+                    "issuer": "Google",
+                    "info": { "secret": SECRET, "algo": "SHA256", "digits": 8, "period": 30 }
+                },
+                {
+                    "type": "hotp",
+                    "name": "Google:bob@gmail.com",
+                    "issuer": "Google",
+                    "info": { "secret": "jbswy3dp ehpk3pxp", "algo": "SHA1", "digits": 6, "counter": 3 }
+                }
+            ]
+        })
+    }
+
+    fn encrypted_aegis(password: &str) -> String {
+        let salt = [7u8; 32];
+        let master_key = [9u8; 32];
+        let params = scrypt::Params::new(10, 8, 1, 32).unwrap();
+        let mut derived = [0u8; 32];
+        scrypt::scrypt(password.as_bytes(), &salt, &params, &mut derived).unwrap();
+        let (key, key_tag) = seal(&derived, &[1u8; 12], &master_key);
+        let (db, db_tag) = seal(&master_key, &[2u8; 12], aegis_db().to_string().as_bytes());
+        serde_json::json!({
+            "version": 1,
+            "header": {
+                "slots": [
+                    { "type": 1, "key": to_hex(&key), "key_params": { "nonce": to_hex(&[1u8; 12]), "tag": to_hex(&key_tag) },
+                      "n": 1024, "r": 8, "p": 1, "salt": to_hex(&salt) }
+                ],
+                "params": { "nonce": to_hex(&[2u8; 12]), "tag": to_hex(&db_tag) }
+            },
+            "db": STANDARD.encode(db)
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn aegis_plain_and_encrypted_backups_decode() {
+        let plain = serde_json::json!({ "version": 1, "header": { "slots": null, "params": null }, "db": aegis_db() })
+            .to_string();
+        let entries =
+            decode_authenticator_backup(AuthenticatorBackupSource::Aegis, &plain, None).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].email, "alice@gmail.com");
+        assert_eq!(entries[0].params.algorithm, "SHA256");
+        assert_eq!(entries[0].params.digits, 8);
+        assert_eq!(entries[1].email, "bob@gmail.com");
+        assert_eq!(entries[1].otp_type, "hotp");
+        assert_eq!(entries[1].secret, "JBSWY3DPEHPK3PXP");
+
+        let encrypted = encrypted_aegis(PASSWORD);
+        let decrypted = decode_authenticator_backup(
+            AuthenticatorBackupSource::Aegis,
+            &encrypted,
+            Some(PASSWORD),
+        )
+        .unwrap();
+        assert_eq!(decrypted, entries);
+
+        let missing =
+            decode_authenticator_backup(AuthenticatorBackupSource::Aegis, &encrypted, None);
+        assert!(missing.unwrap_err().contains("请提供备份密码"));
+        let wrong =
+            decode_authenticator_backup(AuthenticatorBackupSource::Aegis, &encrypted, Some("nope"));
+        assert!(wrong.unwrap_err().contains("密码错误"));
+
+        // 超出上限的 scrypt 参数在派生前直接拒绝
+        for (n, r, p) in [(1u64 << 21, 8, 1), (1024, 8, 9)] {
+            let mut file: serde_json::Value = serde_json::from_str(&encrypted).unwrap();
+            let slot = &mut file["header"]["slots"][0];
+            slot["n"] = n.into();
+            slot["r"] = r.into();
+            slot["p"] = p.into();
+            let err = decode_authenticator_backup(
+                AuthenticatorBackupSource::Aegis,
+                &file.to_string(),
+                Some(PASSWORD),
+            )
+            .unwrap_err();
+            assert!(err.contains("超出上限"), "{}", err);
+        }
+    }
+
+    #[test]
+    fn twofas_plain_and_encrypted_backups_decode() {
+        let services = serde_json::json!([
+            {
+                "name": "Google",
+                "secret": SECRET,
+                "otp": { "label": "Google:carol@gmail.com", "account": "carol@gmail.com", "digits": 6, "period": 60, "algorithm": "SHA1", "tokenType": "TOTP" }
+            },
+            {
+                "name": "Steam",
+                "secret": SECRET,
+                "otp": { "account": "gamer", "tokenType": "STEAM" }
+            }
+        ]);
+        let plain = serde_json::json!({ "schemaVersion": 4, "services": services }).to_string();
+        let entries =
+            decode_authenticator_backup(AuthenticatorBackupSource::TwoFas, &plain, None).unwrap();
+        assert_eq!(entries[0].email, "carol@gmail.com");
+        assert_eq!(entries[0].issuer, "Google");
+        assert_eq!(entries[0].params.period, 60);
+        assert_eq!(entries[1].issuer, "Steam");
+        assert_eq!(entries[1].otp_type, "steam");
+
+        let salt = [3u8; 32];
+        let iv = [4u8; 12];
+        let mut key = [0u8; 32];
+        pbkdf2::pbkdf2_hmac::<Sha256>(PASSWORD.as_bytes(), &salt, TWOFAS_PBKDF2_ROUNDS, &mut key);
+        let (mut data, tag) = seal(&key, &iv, services.to_string().as_bytes());
+        data.extend(tag);
+        let encrypted = serde_json::json!({
+            "schemaVersion": 4,
+            "services": [],
+            "servicesEncrypted": format!("{}:{}:{}", STANDARD.encode(data), STANDARD.encode(salt), STANDARD.encode(iv)),
+        })
+        .to_string();
+        let decrypted = decode_authenticator_backup(
+            AuthenticatorBackupSource::TwoFas,
+            &encrypted,
+            Some(PASSWORD),
+        )
+        .unwrap();
+        assert_eq!(decrypted, entries);
+    }
+
+    #[test]
+    fn andotp_plain_and_encrypted_backups_decode() {
+        let plain = serde_json::json!([
+            { "secret": SECRET, "issuer": "Google", "label": "dave@gmail.com", "digits": 6, "type": "TOTP", "algorithm": "SHA512", "period": 30 }
+        ])
+        .to_string();
+        let entries =
+            decode_authenticator_backup(AuthenticatorBackupSource::Andotp, &plain, None).unwrap();
+        assert_eq!(entries[0].email, "dave@gmail.com");
+        assert_eq!(entries[0].params.algorithm, "SHA512");
+        assert_eq!(entries[0].otp_type, "totp");
+
+        let iterations = 1_000u32;
+        let salt = [5u8; 12];
+        let iv = [6u8; 12];
+        let mut key = [0u8; 32];
+        pbkdf2::pbkdf2_hmac::<sha1::Sha1>(PASSWORD.as_bytes(), &salt, iterations, &mut key);
+        let (data, tag) = seal(&key, &iv, plain.as_bytes());
+        let mut file = iterations.to_be_bytes().to_vec();
+        file.extend(salt);
+        file.extend(iv);
+        file.extend(data);
+        file.extend(tag);
+        let decrypted = decode_authenticator_backup(
+            AuthenticatorBackupSource::Andotp,
+            &STANDARD.encode(&file),
+            Some(PASSWORD),
+        )
+        .unwrap();
+        assert_eq!(decrypted, entries);
+
+        let mut oversized = file;
+        oversized[..4].copy_from_slice(&(MAX_ANDOTP_PBKDF2_ITERATIONS + 1).to_be_bytes());
+        let err = decode_authenticator_backup(
+            AuthenticatorBackupSource::Andotp,
+            &STANDARD.encode(oversized),
+            Some(PASSWORD),
+        )
+        .unwrap_err();
+        assert!(err.contains("超出上限"));
+    }
+
+    #[test]
+    fn backup_entries_match_accounts_by_email_or_label() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE accounts (id INTEGER PRIMARY KEY, email TEXT NOT NULL, deleted_at TEXT);
+             INSERT INTO accounts (id, email) VALUES (1, 'Alice@Gmail.com'), (2, 'bob@gmail.com');",
+        )
+        .unwrap();
+        let actor = AuditActor {
+            user_id: 1,
+            username: "tester",
+        };
+        let plain = serde_json::json!({ "header": {}, "db": aegis_db() }).to_string();
+        let matches = import_authenticator_backup(
+            &conn,
+            AuthenticatorBackupSource::Aegis,
+            &plain,
+            None,
+            false,
+            actor,
+        )
+        .unwrap();
+        assert_eq!(matches[0].account_id, Some(1));
+        assert_eq!(matches[0].matched_by.as_deref(), Some("email"));
+        assert_eq!(matches[1].account_id, Some(2));
+        assert!(matches.iter().all(|m| !m.applied));
+
+        let empty = serde_json::json!({ "db": { "entries": [] } }).to_string();
+        let err = import_authenticator_backup(
+            &conn,
+            AuthenticatorBackupSource::Aegis,
+            &empty,
+            None,
+            false,
+            actor,
+        );
+        assert!(err.unwrap_err().contains("没有任何条目"));
+    }
+}
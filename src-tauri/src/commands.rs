@@ -1,25 +1,209 @@
-use crate::auth::{self, AuthResult};
+use crate::auth::{self, AuthPolicy, AuthResult, SessionClient, SessionInfo, SessionUser};
+use crate::authenticator_backup::AuthenticatorBackupSource;
 use crate::database::{
-    self, Account, AccountHistory, AccountInput, BackupInfo, Database, ACCOUNT_COLUMNS,
+    self, Account, AccountHistory, AccountInput, AccountPage, AccountPageQuery, BackupInfo,
+    BatchImportReport, Database, ExportPreset, ImportOptions, SecretReveal, User, UserRole,
+    ACCOUNT_COLUMNS,
 };
+use crate::export_file::{write_export_file, ExportFileSummary, ExportScope};
+use crate::export_template::LineTemplate;
+use crate::import_formats::CsvImportConfig;
+use crate::import_parser::ImportParseResult;
+use crate::migration::{MigrationExport, MigrationMatch};
+use crate::password_import::{PasswordImportReport, PasswordManagerSource};
+use crate::qr::{DecodedQrSecret, QrCodeImage};
+use crate::totp::{
+    build_otpauth_uri, AccountTotpResult, TotpParams, TotpVerification, OTPAUTH_ISSUER,
+};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use std::io::Write;
 use tauri::State;
-fn require_auth(session_token: &str) -> Result<(), String> {
-    auth::require_auth(Some(session_token))
+fn require_role(db: &Database, session_token: &str, role: UserRole) -> Result<SessionUser, String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    auth::require_role(&conn, &AuthPolicy::from_env(), Some(session_token), role)
+}
+
+#[tauri::command]
+pub fn check_auth(
+    db: State<Database>,
+    session_token: Option<String>,
+) -> Result<AuthResult, String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    auth::check_auth(&conn, &AuthPolicy::from_env(), session_token.as_deref())
+}
+
+/// 桌面端会话的客户端信息（未指定设备标签时使用默认值）
+fn desktop_client(device_label: Option<String>) -> SessionClient {
+    SessionClient {
+        device_label: Some(device_label.unwrap_or_else(|| "桌面端".to_string())),
+        ip: None,
+    }
+}
+
+#[tauri::command]
+pub fn login(
+    db: State<Database>,
+    username: Option<String>,
+    password: String,
+    device_label: Option<String>,
+) -> Result<AuthResult, String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    auth::login(
+        &conn,
+        &AuthPolicy::from_env(),
+        username.as_deref().unwrap_or("admin"),
+        &password,
+        &desktop_client(device_label),
+    )
+}
+
+#[tauri::command]
+pub fn setup_admin_password(
+    db: State<Database>,
+    username: Option<String>,
+    password: String,
+    device_label: Option<String>,
+) -> Result<AuthResult, String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    auth::setup_admin_password(
+        &conn,
+        &AuthPolicy::from_env(),
+        username.as_deref().unwrap_or("admin"),
+        &password,
+        &desktop_client(device_label),
+    )
+}
+
+/// 锁定保险库，之后需要输入密码解锁
+#[tauri::command]
+pub fn lock(db: State<Database>, session_token: String) -> Result<(), String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let user = auth::active_session_user(&conn, &AuthPolicy::from_env(), Some(&session_token))?;
+    auth::lock(&user)
+}
+
+#[tauri::command]
+pub fn unlock(
+    db: State<Database>,
+    session_token: String,
+    password: String,
+) -> Result<AuthResult, String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    auth::unlock(
+        &conn,
+        &AuthPolicy::from_env(),
+        Some(&session_token),
+        &password,
+    )
+}
+
+#[tauri::command]
+pub fn list_sessions(
+    db: State<Database>,
+    session_token: String,
+) -> Result<Vec<SessionInfo>, String> {
+    let actor = require_role(&db, &session_token, UserRole::Viewer)?;
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    auth::list_sessions(&conn, &AuthPolicy::from_env(), &actor, Some(&session_token))
+}
+
+#[tauri::command]
+pub fn revoke_session(
+    db: State<Database>,
+    session_token: String,
+    session_id: String,
+) -> Result<(), String> {
+    let actor = require_role(&db, &session_token, UserRole::Viewer)?;
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    auth::revoke_session(&conn, &actor, &session_id)
+}
+
+#[tauri::command]
+pub fn revoke_all_sessions(
+    db: State<Database>,
+    session_token: String,
+    include_current: Option<bool>,
+) -> Result<usize, String> {
+    let actor = require_role(&db, &session_token, UserRole::Viewer)?;
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    auth::revoke_all_sessions(
+        &conn,
+        &actor,
+        Some(&session_token),
+        include_current.unwrap_or(false),
+    )
+}
+
+#[tauri::command]
+pub fn change_admin_password(
+    db: State<Database>,
+    session_token: String,
+    current_password: String,
+    new_password: String,
+) -> Result<(), String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    auth::change_admin_password(
+        &conn,
+        &AuthPolicy::from_env(),
+        Some(&session_token),
+        &current_password,
+        &new_password,
+    )
+}
+
+#[tauri::command]
+pub fn list_users(db: State<Database>, session_token: String) -> Result<Vec<User>, String> {
+    require_role(&db, &session_token, UserRole::Admin)?;
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    database::list_users(&conn)
+}
+
+#[tauri::command]
+pub fn create_user(
+    db: State<Database>,
+    session_token: String,
+    username: String,
+    password: String,
+    role: UserRole,
+) -> Result<User, String> {
+    require_role(&db, &session_token, UserRole::Admin)?;
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    auth::create_user(&conn, &username, &password, role)
 }
 
 #[tauri::command]
-pub fn check_auth(session_token: Option<String>) -> Result<AuthResult, String> {
-    auth::check_auth(session_token.as_deref())
+pub fn update_user(
+    db: State<Database>,
+    session_token: String,
+    id: i64,
+    role: Option<UserRole>,
+    disabled: Option<bool>,
+    password: Option<String>,
+) -> Result<User, String> {
+    let actor = require_role(&db, &session_token, UserRole::Admin)?;
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    auth::update_user(
+        &conn,
+        &AuthPolicy::from_env(),
+        &actor,
+        id,
+        role,
+        disabled,
+        password.as_deref(),
+    )
 }
 
 #[tauri::command]
-pub fn login(password: String) -> Result<AuthResult, String> {
-    auth::login(&password)
+pub fn delete_user(db: State<Database>, session_token: String, id: i64) -> Result<(), String> {
+    let actor = require_role(&db, &session_token, UserRole::Admin)?;
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    auth::delete_user(&conn, &actor, id)
 }
 
 #[tauri::command]
-pub fn logout(session_token: Option<String>) -> Result<(), String> {
-    auth::logout(session_token.as_deref())
+pub fn logout(db: State<Database>, session_token: Option<String>) -> Result<(), String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    auth::logout(&conn, session_token.as_deref())
 }
 
 #[tauri::command]
@@ -28,10 +212,46 @@ pub fn get_accounts(
     session_token: String,
     search: Option<String>,
     sold_status: Option<String>,
-) -> Result<Vec<Account>, String> {
-    require_auth(&session_token)?;
+    group: Option<String>,
+    page_query: Option<AccountPageQuery>,
+) -> Result<AccountPage, String> {
+    require_role(&db, &session_token, UserRole::Viewer)?;
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    database::query_accounts_page(
+        &conn,
+        search.as_deref(),
+        sold_status.as_deref(),
+        group.as_deref(),
+        &page_query.unwrap_or_default(),
+    )
+}
+
+#[tauri::command]
+pub fn get_account_groups(
+    db: State<Database>,
+    session_token: String,
+) -> Result<Vec<String>, String> {
+    require_role(&db, &session_token, UserRole::Viewer)?;
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    database::list_account_groups(&conn)
+}
+
+#[tauri::command]
+pub fn get_account_ids(
+    db: State<Database>,
+    session_token: String,
+    search: Option<String>,
+    sold_status: Option<String>,
+    group: Option<String>,
+) -> Result<Vec<i64>, String> {
+    require_role(&db, &session_token, UserRole::Viewer)?;
     let conn = db.0.lock().map_err(|e| e.to_string())?;
-    database::query_accounts(&conn, search.as_deref(), sold_status.as_deref())
+    database::query_account_ids(
+        &conn,
+        search.as_deref(),
+        sold_status.as_deref(),
+        group.as_deref(),
+    )
 }
 
 #[tauri::command]
@@ -40,7 +260,7 @@ pub fn create_account(
     session_token: String,
     account: AccountInput,
 ) -> Result<Account, String> {
-    require_auth(&session_token)?;
+    require_role(&db, &session_token, UserRole::Operator)?;
     let conn = db.0.lock().map_err(|e| e.to_string())?;
     database::create_account(&conn, &account)
 }
@@ -52,21 +272,21 @@ pub fn update_account(
     id: i64,
     account: AccountInput,
 ) -> Result<Account, String> {
-    require_auth(&session_token)?;
+    let user = require_role(&db, &session_token, UserRole::Operator)?;
     let conn = db.0.lock().map_err(|e| e.to_string())?;
-    database::update_account(&conn, id, &account)
+    database::update_account(&conn, id, &account, user.audit_actor())
 }
 
 #[tauri::command]
 pub fn delete_account(db: State<Database>, session_token: String, id: i64) -> Result<(), String> {
-    require_auth(&session_token)?;
+    require_role(&db, &session_token, UserRole::Admin)?;
     let conn = db.0.lock().map_err(|e| e.to_string())?;
     database::delete_account(&conn, id)
 }
 
 #[tauri::command]
 pub fn delete_all_accounts(db: State<Database>, session_token: String) -> Result<usize, String> {
-    require_auth(&session_token)?;
+    require_role(&db, &session_token, UserRole::Admin)?;
     let conn = db.0.lock().map_err(|e| e.to_string())?;
     database::delete_all_accounts(&conn)
 }
@@ -75,10 +295,11 @@ pub fn delete_all_accounts(db: State<Database>, session_token: String) -> Result
 pub fn get_deleted_accounts(
     db: State<Database>,
     session_token: String,
-) -> Result<Vec<Account>, String> {
-    require_auth(&session_token)?;
+    page_query: Option<AccountPageQuery>,
+) -> Result<AccountPage, String> {
+    require_role(&db, &session_token, UserRole::Viewer)?;
     let conn = db.0.lock().map_err(|e| e.to_string())?;
-    database::query_deleted_accounts(&conn)
+    database::query_deleted_accounts_page(&conn, &page_query.unwrap_or_default())
 }
 
 #[tauri::command]
@@ -87,21 +308,21 @@ pub fn restore_account(
     session_token: String,
     id: i64,
 ) -> Result<Account, String> {
-    require_auth(&session_token)?;
+    require_role(&db, &session_token, UserRole::Admin)?;
     let conn = db.0.lock().map_err(|e| e.to_string())?;
     database::restore_account(&conn, id)
 }
 
 #[tauri::command]
 pub fn purge_account(db: State<Database>, session_token: String, id: i64) -> Result<(), String> {
-    require_auth(&session_token)?;
+    require_role(&db, &session_token, UserRole::Admin)?;
     let conn = db.0.lock().map_err(|e| e.to_string())?;
     database::purge_account(&conn, id)
 }
 
 #[tauri::command]
 pub fn purge_all_deleted(db: State<Database>, session_token: String) -> Result<usize, String> {
-    require_auth(&session_token)?;
+    require_role(&db, &session_token, UserRole::Admin)?;
     let conn = db.0.lock().map_err(|e| e.to_string())?;
     database::purge_all_deleted(&conn)
 }
@@ -112,18 +333,15 @@ pub fn create_backup(
     session_token: String,
     reason: Option<String>,
 ) -> Result<String, String> {
-    require_auth(&session_token)?;
+    require_role(&db, &session_token, UserRole::Operator)?;
     let conn = db.0.lock().map_err(|e| e.to_string())?;
     let path = database::create_backup(&conn, reason.as_deref())?;
     Ok(path.to_string_lossy().to_string())
 }
 
 #[tauri::command]
-pub fn list_backups(
-    _db: State<Database>,
-    session_token: String,
-) -> Result<Vec<BackupInfo>, String> {
-    require_auth(&session_token)?;
+pub fn list_backups(db: State<Database>, session_token: String) -> Result<Vec<BackupInfo>, String> {
+    require_role(&db, &session_token, UserRole::Viewer)?;
     database::list_backups()
 }
 
@@ -133,7 +351,7 @@ pub fn restore_backup(
     session_token: String,
     backup_name: String,
 ) -> Result<(), String> {
-    require_auth(&session_token)?;
+    require_role(&db, &session_token, UserRole::Admin)?;
     let conn = db.0.lock().map_err(|e| e.to_string())?;
     database::restore_backup(&conn, &backup_name)
 }
@@ -144,9 +362,9 @@ pub fn toggle_sold_status(
     session_token: String,
     id: i64,
 ) -> Result<Account, String> {
-    require_auth(&session_token)?;
+    let user = require_role(&db, &session_token, UserRole::Operator)?;
     let conn = db.0.lock().map_err(|e| e.to_string())?;
-    database::toggle_sold_status(&conn, id)
+    database::toggle_sold_status(&conn, id, user.audit_actor())
 }
 
 #[tauri::command]
@@ -155,9 +373,9 @@ pub fn toggle_status(
     session_token: String,
     id: i64,
 ) -> Result<Account, String> {
-    require_auth(&session_token)?;
+    let user = require_role(&db, &session_token, UserRole::Operator)?;
     let conn = db.0.lock().map_err(|e| e.to_string())?;
-    database::toggle_status(&conn, id)
+    database::toggle_status(&conn, id, user.audit_actor())
 }
 
 #[tauri::command]
@@ -166,7 +384,7 @@ pub fn get_account_history(
     session_token: String,
     account_id: i64,
 ) -> Result<Vec<AccountHistory>, String> {
-    require_auth(&session_token)?;
+    require_role(&db, &session_token, UserRole::Viewer)?;
     let conn = db.0.lock().map_err(|e| e.to_string())?;
     database::get_account_history(&conn, account_id)
 }
@@ -177,61 +395,288 @@ pub fn get_account_by_id(
     session_token: String,
     id: i64,
 ) -> Result<Account, String> {
-    require_auth(&session_token)?;
+    require_role(&db, &session_token, UserRole::Viewer)?;
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    database::get_redacted_account_by_id(&conn, id)
+}
+
+#[tauri::command]
+pub fn reveal_account_secret(
+    db: State<Database>,
+    session_token: String,
+    id: i64,
+    field: String,
+) -> Result<Option<String>, String> {
+    let user = require_role(&db, &session_token, UserRole::Operator)?;
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    database::reveal_account_secret(&conn, id, &field, user.audit_actor())
+}
+
+/// 查询账号的敏感字段查看记录（仅管理员）
+#[tauri::command]
+pub fn get_secret_reveals(
+    db: State<Database>,
+    session_token: String,
+    account_id: i64,
+) -> Result<Vec<SecretReveal>, String> {
+    require_role(&db, &session_token, UserRole::Admin)?;
     let conn = db.0.lock().map_err(|e| e.to_string())?;
-    database::get_account_by_id(&conn, id)
+    database::get_secret_reveals(&conn, account_id)
 }
 
 #[derive(serde::Serialize)]
 pub struct TotpResult {
     pub code: String,
     pub remaining: u32,
+    pub previous_code: String,
+    pub next_code: String,
 }
 
 #[tauri::command]
-pub fn generate_totp(secret: String, session_token: String) -> Result<TotpResult, String> {
-    require_auth(&session_token)?;
-    let result = crate::totp::generate_totp(&secret)?;
+pub fn generate_totp(
+    db: State<Database>,
+    secret: String,
+    session_token: String,
+    params: Option<TotpParams>,
+) -> Result<TotpResult, String> {
+    require_role(&db, &session_token, UserRole::Operator)?;
+    let result = crate::totp::generate_totp_with_params(&secret, &params.unwrap_or_default())?;
     Ok(TotpResult {
         code: result.code,
         remaining: result.remaining,
+        previous_code: result.previous_code,
+        next_code: result.next_code,
     })
 }
 
-#[derive(serde::Serialize)]
-pub struct BatchImportResult {
-    pub success_count: i32,
-    pub failed_count: i32,
+#[tauri::command]
+pub fn generate_totp_for_account(
+    db: State<Database>,
+    session_token: String,
+    id: i64,
+) -> Result<TotpResult, String> {
+    require_role(&db, &session_token, UserRole::Operator)?;
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let result = crate::totp::generate_totp_for_account(&conn, id)?;
+    Ok(TotpResult {
+        code: result.code,
+        remaining: result.remaining,
+        previous_code: result.previous_code,
+        next_code: result.next_code,
+    })
+}
+
+/// 校验对方输入的验证码，默认容忍 ±1 个周期
+#[tauri::command]
+pub fn verify_totp(
+    db: State<Database>,
+    session_token: String,
+    account_id: i64,
+    code: String,
+    window: Option<u32>,
+) -> Result<TotpVerification, String> {
+    require_role(&db, &session_token, UserRole::Operator)?;
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    crate::totp::verify_totp_for_account(&conn, account_id, &code, window)
+}
+
+#[tauri::command]
+pub fn generate_totp_for_accounts(
+    db: State<Database>,
+    session_token: String,
+    ids: Vec<i64>,
+) -> Result<Vec<AccountTotpResult>, String> {
+    require_role(&db, &session_token, UserRole::Operator)?;
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    crate::totp::generate_totp_for_accounts(&conn, &ids)
+}
+
+/// 解析 Google Authenticator 迁移码并按邮箱匹配账号；`apply` 为 true 时写入匹配账号的 2FA 密钥
+#[tauri::command]
+pub fn import_migration_payload(
+    db: State<Database>,
+    session_token: String,
+    uri: String,
+    apply: bool,
+) -> Result<Vec<MigrationMatch>, String> {
+    let user = require_role(&db, &session_token, UserRole::Operator)?;
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    crate::migration::import_migration_uri(&conn, &uri, apply, user.audit_actor())
+}
+
+/// 为选中账号生成 Google Authenticator 迁移码
+#[tauri::command]
+pub fn export_migration_payload(
+    db: State<Database>,
+    session_token: String,
+    ids: Vec<i64>,
+) -> Result<MigrationExport, String> {
+    let user = require_role(&db, &session_token, UserRole::Operator)?;
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    crate::migration::export_migration_uris(&conn, &ids, user.audit_actor())
+}
+
+/// 解析 Aegis / 2FAS / andOTP 备份（加密备份需提供密码）并按邮箱匹配账号；`apply` 为 true 时写入匹配账号的 2FA 密钥
+#[tauri::command]
+pub fn import_authenticator_backup(
+    db: State<Database>,
+    session_token: String,
+    source: AuthenticatorBackupSource,
+    content: String,
+    password: Option<String>,
+    apply: bool,
+) -> Result<Vec<MigrationMatch>, String> {
+    let user = require_role(&db, &session_token, UserRole::Operator)?;
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    crate::authenticator_backup::import_authenticator_backup(
+        &conn,
+        source,
+        &content,
+        password.as_deref(),
+        apply,
+        user.audit_actor(),
+    )
+}
+
+/// 渲染账号 2FA 密钥的二维码（PNG / SVG），并记录一次密钥查看
+#[tauri::command]
+pub fn get_account_qr_code(
+    db: State<Database>,
+    session_token: String,
+    id: i64,
+    format: Option<String>,
+) -> Result<QrCodeImage, String> {
+    let user = require_role(&db, &session_token, UserRole::Operator)?;
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    crate::qr::render_account_qr_code(&conn, id, format.as_deref(), user.audit_actor())
+}
+
+/// 在本地识别截图中的 2FA 二维码（返回的 `uri` 可直接用作导入行的 secret）
+#[tauri::command]
+pub fn decode_qr_secret(
+    db: State<Database>,
+    session_token: String,
+    image_base64: String,
+) -> Result<DecodedQrSecret, String> {
+    require_role(&db, &session_token, UserRole::Operator)?;
+    crate::qr::decode_qr_secret(&image_base64)
+}
+
+/// 识别截图中的 2FA 二维码并写入指定账号
+#[tauri::command]
+pub fn attach_qr_secret(
+    db: State<Database>,
+    session_token: String,
+    id: i64,
+    image_base64: String,
+) -> Result<Account, String> {
+    let user = require_role(&db, &session_token, UserRole::Operator)?;
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    crate::qr::attach_qr_secret(&conn, id, &image_base64, user.audit_actor())
+}
+
+/// 解析粘贴的导入文本，返回可直接传给 `batch_import` 的账号行与逐行警告
+#[tauri::command]
+pub fn parse_import_text(
+    db: State<Database>,
+    session_token: String,
+    text: String,
+) -> Result<ImportParseResult, String> {
+    require_role(&db, &session_token, UserRole::Operator)?;
+    Ok(crate::import_parser::parse_import_text(&text))
 }
 
+/// 批量导入；`options` 控制已存在邮箱与回收站邮箱的处理方式，返回逐行结果
 #[tauri::command]
 pub fn batch_import(
     db: State<Database>,
     session_token: String,
     accounts: Vec<AccountInput>,
-) -> Result<BatchImportResult, String> {
-    require_auth(&session_token)?;
+    options: Option<ImportOptions>,
+) -> Result<BatchImportReport, String> {
+    let user = require_role(&db, &session_token, UserRole::Operator)?;
     let conn = db.0.lock().map_err(|e| e.to_string())?;
-    let (success_count, failed_count) = database::batch_import(&conn, &accounts)?;
-    Ok(BatchImportResult {
-        success_count,
-        failed_count,
-    })
+    database::batch_import(
+        &conn,
+        &accounts,
+        &options.unwrap_or_default(),
+        user.audit_actor(),
+    )
+}
+
+/// 从 CSV 文本批量导入；`config` 指定分隔符、表头与列映射
+#[tauri::command]
+pub fn batch_import_csv(
+    db: State<Database>,
+    session_token: String,
+    content: String,
+    config: Option<CsvImportConfig>,
+    options: Option<ImportOptions>,
+) -> Result<BatchImportReport, String> {
+    let user = require_role(&db, &session_token, UserRole::Operator)?;
+    let accounts =
+        crate::import_formats::parse_csv_accounts(&content, &config.unwrap_or_default())?;
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    database::batch_import(
+        &conn,
+        &accounts,
+        &options.unwrap_or_default(),
+        user.audit_actor(),
+    )
+}
+
+/// 从 JSON 数组或 JSON Lines 批量导入（键与 `export_accounts_text` 的结构化导出一致）
+#[tauri::command]
+pub fn batch_import_json(
+    db: State<Database>,
+    session_token: String,
+    content: String,
+    options: Option<ImportOptions>,
+) -> Result<BatchImportReport, String> {
+    let user = require_role(&db, &session_token, UserRole::Operator)?;
+    let accounts = crate::import_formats::parse_json_accounts(&content)?;
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    database::batch_import(
+        &conn,
+        &accounts,
+        &options.unwrap_or_default(),
+        user.audit_actor(),
+    )
+}
+
+/// 从 Bitwarden / KeePass / Chrome 导出中导入 Google 登录项
+#[tauri::command]
+pub fn import_password_manager(
+    db: State<Database>,
+    session_token: String,
+    source: PasswordManagerSource,
+    content: String,
+    options: Option<ImportOptions>,
+) -> Result<PasswordImportReport, String> {
+    let user = require_role(&db, &session_token, UserRole::Operator)?;
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    crate::password_import::import_password_export(
+        &conn,
+        source,
+        &content,
+        &options.unwrap_or_default(),
+        user.audit_actor(),
+    )
 }
 
-#[derive(serde::Deserialize, Debug, Clone, Default)]
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, Default)]
 pub struct ExportAccountOrder {
     pub field: Option<String>,
     pub direction: Option<String>,
 }
 
-#[derive(serde::Deserialize, Debug, Clone, Default)]
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, Default)]
 pub struct ExportCategorySort {
     pub field: Option<String>,
     pub direction: Option<String>,
 }
 
-#[derive(serde::Deserialize, Debug, Clone)]
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone)]
 pub struct ExportConfig {
     pub separator: String,
     pub fields: Vec<String>,
@@ -240,8 +685,55 @@ pub struct ExportConfig {
     pub account_order: ExportAccountOrder,
     #[serde(default)]
     pub category_sort: ExportCategorySort,
+    /// 分组标题模板，语法同 `line_template`，可用占位符：`{index}`、`{groupField}`（`{field}`）、
+    /// `{groupValue}`（`{value}`）、`{count}`；无法按模板语法解析时（如旧预设中的
+    /// 未知占位符或单独的花括号）按旧版逐字替换处理，其余内容原样输出
     #[serde(default)]
     pub category_label_template: Option<String>,
+    /// 文本导出的行模板（如 `{email}|{password}{?secret}|2FA: {secret}{/secret}`），
+    /// 设置后取代 `fields` + `separator` 的拼接方式，语法见 `export_template`
+    #[serde(default)]
+    pub line_template: Option<String>,
+    /// 输出格式：`text`（默认，按 separator 拼接）、`csv`（RFC 4180，含表头）、
+    /// `json`（对象数组）、`jsonl`（每行一个对象）或 `xlsx`（返回 base64 编码的工作簿）；
+    /// 结构化格式的键 / 表头为导出字段名
+    #[serde(default)]
+    pub format: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ExportFormat {
+    Text,
+    Csv,
+    Json,
+    Jsonl,
+    Xlsx,
+}
+
+impl ExportFormat {
+    fn from_config(format: Option<&str>) -> Result<Self, String> {
+        let normalized = format
+            .map(|value| value.trim().to_ascii_lowercase())
+            .unwrap_or_default();
+        match normalized.as_str() {
+            "" | "text" | "txt" => Ok(Self::Text),
+            "csv" => Ok(Self::Csv),
+            "json" => Ok(Self::Json),
+            "jsonl" | "ndjson" => Ok(Self::Jsonl),
+            "xlsx" | "excel" => Ok(Self::Xlsx),
+            other => Err(format!("不支持的导出格式: {}", other)),
+        }
+    }
+
+    fn as_name(self) -> &'static str {
+        match self {
+            Self::Text => "text",
+            Self::Csv => "csv",
+            Self::Json => "json",
+            Self::Jsonl => "jsonl",
+            Self::Xlsx => "xlsx",
+        }
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -261,6 +753,7 @@ enum ExportField {
     CreatedAt,
     UpdatedAt,
     DeletedAt,
+    OtpauthUri,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -272,6 +765,90 @@ enum SortDirection {
 const DEFAULT_GROUP_LABEL_TEMPLATE: &str =
     "{index}. {groupField}: {groupValue}（共 {count} 条）";
 
+/// 分组标题模板中的占位符
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum GroupLabelField {
+    Index,
+    GroupField,
+    GroupValue,
+    Count,
+}
+
+impl GroupLabelField {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "index" => Some(Self::Index),
+            "groupField" | "group_field" | "field" => Some(Self::GroupField),
+            "groupValue" | "group_value" | "value" => Some(Self::GroupValue),
+            "count" => Some(Self::Count),
+            _ => None,
+        }
+    }
+}
+
+/// 分组标题模板：优先按模板语法解析，失败时回退为旧版的逐字替换
+enum GroupLabelTemplate {
+    Parsed(LineTemplate<GroupLabelField>),
+    Literal(String),
+}
+
+impl GroupLabelTemplate {
+    fn parse(template: &str) -> Self {
+        LineTemplate::parse(template, GroupLabelField::from_name)
+            .map(Self::Parsed)
+            .unwrap_or_else(|_| Self::Literal(template.to_string()))
+    }
+
+    fn render(&self, value: impl Fn(GroupLabelField) -> String) -> String {
+        match self {
+            Self::Parsed(template) => template.render(value),
+            Self::Literal(template) => {
+                let index = value(GroupLabelField::Index);
+                let field = value(GroupLabelField::GroupField);
+                let group_value = value(GroupLabelField::GroupValue);
+                template
+                    .replace("{index}", &index)
+                    .replace("{groupField}", &field)
+                    .replace("{field}", &field)
+                    .replace("{groupValue}", &group_value)
+                    .replace("{value}", &group_value)
+                    .replace("{count}", &value(GroupLabelField::Count))
+            }
+        }
+    }
+}
+
+/// 文本导出用到的模板（解析一次，逐行渲染）
+struct TextExportTemplates {
+    /// 未设置 `line_template` 时按 `fields` + `separator` 拼接
+    line: Option<LineTemplate<ExportField>>,
+    group_label: GroupLabelTemplate,
+}
+
+impl TextExportTemplates {
+    fn parse(config: &ExportConfig) -> Result<Self, String> {
+        let line = config
+            .line_template
+            .as_deref()
+            .filter(|template| !template.trim().is_empty())
+            .map(|template| {
+                LineTemplate::parse(template, |name| ExportField::from_config(Some(name)))
+                    .map_err(|e| format!("行模板无效: {}", e))
+            })
+            .transpose()?;
+        let group_label_template = config
+            .category_label_template
+            .as_deref()
+            .map(str::trim)
+            .filter(|template| !template.is_empty())
+            .unwrap_or(DEFAULT_GROUP_LABEL_TEMPLATE);
+        Ok(Self {
+            line,
+            group_label: GroupLabelTemplate::parse(group_label_template),
+        })
+    }
+}
+
 impl ExportField {
     fn from_config(field: Option<&str>) -> Option<Self> {
         let normalized = field?.trim().to_ascii_lowercase();
@@ -291,6 +868,7 @@ impl ExportField {
             "created_at" => Some(Self::CreatedAt),
             "updated_at" => Some(Self::UpdatedAt),
             "deleted_at" => Some(Self::DeletedAt),
+            "otpauth_uri" => Some(Self::OtpauthUri),
             _ => None,
         }
     }
@@ -312,6 +890,7 @@ impl ExportField {
             Self::CreatedAt => "created_at",
             Self::UpdatedAt => "updated_at",
             Self::DeletedAt => "deleted_at",
+            Self::OtpauthUri => "otpauth_uri",
         }
     }
 
@@ -364,6 +943,7 @@ impl ExportField {
                 .as_deref()
                 .unwrap_or("")
                 .cmp(right.deleted_at.as_deref().unwrap_or("")),
+            Self::OtpauthUri => account_otpauth_uri(left).cmp(&account_otpauth_uri(right)),
         }
     }
 
@@ -384,7 +964,33 @@ impl ExportField {
             Self::CreatedAt => account.created_at.clone(),
             Self::UpdatedAt => account.updated_at.clone(),
             Self::DeletedAt => account.deleted_at.clone().unwrap_or_default(),
+            Self::OtpauthUri => account_otpauth_uri(account),
+        }
+    }
+}
+
+impl ExportField {
+    /// 结构化导出中的取值：id 为数字，空值为 null
+    fn json_value(self, account: &Account) -> serde_json::Value {
+        if self == Self::Id {
+            return account.id.into();
+        }
+        let value = self.export_value(account);
+        if value.is_empty() {
+            serde_json::Value::Null
+        } else {
+            value.into()
+        }
+    }
+}
+
+/// 账号的 otpauth:// URI（无 2FA 密钥时为空）
+fn account_otpauth_uri(account: &Account) -> String {
+    match account.secret.as_deref().map(str::trim) {
+        Some(secret) if !secret.is_empty() => {
+            build_otpauth_uri(&account.email, Some(OTPAUTH_ISSUER), secret, &account.totp)
         }
+        _ => String::new(),
     }
 }
 
@@ -416,8 +1022,15 @@ fn apply_export_sort(accounts: &mut [Account], config: &ExportConfig) {
     });
 }
 
-fn build_account_export_line(account: &Account, config: &ExportConfig) -> String {
-    let field_values: Vec<String> = config
+fn build_account_export_line(
+    account: &Account,
+    config: &ExportConfig,
+    templates: &TextExportTemplates,
+) -> String {
+    if let Some(template) = &templates.line {
+        return template.render(|field| field.export_value(account));
+    }
+    let field_values: Vec<String> = config
         .fields
         .iter()
         .map(|field| {
@@ -430,7 +1043,7 @@ fn build_account_export_line(account: &Account, config: &ExportConfig) -> String
 }
 
 fn render_group_label(
-    group_label_template: &str,
+    group_label_template: &GroupLabelTemplate,
     group_field: ExportField,
     group_value: &str,
     group_count: usize,
@@ -441,31 +1054,37 @@ fn render_group_label(
     } else {
         group_value
     };
-    let field_name = group_field.as_config_name();
 
-    group_label_template
-        .replace("{index}", &group_index.to_string())
-        .replace("{groupField}", field_name)
-        .replace("{field}", field_name)
-        .replace("{groupValue}", display_value)
-        .replace("{value}", display_value)
-        .replace("{count}", &group_count.to_string())
+    group_label_template.render(|placeholder| match placeholder {
+        GroupLabelField::Index => group_index.to_string(),
+        GroupLabelField::GroupField => group_field.as_config_name().to_string(),
+        GroupLabelField::GroupValue => display_value.to_string(),
+        GroupLabelField::Count => group_count.to_string(),
+    })
 }
 
-fn build_flat_export_output(accounts: &[Account], config: &ExportConfig) -> String {
-    let mut output = String::new();
+fn write_flat_export_output(
+    accounts: impl IntoIterator<Item = Account>,
+    config: &ExportConfig,
+    templates: &TextExportTemplates,
+    out: &mut impl Write,
+) -> std::io::Result<()> {
     for account in accounts {
-        output.push_str(&build_account_export_line(account, config));
-        output.push('\n');
+        writeln!(
+            out,
+            "{}",
+            build_account_export_line(&account, config, templates)
+        )?;
     }
-    output
+    Ok(())
 }
 
-fn build_grouped_export_output(
+/// 按分组字段拆分账号，分组顺序遵循 `category_sort.direction`，组内保持原有顺序
+fn group_export_accounts(
     accounts: Vec<Account>,
     config: &ExportConfig,
     group_field: ExportField,
-) -> String {
+) -> Vec<(String, Vec<Account>)> {
     let mut grouped_accounts: std::collections::HashMap<String, Vec<Account>> =
         std::collections::HashMap::new();
     for account in accounts {
@@ -473,70 +1092,613 @@ fn build_grouped_export_output(
         grouped_accounts.entry(group_key).or_default().push(account);
     }
 
-    let mut group_keys: Vec<String> = grouped_accounts.keys().cloned().collect();
-    group_keys.sort();
+    let mut groups: Vec<(String, Vec<Account>)> = grouped_accounts.into_iter().collect();
+    groups.sort_by(|left, right| left.0.cmp(&right.0));
     if SortDirection::from_config(config.category_sort.direction.as_deref()) == SortDirection::Desc
     {
-        group_keys.reverse();
+        groups.reverse();
     }
+    groups
+}
 
-    let group_label_template = config
-        .category_label_template
-        .as_deref()
-        .map(str::trim)
-        .filter(|template| !template.is_empty())
-        .unwrap_or(DEFAULT_GROUP_LABEL_TEMPLATE);
+/// 按分组键稳定排序（分组顺序遵循 `category_sort.direction`，组内保持原有顺序），返回各组账号数
+fn sort_export_groups(
+    accounts: &mut [Account],
+    config: &ExportConfig,
+    group_field: ExportField,
+) -> std::collections::HashMap<String, usize> {
+    if SortDirection::from_config(config.category_sort.direction.as_deref()) == SortDirection::Desc
+    {
+        accounts.sort_by_cached_key(|account| std::cmp::Reverse(group_field.export_value(account)));
+    } else {
+        accounts.sort_by_cached_key(|account| group_field.export_value(account));
+    }
+    let mut group_sizes = std::collections::HashMap::new();
+    for account in accounts.iter() {
+        *group_sizes
+            .entry(group_field.export_value(account))
+            .or_insert(0) += 1;
+    }
+    group_sizes
+}
 
-    let mut output = String::new();
-    let group_count = group_keys.len();
-    for (index, group_key) in group_keys.into_iter().enumerate() {
-        let Some(accounts_in_group) = grouped_accounts.remove(&group_key) else {
-            continue;
+/// 写出已按分组键排好序的账号，分组切换时写入分组标题（`group_sizes` 为各组账号数）
+fn write_grouped_export_output(
+    accounts: impl IntoIterator<Item = Account>,
+    group_sizes: &std::collections::HashMap<String, usize>,
+    config: &ExportConfig,
+    group_field: ExportField,
+    templates: &TextExportTemplates,
+    out: &mut impl Write,
+) -> std::io::Result<()> {
+    let mut current_group: Option<String> = None;
+    let mut group_index = 0;
+    for account in accounts {
+        let group_key = group_field.export_value(&account);
+        if current_group.as_deref() != Some(group_key.as_str()) {
+            if group_index > 0 {
+                writeln!(out)?;
+            }
+            group_index += 1;
+            let label = render_group_label(
+                &templates.group_label,
+                group_field,
+                &group_key,
+                group_sizes.get(&group_key).copied().unwrap_or_default(),
+                group_index,
+            );
+            writeln!(out, "{}", label)?;
+            current_group = Some(group_key);
+        }
+        writeln!(
+            out,
+            "{}",
+            build_account_export_line(&account, config, templates)
+        )?;
+    }
+    Ok(())
+}
+
+/// 导出统计汇总（文本导出的头部与 XLSX 的汇总表共用）
+struct ExportStats {
+    exported_at: String,
+    total: usize,
+    pro_count: usize,
+    normal_count: usize,
+    sold_count: usize,
+    unsold_count: usize,
+    /// 标签分布（按数量降序）
+    groups: Vec<(String, usize)>,
+    /// 国家分布（按数量降序）
+    countries: Vec<(String, usize)>,
+    /// 注册年份分布（按年份升序）
+    years: Vec<(String, usize)>,
+}
+
+/// 逐个账号累计统计（流式导出时在预扫描中累计，无需保留账号列表）
+#[derive(Default)]
+struct ExportStatsCounter {
+    total: usize,
+    pro_count: usize,
+    sold_count: usize,
+    group_counts: std::collections::HashMap<String, usize>,
+    country_counts: std::collections::HashMap<String, usize>,
+    year_counts: std::collections::HashMap<String, usize>,
+}
+
+impl ExportStatsCounter {
+    fn add(&mut self, acc: &Account) {
+        self.total += 1;
+        if acc.status == "pro" {
+            self.pro_count += 1;
+        }
+        if acc.sold_status == "sold" {
+            self.sold_count += 1;
+        }
+        if let Some(ref g) = acc.group_name {
+            for tag in g.split(|c: char| c == ',' || c == '，' || c.is_whitespace()) {
+                let tag = tag.trim();
+                if !tag.is_empty() {
+                    *self.group_counts.entry(tag.to_string()).or_insert(0) += 1;
+                }
+            }
+        }
+        if let Some(ref c) = acc.country {
+            if !c.is_empty() {
+                *self.country_counts.entry(c.clone()).or_insert(0) += 1;
+            }
+        }
+        if let Some(ref y) = acc.reg_year {
+            if !y.is_empty() {
+                *self.year_counts.entry(y.clone()).or_insert(0) += 1;
+            }
+        }
+    }
+
+    fn finish(self) -> ExportStats {
+        let by_count_desc = |counts: std::collections::HashMap<String, usize>| {
+            let mut entries: Vec<_> = counts.into_iter().collect();
+            entries.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+            entries
         };
+        let mut years: Vec<_> = self.year_counts.into_iter().collect();
+        years.sort();
+
+        ExportStats {
+            exported_at: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            total: self.total,
+            pro_count: self.pro_count,
+            normal_count: self.total - self.pro_count,
+            sold_count: self.sold_count,
+            unsold_count: self.total - self.sold_count,
+            groups: by_count_desc(self.group_counts),
+            countries: by_count_desc(self.country_counts),
+            years,
+        }
+    }
+}
+
+impl ExportStats {
+    fn collect(accounts: &[Account]) -> Self {
+        let mut counter = ExportStatsCounter::default();
+        for acc in accounts {
+            counter.add(acc);
+        }
+        counter.finish()
+    }
 
-        output.push_str(&render_group_label(
-            group_label_template,
-            group_field,
-            &group_key,
-            accounts_in_group.len(),
-            index + 1,
+    /// 各分布小节：(标题, 条目)
+    fn distributions(&self) -> [(&'static str, &[(String, usize)]); 3] {
+        [
+            ("标签分布", &self.groups),
+            ("国家分布", &self.countries),
+            ("注册年份分布", &self.years),
+        ]
+    }
+
+    fn render_text(&self) -> String {
+        let mut output = String::new();
+        output.push_str("========== 账号统计汇总 ==========\n");
+        output.push_str(&format!("导出时间: {}\n", self.exported_at));
+        output.push_str(&format!("总账号数: {}\n", self.total));
+        output.push_str(&format!(
+            "Pro账号: {} | 普通账号: {}\n",
+            self.pro_count, self.normal_count
+        ));
+        output.push_str(&format!(
+            "已售出: {} | 未售出: {}\n",
+            self.sold_count, self.unsold_count
         ));
-        output.push('\n');
+        for (title, entries) in self.distributions() {
+            if entries.is_empty() {
+                continue;
+            }
+            output.push_str(&format!("\n{}:\n", title));
+            for (name, count) in entries {
+                output.push_str(&format!("  - {}: {} 个\n", name, count));
+            }
+        }
+        output.push_str("=====================================\n\n");
+        output
+    }
+}
+
+/// XLSX 汇总表名称
+const XLSX_SUMMARY_SHEET: &str = "统计汇总";
+/// 未分组导出时的工作表名称
+const XLSX_ACCOUNTS_SHEET: &str = "账号";
+/// Excel 工作表名称的最大长度
+const XLSX_SHEET_NAME_MAX_CHARS: usize = 31;
+
+/// 生成合法且不重复的工作表名称（去除 `[]:*?/\`，截断到 31 个字符）
+fn xlsx_sheet_name(value: &str, used: &mut Vec<String>) -> String {
+    let cleaned: String = value
+        .chars()
+        .map(|c| match c {
+            '[' | ']' | ':' | '*' | '?' | '/' | '\\' => '_',
+            other => other,
+        })
+        .collect();
+    let mut base = cleaned.trim().trim_matches('\'').to_string();
+    if base.is_empty() {
+        base = "未设置".to_string();
+    }
+    // "History" 是 Excel 保留名称
+    if base.eq_ignore_ascii_case("history") {
+        base.push('_');
+    }
 
-        for account in &accounts_in_group {
-            output.push_str(&build_account_export_line(account, config));
-            output.push('\n');
+    let mut suffix_index = 1;
+    loop {
+        let suffix = if suffix_index == 1 {
+            String::new()
+        } else {
+            format!(" ({})", suffix_index)
+        };
+        let max_base_chars = XLSX_SHEET_NAME_MAX_CHARS - suffix.chars().count();
+        let candidate: String = base.chars().take(max_base_chars).collect::<String>() + &suffix;
+        if !used
+            .iter()
+            .any(|name| name.to_lowercase() == candidate.to_lowercase())
+        {
+            used.push(candidate.clone());
+            return candidate;
         }
+        suffix_index += 1;
+    }
+}
 
-        if index + 1 < group_count {
-            output.push('\n');
+fn write_xlsx_summary_sheet(
+    workbook: &mut rust_xlsxwriter::Workbook,
+    stats: &ExportStats,
+    bold: &rust_xlsxwriter::Format,
+) -> Result<(), rust_xlsxwriter::XlsxError> {
+    let sheet = workbook.add_worksheet();
+    sheet.set_name(XLSX_SUMMARY_SHEET)?;
+
+    let overview = [
+        ("Pro账号", stats.pro_count),
+        ("普通账号", stats.normal_count),
+        ("已售出", stats.sold_count),
+        ("未售出", stats.unsold_count),
+    ];
+    sheet.write_string_with_format(0, 0, "导出时间", bold)?;
+    sheet.write_string(0, 1, &stats.exported_at)?;
+    sheet.write_string_with_format(1, 0, "总账号数", bold)?;
+    sheet.write_number(1, 1, stats.total as f64)?;
+    let mut row: u32 = 2;
+    for (label, count) in overview {
+        sheet.write_string_with_format(row, 0, label, bold)?;
+        sheet.write_number(row, 1, count as f64)?;
+        row += 1;
+    }
+
+    for (title, entries) in stats.distributions() {
+        if entries.is_empty() {
+            continue;
+        }
+        row += 1;
+        sheet.write_string_with_format(row, 0, title, bold)?;
+        row += 1;
+        for (name, count) in entries {
+            sheet.write_string(row, 0, name)?;
+            sheet.write_number(row, 1, *count as f64)?;
+            row += 1;
         }
     }
+    sheet.autofit();
+    Ok(())
+}
+
+fn write_xlsx_accounts_sheet(
+    workbook: &mut rust_xlsxwriter::Workbook,
+    name: &str,
+    accounts: &[Account],
+    config: &ExportConfig,
+    bold: &rust_xlsxwriter::Format,
+) -> Result<(), rust_xlsxwriter::XlsxError> {
+    let sheet = workbook.add_worksheet();
+    sheet.set_name(name)?;
 
-    output
+    let fields: Vec<Option<ExportField>> = config
+        .fields
+        .iter()
+        .map(|field| ExportField::from_config(Some(field.as_str())))
+        .collect();
+    for (col, field) in config.fields.iter().enumerate() {
+        sheet.write_string_with_format(0, col as u16, field.trim(), bold)?;
+    }
+    for (index, account) in accounts.iter().enumerate() {
+        let row = index as u32 + 1;
+        for (col, field) in fields.iter().enumerate() {
+            match field {
+                Some(ExportField::Id) => sheet.write_number(row, col as u16, account.id as f64)?,
+                Some(field) => sheet.write_string(row, col as u16, field.export_value(account))?,
+                None => continue,
+            };
+        }
+    }
+    sheet.set_freeze_panes(1, 0)?;
+    sheet.autofit();
+    Ok(())
 }
 
-fn build_export_accounts_output(mut accounts: Vec<Account>, config: &ExportConfig) -> String {
+/// XLSX 工作簿：可选的统计汇总表 + 每个分组一个工作表（未分组时为单个工作表），
+/// 表头为 `ExportConfig.fields`
+fn build_xlsx_export_output(
+    mut accounts: Vec<Account>,
+    config: &ExportConfig,
+    stats: Option<&ExportStats>,
+) -> Result<Vec<u8>, String> {
     apply_export_sort(&mut accounts, config);
+    let groups = match ExportField::from_config(config.category_sort.field.as_deref()) {
+        Some(group_field) => group_export_accounts(accounts, config, group_field),
+        None => vec![(XLSX_ACCOUNTS_SHEET.to_string(), accounts)],
+    };
 
-    if let Some(group_field) = ExportField::from_config(config.category_sort.field.as_deref()) {
-        return build_grouped_export_output(accounts, config, group_field);
+    let mut workbook = rust_xlsxwriter::Workbook::new();
+    let bold = rust_xlsxwriter::Format::new().set_bold();
+    let mut used_names = vec![XLSX_SUMMARY_SHEET.to_string()];
+    if let Some(stats) = stats {
+        write_xlsx_summary_sheet(&mut workbook, stats, &bold).map_err(|e| e.to_string())?;
     }
+    for (group_key, accounts_in_group) in &groups {
+        let name = xlsx_sheet_name(group_key, &mut used_names);
+        write_xlsx_accounts_sheet(&mut workbook, &name, accounts_in_group, config, &bold)
+            .map_err(|e| e.to_string())?;
+    }
+    workbook.save_to_buffer().map_err(|e| e.to_string())
+}
 
-    build_flat_export_output(&accounts, config)
+/// RFC 4180 CSV：逗号分隔、CRLF 换行、按需加引号，首行为字段名表头（不分组、不含统计）
+fn write_csv_export_output(
+    accounts: impl IntoIterator<Item = Account>,
+    config: &ExportConfig,
+    out: &mut impl Write,
+) -> Result<(), String> {
+    let fields: Vec<Option<ExportField>> = config
+        .fields
+        .iter()
+        .map(|field| ExportField::from_config(Some(field.as_str())))
+        .collect();
+
+    let mut writer = csv::WriterBuilder::new()
+        .terminator(csv::Terminator::CRLF)
+        .from_writer(out);
+    writer
+        .write_record(config.fields.iter().map(|field| field.trim()))
+        .map_err(|e| e.to_string())?;
+    for account in accounts {
+        writer
+            .write_record(fields.iter().map(|field| {
+                field
+                    .map(|parsed_field| parsed_field.export_value(&account))
+                    .unwrap_or_default()
+            }))
+            .map_err(|e| e.to_string())?;
+    }
+
+    writer.flush().map_err(|e| e.to_string())
 }
 
-#[tauri::command]
-pub fn export_database_sql(db: State<Database>, session_token: String) -> Result<String, String> {
-    require_auth(&session_token)?;
-    let conn = db.0.lock().map_err(|e| e.to_string())?;
-    let mut output = String::new();
+/// JSON / JSONL 导出中的一行：按配置字段顺序输出键值，忽略无法识别的字段
+struct JsonExportRow<'a> {
+    account: Account,
+    fields: &'a [ExportField],
+}
+
+impl serde::Serialize for JsonExportRow<'_> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeMap;
+        let mut map = serializer.serialize_map(Some(self.fields.len()))?;
+        for field in self.fields {
+            map.serialize_entry(field.as_config_name(), &field.json_value(&self.account))?;
+        }
+        map.end()
+    }
+}
+
+fn write_json_export_output(
+    accounts: impl IntoIterator<Item = Account>,
+    config: &ExportConfig,
+    format: ExportFormat,
+    out: &mut impl Write,
+) -> Result<(), String> {
+    let fields: Vec<ExportField> = config
+        .fields
+        .iter()
+        .filter_map(|field| ExportField::from_config(Some(field.as_str())))
+        .collect();
+    let rows = accounts.into_iter().map(|account| JsonExportRow {
+        account,
+        fields: &fields,
+    });
+
+    if format == ExportFormat::Json {
+        // 逐个序列化数组元素，不在内存中拼出完整 JSON
+        use serde::Serializer as _;
+        serde_json::Serializer::pretty(&mut *out)
+            .collect_seq(rows)
+            .map_err(|e| e.to_string())?;
+        return writeln!(out).map_err(|e| e.to_string());
+    }
+
+    for row in rows {
+        serde_json::to_writer(&mut *out, &row).map_err(|e| e.to_string())?;
+        writeln!(out).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+fn write_export_accounts_output(
+    mut accounts: Vec<Account>,
+    config: &ExportConfig,
+    out: &mut impl Write,
+) -> Result<(), String> {
+    let templates = TextExportTemplates::parse(config)?;
+    apply_export_sort(&mut accounts, config);
+
+    match ExportField::from_config(config.category_sort.field.as_deref()) {
+        Some(group_field) => {
+            let group_sizes = sort_export_groups(&mut accounts, config, group_field);
+            write_grouped_export_output(
+                accounts,
+                &group_sizes,
+                config,
+                group_field,
+                &templates,
+                out,
+            )
+        }
+        None => write_flat_export_output(accounts, config, &templates, out),
+    }
+    .map_err(|e| e.to_string())
+}
+
+/// 按格式写出账号导出内容（XLSX 为二进制工作簿），返回导出的账号数
+fn write_accounts_export(
+    mut accounts: Vec<Account>,
+    config: &ExportConfig,
+    format: ExportFormat,
+    out: &mut impl Write,
+) -> Result<usize, String> {
+    let rows = accounts.len();
+    // CSV / JSON 只输出账号本身（排序生效，不分组、不含统计）；XLSX 按分组拆分工作表
+    match format {
+        ExportFormat::Text => {
+            if config.include_stats {
+                out.write_all(ExportStats::collect(&accounts).render_text().as_bytes())
+                    .map_err(|e| e.to_string())?;
+            }
+            // 在内存中完成排序/分组（逐行读取数据库的版本见 `write_accounts_export_from_db`）
+            write_export_accounts_output(accounts, config, out)?;
+        }
+        ExportFormat::Csv => {
+            apply_export_sort(&mut accounts, config);
+            write_csv_export_output(accounts, config, out)?;
+        }
+        ExportFormat::Json | ExportFormat::Jsonl => {
+            apply_export_sort(&mut accounts, config);
+            write_json_export_output(accounts, config, format, out)?;
+        }
+        ExportFormat::Xlsx => {
+            let stats = config
+                .include_stats
+                .then(|| ExportStats::collect(&accounts));
+            let workbook = build_xlsx_export_output(accounts, config, stats.as_ref())?;
+            out.write_all(&workbook).map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(rows)
+}
+
+/// 按导出条件从数据库逐行读取并写出，不在内存中收集解密后的账号，返回导出的账号数。
+/// 排序 / 分组下推到 SQL（仅限白名单列）；XLSX 或按加密字段排序 / 分组时回退为先查询再写出
+fn write_accounts_export_from_db(
+    conn: &rusqlite::Connection,
+    account_ids: Option<&[i64]>,
+    search: Option<&str>,
+    sold_status: Option<&str>,
+    config: &ExportConfig,
+    format: ExportFormat,
+    out: &mut impl Write,
+) -> Result<usize, String> {
+    let group_field = match format {
+        ExportFormat::Text => ExportField::from_config(config.category_sort.field.as_deref()),
+        _ => None,
+    };
+    let mut sort = Vec::new();
+    if let Some(group_field) = group_field {
+        sort.push(database::ExportSortKey {
+            field: group_field.as_config_name(),
+            descending: SortDirection::from_config(config.category_sort.direction.as_deref())
+                == SortDirection::Desc,
+            as_text: true,
+        });
+    }
+    if let Some(order_field) = ExportField::from_config(config.account_order.field.as_deref()) {
+        sort.push(database::ExportSortKey {
+            field: order_field.as_config_name(),
+            descending: SortDirection::from_config(config.account_order.direction.as_deref())
+                == SortDirection::Desc,
+            as_text: false,
+        });
+    }
+    let streamable = format != ExportFormat::Xlsx
+        && sort
+            .iter()
+            .all(|key| database::export_sort_expr(key.field, key.as_text).is_some());
+    if !streamable {
+        let accounts = database::query_accounts_for_export(conn, account_ids, search, sold_status)?;
+        return write_accounts_export(accounts, config, format, out);
+    }
+
+    let templates = match format {
+        ExportFormat::Text => Some(TextExportTemplates::parse(config)?),
+        _ => None,
+    };
+    // 统计头部与分组标题需要总数：先不解密地预扫描一遍
+    let mut stats = None;
+    let mut group_sizes = std::collections::HashMap::new();
+    if format == ExportFormat::Text && (config.include_stats || group_field.is_some()) {
+        database::read_accounts_for_export(
+            conn,
+            account_ids,
+            search,
+            sold_status,
+            &[],
+            true,
+            |rows| {
+                let mut counter = ExportStatsCounter::default();
+                for account in rows {
+                    let account = account?;
+                    counter.add(&account);
+                    if let Some(group_field) = group_field {
+                        *group_sizes
+                            .entry(group_field.export_value(&account))
+                            .or_insert(0) += 1;
+                    }
+                }
+                stats = config.include_stats.then(|| counter.finish());
+                Ok(())
+            },
+        )?;
+    }
+
+    database::read_accounts_for_export(
+        conn,
+        account_ids,
+        search,
+        sold_status,
+        &sort,
+        false,
+        |rows| {
+            let mut error = None;
+            let mut written = 0;
+            let accounts = rows
+                .map_while(|row| row.map_err(|e| error = Some(e)).ok())
+                .inspect(|_| written += 1);
+            match (format, &templates) {
+                (ExportFormat::Text, Some(templates)) => {
+                    if let Some(stats) = &stats {
+                        out.write_all(stats.render_text().as_bytes())
+                            .map_err(|e| e.to_string())?;
+                    }
+                    match group_field {
+                        Some(group_field) => write_grouped_export_output(
+                            accounts,
+                            &group_sizes,
+                            config,
+                            group_field,
+                            templates,
+                            out,
+                        ),
+                        None => write_flat_export_output(accounts, config, templates, out),
+                    }
+                    .map_err(|e| e.to_string())?;
+                }
+                (ExportFormat::Csv, _) => write_csv_export_output(accounts, config, out)?,
+                _ => write_json_export_output(accounts, config, format, out)?,
+            }
+            match error {
+                Some(e) => Err(e),
+                None => Ok(written),
+            }
+        },
+    )
+}
+
+/// 逐行写出 SQL 格式的数据库导出，返回 INSERT 语句数
+fn write_database_sql(conn: &rusqlite::Connection, out: &mut impl Write) -> Result<usize, String> {
+    let mut inserts = 0;
 
     let now = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
-    output.push_str(&format!(
+    write!(
+        out,
         "-- Google Manager Database Export\n-- Export Time: {}\n-- Version: 0.1.0\n\n",
         now
-    ));
+    )
+    .map_err(|e| e.to_string())?;
 
     // 导出 CREATE TABLE 语句
     let mut stmt = conn.prepare(
@@ -550,7 +1712,7 @@ pub fn export_database_sql(db: State<Database>, session_token: String) -> Result
         .collect();
 
     for schema in &schemas {
-        output.push_str(&format!("{};\n\n", schema));
+        write!(out, "{};\n\n", schema).map_err(|e| e.to_string())?;
     }
 
     // 导出 accounts 表数据
@@ -579,6 +1741,9 @@ pub fn export_database_sql(db: State<Database>, session_token: String) -> Result
                 row.get::<_, String>(12)?,
                 row.get::<_, String>(13)?,
                 row.get::<_, Option<String>>(14)?,
+                row.get::<_, Option<String>>(15)?,
+                row.get::<_, Option<i64>>(16)?,
+                row.get::<_, Option<i64>>(17)?,
             ))
         })
         .map_err(|e| e.to_string())?;
@@ -588,6 +1753,10 @@ pub fn export_database_sql(db: State<Database>, session_token: String) -> Result
         Some(v) => format!("'{}'", escape(v)),
         None => "NULL".to_string(),
     };
+    let sql_int = |opt: &Option<i64>| match opt {
+        Some(v) => v.to_string(),
+        None => "NULL".to_string(),
+    };
 
     for row in rows {
         let (
@@ -606,21 +1775,28 @@ pub fn export_database_sql(db: State<Database>, session_token: String) -> Result
             created_at,
             updated_at,
             deleted_at,
+            totp_algorithm,
+            totp_digits,
+            totp_period,
         ) = row.map_err(|e| e.to_string())?;
-        output.push_str(&format!(
-            "INSERT INTO accounts (id, email, password, recovery, phone, secret, reg_year, country, group_name, remark, status, sold_status, created_at, updated_at, deleted_at) VALUES ({}, '{}', '{}', {}, {}, {}, {}, {}, {}, {}, '{}', '{}', '{}', '{}', {});\n",
+        writeln!(
+            out,
+            "INSERT INTO accounts (id, email, password, recovery, phone, secret, reg_year, country, group_name, remark, status, sold_status, created_at, updated_at, deleted_at, totp_algorithm, totp_digits, totp_period) VALUES ({}, '{}', '{}', {}, {}, {}, {}, {}, {}, {}, '{}', '{}', '{}', '{}', {}, {}, {}, {});",
             id, escape(&email), escape(&password),
             sql_val(&recovery), sql_val(&phone), sql_val(&secret),
             sql_val(&reg_year), sql_val(&country), sql_val(&group_name), sql_val(&remark),
             escape(&status), escape(&sold_status), escape(&created_at), escape(&updated_at), sql_val(&deleted_at),
-        ));
+            sql_val(&totp_algorithm), sql_int(&totp_digits), sql_int(&totp_period),
+        )
+        .map_err(|e| e.to_string())?;
+        inserts += 1;
     }
 
-    output.push('\n');
+    writeln!(out).map_err(|e| e.to_string())?;
 
     // 导出 account_history 表数据
     let mut stmt = conn.prepare(
-        "SELECT id, account_id, field_name, old_value, new_value, changed_at FROM account_history ORDER BY id"
+        "SELECT id, account_id, field_name, old_value, new_value, changed_at, actor_user_id, actor_username FROM account_history ORDER BY id"
     ).map_err(|e| e.to_string())?;
 
     let rows = stmt
@@ -632,23 +1808,159 @@ pub fn export_database_sql(db: State<Database>, session_token: String) -> Result
                 row.get::<_, Option<String>>(3)?,
                 row.get::<_, Option<String>>(4)?,
                 row.get::<_, String>(5)?,
+                row.get::<_, Option<i64>>(6)?,
+                row.get::<_, Option<String>>(7)?,
             ))
         })
         .map_err(|e| e.to_string())?;
 
     for row in rows {
-        let (id, account_id, field_name, old_value, new_value, changed_at) =
-            row.map_err(|e| e.to_string())?;
-        output.push_str(&format!(
-            "INSERT INTO account_history (id, account_id, field_name, old_value, new_value, changed_at) VALUES ({}, {}, '{}', {}, {}, '{}');\n",
+        let (
+            id,
+            account_id,
+            field_name,
+            old_value,
+            new_value,
+            changed_at,
+            actor_user_id,
+            actor_username,
+        ) = row.map_err(|e| e.to_string())?;
+        writeln!(
+            out,
+            "INSERT INTO account_history (id, account_id, field_name, old_value, new_value, changed_at, actor_user_id, actor_username) VALUES ({}, {}, '{}', {}, {}, '{}', {}, {});",
             id, account_id, escape(&field_name),
             sql_val(&old_value), sql_val(&new_value), escape(&changed_at),
-        ));
+            sql_int(&actor_user_id), sql_val(&actor_username),
+        )
+        .map_err(|e| e.to_string())?;
+        inserts += 1;
     }
 
-    Ok(output)
+    Ok(inserts)
+}
+
+#[tauri::command]
+pub fn export_database_sql(db: State<Database>, session_token: String) -> Result<String, String> {
+    require_role(&db, &session_token, UserRole::Operator)?;
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let mut output = Vec::new();
+    write_database_sql(&conn, &mut output)?;
+    String::from_utf8(output).map_err(|e| e.to_string())
 }
 
+/// 把 SQL 格式的数据库导出直接写入 `path`（需位于数据目录或允许的导出目录内），只返回文件摘要
+#[tauri::command]
+pub fn export_database_sql_to_file(
+    db: State<Database>,
+    session_token: String,
+    path: String,
+) -> Result<ExportFileSummary, String> {
+    let user = require_role(&db, &session_token, UserRole::Operator)?;
+    // 在独立的只读快照上写出，写文件期间不占用共享连接
+    let snapshot = database::open_read_snapshot()?;
+    let summary = write_export_file(&ExportScope::from_env(), &path, |out| {
+        write_database_sql(&snapshot, out)
+    })?;
+    drop(snapshot);
+
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    database::record_export_audit(
+        &conn,
+        "database",
+        "sql",
+        summary.rows,
+        Some(&summary.path),
+        user.audit_actor(),
+    )?;
+    Ok(summary)
+}
+
+/// 导出审计中记录的范围：指定账号 ID 时只记 ID，否则记筛选条件（与导出查询的取舍一致）
+fn export_audit_scope(
+    account_ids: Option<&[i64]>,
+    search: Option<&str>,
+    sold_status: Option<&str>,
+) -> String {
+    match account_ids.filter(|ids| !ids.is_empty()) {
+        Some(ids) => serde_json::json!({ "account_ids": ids }),
+        None => serde_json::json!({ "search": search, "sold_status": sold_status }),
+    }
+    .to_string()
+}
+
+/// 序列化导出配置用于保存预设（保存前校验输出格式）
+fn export_preset_value(config: &ExportConfig) -> Result<serde_json::Value, String> {
+    ExportFormat::from_config(config.format.as_deref())?;
+    serde_json::to_value(config).map_err(|e| e.to_string())
+}
+
+/// 确定本次导出配置：显式传入的配置或按名称读取的已保存预设（二选一）
+fn resolve_export_config(
+    conn: &rusqlite::Connection,
+    config: Option<ExportConfig>,
+    preset: Option<&str>,
+) -> Result<ExportConfig, String> {
+    match (config, preset.filter(|name| !name.trim().is_empty())) {
+        (Some(_), Some(_)) => Err("导出配置与预设名称只能指定一个".to_string()),
+        (Some(config), None) => Ok(config),
+        (None, Some(name)) => {
+            let preset = database::get_export_preset(conn, name)?;
+            serde_json::from_value(preset.config)
+                .map_err(|e| format!("导出预设 {} 的配置无效: {}", preset.name, e))
+        }
+        (None, None) => Err("请提供导出配置或预设名称".to_string()),
+    }
+}
+
+#[tauri::command]
+pub fn list_export_presets(
+    db: State<Database>,
+    session_token: String,
+) -> Result<Vec<ExportPreset>, String> {
+    require_role(&db, &session_token, UserRole::Viewer)?;
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    database::list_export_presets(&conn)
+}
+
+#[tauri::command]
+pub fn create_export_preset(
+    db: State<Database>,
+    session_token: String,
+    name: String,
+    config: ExportConfig,
+) -> Result<ExportPreset, String> {
+    require_role(&db, &session_token, UserRole::Operator)?;
+    let value = export_preset_value(&config)?;
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    database::create_export_preset(&conn, &name, &value)
+}
+
+#[tauri::command]
+pub fn update_export_preset(
+    db: State<Database>,
+    session_token: String,
+    id: i64,
+    name: String,
+    config: ExportConfig,
+) -> Result<ExportPreset, String> {
+    require_role(&db, &session_token, UserRole::Operator)?;
+    let value = export_preset_value(&config)?;
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    database::update_export_preset(&conn, id, &name, &value)
+}
+
+#[tauri::command]
+pub fn delete_export_preset(
+    db: State<Database>,
+    session_token: String,
+    id: i64,
+) -> Result<(), String> {
+    require_role(&db, &session_token, UserRole::Operator)?;
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    database::delete_export_preset(&conn, id)
+}
+
+/// 导出账号；`config` 与 `preset`（已保存的预设名称）二选一
 #[tauri::command]
 pub fn export_accounts_text(
     db: State<Database>,
@@ -656,10 +1968,13 @@ pub fn export_accounts_text(
     account_ids: Option<Vec<i64>>,
     search: Option<String>,
     sold_status: Option<String>,
-    config: ExportConfig,
+    config: Option<ExportConfig>,
+    preset: Option<String>,
 ) -> Result<String, String> {
-    require_auth(&session_token)?;
+    let user = require_role(&db, &session_token, UserRole::Operator)?;
     let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let config = resolve_export_config(&conn, config, preset.as_deref())?;
+    let format = ExportFormat::from_config(config.format.as_deref())?;
 
     let accounts = database::query_accounts_for_export(
         &conn,
@@ -667,99 +1982,75 @@ pub fn export_accounts_text(
         search.as_deref(),
         sold_status.as_deref(),
     )?;
+    database::record_export_audit(
+        &conn,
+        &export_audit_scope(
+            account_ids.as_deref(),
+            search.as_deref(),
+            sold_status.as_deref(),
+        ),
+        format.as_name(),
+        accounts.len(),
+        None,
+        user.audit_actor(),
+    )?;
+    drop(conn);
 
-    let mut output = String::new();
-
-    // 统计汇总
-    if config.include_stats {
-        let now = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
-        let total = accounts.len();
-        let pro_count = accounts.iter().filter(|a| a.status == "pro").count();
-        let normal_count = total - pro_count;
-        let sold_count = accounts.iter().filter(|a| a.sold_status == "sold").count();
-        let unsold_count = total - sold_count;
-
-        output.push_str(&format!("========== 账号统计汇总 ==========\n"));
-        output.push_str(&format!("导出时间: {}\n", now));
-        output.push_str(&format!("总账号数: {}\n", total));
-        output.push_str(&format!(
-            "Pro账号: {} | 普通账号: {}\n",
-            pro_count, normal_count
-        ));
-        output.push_str(&format!(
-            "已售出: {} | 未售出: {}\n",
-            sold_count, unsold_count
-        ));
-
-        // 标签分布
-        let mut group_counts: std::collections::HashMap<String, usize> =
-            std::collections::HashMap::new();
-        for acc in &accounts {
-            if let Some(ref g) = acc.group_name {
-                if !g.is_empty() {
-                    for tag in g.split(|c: char| c == ',' || c == '，' || c.is_whitespace()) {
-                        let tag = tag.trim();
-                        if !tag.is_empty() {
-                            *group_counts.entry(tag.to_string()).or_insert(0) += 1;
-                        }
-                    }
-                }
-            }
-        }
-        if !group_counts.is_empty() {
-            output.push_str("\n标签分布:\n");
-            let mut groups: Vec<_> = group_counts.into_iter().collect();
-            groups.sort_by(|a, b| b.1.cmp(&a.1));
-            for (name, count) in &groups {
-                output.push_str(&format!("  - {}: {} 个\n", name, count));
-            }
-        }
-
-        // 国家分布
-        let mut country_counts: std::collections::HashMap<String, usize> =
-            std::collections::HashMap::new();
-        for acc in &accounts {
-            if let Some(ref c) = acc.country {
-                if !c.is_empty() {
-                    *country_counts.entry(c.clone()).or_insert(0) += 1;
-                }
-            }
-        }
-        if !country_counts.is_empty() {
-            output.push_str("\n国家分布:\n");
-            let mut countries: Vec<_> = country_counts.into_iter().collect();
-            countries.sort_by(|a, b| b.1.cmp(&a.1));
-            for (name, count) in &countries {
-                output.push_str(&format!("  - {}: {} 个\n", name, count));
-            }
-        }
-
-        // 注册年份分布
-        let mut year_counts: std::collections::HashMap<String, usize> =
-            std::collections::HashMap::new();
-        for acc in &accounts {
-            if let Some(ref y) = acc.reg_year {
-                if !y.is_empty() {
-                    *year_counts.entry(y.clone()).or_insert(0) += 1;
-                }
-            }
-        }
-        if !year_counts.is_empty() {
-            output.push_str("\n注册年份分布:\n");
-            let mut years: Vec<_> = year_counts.into_iter().collect();
-            years.sort_by(|a, b| a.0.cmp(&b.0));
-            for (year, count) in &years {
-                output.push_str(&format!("  - {}: {} 个\n", year, count));
-            }
-        }
-
-        output.push_str("=====================================\n\n");
+    let mut output = Vec::new();
+    write_accounts_export(accounts, &config, format, &mut output)?;
+    if format == ExportFormat::Xlsx {
+        return Ok(STANDARD.encode(output));
     }
+    String::from_utf8(output).map_err(|e| e.to_string())
+}
 
-    // 导出账号数据（在内存中完成排序/分组，避免动态 SQL 带来的注入风险）
-    output.push_str(&build_export_accounts_output(accounts, &config));
+/// 导出账号并直接写入 `path`（需位于数据目录或允许的导出目录内），只返回文件摘要
+#[allow(clippy::too_many_arguments)]
+#[tauri::command]
+pub fn export_accounts_to_file(
+    db: State<Database>,
+    session_token: String,
+    account_ids: Option<Vec<i64>>,
+    search: Option<String>,
+    sold_status: Option<String>,
+    config: Option<ExportConfig>,
+    preset: Option<String>,
+    path: String,
+) -> Result<ExportFileSummary, String> {
+    let user = require_role(&db, &session_token, UserRole::Operator)?;
+    // 逐行读取并写入文件：读取走独立的只读快照，预扫描统计与写出的数据一致，
+    // 且写文件期间不占用共享连接的锁
+    let snapshot = database::open_read_snapshot()?;
+    let config = resolve_export_config(&snapshot, config, preset.as_deref())?;
+    let format = ExportFormat::from_config(config.format.as_deref())?;
+
+    let summary = write_export_file(&ExportScope::from_env(), &path, |out| {
+        write_accounts_export_from_db(
+            &snapshot,
+            account_ids.as_deref(),
+            search.as_deref(),
+            sold_status.as_deref(),
+            &config,
+            format,
+            out,
+        )
+    })?;
+    drop(snapshot);
 
-    Ok(output)
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    database::record_export_audit(
+        &conn,
+        &export_audit_scope(
+            account_ids.as_deref(),
+            search.as_deref(),
+            sold_status.as_deref(),
+        ),
+        format.as_name(),
+        summary.rows,
+        Some(&summary.path),
+        user.audit_actor(),
+    )?;
+    Ok(summary)
 }
 
 #[cfg(test)]
@@ -790,7 +2081,10 @@ mod tests {
                 sold_status TEXT DEFAULT \"unsold\",\
                 created_at TEXT DEFAULT CURRENT_TIMESTAMP,\
                 updated_at TEXT DEFAULT CURRENT_TIMESTAMP,\
-                deleted_at TEXT\
+                deleted_at TEXT,\
+                totp_algorithm TEXT DEFAULT \"SHA1\",\
+                totp_digits INTEGER DEFAULT 6,\
+                totp_period INTEGER DEFAULT 30\
             )",
             [],
         )
@@ -803,6 +2097,59 @@ mod tests {
         conn
     }
 
+    #[test]
+    fn test_database_sql_round_trips_history_actor() {
+        let conn = setup_export_query_test_db();
+        conn.execute(
+            "CREATE TABLE account_history (\
+                id INTEGER PRIMARY KEY AUTOINCREMENT,\
+                account_id INTEGER NOT NULL,\
+                field_name TEXT NOT NULL,\
+                old_value TEXT,\
+                new_value TEXT,\
+                changed_at TEXT DEFAULT CURRENT_TIMESTAMP,\
+                actor_user_id INTEGER,\
+                actor_username TEXT\
+            )",
+            [],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO accounts (id, email, password) VALUES (1, 'a@gmail.com', 'enc')",
+            [],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO account_history (account_id, field_name, old_value, new_value, changed_at, actor_user_id, actor_username) VALUES (1, 'remark', 'old', 'it''s new', '2026-01-01 00:00:00', 7, 'operator')",
+            [],
+        )
+        .unwrap();
+
+        let mut dump = Vec::new();
+        assert_eq!(write_database_sql(&conn, &mut dump).unwrap(), 2);
+
+        let restored = Connection::open_in_memory().unwrap();
+        restored
+            .execute_batch(&String::from_utf8(dump).unwrap())
+            .unwrap();
+        let row: (String, Option<String>, Option<i64>, Option<String>) = restored
+            .query_row(
+                "SELECT field_name, new_value, actor_user_id, actor_username FROM account_history WHERE account_id = 1",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+            )
+            .unwrap();
+        assert_eq!(
+            row,
+            (
+                "remark".to_string(),
+                Some("it's new".to_string()),
+                Some(7),
+                Some("operator".to_string())
+            )
+        );
+    }
+
     fn build_test_account(id: i64, email: &str, country: Option<&str>) -> Account {
         Account {
             id,
@@ -820,9 +2167,25 @@ mod tests {
             created_at: "2026-01-01 00:00:00".to_string(),
             updated_at: "2026-01-01 00:00:00".to_string(),
             deleted_at: None,
+            totp: TotpParams::default(),
         }
     }
 
+    fn export_to_string(write: impl FnOnce(&mut Vec<u8>) -> Result<(), String>) -> String {
+        let mut output = Vec::new();
+        write(&mut output).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    fn build_export_accounts_output(
+        accounts: Vec<Account>,
+        config: &ExportConfig,
+    ) -> Result<String, String> {
+        let mut output = Vec::new();
+        write_export_accounts_output(accounts, config, &mut output)?;
+        Ok(String::from_utf8(output).unwrap())
+    }
+
     fn build_base_export_config() -> ExportConfig {
         ExportConfig {
             separator: "----".to_string(),
@@ -831,6 +2194,8 @@ mod tests {
             account_order: ExportAccountOrder::default(),
             category_sort: ExportCategorySort::default(),
             category_label_template: None,
+            line_template: None,
+            format: None,
         }
     }
 
@@ -873,7 +2238,7 @@ mod tests {
         ];
         let config = build_base_export_config();
 
-        let output = build_export_accounts_output(accounts, &config);
+        let output = build_export_accounts_output(accounts, &config).unwrap();
 
         assert_eq!(output, "c@example.com\na@example.com\n");
     }
@@ -889,11 +2254,11 @@ mod tests {
         config.account_order.field = Some("email".to_string());
         config.account_order.direction = Some("asc".to_string());
 
-        let asc_output = build_export_accounts_output(accounts.clone(), &config);
+        let asc_output = build_export_accounts_output(accounts.clone(), &config).unwrap();
         assert_eq!(asc_output, "a@example.com\nb@example.com\nc@example.com\n");
 
         config.account_order.direction = Some("desc".to_string());
-        let desc_output = build_export_accounts_output(accounts, &config);
+        let desc_output = build_export_accounts_output(accounts, &config).unwrap();
         assert_eq!(desc_output, "c@example.com\nb@example.com\na@example.com\n");
     }
 
@@ -912,7 +2277,7 @@ mod tests {
         config.category_label_template =
             Some("分组:{index}:{groupField}:{groupValue}:{count}".to_string());
 
-        let output = build_export_accounts_output(accounts, &config);
+        let output = build_export_accounts_output(accounts, &config).unwrap();
 
         assert_eq!(
             output,
@@ -927,11 +2292,57 @@ mod tests {
         config.category_sort.field = Some("country".to_string());
         config.category_label_template = Some("   ".to_string());
 
-        let output = build_export_accounts_output(accounts, &config);
+        let output = build_export_accounts_output(accounts, &config).unwrap();
 
         assert_eq!(output, "1. country: 未设置（共 1 条）\na@example.com\n");
     }
 
+    #[test]
+    fn test_export_output_group_template_falls_back_to_literal_replace() {
+        let accounts = vec![build_test_account(1, "a@example.com", Some("US"))];
+        let mut config = build_base_export_config();
+        config.category_sort.field = Some("country".to_string());
+
+        // 前端默认模板按模板语法渲染
+        config.category_label_template =
+            Some("{index}. {groupField}: {groupValue}（共 {count} 条）".to_string());
+        let output = build_export_accounts_output(accounts.clone(), &config).unwrap();
+        assert_eq!(output, "1. country: US（共 1 条）\na@example.com\n");
+
+        // 旧预设中的未知占位符与单独花括号原样保留，已知占位符照常替换
+        config.category_label_template = Some("{category} {groupValue} ({count})".to_string());
+        let output = build_export_accounts_output(accounts.clone(), &config).unwrap();
+        assert_eq!(output, "{category} US (1)\na@example.com\n");
+
+        config.category_label_template = Some("{ {value} }".to_string());
+        let output = build_export_accounts_output(accounts, &config).unwrap();
+        assert_eq!(output, "{ US }\na@example.com\n");
+    }
+
+    #[test]
+    fn test_export_output_renders_line_template() {
+        let mut with_secret = build_test_account(1, "a@example.com", Some("US"));
+        with_secret.secret = Some("JBSWY3DPEHPK3PXP".to_string());
+        let without_secret = build_test_account(2, "b@example.com", None);
+        let mut config = build_base_export_config();
+        config.line_template =
+            Some("{email}|{password|mask:2}{?secret} 2FA: {secret|last:4}{/secret}".to_string());
+        config.category_sort.field = Some("country".to_string());
+        config.category_label_template = Some("== {value|lower} ({count}) ==".to_string());
+
+        let output =
+            build_export_accounts_output(vec![with_secret, without_secret], &config).unwrap();
+
+        assert_eq!(
+            output,
+            "== 未设置 (1) ==\nb@example.com|*wd\n\n== us (1) ==\na@example.com|*wd 2FA: 3PXP\n"
+        );
+
+        config.line_template = Some("{email}|{unknown}".to_string());
+        let err = build_export_accounts_output(Vec::new(), &config).unwrap_err();
+        assert!(err.starts_with("行模板无效"));
+    }
+
     #[test]
     fn test_export_output_ignores_invalid_sort_and_group_config() {
         let accounts = vec![
@@ -944,57 +2355,326 @@ mod tests {
         config.category_sort.field = Some("also_not_exists".to_string());
         config.category_sort.direction = Some("invalid_direction".to_string());
 
-        let output = build_export_accounts_output(accounts, &config);
+        let output = build_export_accounts_output(accounts, &config).unwrap();
 
         assert_eq!(output, "c@example.com\na@example.com\n");
     }
 
     #[test]
-    fn test_totp_generation() {
-        let secret = "JBSWY3DPEHPK3PXPJBSWY3DPEHPK3PXP".to_string();
-        let result = crate::totp::generate_totp(&secret);
+    fn test_export_output_emits_otpauth_uri_field() {
+        let mut with_secret = build_test_account(1, "a@example.com", None);
+        with_secret.secret = Some("JBSWY3DPEHPK3PXP".to_string());
+        with_secret.totp.digits = 8;
+        let without_secret = build_test_account(2, "b@example.com", None);
+        let mut config = build_base_export_config();
+        config.fields = vec!["email".to_string(), "otpauth_uri".to_string()];
 
-        assert!(result.is_ok());
-        let totp_result = result.unwrap();
-        assert_eq!(totp_result.code.len(), 6);
-        assert!(totp_result.remaining <= 30);
-        assert!(totp_result.remaining >= 1);
+        let output =
+            build_export_accounts_output(vec![with_secret, without_secret], &config).unwrap();
+
+        assert_eq!(
+            output,
+            "a@example.com----otpauth://totp/Google:a%40example.com?secret=JBSWY3DPEHPK3PXP&issuer=Google&algorithm=SHA1&digits=8&period=30\nb@example.com----\n"
+        );
     }
 
     #[test]
-    fn test_totp_with_spaces() {
-        let secret = "JBSW Y3DP EHPK 3PXP JBSW Y3DP EHPK 3PXP".to_string();
-        let result = crate::totp::generate_totp(&secret);
+    fn test_csv_export_quotes_fields_and_round_trips() {
+        let mut first = build_test_account(1, "a@example.com", Some("US"));
+        first.remark = Some("含----分隔符, \"引号\"\n换行".to_string());
+        let second = build_test_account(2, "b@example.com", None);
+        let mut config = build_base_export_config();
+        config.fields = vec![
+            "email".to_string(),
+            "password".to_string(),
+            "remark".to_string(),
+            "country".to_string(),
+        ];
+        config.account_order = ExportAccountOrder {
+            field: Some("email".to_string()),
+            direction: Some("desc".to_string()),
+        };
+        config.format = Some("csv".to_string());
+        assert_eq!(
+            ExportFormat::from_config(config.format.as_deref()),
+            Ok(ExportFormat::Csv)
+        );
+        assert!(ExportFormat::from_config(Some("xml")).is_err());
 
-        assert!(result.is_ok());
-        let totp_result = result.unwrap();
-        assert_eq!(totp_result.code.len(), 6);
+        let mut accounts = vec![first, second];
+        apply_export_sort(&mut accounts, &config);
+        let output =
+            export_to_string(|out| write_csv_export_output(accounts.clone(), &config, out));
+        assert_eq!(
+            output,
+            "email,password,remark,country\r\nb@example.com,pwd,,\r\na@example.com,pwd,\"含----分隔符, \"\"引号\"\"\n换行\",US\r\n"
+        );
+
+        let parsed =
+            crate::import_formats::parse_csv_accounts(&output, &CsvImportConfig::default())
+                .unwrap();
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[1].remark, accounts[1].remark);
+        assert_eq!(parsed[1].country.as_deref(), Some("US"));
+    }
+
+    #[test]
+    fn test_json_export_round_trips_through_importer() {
+        let mut account = build_test_account(3, "a@example.com", None);
+        account.remark = Some("备注----含分隔符".to_string());
+        account.secret = Some("JBSWY3DPEHPK3PXP".to_string());
+        account.totp.digits = 8;
+        let mut config = build_base_export_config();
+        config.fields = vec![
+            "id".to_string(),
+            "email".to_string(),
+            "password".to_string(),
+            "remark".to_string(),
+            "country".to_string(),
+            "otpauth_uri".to_string(),
+            "bogus".to_string(),
+        ];
+
+        let jsonl = export_to_string(|out| {
+            write_json_export_output([account.clone()], &config, ExportFormat::Jsonl, out)
+        });
+        assert!(jsonl.starts_with(
+            "{\"id\":3,\"email\":\"a@example.com\",\"password\":\"pwd\",\"remark\":\"备注----含分隔符\",\"country\":null,"
+        ));
+        assert_eq!(jsonl.lines().count(), 1);
+
+        let json = export_to_string(|out| {
+            write_json_export_output([account.clone()], &config, ExportFormat::Json, out)
+        });
+        for output in [json, jsonl] {
+            let parsed = crate::import_formats::parse_json_accounts(&output).unwrap();
+            assert_eq!(parsed.len(), 1);
+            assert_eq!(parsed[0].remark, account.remark);
+            assert_eq!(parsed[0].country, None);
+            let (secret, params) = database::resolve_secret_and_params(
+                parsed[0].secret.as_deref(),
+                None,
+                &TotpParams::default(),
+            )
+            .unwrap();
+            assert_eq!(secret.as_deref(), Some("JBSWY3DPEHPK3PXP"));
+            assert_eq!(params.digits, 8);
+        }
     }
 
     #[test]
-    fn test_totp_lowercase() {
-        let secret = "jbswy3dpehpk3pxpjbswy3dpehpk3pxp".to_string();
-        let result = crate::totp::generate_totp(&secret);
+    fn test_export_stats_text_and_xlsx_workbook() {
+        let mut first = build_test_account(1, "a@example.com", Some("US"));
+        first.status = "pro".to_string();
+        first.group_name = Some("vip，批发".to_string());
+        first.reg_year = Some("2021".to_string());
+        let second = build_test_account(2, "b@example.com", Some("US"));
+        let third = build_test_account(3, "c@example.com", Some("a/b:c"));
+
+        let stats = ExportStats::collect(&[first.clone(), second.clone(), third.clone()]);
+        assert_eq!(
+            (stats.total, stats.pro_count, stats.unsold_count),
+            (3, 1, 3)
+        );
+        assert_eq!(
+            stats.countries,
+            vec![("US".to_string(), 2), ("a/b:c".to_string(), 1)]
+        );
+        let text = stats.render_text();
+        assert!(text.starts_with("========== 账号统计汇总 ==========\n"));
+        assert!(text.contains("Pro账号: 1 | 普通账号: 2\n"));
+        assert!(text.contains("\n标签分布:\n  - vip: 1 个\n  - 批发: 1 个\n"));
+        assert!(text.contains("\n注册年份分布:\n  - 2021: 1 个\n"));
 
-        assert!(result.is_ok());
+        let mut config = build_base_export_config();
+        config.fields = vec!["id".to_string(), "email".to_string()];
+        config.category_sort = ExportCategorySort {
+            field: Some("country".to_string()),
+            direction: Some("asc".to_string()),
+        };
+        let workbook =
+            build_xlsx_export_output(vec![first, second, third], &config, Some(&stats)).unwrap();
+        assert_eq!(&workbook[..2], b"PK");
     }
 
     #[test]
-    fn test_totp_invalid_secret() {
-        let secret = "invalid!@#".to_string();
-        let result = crate::totp::generate_totp(&secret);
+    fn test_xlsx_sheet_names_are_sanitized_and_unique() {
+        let mut used = vec![XLSX_SUMMARY_SHEET.to_string()];
+        assert_eq!(xlsx_sheet_name("a/b:c", &mut used), "a_b_c");
+        assert_eq!(xlsx_sheet_name("", &mut used), "未设置");
+        assert_eq!(xlsx_sheet_name("A_B_C", &mut used), "A_B_C (2)");
+        assert_eq!(xlsx_sheet_name("统计汇总", &mut used), "统计汇总 (2)");
+        assert_eq!(xlsx_sheet_name("'History'", &mut used), "History_");
+
+        let long = "x".repeat(40);
+        assert_eq!(xlsx_sheet_name(&long, &mut used), "x".repeat(31));
+        assert_eq!(
+            xlsx_sheet_name(&long, &mut used),
+            format!("{} (2)", "x".repeat(27))
+        );
+    }
 
-        assert!(result.is_err());
+    #[test]
+    fn test_export_config_resolves_from_saved_preset() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute(
+            "CREATE TABLE export_presets (\
+                id INTEGER PRIMARY KEY AUTOINCREMENT,\
+                name TEXT NOT NULL UNIQUE,\
+                config TEXT NOT NULL,\
+                created_at TEXT DEFAULT CURRENT_TIMESTAMP,\
+                updated_at TEXT DEFAULT CURRENT_TIMESTAMP\
+            )",
+            [],
+        )
+        .unwrap();
+        let mut config = build_base_export_config();
+        config.separator = "|".to_string();
+        config.format = Some("csv".to_string());
+        database::create_export_preset(&conn, "CSV", &export_preset_value(&config).unwrap())
+            .unwrap();
+
+        let resolved = resolve_export_config(&conn, None, Some("CSV")).unwrap();
+        assert_eq!(resolved.separator, "|");
+        assert_eq!(resolved.fields, vec!["email".to_string()]);
+        assert_eq!(resolved.format.as_deref(), Some("csv"));
+
+        let explicit =
+            resolve_export_config(&conn, Some(build_base_export_config()), None).unwrap();
+        assert_eq!(explicit.separator, "----");
+        assert!(resolve_export_config(&conn, Some(config.clone()), Some("CSV")).is_err());
+        assert!(resolve_export_config(&conn, None, None).is_err());
+        assert!(resolve_export_config(&conn, None, Some("missing"))
+            .unwrap_err()
+            .contains("不存在"));
+
+        config.format = Some("pdf".to_string());
+        assert!(export_preset_value(&config).is_err());
     }
 
     #[test]
-    fn test_totp_consistency() {
-        let secret = "JBSWY3DPEHPK3PXPJBSWY3DPEHPK3PXP".to_string();
-        let result1 = crate::totp::generate_totp(&secret).unwrap();
-        let result2 = crate::totp::generate_totp(&secret).unwrap();
+    fn test_export_from_db_matches_in_memory_export() {
+        let conn = setup_export_query_test_db();
+        for (email, password, country, remark) in [
+            ("b@example.com", "pwd-b", Some("US"), Some("keep")),
+            ("a@example.com", "pwd-a", None, None),
+            ("d@example.com", "pwd-d", Some("CN"), Some("keep")),
+            ("c@example.com", "pwd-c", Some("US"), None),
+        ] {
+            conn.execute(
+                "INSERT INTO accounts (email, password, secret, country, remark, group_name) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    email,
+                    encrypt_for_test(password),
+                    encrypt_for_test("JBSWY3DPEHPK3PXP"),
+                    country,
+                    remark,
+                    "tag1, tag2"
+                ],
+            )
+            .unwrap();
+        }
 
-        if result1.remaining == result2.remaining {
-            assert_eq!(result1.code, result2.code);
+        let export = |ids: Option<&[i64]>, config: &ExportConfig, from_db: bool| {
+            let format = ExportFormat::from_config(config.format.as_deref()).unwrap();
+            let output = export_to_string(|out| {
+                if from_db {
+                    write_accounts_export_from_db(&conn, ids, None, None, config, format, out)
+                } else {
+                    let accounts =
+                        database::query_accounts_for_export(&conn, ids, None, None).unwrap();
+                    write_accounts_export(accounts, config, format, out)
+                }
+                .map(|_| ())
+            });
+            // 统计头部含导出时间，比较时忽略
+            output
+                .lines()
+                .filter(|line| !line.starts_with("导出时间"))
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+
+        let mut configs = Vec::new();
+        let mut grouped = build_base_export_config();
+        grouped.line_template = Some("{email}|{password}|{secret|last:4}".to_string());
+        grouped.category_sort.field = Some("country".to_string());
+        grouped.category_sort.direction = Some("desc".to_string());
+        grouped.account_order.field = Some("email".to_string());
+        grouped.include_stats = true;
+        configs.push(grouped.clone());
+        grouped.category_sort.field = Some("id".to_string());
+        grouped.category_sort.direction = Some("asc".to_string());
+        grouped.include_stats = false;
+        configs.push(grouped);
+        let mut csv = build_base_export_config();
+        csv.fields = vec![
+            "email".to_string(),
+            "password".to_string(),
+            "remark".to_string(),
+        ];
+        csv.account_order.field = Some("remark".to_string());
+        csv.account_order.direction = Some("desc".to_string());
+        csv.format = Some("csv".to_string());
+        configs.push(csv.clone());
+        csv.format = Some("json".to_string());
+        configs.push(csv.clone());
+        // 按加密字段排序时回退为内存排序
+        csv.account_order.field = Some("password".to_string());
+        csv.format = Some("jsonl".to_string());
+        configs.push(csv);
+
+        let ids: Vec<i64> = vec![1, 3, 4];
+        for config in &configs {
+            for ids in [None, Some(ids.as_slice())] {
+                let streamed = export(ids, config, true);
+                assert!(streamed.contains("pwd-"));
+                assert_eq!(streamed, export(ids, config, false), "{:?}", config);
+            }
         }
+        let streamed = export(None, &configs[0], true);
+        assert!(streamed.starts_with("========== 账号统计汇总 =========="));
+        assert!(streamed.contains("总账号数: 4"));
+        assert!(streamed.contains("1. country: US（共 2 条）"));
+    }
+
+    #[test]
+    fn test_accounts_export_streams_to_file() {
+        let dir = tempfile::tempdir().unwrap();
+        std::env::set_var(crate::export_file::EXPORT_DIR_ENV, dir.path());
+        let accounts = vec![
+            build_test_account(2, "b@example.com", None),
+            build_test_account(1, "a@example.com", None),
+        ];
+        let mut config = build_base_export_config();
+        config.account_order.field = Some("email".to_string());
+        config.format = Some("jsonl".to_string());
+        let path = dir.path().join("accounts.jsonl");
+
+        let summary = write_export_file(&ExportScope::from_env(), path.to_str().unwrap(), |out| {
+            write_accounts_export(accounts, &config, ExportFormat::Jsonl, out)
+        })
+        .unwrap();
+
+        let written = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(
+            written,
+            "{\"email\":\"a@example.com\"}\n{\"email\":\"b@example.com\"}\n"
+        );
+        assert_eq!(summary.rows, 2);
+        assert_eq!(summary.bytes, written.len() as u64);
+        assert_eq!(summary.sha256.len(), 64);
+    }
+
+    #[test]
+    fn test_export_audit_scope_prefers_account_ids() {
+        assert_eq!(
+            export_audit_scope(Some(&[3, 5]), Some("gmail"), Some("sold")),
+            r#"{"account_ids":[3,5]}"#
+        );
+        assert_eq!(
+            export_audit_scope(Some(&[]), Some("gmail"), None),
+            r#"{"search":"gmail","sold_status":null}"#
+        );
     }
 }
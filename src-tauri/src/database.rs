@@ -1,6 +1,7 @@
 use crate::crypto;
 use crate::key_manager;
-use rusqlite::{params, Connection, Result, Row};
+use crate::totp::{self, TotpParams};
+use rusqlite::{params, Connection, OptionalExtension, Result, Row};
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use std::fs;
@@ -25,9 +26,10 @@ pub struct Account {
     pub created_at: String,
     pub updated_at: String,
     pub deleted_at: Option<String>,
+    pub totp: TotpParams,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct AccountInput {
     pub email: String,
     pub password: String,
@@ -38,6 +40,9 @@ pub struct AccountInput {
     pub country: Option<String>,
     pub group_name: Option<String>,
     pub remark: Option<String>,
+    /// TOTP 参数；创建/导入时为空则使用默认值，更新时为空则保留原值
+    #[serde(default)]
+    pub totp: Option<TotpParams>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -48,6 +53,34 @@ pub struct AccountHistory {
     pub old_value: Option<String>,
     pub new_value: Option<String>,
     pub changed_at: String,
+    /// 操作人（旧记录为空）
+    pub actor_user_id: Option<i64>,
+    pub actor_username: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SecretReveal {
+    pub id: i64,
+    pub account_id: i64,
+    pub field_name: String,
+    pub channel: String,
+    pub actor_user_id: Option<i64>,
+    pub actor_username: Option<String>,
+    pub revealed_at: String,
+}
+
+/// 审计记录中的操作人（会话用户 ID 与用户名快照）
+#[derive(Debug, Clone, Copy)]
+pub struct AuditActor<'a> {
+    pub user_id: i64,
+    pub username: &'a str,
+}
+
+/// 解密后的 2FA 密钥及其 TOTP 参数
+#[derive(Debug, Clone)]
+pub struct AccountSecret {
+    pub secret: String,
+    pub params: TotpParams,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -58,10 +91,211 @@ pub struct BackupInfo {
     pub checksum: Option<String>,
 }
 
+/// 命名导出预设（`config` 为导出配置 JSON，由导出命令负责解析与校验）
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ExportPreset {
+    pub id: i64,
+    pub name: String,
+    pub config: serde_json::Value,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// 用户角色：权限依次递增，高级角色拥有低级角色的全部权限
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum UserRole {
+    /// 只能查看账号列表
+    Viewer,
+    /// 可编辑、切换状态、导入导出
+    Operator,
+    /// 可删除 / 彻底删除账号、恢复备份、管理用户
+    Admin,
+}
+
+impl UserRole {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "viewer" => Ok(Self::Viewer),
+            "operator" => Ok(Self::Operator),
+            "admin" => Ok(Self::Admin),
+            other => Err(format!("未知的用户角色: {}", other)),
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Viewer => "viewer",
+            Self::Operator => "operator",
+            Self::Admin => "admin",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Viewer => "只读",
+            Self::Operator => "操作员",
+            Self::Admin => "管理员",
+        }
+    }
+}
+
+/// 登录用户（不含密码哈希）
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct User {
+    pub id: i64,
+    pub username: String,
+    pub role: UserRole,
+    pub disabled: bool,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// 列表分页与排序参数（均可选）
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct AccountPageQuery {
+    pub page: Option<u32>,
+    pub page_size: Option<u32>,
+    pub sort_field: Option<String>,
+    pub sort_direction: Option<String>,
+}
+
+/// 分页查询结果
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AccountPage {
+    pub items: Vec<Account>,
+    pub total: i64,
+    pub page: u32,
+    pub page_size: u32,
+}
+
+/// 导入时邮箱已存在（活跃账号）的处理方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DuplicateMode {
+    /// 跳过该行
+    #[default]
+    Skip,
+    /// 用导入行中非空的字段覆盖现有值
+    Overwrite,
+    /// 只填充现有账号中为空的字段
+    Merge,
+}
+
+/// 导入时邮箱只存在于回收站中的处理方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecycledMode {
+    /// 新建账号，回收站中的旧记录保持不变
+    #[default]
+    Insert,
+    /// 恢复回收站中的账号，再按 `DuplicateMode` 处理字段
+    Restore,
+    /// 跳过该行
+    Skip,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImportOptions {
+    #[serde(default)]
+    pub duplicate_mode: DuplicateMode,
+    #[serde(default)]
+    pub recycled_mode: RecycledMode,
+    /// 预览模式：在事务内完整执行后回滚，不落库
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportRowStatus {
+    Inserted,
+    Updated,
+    Restored,
+    SkippedDuplicate,
+    SkippedDeleted,
+    Failed,
+}
+
+/// 导入行与已有邮箱的冲突类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportConflict {
+    /// 与活跃账号邮箱重复
+    Active,
+    /// 与回收站中的账号邮箱重复
+    Recycled,
+}
+
+/// 导入行失败的类别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportFailure {
+    InvalidSecret,
+    InvalidPhone,
+    Other,
+}
+
+/// 批量导入的单行结果（`index` 为输入中的下标）
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportRowResult {
+    pub index: usize,
+    pub email: String,
+    pub status: ImportRowStatus,
+    pub account_id: Option<i64>,
+    pub conflict: Option<ImportConflict>,
+    pub failure: Option<ImportFailure>,
+    pub reason: Option<String>,
+}
+
+/// 批量导入结果；`success_count` 为新增、更新与恢复的行数之和
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BatchImportReport {
+    pub dry_run: bool,
+    pub success_count: i32,
+    pub failed_count: i32,
+    pub inserted_count: i32,
+    pub updated_count: i32,
+    pub restored_count: i32,
+    pub skipped_count: i32,
+    pub rows: Vec<ImportRowResult>,
+}
+
 pub struct Database(pub Mutex<Connection>);
 
 /// SELECT 列列表常量
-pub const ACCOUNT_COLUMNS: &str = "id, email, password, recovery, phone, secret, reg_year, country, group_name, remark, status, sold_status, created_at, updated_at, deleted_at";
+pub const ACCOUNT_COLUMNS: &str = "id, email, password, recovery, phone, secret, reg_year, country, group_name, remark, status, sold_status, created_at, updated_at, deleted_at, totp_algorithm, totp_digits, totp_period";
+
+/// 备份恢复时复制的列及其缺失时的默认值表达式
+const RESTORE_ACCOUNT_COLUMNS: &[(&str, &str)] = &[
+    ("id", "id"),
+    ("email", "email"),
+    ("password", "password"),
+    ("recovery", "recovery"),
+    ("phone", "phone"),
+    ("secret", "secret"),
+    ("reg_year", "reg_year"),
+    ("country", "country"),
+    ("group_name", "group_name"),
+    ("remark", "remark"),
+    ("status", "status"),
+    ("sold_status", "sold_status"),
+    ("created_at", "created_at"),
+    ("updated_at", "updated_at"),
+    ("deleted_at", "NULL"),
+    ("totp_algorithm", "'SHA1'"),
+    ("totp_digits", "6"),
+    ("totp_period", "30"),
+];
+
+/// 脱敏列表中 password/secret 的占位符
+pub const REDACTED_MARKER: &str = "******";
+
+/// 未指定 `page_size` 时的默认每页条数
+pub const DEFAULT_PAGE_SIZE: u32 = 20;
+
+/// 单页最大条数
+pub const MAX_PAGE_SIZE: u32 = 500;
 
 /// 需要追踪历史变更的字段（敏感字段 password/secret 不记录明文历史）
 const TRACKED_FIELDS: &[(&str, fn(&Account) -> Option<&str>)] = &[
@@ -89,6 +323,22 @@ fn data_decode_error(account_id: i64, field: &str, reason: &str) -> rusqlite::Er
     )
 }
 
+/// 从 Row 读取 TOTP 参数（旧数据为空时回退到默认值）
+fn map_row_to_totp_params(row: &Row) -> rusqlite::Result<TotpParams> {
+    let defaults = TotpParams::default();
+    Ok(TotpParams {
+        algorithm: row
+            .get::<_, Option<String>>("totp_algorithm")?
+            .unwrap_or(defaults.algorithm),
+        digits: row
+            .get::<_, Option<u32>>("totp_digits")?
+            .unwrap_or(defaults.digits),
+        period: row
+            .get::<_, Option<u32>>("totp_period")?
+            .unwrap_or(defaults.period),
+    })
+}
+
 /// 从 Row 映射到 Account（解密 password 和 secret 字段）
 pub fn map_row_to_account(row: &Row) -> rusqlite::Result<Account> {
     let key = master_key().map_err(|e| {
@@ -130,6 +380,34 @@ pub fn map_row_to_account(row: &Row) -> rusqlite::Result<Account> {
         created_at: row.get("created_at")?,
         updated_at: row.get("updated_at")?,
         deleted_at: row.get("deleted_at")?,
+        totp: map_row_to_totp_params(row)?,
+    })
+}
+
+/// 从 Row 映射到脱敏 Account（不解密，password/secret 以占位符返回）
+pub fn map_row_to_redacted_account(row: &Row) -> rusqlite::Result<Account> {
+    let encrypted_secret: Option<String> = row.get("secret")?;
+    let secret = encrypted_secret
+        .filter(|s| !s.is_empty())
+        .map(|_| REDACTED_MARKER.to_string());
+
+    Ok(Account {
+        id: row.get("id")?,
+        email: row.get("email")?,
+        password: REDACTED_MARKER.to_string(),
+        recovery: row.get("recovery")?,
+        phone: row.get("phone")?,
+        secret,
+        reg_year: row.get("reg_year")?,
+        country: row.get("country")?,
+        group_name: row.get("group_name")?,
+        remark: row.get("remark")?,
+        status: row.get("status")?,
+        sold_status: row.get("sold_status")?,
+        created_at: row.get("created_at")?,
+        updated_at: row.get("updated_at")?,
+        deleted_at: row.get("deleted_at")?,
+        totp: map_row_to_totp_params(row)?,
     })
 }
 
@@ -146,12 +424,25 @@ pub fn get_account_by_id(conn: &Connection, id: i64) -> Result<Account, String>
     .map_err(|e| e.to_string())
 }
 
-/// 查询账号列表（支持搜索和过滤）
-pub fn query_accounts(
-    conn: &Connection,
+/// 通过 ID 查询单个账号（脱敏，不解密 password/secret）
+pub fn get_redacted_account_by_id(conn: &Connection, id: i64) -> Result<Account, String> {
+    conn.query_row(
+        &format!(
+            "SELECT {} FROM accounts WHERE id = ?1 AND deleted_at IS NULL",
+            ACCOUNT_COLUMNS
+        ),
+        [id],
+        map_row_to_redacted_account,
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// 构造列表查询的 WHERE 子句与参数（搜索 + 出售状态 + 标签过滤）
+fn build_account_filter(
     search: Option<&str>,
     sold_status: Option<&str>,
-) -> Result<Vec<Account>, String> {
+    group: Option<&str>,
+) -> (String, Vec<String>) {
     let mut where_clauses = vec!["deleted_at IS NULL".to_string()];
     let mut params_vec: Vec<String> = Vec::new();
 
@@ -171,10 +462,74 @@ pub fn query_accounts(
         }
     }
 
+    // group_name 可包含多个标签（逗号 / 中文逗号 / 空白分隔），按完整标签匹配
+    if let Some(tag) = group.map(str::trim).filter(|tag| !tag.is_empty()) {
+        where_clauses.push(format!("instr({}, ?) > 0", GROUP_TAGS_SQL));
+        params_vec.push(format!(" {} ", tag));
+    }
+
+    (where_clauses.join(" AND "), params_vec)
+}
+
+/// 把 group_name 规范化为 " 标签1 标签2 " 形式，便于按完整标签匹配
+const GROUP_TAGS_SQL: &str = "(' ' || replace(replace(replace(replace(replace(COALESCE(group_name, ''), '，', ' '), ',', ' '), char(9), ' '), char(10), ' '), char(13), ' ') || ' ')";
+
+/// 拆分 group_name 中的标签（与前端标签筛选规则一致）
+fn split_group_tags(group_name: &str) -> impl Iterator<Item = &str> {
+    group_name
+        .split(|c: char| c == ',' || c == '，' || c.is_whitespace())
+        .filter(|tag| !tag.is_empty())
+}
+
+/// 列出活跃账号中出现过的全部标签（去重并排序），供标签筛选使用
+pub fn list_account_groups(conn: &Connection) -> Result<Vec<String>, String> {
+    let mut stmt = conn
+        .prepare_cached(
+            "SELECT DISTINCT group_name FROM accounts WHERE deleted_at IS NULL AND group_name IS NOT NULL",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([], |row| row.get::<_, String>(0))
+        .map_err(|e| e.to_string())?;
+
+    let mut tags = std::collections::BTreeSet::new();
+    for row in rows {
+        let group_name = row.map_err(|e| e.to_string())?;
+        tags.extend(split_group_tags(&group_name).map(str::to_string));
+    }
+    Ok(tags.into_iter().collect())
+}
+
+/// 查询符合筛选条件的全部账号 ID（按 id 倒序），用于跨页全选与按筛选结果导出
+pub fn query_account_ids(
+    conn: &Connection,
+    search: Option<&str>,
+    sold_status: Option<&str>,
+    group: Option<&str>,
+) -> Result<Vec<i64>, String> {
+    let (where_sql, params_vec) = build_account_filter(search, sold_status, group);
+    let query = format!(
+        "SELECT id FROM accounts WHERE {} ORDER BY id DESC",
+        where_sql
+    );
+    let mut stmt = conn.prepare_cached(&query).map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(rusqlite::params_from_iter(params_vec), |row| row.get(0))
+        .map_err(|e| e.to_string())?;
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())
+}
+
+/// 查询账号列表（支持搜索和过滤）
+pub fn query_accounts(
+    conn: &Connection,
+    search: Option<&str>,
+    sold_status: Option<&str>,
+) -> Result<Vec<Account>, String> {
+    let (where_sql, params_vec) = build_account_filter(search, sold_status, None);
     let query = format!(
         "SELECT {} FROM accounts WHERE {} ORDER BY id DESC",
-        ACCOUNT_COLUMNS,
-        where_clauses.join(" AND ")
+        ACCOUNT_COLUMNS, where_sql
     );
 
     let mut stmt = conn.prepare_cached(&query).map_err(|e| e.to_string())?;
@@ -190,6 +545,115 @@ pub fn query_accounts(
         .map_err(|e| e.to_string())
 }
 
+/// 列表排序字段白名单（只允许映射到固定列名，避免拼接任意 SQL）
+fn account_sort_column(field: Option<&str>) -> &'static str {
+    let normalized = field
+        .map(|value| value.trim().to_ascii_lowercase())
+        .unwrap_or_default();
+    match normalized.as_str() {
+        "email" => "email",
+        "recovery" => "recovery",
+        "phone" => "phone",
+        "reg_year" => "reg_year",
+        "country" => "country",
+        "group_name" => "group_name",
+        "remark" => "remark",
+        "status" => "status",
+        "sold_status" => "sold_status",
+        "created_at" => "created_at",
+        "updated_at" => "updated_at",
+        _ => "id",
+    }
+}
+
+fn account_sort_direction(direction: Option<&str>) -> &'static str {
+    let normalized = direction
+        .map(|value| value.trim().to_ascii_lowercase())
+        .unwrap_or_default();
+    match normalized.as_str() {
+        "asc" | "ascending" => "ASC",
+        _ => "DESC",
+    }
+}
+
+/// 分页查询账号列表（服务端分页 + 白名单排序 + 总数统计），password/secret 始终脱敏
+///
+/// `page_size` 为空时使用 `DEFAULT_PAGE_SIZE`，列表接口不会一次返回全部账号。
+pub fn query_accounts_page(
+    conn: &Connection,
+    search: Option<&str>,
+    sold_status: Option<&str>,
+    group: Option<&str>,
+    page_query: &AccountPageQuery,
+) -> Result<AccountPage, String> {
+    let (where_sql, params_vec) = build_account_filter(search, sold_status, group);
+    let sort_column = account_sort_column(page_query.sort_field.as_deref());
+    let sort_direction = account_sort_direction(page_query.sort_direction.as_deref());
+    let order_sql = format!("{} {}, id {}", sort_column, sort_direction, sort_direction);
+    query_page(conn, &where_sql, &params_vec, &order_sql, page_query)
+}
+
+/// 分页查询回收站账号（按删除时间倒序）
+pub fn query_deleted_accounts_page(
+    conn: &Connection,
+    page_query: &AccountPageQuery,
+) -> Result<AccountPage, String> {
+    query_page(
+        conn,
+        "deleted_at IS NOT NULL",
+        &[],
+        "deleted_at DESC, id DESC",
+        page_query,
+    )
+}
+
+fn query_page(
+    conn: &Connection,
+    where_sql: &str,
+    params_vec: &[String],
+    order_sql: &str,
+    page_query: &AccountPageQuery,
+) -> Result<AccountPage, String> {
+    let params_refs: Vec<&dyn rusqlite::ToSql> = params_vec
+        .iter()
+        .map(|p| p as &dyn rusqlite::ToSql)
+        .collect();
+
+    let total: i64 = conn
+        .query_row(
+            &format!("SELECT COUNT(*) FROM accounts WHERE {}", where_sql),
+            params_refs.as_slice(),
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+
+    let page_size = page_query
+        .page_size
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+    let page = page_query.page.unwrap_or(1).max(1);
+    let offset = (page as i64 - 1) * page_size as i64;
+    let query = format!(
+        "SELECT {} FROM accounts WHERE {} ORDER BY {} LIMIT {} OFFSET {}",
+        ACCOUNT_COLUMNS, where_sql, order_sql, page_size, offset
+    );
+
+    let mut stmt = conn.prepare_cached(&query).map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params_refs.as_slice(), map_row_to_redacted_account)
+        .map_err(|e| e.to_string())?;
+    let items = rows
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(AccountPage {
+        items,
+        total,
+        page,
+        page_size,
+    })
+}
+
 /// 按 ID 列表查询账号
 pub fn query_accounts_by_ids(conn: &Connection, ids: &[i64]) -> Result<Vec<Account>, String> {
     if ids.is_empty() {
@@ -225,35 +689,135 @@ pub fn query_accounts_for_export(
     }
 }
 
-/// 查询回收站账号
-pub fn query_deleted_accounts(conn: &Connection) -> Result<Vec<Account>, String> {
+/// 导出排序键：`field` 为导出字段名；`as_text` 时按字符串比较（与分组键一致）
+#[derive(Debug, Clone, Copy)]
+pub struct ExportSortKey<'a> {
+    pub field: &'a str,
+    pub descending: bool,
+    pub as_text: bool,
+}
+
+/// 导出字段对应的 SQL 排序表达式（空值按空串处理，与内存排序一致）；
+/// password / secret 为密文、otpauth_uri 为派生值，无法在 SQL 中排序
+pub fn export_sort_expr(field: &str, as_text: bool) -> Option<&'static str> {
+    Some(match field {
+        "id" if as_text => "CAST(id AS TEXT)",
+        "id" => "id",
+        "email" => "email",
+        "recovery" => "COALESCE(recovery, '')",
+        "phone" => "COALESCE(phone, '')",
+        "reg_year" => "COALESCE(reg_year, '')",
+        "country" => "COALESCE(country, '')",
+        "group_name" => "COALESCE(group_name, '')",
+        "remark" => "COALESCE(remark, '')",
+        "status" => "status",
+        "sold_status" => "sold_status",
+        "created_at" => "created_at",
+        "updated_at" => "updated_at",
+        "deleted_at" => "COALESCE(deleted_at, '')",
+        _ => return None,
+    })
+}
+
+/// 按导出条件逐行读取账号交给 `read`，不在内存中收集整个结果集（`read` 返回前连接保持占用）；
+/// 条件同 `query_accounts_for_export`，按 `sort` 依次排序后以 id 倒序兜底；
+/// `redacted` 为 true 时不解密 password / secret（用于统计等预扫描）
+pub fn read_accounts_for_export<T>(
+    conn: &Connection,
+    account_ids: Option<&[i64]>,
+    search: Option<&str>,
+    sold_status: Option<&str>,
+    sort: &[ExportSortKey],
+    redacted: bool,
+    read: impl FnOnce(&mut dyn Iterator<Item = Result<Account, String>>) -> Result<T, String>,
+) -> Result<T, String> {
+    let (where_sql, params_vec): (String, Vec<rusqlite::types::Value>) =
+        match account_ids.filter(|ids| !ids.is_empty()) {
+            Some(ids) => (
+                format!(
+                    "id IN ({}) AND deleted_at IS NULL",
+                    vec!["?"; ids.len()].join(", ")
+                ),
+                ids.iter().map(|&id| id.into()).collect(),
+            ),
+            None => {
+                let (where_sql, params_vec) = build_account_filter(search, sold_status, None);
+                (where_sql, params_vec.into_iter().map(Into::into).collect())
+            }
+        };
+    let mut order_by = Vec::with_capacity(sort.len() + 1);
+    for key in sort {
+        let expr = export_sort_expr(key.field, key.as_text)
+            .ok_or_else(|| format!("导出字段 {} 无法在数据库中排序", key.field))?;
+        order_by.push(format!(
+            "{} {}",
+            expr,
+            if key.descending { "DESC" } else { "ASC" }
+        ));
+    }
+    order_by.push("id DESC".to_string());
+
     let query = format!(
-        "SELECT {} FROM accounts WHERE deleted_at IS NOT NULL ORDER BY deleted_at DESC, id DESC",
-        ACCOUNT_COLUMNS
+        "SELECT {} FROM accounts WHERE {} ORDER BY {}",
+        ACCOUNT_COLUMNS,
+        where_sql,
+        order_by.join(", ")
     );
-    let mut stmt = conn.prepare_cached(&query).map_err(|e| e.to_string())?;
-    let rows = stmt
-        .query_map([], map_row_to_account)
-        .map_err(|e| e.to_string())?;
-    rows.collect::<Result<Vec<_>, _>>()
-        .map_err(|e| e.to_string())
+    let mapper: fn(&Row) -> rusqlite::Result<Account> = if redacted {
+        map_row_to_redacted_account
+    } else {
+        map_row_to_account
+    };
+    let mut stmt = conn.prepare(&query).map_err(|e| e.to_string())?;
+    let mut rows = stmt
+        .query_map(rusqlite::params_from_iter(params_vec), mapper)
+        .map_err(|e| e.to_string())?
+        .map(|row| row.map_err(|e| e.to_string()));
+    read(&mut rows)
+}
+
+/// 解析输入中的 secret 与 TOTP 参数
+///
+/// secret 为 otpauth:// URI 时提取其中的密钥与参数；显式传入的 `totp` 优先，
+/// 两者都没有时使用 `fallback`。
+pub fn resolve_secret_and_params(
+    secret: Option<&str>,
+    totp: Option<&TotpParams>,
+    fallback: &TotpParams,
+) -> Result<(Option<String>, TotpParams), String> {
+    match secret {
+        Some(value) if totp::is_otpauth_uri(value) => {
+            let parsed = totp::parse_otpauth_uri(value)?;
+            let params = totp.unwrap_or(&parsed.params).normalized()?;
+            Ok((Some(parsed.secret), params))
+        }
+        _ => Ok((
+            secret.map(str::to_string),
+            totp.unwrap_or(fallback).normalized()?,
+        )),
+    }
 }
 
 /// 创建账号
 pub fn create_account(conn: &Connection, input: &AccountInput) -> Result<Account, String> {
+    let (secret, totp) = resolve_secret_and_params(
+        input.secret.as_deref(),
+        input.totp.as_ref(),
+        &TotpParams::default(),
+    )?;
     let key = master_key()?;
     // AES 加密密码
     let encrypted_password = crypto::encrypt_secret(&input.password, &key)?;
 
     // 加密 secret
-    let encrypted_secret = match &input.secret {
+    let encrypted_secret = match &secret {
         Some(secret) if !secret.is_empty() => Some(crypto::encrypt_secret(secret, &key)?),
         _ => None,
     };
 
     conn.execute(
-        "INSERT INTO accounts (email, password, recovery, phone, secret, reg_year, country, group_name, remark, status, sold_status) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
-        params![input.email, encrypted_password, input.recovery, input.phone, encrypted_secret, input.reg_year, input.country, input.group_name, input.remark, "inactive", "unsold"],
+        "INSERT INTO accounts (email, password, recovery, phone, secret, reg_year, country, group_name, remark, status, sold_status, totp_algorithm, totp_digits, totp_period) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
+        params![input.email, encrypted_password, input.recovery, input.phone, encrypted_secret, input.reg_year, input.country, input.group_name, input.remark, "inactive", "unsold", totp.algorithm, totp.digits, totp.period],
     ).map_err(|e| e.to_string())?;
 
     let id = conn.last_insert_rowid();
@@ -261,27 +825,61 @@ pub fn create_account(conn: &Connection, input: &AccountInput) -> Result<Account
 }
 
 /// 更新账号（含历史追踪）
-pub fn update_account(conn: &Connection, id: i64, input: &AccountInput) -> Result<Account, String> {
+pub fn update_account(
+    conn: &Connection,
+    id: i64,
+    input: &AccountInput,
+    actor: AuditActor,
+) -> Result<Account, String> {
     let old = get_account_by_id(conn, id)?;
     let key = master_key()?;
 
+    let tx = conn
+        .unchecked_transaction()
+        .map_err(|e| format!("开启事务失败: {}", e))?;
+    apply_account_update(&tx, &old, input, &key, actor)?;
+    tx.commit().map_err(|e| e.to_string())?;
+
+    get_account_by_id(conn, id)
+}
+
+/// 在调用方的事务内按输入更新账号并记录字段变更（状态字段保持不变）
+fn apply_account_update(
+    conn: &Connection,
+    old: &Account,
+    input: &AccountInput,
+    key: &[u8; 32],
+    actor: AuditActor,
+) -> Result<(), String> {
+    // 脱敏占位符表示客户端未取得明文、字段未修改，保留原值而不是写入占位符
+    let password = if input.password == REDACTED_MARKER {
+        old.password.as_str()
+    } else {
+        input.password.as_str()
+    };
+    let secret_input = match input.secret.as_deref() {
+        Some(REDACTED_MARKER) => old.secret.as_deref(),
+        other => other,
+    };
+    let (secret, totp) = resolve_secret_and_params(secret_input, input.totp.as_ref(), &old.totp)?;
+
     // AES 加密密码
-    let encrypted_password = crypto::encrypt_secret(&input.password, &key)?;
+    let encrypted_password = crypto::encrypt_secret(password, key)?;
 
     // 加密 secret
-    let encrypted_secret = match &input.secret {
-        Some(secret) if !secret.is_empty() => Some(crypto::encrypt_secret(secret, &key)?),
+    let encrypted_secret = match &secret {
+        Some(secret) if !secret.is_empty() => Some(crypto::encrypt_secret(secret, key)?),
         _ => None,
     };
 
     // 构造新账号用于字段对比
     let new_account = Account {
-        id,
+        id: old.id,
         email: input.email.clone(),
-        password: input.password.clone(),
+        password: password.to_string(),
         recovery: input.recovery.clone(),
         phone: input.phone.clone(),
-        secret: input.secret.clone(), // 历始 secret（用于历史记录）
+        secret: secret.clone(), // 历始 secret（用于历史记录）
         reg_year: input.reg_year.clone(),
         country: input.country.clone(),
         group_name: input.group_name.clone(),
@@ -291,19 +889,63 @@ pub fn update_account(conn: &Connection, id: i64, input: &AccountInput) -> Resul
         created_at: old.created_at.clone(),
         updated_at: old.updated_at.clone(),
         deleted_at: old.deleted_at.clone(),
+        totp: totp.clone(),
     };
 
-    let tx = conn
-        .unchecked_transaction()
-        .map_err(|e| format!("开启事务失败: {}", e))?;
-    record_field_changes(&tx, id, &old, &new_account)?;
-    tx.execute(
-        "UPDATE accounts SET email = ?1, password = ?2, recovery = ?3, phone = ?4, secret = ?5, reg_year = ?6, country = ?7, group_name = ?8, remark = ?9, updated_at = CURRENT_TIMESTAMP WHERE id = ?10 AND deleted_at IS NULL",
-        params![input.email, encrypted_password, input.recovery, input.phone, encrypted_secret, input.reg_year, input.country, input.group_name, input.remark, id],
+    record_field_changes(conn, old.id, old, &new_account, actor)?;
+    conn.execute(
+        "UPDATE accounts SET email = ?1, password = ?2, recovery = ?3, phone = ?4, secret = ?5, reg_year = ?6, country = ?7, group_name = ?8, remark = ?9, totp_algorithm = ?10, totp_digits = ?11, totp_period = ?12, updated_at = CURRENT_TIMESTAMP WHERE id = ?13 AND deleted_at IS NULL",
+        params![input.email, encrypted_password, input.recovery, input.phone, encrypted_secret, input.reg_year, input.country, input.group_name, input.remark, totp.algorithm, totp.digits, totp.period, old.id],
     ).map_err(|e| e.to_string())?;
-    tx.commit().map_err(|e| e.to_string())?;
+    Ok(())
+}
 
-    get_account_by_id(conn, id)
+/// 账号当前值对应的输入（`totp` 为空表示保留原参数）
+fn account_as_input(account: &Account) -> AccountInput {
+    AccountInput {
+        email: account.email.clone(),
+        password: account.password.clone(),
+        recovery: account.recovery.clone(),
+        phone: account.phone.clone(),
+        secret: account.secret.clone(),
+        reg_year: account.reg_year.clone(),
+        country: account.country.clone(),
+        group_name: account.group_name.clone(),
+        remark: account.remark.clone(),
+        totp: None,
+    }
+}
+
+/// 仅替换账号的 2FA 密钥与 TOTP 参数（其余字段保持不变，走常规更新以记录历史）
+pub fn update_account_secret(
+    conn: &Connection,
+    id: i64,
+    secret: &str,
+    totp: &TotpParams,
+    actor: AuditActor,
+) -> Result<Account, String> {
+    let mut input = account_as_input(&get_account_by_id(conn, id)?);
+    input.secret = Some(secret.to_string());
+    input.totp = Some(totp.clone());
+    update_account(conn, id, &input, actor)
+}
+
+/// 活跃账号邮箱索引（邮箱小写 -> 账号 ID），用于按邮箱匹配外部导入的条目
+pub fn query_active_email_index(
+    conn: &Connection,
+) -> Result<std::collections::HashMap<String, i64>, String> {
+    let mut stmt = conn
+        .prepare("SELECT id, email FROM accounts WHERE deleted_at IS NULL")
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, String>("email")?.trim().to_lowercase(),
+                row.get::<_, i64>("id")?,
+            ))
+        })
+        .map_err(|e| e.to_string())?;
+    rows.collect::<Result<_, _>>().map_err(|e| e.to_string())
 }
 
 /// 删除账号
@@ -320,222 +962,1296 @@ pub fn delete_account(conn: &Connection, id: i64) -> Result<(), String> {
     Ok(())
 }
 
-/// 删除所有账号
-pub fn delete_all_accounts(conn: &Connection) -> Result<usize, String> {
-    create_backup(conn, Some("before_delete_all"))?;
-    let deleted = conn
-        .execute(
-            "UPDATE accounts SET deleted_at = CURRENT_TIMESTAMP, updated_at = CURRENT_TIMESTAMP WHERE deleted_at IS NULL",
-            [],
-        )
-        .map_err(|e| e.to_string())?;
-    Ok(deleted)
+/// 删除所有账号
+pub fn delete_all_accounts(conn: &Connection) -> Result<usize, String> {
+    create_backup(conn, Some("before_delete_all"))?;
+    let deleted = conn
+        .execute(
+            "UPDATE accounts SET deleted_at = CURRENT_TIMESTAMP, updated_at = CURRENT_TIMESTAMP WHERE deleted_at IS NULL",
+            [],
+        )
+        .map_err(|e| e.to_string())?;
+    Ok(deleted)
+}
+
+/// 恢复账号
+pub fn restore_account(conn: &Connection, id: i64) -> Result<Account, String> {
+    let changed = conn
+        .execute(
+            "UPDATE accounts SET deleted_at = NULL, updated_at = CURRENT_TIMESTAMP WHERE id = ?1 AND deleted_at IS NOT NULL",
+            [id],
+        )
+        .map_err(|e| e.to_string())?;
+    if changed == 0 {
+        return Err("账号不存在或未删除".to_string());
+    }
+    get_account_by_id(conn, id)
+}
+
+/// 永久删除账号
+pub fn purge_account(conn: &Connection, id: i64) -> Result<(), String> {
+    let changed = conn
+        .execute(
+            "DELETE FROM accounts WHERE id = ?1 AND deleted_at IS NOT NULL",
+            [id],
+        )
+        .map_err(|e| e.to_string())?;
+    if changed == 0 {
+        return Err("账号不存在或未进入回收站".to_string());
+    }
+    Ok(())
+}
+
+/// 清空回收站
+pub fn purge_all_deleted(conn: &Connection) -> Result<usize, String> {
+    conn.execute("DELETE FROM accounts WHERE deleted_at IS NOT NULL", [])
+        .map_err(|e| e.to_string())
+}
+
+/// 切换 status（inactive/pro）
+pub fn toggle_status(conn: &Connection, id: i64, actor: AuditActor) -> Result<Account, String> {
+    let tx = conn
+        .unchecked_transaction()
+        .map_err(|e| format!("开启事务失败: {}", e))?;
+    let current: String = tx
+        .query_row(
+            "SELECT status FROM accounts WHERE id = ?1 AND deleted_at IS NULL",
+            [id],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+
+    let new_status = if current == "pro" { "inactive" } else { "pro" };
+    record_history(&tx, id, "status", Some(&current), Some(new_status), actor)?;
+    tx.execute(
+        "UPDATE accounts SET status = ?1, updated_at = CURRENT_TIMESTAMP WHERE id = ?2 AND deleted_at IS NULL",
+        params![new_status, id],
+    )
+    .map_err(|e| e.to_string())?;
+    tx.commit().map_err(|e| e.to_string())?;
+    get_account_by_id(conn, id)
+}
+
+/// 切换 sold_status（unsold/sold）
+pub fn toggle_sold_status(
+    conn: &Connection,
+    id: i64,
+    actor: AuditActor,
+) -> Result<Account, String> {
+    let tx = conn
+        .unchecked_transaction()
+        .map_err(|e| format!("开启事务失败: {}", e))?;
+    let current: String = tx
+        .query_row(
+            "SELECT sold_status FROM accounts WHERE id = ?1 AND deleted_at IS NULL",
+            [id],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+
+    let new_status = if current == "sold" { "unsold" } else { "sold" };
+    record_history(
+        &tx,
+        id,
+        "sold_status",
+        Some(&current),
+        Some(new_status),
+        actor,
+    )?;
+    tx.execute(
+        "UPDATE accounts SET sold_status = ?1, updated_at = CURRENT_TIMESTAMP WHERE id = ?2 AND deleted_at IS NULL",
+        params![new_status, id],
+    )
+    .map_err(|e| e.to_string())?;
+    tx.commit().map_err(|e| e.to_string())?;
+    get_account_by_id(conn, id)
+}
+
+/// 记录一次敏感字段查看
+fn record_secret_reveal(
+    conn: &Connection,
+    account_id: i64,
+    field_name: &str,
+    channel: &str,
+    actor: AuditActor,
+) -> Result<(), String> {
+    conn.execute(
+        "INSERT INTO secret_reveals (account_id, field_name, channel, actor_user_id, actor_username)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![account_id, field_name, channel, actor.user_id, actor.username],
+    )
+    .map_err(|e| {
+        format!(
+            "记录敏感字段查看失败 (account_id={}, field={}): {}",
+            account_id, field_name, e
+        )
+    })?;
+    Ok(())
+}
+
+/// 记录一次账号导出：每次导出只写一行（范围、格式与导出行数），不逐账号展开
+pub fn record_export_audit(
+    conn: &Connection,
+    scope: &str,
+    format: &str,
+    row_count: usize,
+    target: Option<&str>,
+    actor: AuditActor,
+) -> Result<(), String> {
+    conn.execute(
+        "INSERT INTO export_audits (scope, format, row_count, target, actor_user_id, actor_username)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![scope, format, row_count as i64, target, actor.user_id, actor.username],
+    )
+    .map_err(|e| format!("记录导出审计失败: {}", e))?;
+    Ok(())
+}
+
+/// 读取并解密单个账号的加密列（调用方保证 `field_name` 为 password/secret）
+fn decrypt_account_field(
+    conn: &Connection,
+    id: i64,
+    field_name: &str,
+) -> Result<Option<String>, String> {
+    let encrypted: Option<String> = conn
+        .query_row(
+            &format!(
+                "SELECT {} FROM accounts WHERE id = ?1 AND deleted_at IS NULL",
+                field_name
+            ),
+            [id],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+
+    let key = master_key()?;
+    match encrypted {
+        Some(ref value) if !value.is_empty() => crypto::decrypt_secret(value, &key)
+            .map(Some)
+            .map_err(|e| format!("账号 {} 字段 {} 解密失败: {}", id, field_name, e)),
+        _ => Ok(None),
+    }
+}
+
+/// 解密单个账号的 2FA 密钥及其 TOTP 参数（仅供服务端生成验证码使用，不记录查看）
+pub fn get_account_secret(conn: &Connection, id: i64) -> Result<Option<AccountSecret>, String> {
+    get_account_secrets(conn, &[id])?
+        .into_iter()
+        .next()
+        .map(|(_, result)| result)
+        .unwrap_or_else(|| Err("账号不存在或已删除".to_string()))
+}
+
+/// 单账号 secret 解密结果：(账号 ID, 明文 secret 或错误原因)
+pub type AccountSecretResult = (i64, Result<Option<AccountSecret>, String>);
+
+/// 批量解密 2FA 密钥，按 ID 返回逐条结果（不存在或解密失败的账号返回错误信息）
+pub fn get_account_secrets(
+    conn: &Connection,
+    ids: &[i64],
+) -> Result<Vec<AccountSecretResult>, String> {
+    if ids.is_empty() {
+        return Ok(Vec::new());
+    }
+    let placeholders: Vec<String> = ids.iter().map(|_| "?".to_string()).collect();
+    let query = format!(
+        "SELECT id, secret, totp_algorithm, totp_digits, totp_period FROM accounts WHERE id IN ({}) AND deleted_at IS NULL",
+        placeholders.join(", ")
+    );
+    let mut stmt = conn.prepare(&query).map_err(|e| e.to_string())?;
+    let params_refs: Vec<&dyn rusqlite::ToSql> =
+        ids.iter().map(|id| id as &dyn rusqlite::ToSql).collect();
+    let rows = stmt
+        .query_map(params_refs.as_slice(), |row| {
+            Ok((
+                row.get::<_, i64>("id")?,
+                (
+                    row.get::<_, Option<String>>("secret")?,
+                    map_row_to_totp_params(row)?,
+                ),
+            ))
+        })
+        .map_err(|e| e.to_string())?;
+    let encrypted: std::collections::HashMap<i64, (Option<String>, TotpParams)> =
+        rows.collect::<Result<_, _>>().map_err(|e| e.to_string())?;
+
+    let key = master_key()?;
+    Ok(ids
+        .iter()
+        .map(|&id| {
+            let result = match encrypted.get(&id) {
+                None => Err("账号不存在或已删除".to_string()),
+                Some((Some(value), params)) if !value.is_empty() => {
+                    crypto::decrypt_secret(value, &key)
+                        .map(|secret| {
+                            Some(AccountSecret {
+                                secret,
+                                params: params.clone(),
+                            })
+                        })
+                        .map_err(|e| format!("账号 {} 字段 secret 解密失败: {}", id, e))
+                }
+                Some(_) => Ok(None),
+            };
+            (id, result)
+        })
+        .collect())
+}
+
+/// 批量解密 2FA 密钥用于导出（迁移码、二维码等），成功解密的账号写入查看记录
+pub fn reveal_account_secrets(
+    conn: &Connection,
+    ids: &[i64],
+    channel: &str,
+    actor: AuditActor,
+) -> Result<Vec<AccountSecretResult>, String> {
+    let secrets = get_account_secrets(conn, ids)?;
+    for (id, result) in &secrets {
+        if let Ok(Some(_)) = result {
+            record_secret_reveal(conn, *id, "secret", channel, actor)?;
+        }
+    }
+    Ok(secrets)
+}
+
+/// 生成账号的 otpauth:// URI（用于二维码等场景），并写入查看记录
+pub fn reveal_account_otpauth_uri(
+    conn: &Connection,
+    id: i64,
+    channel: &str,
+    actor: AuditActor,
+) -> Result<String, String> {
+    let account = get_account_by_id(conn, id)?;
+    let secret = account
+        .secret
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .ok_or_else(|| "该账号未设置 2FA 密钥".to_string())?;
+    let uri = totp::build_otpauth_uri(
+        &account.email,
+        Some(totp::OTPAUTH_ISSUER),
+        secret,
+        &account.totp,
+    );
+    record_secret_reveal(conn, id, "secret", channel, actor)?;
+    Ok(uri)
+}
+
+/// 按需解密单个敏感字段（password / secret），并写入查看记录
+pub fn reveal_account_secret(
+    conn: &Connection,
+    id: i64,
+    field: &str,
+    actor: AuditActor,
+) -> Result<Option<String>, String> {
+    let field_name = match field.trim().to_ascii_lowercase().as_str() {
+        "password" => "password",
+        "secret" => "secret",
+        _ => return Err(format!("不支持查看的字段: {}", field)),
+    };
+
+    let plain = decrypt_account_field(conn, id, field_name)?;
+    record_secret_reveal(conn, id, field_name, "reveal", actor)?;
+    Ok(plain)
+}
+
+/// 查询账号的敏感字段查看记录（含查看人）
+pub fn get_secret_reveals(conn: &Connection, account_id: i64) -> Result<Vec<SecretReveal>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, account_id, field_name, channel, actor_user_id, actor_username, revealed_at
+             FROM secret_reveals WHERE account_id = ?1 ORDER BY id DESC",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([account_id], |row| {
+            Ok(SecretReveal {
+                id: row.get(0)?,
+                account_id: row.get(1)?,
+                field_name: row.get(2)?,
+                channel: row.get(3)?,
+                actor_user_id: row.get(4)?,
+                actor_username: row.get(5)?,
+                revealed_at: row.get(6)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())
+}
+
+/// 批量导入（使用事务保证原子性；`dry_run` 时执行完整流程后回滚，仅返回预览结果）
+pub fn batch_import(
+    conn: &Connection,
+    accounts: &[AccountInput],
+    options: &ImportOptions,
+    actor: AuditActor,
+) -> Result<BatchImportReport, String> {
+    let tx = conn
+        .unchecked_transaction()
+        .map_err(|e| format!("开始事务失败: {}", e))?;
+    let report = import_rows(&tx, accounts, options, actor)?;
+    if options.dry_run {
+        tx.rollback()
+            .map_err(|e| format!("回滚预览事务失败: {}", e))?;
+    } else {
+        tx.commit()
+            .map_err(|e| format!("提交事务失败（已回滚）: {}", e))?;
+    }
+
+    Ok(report)
+}
+
+/// 单行导入成功时的结果
+struct ImportRowOutcome {
+    status: ImportRowStatus,
+    account_id: i64,
+    conflict: Option<ImportConflict>,
+    reason: Option<String>,
+}
+
+/// 在调用方的事务内逐行导入；每行使用独立的 SAVEPOINT，单行失败只回滚该行
+fn import_rows(
+    conn: &Connection,
+    accounts: &[AccountInput],
+    options: &ImportOptions,
+    actor: AuditActor,
+) -> Result<BatchImportReport, String> {
+    let key = master_key()?;
+    let mut report = BatchImportReport {
+        dry_run: options.dry_run,
+        ..BatchImportReport::default()
+    };
+
+    for (index, account) in accounts.iter().enumerate() {
+        conn.execute_batch("SAVEPOINT import_row")
+            .map_err(|e| format!("创建导入保存点失败: {}", e))?;
+        let outcome = import_row(conn, account, options, &key, actor);
+        let finish = if outcome.is_ok() {
+            "RELEASE import_row"
+        } else {
+            "ROLLBACK TO import_row; RELEASE import_row"
+        };
+        conn.execute_batch(finish)
+            .map_err(|e| format!("结束导入保存点失败: {}", e))?;
+
+        let row = match outcome {
+            Ok(outcome) => ImportRowResult {
+                index,
+                email: account.email.clone(),
+                status: outcome.status,
+                // 预览时新插入的 ID 会随回滚失效，不返回
+                account_id: Some(outcome.account_id)
+                    .filter(|_| !(options.dry_run && outcome.status == ImportRowStatus::Inserted)),
+                conflict: outcome.conflict,
+                failure: None,
+                reason: outcome.reason,
+            },
+            Err((failure, e)) => {
+                if !options.dry_run {
+                    log::warn!("批量导入单条失败 (email={}): {}", account.email, e);
+                }
+                ImportRowResult {
+                    index,
+                    email: account.email.clone(),
+                    status: ImportRowStatus::Failed,
+                    account_id: None,
+                    conflict: None,
+                    failure: Some(failure),
+                    reason: Some(e),
+                }
+            }
+        };
+        match row.status {
+            ImportRowStatus::Inserted => report.inserted_count += 1,
+            ImportRowStatus::Updated => report.updated_count += 1,
+            ImportRowStatus::Restored => report.restored_count += 1,
+            ImportRowStatus::SkippedDuplicate | ImportRowStatus::SkippedDeleted => {
+                report.skipped_count += 1
+            }
+            ImportRowStatus::Failed => report.failed_count += 1,
+        }
+        report.rows.push(row);
+    }
+
+    report.success_count = report.inserted_count + report.updated_count + report.restored_count;
+    Ok(report)
+}
+
+/// 校验手机号：去掉常见分隔符后为可选的 `+` 加 8~18 位数字
+fn validate_phone(phone: &str) -> Result<(), String> {
+    let compact: String = phone
+        .chars()
+        .filter(|c| !matches!(c, ' ' | '-' | '(' | ')' | '.'))
+        .collect();
+    let digits = compact.strip_prefix('+').unwrap_or(&compact);
+    if (8..=18).contains(&digits.len()) && digits.chars().all(|c| c.is_ascii_digit()) {
+        Ok(())
+    } else {
+        Err(format!("手机号格式不正确: {}", phone))
+    }
+}
+
+/// 导入前校验 2FA 密钥与手机号（导入与预览共用）
+fn validate_import_row(input: &AccountInput) -> Result<(), (ImportFailure, String)> {
+    if input.email.trim().is_empty() || input.password.trim().is_empty() {
+        return Err((ImportFailure::Other, "邮箱和密码不能为空".to_string()));
+    }
+    let (secret, _) = resolve_secret_and_params(
+        input.secret.as_deref(),
+        input.totp.as_ref(),
+        &TotpParams::default(),
+    )
+    .map_err(|e| {
+        (
+            ImportFailure::InvalidSecret,
+            format!("2FA 密钥或 TOTP 参数非法: {}", e),
+        )
+    })?;
+    if let Some(secret) = secret.as_deref().map(str::trim).filter(|v| !v.is_empty()) {
+        totp_rs::Secret::Encoded(secret.replace(' ', "").to_uppercase())
+            .to_bytes()
+            .map_err(|_| {
+                (
+                    ImportFailure::InvalidSecret,
+                    "2FA 密钥不是合法的 Base32".to_string(),
+                )
+            })?;
+    }
+    if let Some(phone) = input
+        .phone
+        .as_deref()
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+    {
+        validate_phone(phone).map_err(|e| (ImportFailure::InvalidPhone, e))?;
+    }
+    Ok(())
+}
+
+/// 导入单行；失败时返回失败类别与原因
+fn import_row(
+    conn: &Connection,
+    input: &AccountInput,
+    options: &ImportOptions,
+    key: &[u8; 32],
+    actor: AuditActor,
+) -> Result<ImportRowOutcome, (ImportFailure, String)> {
+    validate_import_row(input)?;
+    let other = |e: String| (ImportFailure::Other, e);
+    // 邮箱不区分大小写，大小写不同的同一地址按重复处理
+    let existing = conn
+        .query_row(
+            "SELECT id, deleted_at IS NOT NULL FROM accounts WHERE lower(email) = lower(?1) ORDER BY deleted_at IS NULL DESC, id DESC LIMIT 1",
+            [&input.email],
+            |row| Ok((row.get::<_, i64>(0)?, row.get::<_, bool>(1)?)),
+        )
+        .optional()
+        .map_err(|e| other(e.to_string()))?;
+    let outcome = |status, account_id, conflict, reason: Option<&str>| ImportRowOutcome {
+        status,
+        account_id,
+        conflict,
+        reason: reason.map(str::to_string),
+    };
+
+    match existing {
+        None => Ok(outcome(
+            ImportRowStatus::Inserted,
+            insert_import_row(conn, input, key).map_err(other)?,
+            None,
+            None,
+        )),
+        Some((id, false)) => {
+            let conflict = Some(ImportConflict::Active);
+            if options.duplicate_mode == DuplicateMode::Skip {
+                return Ok(outcome(
+                    ImportRowStatus::SkippedDuplicate,
+                    id,
+                    conflict,
+                    Some("邮箱已存在"),
+                ));
+            }
+            let changed = merge_import_row(conn, id, input, options.duplicate_mode, key, actor)
+                .map_err(other)?;
+            if changed {
+                Ok(outcome(ImportRowStatus::Updated, id, conflict, None))
+            } else {
+                Ok(outcome(
+                    ImportRowStatus::SkippedDuplicate,
+                    id,
+                    conflict,
+                    Some("邮箱已存在且没有可更新的字段"),
+                ))
+            }
+        }
+        Some((id, true)) => {
+            let conflict = Some(ImportConflict::Recycled);
+            match options.recycled_mode {
+                RecycledMode::Skip => Ok(outcome(
+                    ImportRowStatus::SkippedDeleted,
+                    id,
+                    conflict,
+                    Some("邮箱在回收站中"),
+                )),
+                RecycledMode::Insert => Ok(outcome(
+                    ImportRowStatus::Inserted,
+                    insert_import_row(conn, input, key).map_err(other)?,
+                    conflict,
+                    None,
+                )),
+                RecycledMode::Restore => {
+                    restore_account(conn, id).map_err(other)?;
+                    if options.duplicate_mode != DuplicateMode::Skip {
+                        merge_import_row(conn, id, input, options.duplicate_mode, key, actor)
+                            .map_err(other)?;
+                    }
+                    Ok(outcome(ImportRowStatus::Restored, id, conflict, None))
+                }
+            }
+        }
+    }
+}
+
+fn insert_import_row(
+    conn: &Connection,
+    input: &AccountInput,
+    key: &[u8; 32],
+) -> Result<i64, String> {
+    let (secret, totp) = resolve_secret_and_params(
+        input.secret.as_deref(),
+        input.totp.as_ref(),
+        &TotpParams::default(),
+    )
+    .map_err(|e| format!("2FA 密钥或 TOTP 参数非法: {}", e))?;
+
+    // AES 加密密码
+    let encrypted_password =
+        crypto::encrypt_secret(&input.password, key).map_err(|e| format!("密码加密失败: {}", e))?;
+
+    // 加密 secret
+    let encrypted_secret = match &secret {
+        Some(secret) if !secret.is_empty() => Some(
+            crypto::encrypt_secret(secret, key).map_err(|e| format!("Secret 加密失败: {}", e))?,
+        ),
+        _ => None,
+    };
+
+    let mut insert_stmt = conn
+        .prepare_cached(
+            "INSERT INTO accounts (email, password, recovery, phone, secret, reg_year, country, group_name, remark, status, sold_status, totp_algorithm, totp_digits, totp_period, deleted_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, NULL)"
+        )
+        .map_err(|e| format!("准备批量导入语句失败: {}", e))?;
+    insert_stmt
+        .execute(params![
+            input.email,
+            encrypted_password,
+            input.recovery,
+            input.phone,
+            encrypted_secret,
+            input.reg_year,
+            input.country,
+            input.group_name,
+            input.remark,
+            "inactive",
+            "unsold",
+            totp.algorithm,
+            totp.digits,
+            totp.period
+        ])
+        .map_err(|e| e.to_string())?;
+    Ok(conn.last_insert_rowid())
+}
+
+/// 按覆盖/合并规则把导入行写入已有账号，返回是否有字段被更新
+fn merge_import_row(
+    conn: &Connection,
+    id: i64,
+    input: &AccountInput,
+    mode: DuplicateMode,
+    key: &[u8; 32],
+    actor: AuditActor,
+) -> Result<bool, String> {
+    let old = get_account_by_id(conn, id)?;
+    let current = account_as_input(&old);
+    let take = |existing: &str, incoming: &str| {
+        !incoming.trim().is_empty()
+            && (mode == DuplicateMode::Overwrite || existing.trim().is_empty())
+    };
+    let pick = |existing: &Option<String>, incoming: &Option<String>| {
+        if take(
+            existing.as_deref().unwrap_or(""),
+            incoming.as_deref().unwrap_or(""),
+        ) {
+            incoming.clone()
+        } else {
+            existing.clone()
+        }
+    };
+
+    let mut merged = AccountInput {
+        email: current.email.clone(),
+        password: if take(&current.password, &input.password) {
+            input.password.clone()
+        } else {
+            current.password.clone()
+        },
+        recovery: pick(&current.recovery, &input.recovery),
+        phone: pick(&current.phone, &input.phone),
+        secret: pick(&current.secret, &input.secret),
+        reg_year: pick(&current.reg_year, &input.reg_year),
+        country: pick(&current.country, &input.country),
+        group_name: pick(&current.group_name, &input.group_name),
+        remark: pick(&current.remark, &input.remark),
+        totp: None,
+    };
+    // 采用导入行的 secret 时同时采用其 TOTP 参数（未指定则为默认值，otpauth URI 自带参数）
+    if merged.secret != current.secret {
+        merged.totp = match (&input.totp, merged.secret.as_deref()) {
+            (Some(params), _) => Some(params.clone()),
+            (None, Some(secret)) if totp::is_otpauth_uri(secret) => None,
+            (None, _) => Some(TotpParams::default()),
+        };
+    }
+    if merged == current {
+        return Ok(false);
+    }
+    apply_account_update(conn, &old, &merged, key, actor)?;
+    Ok(true)
+}
+
+/// 获取账号历史
+pub fn get_account_history(
+    conn: &Connection,
+    account_id: i64,
+) -> Result<Vec<AccountHistory>, String> {
+    let mut stmt = conn.prepare(
+        "SELECT id, account_id, field_name, old_value, new_value, changed_at, actor_user_id, actor_username FROM account_history WHERE account_id = ?1 ORDER BY changed_at DESC"
+    ).map_err(|e| e.to_string())?;
+
+    let rows = stmt
+        .query_map([account_id], |row| {
+            Ok(AccountHistory {
+                id: row.get(0)?,
+                account_id: row.get(1)?,
+                field_name: row.get(2)?,
+                old_value: row.get(3)?,
+                new_value: row.get(4)?,
+                changed_at: row.get(5)?,
+                actor_user_id: row.get(6)?,
+                actor_username: row.get(7)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())
+}
+
+/// 记录字段变更历史（循环对比所有追踪字段）
+fn record_field_changes(
+    conn: &Connection,
+    account_id: i64,
+    old: &Account,
+    new: &Account,
+    actor: AuditActor,
+) -> Result<(), String> {
+    for &(field_name, getter) in TRACKED_FIELDS {
+        let old_val = getter(old);
+        let new_val = getter(new);
+        if old_val != new_val {
+            record_history(conn, account_id, field_name, old_val, new_val, actor)?;
+        }
+    }
+    Ok(())
+}
+
+/// 写入一条字段变更历史
+fn record_history(
+    conn: &Connection,
+    account_id: i64,
+    field_name: &str,
+    old_value: Option<&str>,
+    new_value: Option<&str>,
+    actor: AuditActor,
+) -> Result<(), String> {
+    conn.execute(
+        "INSERT INTO account_history (account_id, field_name, old_value, new_value, actor_user_id, actor_username)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![account_id, field_name, old_value, new_value, actor.user_id, actor.username],
+    )
+    .map_err(|e| {
+        format!(
+            "记录字段变更失败 (account_id={}, field={}): {}",
+            account_id, field_name, e
+        )
+    })?;
+    Ok(())
+}
+
+/// 导出预设名称上限（字符数）
+const EXPORT_PRESET_NAME_MAX_CHARS: usize = 64;
+
+fn normalize_export_preset_name(name: &str) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("预设名称不能为空".to_string());
+    }
+    if name.chars().count() > EXPORT_PRESET_NAME_MAX_CHARS {
+        return Err(format!(
+            "预设名称不能超过 {} 个字符",
+            EXPORT_PRESET_NAME_MAX_CHARS
+        ));
+    }
+    Ok(name.to_string())
+}
+
+fn validate_export_preset_config(config: &serde_json::Value) -> Result<String, String> {
+    if !config.is_object() {
+        return Err("导出预设配置必须是 JSON 对象".to_string());
+    }
+    serde_json::to_string(config).map_err(|e| e.to_string())
+}
+
+fn map_row_to_export_preset(row: &Row) -> rusqlite::Result<ExportPreset> {
+    let config: String = row.get("config")?;
+    Ok(ExportPreset {
+        id: row.get("id")?,
+        name: row.get("name")?,
+        config: serde_json::from_str(&config).map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, Box::new(e))
+        })?,
+        created_at: row.get("created_at")?,
+        updated_at: row.get("updated_at")?,
+    })
+}
+
+fn ensure_export_preset_name_available(
+    conn: &Connection,
+    name: &str,
+    exclude_id: Option<i64>,
+) -> Result<(), String> {
+    let taken: bool = conn
+        .query_row(
+            "SELECT EXISTS(SELECT 1 FROM export_presets WHERE name = ?1 AND id != ?2)",
+            params![name, exclude_id.unwrap_or(0)],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    if taken {
+        return Err(format!("导出预设已存在: {}", name));
+    }
+    Ok(())
+}
+
+/// 按名称排序列出导出预设
+pub fn list_export_presets(conn: &Connection) -> Result<Vec<ExportPreset>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, name, config, created_at, updated_at FROM export_presets ORDER BY name",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([], map_row_to_export_preset)
+        .map_err(|e| e.to_string())?;
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())
+}
+
+/// 按名称读取导出预设
+pub fn get_export_preset(conn: &Connection, name: &str) -> Result<ExportPreset, String> {
+    conn.query_row(
+        "SELECT id, name, config, created_at, updated_at FROM export_presets WHERE name = ?1",
+        [name.trim()],
+        map_row_to_export_preset,
+    )
+    .optional()
+    .map_err(|e| e.to_string())?
+    .ok_or_else(|| format!("导出预设不存在: {}", name.trim()))
+}
+
+fn get_export_preset_by_id(conn: &Connection, id: i64) -> Result<ExportPreset, String> {
+    conn.query_row(
+        "SELECT id, name, config, created_at, updated_at FROM export_presets WHERE id = ?1",
+        [id],
+        map_row_to_export_preset,
+    )
+    .optional()
+    .map_err(|e| e.to_string())?
+    .ok_or_else(|| "导出预设不存在".to_string())
+}
+
+/// 新建导出预设（名称唯一）
+pub fn create_export_preset(
+    conn: &Connection,
+    name: &str,
+    config: &serde_json::Value,
+) -> Result<ExportPreset, String> {
+    let name = normalize_export_preset_name(name)?;
+    let config = validate_export_preset_config(config)?;
+    ensure_export_preset_name_available(conn, &name, None)?;
+    conn.execute(
+        "INSERT INTO export_presets (name, config) VALUES (?1, ?2)",
+        params![name, config],
+    )
+    .map_err(|e| e.to_string())?;
+    get_export_preset_by_id(conn, conn.last_insert_rowid())
+}
+
+/// 重命名或覆盖导出预设
+pub fn update_export_preset(
+    conn: &Connection,
+    id: i64,
+    name: &str,
+    config: &serde_json::Value,
+) -> Result<ExportPreset, String> {
+    let name = normalize_export_preset_name(name)?;
+    let config = validate_export_preset_config(config)?;
+    ensure_export_preset_name_available(conn, &name, Some(id))?;
+    let changed = conn
+        .execute(
+            "UPDATE export_presets SET name = ?1, config = ?2, updated_at = CURRENT_TIMESTAMP WHERE id = ?3",
+            params![name, config, id],
+        )
+        .map_err(|e| e.to_string())?;
+    if changed == 0 {
+        return Err("导出预设不存在".to_string());
+    }
+    get_export_preset_by_id(conn, id)
+}
+
+/// 删除导出预设
+pub fn delete_export_preset(conn: &Connection, id: i64) -> Result<(), String> {
+    let changed = conn
+        .execute("DELETE FROM export_presets WHERE id = ?1", [id])
+        .map_err(|e| e.to_string())?;
+    if changed == 0 {
+        return Err("导出预设不存在".to_string());
+    }
+    Ok(())
+}
+
+/// 用户名上限（字符数）
+const USERNAME_MAX_CHARS: usize = 32;
+
+/// 创建登录认证相关的表（用户、会话、登录锁定），并把旧版单一管理员凭据迁移为 `admin` 用户
+pub fn create_auth_tables(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS users (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            username TEXT NOT NULL UNIQUE COLLATE NOCASE,
+            password_hash TEXT NOT NULL,
+            role TEXT NOT NULL DEFAULT 'viewer',
+            disabled INTEGER NOT NULL DEFAULT 0,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+            updated_at TEXT DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+
+    // 会话只保存令牌的 SHA-256，时间均为 Unix 秒
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS auth_sessions (
+            id TEXT PRIMARY KEY,
+            token_hash TEXT NOT NULL UNIQUE,
+            user_id INTEGER NOT NULL,
+            device_label TEXT,
+            ip TEXT,
+            created_at INTEGER NOT NULL,
+            expires_at INTEGER NOT NULL,
+            last_seen_at INTEGER NOT NULL,
+            FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_auth_sessions_user_id ON auth_sessions(user_id)",
+        [],
+    )?;
+    // 失败计数按锁定键（登录用户名 + 客户端 IP、会话用户等）分别记录；旧版全局单行表直接丢弃
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS auth_lockouts (
+            lockout_key TEXT PRIMARY KEY,
+            failed_attempts INTEGER NOT NULL DEFAULT 0,
+            banned_until INTEGER,
+            updated_at INTEGER NOT NULL
+        )",
+        [],
+    )?;
+    conn.execute("DROP TABLE IF EXISTS auth_lockout", [])?;
+
+    let has_legacy_credential: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type='table' AND name='admin_credential')",
+        [],
+        |row| row.get(0),
+    )?;
+    if has_legacy_credential {
+        conn.execute_batch(
+            "INSERT INTO users (username, password_hash, role)
+             SELECT 'admin', password_hash, 'admin' FROM admin_credential
+             WHERE id = 1 AND NOT EXISTS (SELECT 1 FROM users);
+             DROP TABLE admin_credential;",
+        )?;
+    }
+    Ok(())
+}
+
+/// 规范化用户名：去除首尾空白，不能包含空白字符
+pub fn normalize_username(username: &str) -> Result<String, String> {
+    let username = username.trim();
+    if username.is_empty() {
+        return Err("用户名不能为空".to_string());
+    }
+    if username.chars().count() > USERNAME_MAX_CHARS {
+        return Err(format!("用户名不能超过 {} 个字符", USERNAME_MAX_CHARS));
+    }
+    if username.chars().any(char::is_whitespace) {
+        return Err("用户名不能包含空白字符".to_string());
+    }
+    Ok(username.to_string())
+}
+
+fn map_row_to_user(row: &Row) -> rusqlite::Result<User> {
+    let role: String = row.get("role")?;
+    Ok(User {
+        id: row.get("id")?,
+        username: row.get("username")?,
+        role: UserRole::parse(&role).map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(
+                0,
+                rusqlite::types::Type::Text,
+                Box::new(std::io::Error::new(std::io::ErrorKind::InvalidData, e)),
+            )
+        })?,
+        disabled: row.get("disabled")?,
+        created_at: row.get("created_at")?,
+        updated_at: row.get("updated_at")?,
+    })
+}
+
+const USER_COLUMNS: &str = "id, username, role, disabled, created_at, updated_at";
+
+/// 用户总数（为 0 时需要走首次初始化流程）
+pub fn count_users(conn: &Connection) -> Result<i64, String> {
+    conn.query_row("SELECT COUNT(1) FROM users", [], |row| row.get(0))
+        .map_err(|e| e.to_string())
+}
+
+/// 未禁用的管理员数量
+pub fn count_active_admins(conn: &Connection) -> Result<i64, String> {
+    conn.query_row(
+        "SELECT COUNT(1) FROM users WHERE role = 'admin' AND disabled = 0",
+        [],
+        |row| row.get(0),
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// 按用户名排序列出用户（不含密码哈希）
+pub fn list_users(conn: &Connection) -> Result<Vec<User>, String> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM users ORDER BY username COLLATE NOCASE",
+            USER_COLUMNS
+        ))
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([], map_row_to_user)
+        .map_err(|e| e.to_string())?;
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())
+}
+
+pub fn get_user(conn: &Connection, id: i64) -> Result<User, String> {
+    conn.query_row(
+        &format!("SELECT {} FROM users WHERE id = ?1", USER_COLUMNS),
+        [id],
+        map_row_to_user,
+    )
+    .optional()
+    .map_err(|e| e.to_string())?
+    .ok_or_else(|| "用户不存在".to_string())
+}
+
+/// 按用户名（不区分大小写）读取用户及其密码哈希
+pub fn find_user_credential(
+    conn: &Connection,
+    username: &str,
+) -> Result<Option<(User, String)>, String> {
+    conn.query_row(
+        &format!(
+            "SELECT {}, password_hash FROM users WHERE username = ?1",
+            USER_COLUMNS
+        ),
+        [username.trim()],
+        |row| Ok((map_row_to_user(row)?, row.get("password_hash")?)),
+    )
+    .optional()
+    .map_err(|e| e.to_string())
+}
+
+pub fn create_user(
+    conn: &Connection,
+    username: &str,
+    password_hash: &str,
+    role: UserRole,
+) -> Result<User, String> {
+    let username = normalize_username(username)?;
+    if find_user_credential(conn, &username)?.is_some() {
+        return Err(format!("用户已存在: {}", username));
+    }
+    conn.execute(
+        "INSERT INTO users (username, password_hash, role) VALUES (?1, ?2, ?3)",
+        params![username, password_hash, role.as_str()],
+    )
+    .map_err(|e| e.to_string())?;
+    get_user(conn, conn.last_insert_rowid())
+}
+
+/// 更新用户角色与禁用状态（`None` 表示保持不变）
+pub fn update_user(
+    conn: &Connection,
+    id: i64,
+    role: Option<UserRole>,
+    disabled: Option<bool>,
+) -> Result<User, String> {
+    let current = get_user(conn, id)?;
+    conn.execute(
+        "UPDATE users SET role = ?1, disabled = ?2, updated_at = CURRENT_TIMESTAMP WHERE id = ?3",
+        params![
+            role.unwrap_or(current.role).as_str(),
+            disabled.unwrap_or(current.disabled),
+            id
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    get_user(conn, id)
 }
 
-/// 恢复账号
-pub fn restore_account(conn: &Connection, id: i64) -> Result<Account, String> {
+pub fn set_user_password_hash(
+    conn: &Connection,
+    id: i64,
+    password_hash: &str,
+) -> Result<(), String> {
     let changed = conn
         .execute(
-            "UPDATE accounts SET deleted_at = NULL, updated_at = CURRENT_TIMESTAMP WHERE id = ?1 AND deleted_at IS NOT NULL",
-            [id],
+            "UPDATE users SET password_hash = ?1, updated_at = CURRENT_TIMESTAMP WHERE id = ?2",
+            params![password_hash, id],
         )
         .map_err(|e| e.to_string())?;
     if changed == 0 {
-        return Err("账号不存在或未删除".to_string());
+        return Err("用户不存在".to_string());
     }
-    get_account_by_id(conn, id)
+    Ok(())
 }
 
-/// 永久删除账号
-pub fn purge_account(conn: &Connection, id: i64) -> Result<(), String> {
+pub fn delete_user(conn: &Connection, id: i64) -> Result<(), String> {
     let changed = conn
-        .execute(
-            "DELETE FROM accounts WHERE id = ?1 AND deleted_at IS NOT NULL",
-            [id],
-        )
+        .execute("DELETE FROM users WHERE id = ?1", [id])
         .map_err(|e| e.to_string())?;
     if changed == 0 {
-        return Err("账号不存在或未进入回收站".to_string());
+        return Err("用户不存在".to_string());
     }
     Ok(())
 }
 
-/// 清空回收站
-pub fn purge_all_deleted(conn: &Connection) -> Result<usize, String> {
-    conn.execute("DELETE FROM accounts WHERE deleted_at IS NOT NULL", [])
-        .map_err(|e| e.to_string())
+/// 持久化的登录会话（关联的用户为会话校验时的最新状态）
+#[derive(Debug, Clone)]
+pub struct StoredSession {
+    pub id: String,
+    pub token_hash: String,
+    pub user: User,
+    pub device_label: Option<String>,
+    pub ip: Option<String>,
+    pub created_at: i64,
+    pub expires_at: i64,
+    pub last_seen_at: i64,
 }
 
-/// 切换 status（inactive/pro）
-pub fn toggle_status(conn: &Connection, id: i64) -> Result<Account, String> {
-    let tx = conn
-        .unchecked_transaction()
-        .map_err(|e| format!("开启事务失败: {}", e))?;
-    let current: String = tx
-        .query_row(
-            "SELECT status FROM accounts WHERE id = ?1 AND deleted_at IS NULL",
-            [id],
-            |row| row.get(0),
-        )
-        .map_err(|e| e.to_string())?;
+const STORED_SESSION_SELECT: &str = "SELECT s.id AS session_id, s.token_hash, s.device_label, s.ip,
+        s.created_at AS session_created_at, s.expires_at, s.last_seen_at,
+        u.id, u.username, u.role, u.disabled, u.created_at, u.updated_at
+     FROM auth_sessions s JOIN users u ON u.id = s.user_id";
+
+fn map_row_to_stored_session(row: &Row) -> rusqlite::Result<StoredSession> {
+    Ok(StoredSession {
+        id: row.get("session_id")?,
+        token_hash: row.get("token_hash")?,
+        user: map_row_to_user(row)?,
+        device_label: row.get("device_label")?,
+        ip: row.get("ip")?,
+        created_at: row.get("session_created_at")?,
+        expires_at: row.get("expires_at")?,
+        last_seen_at: row.get("last_seen_at")?,
+    })
+}
 
-    let new_status = if current == "pro" { "inactive" } else { "pro" };
-    tx.execute(
-        "INSERT INTO account_history (account_id, field_name, old_value, new_value) VALUES (?1, ?2, ?3, ?4)",
-        params![id, "status", current, new_status],
+pub fn insert_session(conn: &Connection, session: &StoredSession) -> Result<(), String> {
+    conn.execute(
+        "INSERT INTO auth_sessions
+            (id, token_hash, user_id, device_label, ip, created_at, expires_at, last_seen_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        params![
+            session.id,
+            session.token_hash,
+            session.user.id,
+            session.device_label,
+            session.ip,
+            session.created_at,
+            session.expires_at,
+            session.last_seen_at
+        ],
     )
     .map_err(|e| e.to_string())?;
-    tx.execute(
-        "UPDATE accounts SET status = ?1, updated_at = CURRENT_TIMESTAMP WHERE id = ?2 AND deleted_at IS NULL",
-        params![new_status, id],
+    Ok(())
+}
+
+/// 按令牌哈希查找未过期、未空闲超时（最近活动晚于 `idle_cutoff`）且用户未被禁用的会话
+pub fn find_session(
+    conn: &Connection,
+    token_hash: &str,
+    now: i64,
+    idle_cutoff: i64,
+) -> Result<Option<StoredSession>, String> {
+    conn.query_row(
+        &format!(
+            "{} WHERE s.token_hash = ?1 AND s.expires_at > ?2 AND s.last_seen_at > ?3
+               AND u.disabled = 0",
+            STORED_SESSION_SELECT
+        ),
+        params![token_hash, now, idle_cutoff],
+        map_row_to_stored_session,
     )
-    .map_err(|e| e.to_string())?;
-    tx.commit().map_err(|e| e.to_string())?;
-    get_account_by_id(conn, id)
+    .optional()
+    .map_err(|e| e.to_string())
 }
 
-/// 切换 sold_status（unsold/sold）
-pub fn toggle_sold_status(conn: &Connection, id: i64) -> Result<Account, String> {
-    let tx = conn
-        .unchecked_transaction()
-        .map_err(|e| format!("开启事务失败: {}", e))?;
-    let current: String = tx
-        .query_row(
-            "SELECT sold_status FROM accounts WHERE id = ?1 AND deleted_at IS NULL",
-            [id],
-            |row| row.get(0),
+/// 列出未过期且未空闲超时的会话（`user_id` 为 `None` 时列出所有用户），按最近活动时间倒序
+pub fn list_sessions(
+    conn: &Connection,
+    user_id: Option<i64>,
+    now: i64,
+    idle_cutoff: i64,
+) -> Result<Vec<StoredSession>, String> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "{} WHERE s.expires_at > ?1 AND s.last_seen_at > ?3 AND (?2 IS NULL OR s.user_id = ?2)
+             ORDER BY s.last_seen_at DESC",
+            STORED_SESSION_SELECT
+        ))
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(
+            params![now, user_id, idle_cutoff],
+            map_row_to_stored_session,
         )
         .map_err(|e| e.to_string())?;
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())
+}
 
-    let new_status = if current == "sold" { "unsold" } else { "sold" };
-    tx.execute(
-        "INSERT INTO account_history (account_id, field_name, old_value, new_value) VALUES (?1, ?2, ?3, ?4)",
-        params![id, "sold_status", current, new_status],
-    )
-    .map_err(|e| e.to_string())?;
-    tx.execute(
-        "UPDATE accounts SET sold_status = ?1, updated_at = CURRENT_TIMESTAMP WHERE id = ?2 AND deleted_at IS NULL",
-        params![new_status, id],
+/// 更新会话的最近活动时间
+pub fn touch_session(conn: &Connection, token_hash: &str, last_seen_at: i64) -> Result<(), String> {
+    conn.execute(
+        "UPDATE auth_sessions SET last_seen_at = ?2 WHERE token_hash = ?1",
+        params![token_hash, last_seen_at],
     )
     .map_err(|e| e.to_string())?;
-    tx.commit().map_err(|e| e.to_string())?;
-    get_account_by_id(conn, id)
+    Ok(())
 }
 
-/// 批量导入（使用事务保证原子性）
-pub fn batch_import(conn: &Connection, accounts: &[AccountInput]) -> Result<(i32, i32), String> {
-    let mut success_count = 0i32;
-    let mut failed_count = 0i32;
-    let key = master_key()?;
-    let tx = conn
-        .unchecked_transaction()
-        .map_err(|e| format!("开始事务失败: {}", e))?;
-    let mut insert_stmt = tx
-        .prepare_cached(
-            "INSERT INTO accounts (email, password, recovery, phone, secret, reg_year, country, group_name, remark, status, sold_status, deleted_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, NULL)"
-        )
-        .map_err(|e| format!("准备批量导入语句失败: {}", e))?;
-
-    for account in accounts {
-        // AES 加密密码
-        let encrypted_password = match crypto::encrypt_secret(&account.password, &key) {
-            Ok(h) => h,
-            Err(e) => {
-                log::warn!("密码加密失败 (email={}): {}", account.email, e);
-                failed_count += 1;
-                continue;
-            }
-        };
-
-        // 加密 secret
-        let encrypted_secret = match &account.secret {
-            Some(secret) if !secret.is_empty() => match crypto::encrypt_secret(secret, &key) {
-                Ok(encrypted) => Some(encrypted),
-                Err(e) => {
-                    log::warn!("Secret 加密失败 (email={}): {}", account.email, e);
-                    failed_count += 1;
-                    continue;
-                }
-            },
-            _ => None,
-        };
-
-        let result = insert_stmt.execute(params![
-            account.email,
-            encrypted_password,
-            account.recovery,
-            account.phone,
-            encrypted_secret,
-            account.reg_year,
-            account.country,
-            account.group_name,
-            account.remark,
-            "inactive",
-            "unsold"
-        ]);
-        match result {
-            Ok(_) => success_count += 1,
-            Err(e) => {
-                log::warn!("批量导入单条失败 (email={}): {}", account.email, e);
-                failed_count += 1;
-            }
-        }
-    }
+pub fn delete_session_by_token_hash(conn: &Connection, token_hash: &str) -> Result<usize, String> {
+    conn.execute(
+        "DELETE FROM auth_sessions WHERE token_hash = ?1",
+        [token_hash],
+    )
+    .map_err(|e| e.to_string())
+}
 
-    drop(insert_stmt);
-    tx.commit()
-        .map_err(|e| format!("提交事务失败（已回滚）: {}", e))?;
+/// 按会话 ID 删除；`user_id` 不为空时只删除该用户的会话
+pub fn delete_session(conn: &Connection, id: &str, user_id: Option<i64>) -> Result<usize, String> {
+    conn.execute(
+        "DELETE FROM auth_sessions WHERE id = ?1 AND (?2 IS NULL OR user_id = ?2)",
+        params![id, user_id],
+    )
+    .map_err(|e| e.to_string())
+}
 
-    Ok((success_count, failed_count))
+/// 删除用户的全部会话，`keep_token_hash` 指定的会话除外
+pub fn delete_user_sessions(
+    conn: &Connection,
+    user_id: i64,
+    keep_token_hash: Option<&str>,
+) -> Result<usize, String> {
+    conn.execute(
+        "DELETE FROM auth_sessions WHERE user_id = ?1 AND (?2 IS NULL OR token_hash != ?2)",
+        params![user_id, keep_token_hash],
+    )
+    .map_err(|e| e.to_string())
 }
 
-/// 获取账号历史
-pub fn get_account_history(
+/// 删除已过期或空闲超时的会话
+pub fn delete_expired_sessions(
     conn: &Connection,
-    account_id: i64,
-) -> Result<Vec<AccountHistory>, String> {
-    let mut stmt = conn.prepare(
-        "SELECT id, account_id, field_name, old_value, new_value, changed_at FROM account_history WHERE account_id = ?1 ORDER BY changed_at DESC"
-    ).map_err(|e| e.to_string())?;
+    now: i64,
+    idle_cutoff: i64,
+) -> Result<usize, String> {
+    conn.execute(
+        "DELETE FROM auth_sessions WHERE expires_at <= ?1 OR last_seen_at <= ?2",
+        params![now, idle_cutoff],
+    )
+    .map_err(|e| e.to_string())
+}
 
-    let rows = stmt
-        .query_map([account_id], |row| {
-            Ok(AccountHistory {
-                id: row.get(0)?,
-                account_id: row.get(1)?,
-                field_name: row.get(2)?,
-                old_value: row.get(3)?,
-                new_value: row.get(4)?,
-                changed_at: row.get(5)?,
-            })
-        })
-        .map_err(|e| e.to_string())?;
-    rows.collect::<Result<Vec<_>, _>>()
-        .map_err(|e| e.to_string())
+/// 指定锁定键的失败计数与封禁截止时间（Unix 秒）
+pub fn get_lockout(conn: &Connection, key: &str) -> Result<(u32, Option<i64>), String> {
+    conn.query_row(
+        "SELECT failed_attempts, banned_until FROM auth_lockouts WHERE lockout_key = ?1",
+        [key],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )
+    .optional()
+    .map(|lockout| lockout.unwrap_or((0, None)))
+    .map_err(|e| e.to_string())
 }
 
-/// 记录字段变更历史（循环对比所有追踪字段）
-fn record_field_changes(
+pub fn set_lockout(
     conn: &Connection,
-    account_id: i64,
-    old: &Account,
-    new: &Account,
+    key: &str,
+    failed_attempts: u32,
+    banned_until: Option<i64>,
+    now: i64,
 ) -> Result<(), String> {
-    for &(field_name, getter) in TRACKED_FIELDS {
-        let old_val = getter(old);
-        let new_val = getter(new);
-        if old_val != new_val {
-            conn.execute(
-                "INSERT INTO account_history (account_id, field_name, old_value, new_value) VALUES (?1, ?2, ?3, ?4)",
-                params![account_id, field_name, old_val, new_val],
-            )
-            .map_err(|e| format!("记录字段变更失败 (account_id={}, field={}): {}", account_id, field_name, e))?;
-        }
-    }
+    conn.execute(
+        "INSERT INTO auth_lockouts (lockout_key, failed_attempts, banned_until, updated_at)
+         VALUES (?1, ?2, ?3, ?4)
+         ON CONFLICT(lockout_key) DO UPDATE SET
+             failed_attempts = excluded.failed_attempts,
+             banned_until = excluded.banned_until,
+             updated_at = excluded.updated_at",
+        params![key, failed_attempts, banned_until, now],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+pub fn clear_lockout(conn: &Connection, key: &str) -> Result<(), String> {
+    conn.execute("DELETE FROM auth_lockouts WHERE lockout_key = ?1", [key])
+        .map_err(|e| e.to_string())?;
     Ok(())
 }
 
-fn data_dir() -> PathBuf {
+/// 删除已过期的封禁，以及 `stale_before` 之前就不再变化的失败计数
+pub fn prune_lockouts(conn: &Connection, now: i64, stale_before: i64) -> Result<usize, String> {
+    conn.execute(
+        "DELETE FROM auth_lockouts
+         WHERE banned_until <= ?1 OR (banned_until IS NULL AND updated_at < ?2)",
+        params![now, stale_before],
+    )
+    .map_err(|e| e.to_string())
+}
+
+pub fn data_dir() -> PathBuf {
     let mut path = dirs::data_dir().unwrap_or_else(|| PathBuf::from("."));
     path.push("googlemanager");
     if let Err(e) = fs::create_dir_all(&path) {
@@ -550,6 +2266,23 @@ pub fn get_db_path() -> PathBuf {
     path
 }
 
+/// 打开一条独立的只读连接并开启读事务：WAL 模式下首次读取后即固定快照，
+/// 长时间的导出读取不必占用共享连接的锁，也不会读到导出过程中的写入
+pub fn open_read_snapshot() -> Result<Connection, String> {
+    open_read_snapshot_at(&get_db_path())
+}
+
+fn open_read_snapshot_at(path: &Path) -> Result<Connection, String> {
+    let conn = Connection::open_with_flags(
+        path,
+        rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY | rusqlite::OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )
+    .map_err(|e| format!("打开只读数据库连接失败: {}", e))?;
+    conn.execute_batch("BEGIN")
+        .map_err(|e| format!("开启只读事务失败: {}", e))?;
+    Ok(conn)
+}
+
 fn backups_dir() -> Result<PathBuf, String> {
     let mut path = data_dir();
     path.push("backups");
@@ -747,6 +2480,20 @@ pub fn list_backups() -> Result<Vec<BackupInfo>, String> {
     Ok(backups)
 }
 
+/// 已挂载备份库（backup_db）中某张表的列名
+fn backup_table_columns(
+    conn: &Connection,
+    table: &str,
+) -> Result<std::collections::HashSet<String>, String> {
+    let mut stmt = conn
+        .prepare(&format!("PRAGMA backup_db.table_info({})", table))
+        .map_err(|e| e.to_string())?;
+    let cols = stmt
+        .query_map([], |row| row.get::<_, String>(1))
+        .map_err(|e| e.to_string())?;
+    Ok(cols.filter_map(|col| col.ok()).collect())
+}
+
 pub fn restore_backup(conn: &Connection, backup_name: &str) -> Result<(), String> {
     let backup_name = sanitize_backup_name(backup_name)?;
     let backup_path = backups_dir()?.join(&backup_name);
@@ -773,43 +2520,35 @@ pub fn restore_backup(conn: &Connection, backup_name: &str) -> Result<(), String
     )
     .map_err(|e| format!("挂载备份库失败: {}", e))?;
 
-    let has_deleted_col = {
-        let mut stmt = tx
-            .prepare("PRAGMA backup_db.table_info(accounts)")
-            .map_err(|e| e.to_string())?;
-        let cols = stmt
-            .query_map([], |row| row.get::<_, String>(1))
-            .map_err(|e| e.to_string())?;
-        let mut found = false;
-        for col in cols {
-            if col.unwrap_or_default() == "deleted_at" {
-                found = true;
-                break;
+    let backup_columns = backup_table_columns(&tx, "accounts")?;
+
+    // 旧版备份缺失的列使用默认值补齐
+    let select_columns: Vec<&str> = RESTORE_ACCOUNT_COLUMNS
+        .iter()
+        .map(|&(column, fallback)| {
+            if backup_columns.contains(column) {
+                column
+            } else {
+                fallback
             }
-        }
-        found
-    };
+        })
+        .collect();
+    let insert_columns: Vec<&str> = RESTORE_ACCOUNT_COLUMNS
+        .iter()
+        .map(|&(column, _)| column)
+        .collect();
 
     tx.execute("DELETE FROM account_history", [])
         .map_err(|e| e.to_string())?;
     tx.execute("DELETE FROM accounts", [])
         .map_err(|e| e.to_string())?;
 
-    if has_deleted_col {
-        tx.execute_batch(
-            "INSERT INTO accounts (id, email, password, recovery, phone, secret, reg_year, country, group_name, remark, status, sold_status, created_at, updated_at, deleted_at)
-             SELECT id, email, password, recovery, phone, secret, reg_year, country, group_name, remark, status, sold_status, created_at, updated_at, deleted_at
-             FROM backup_db.accounts",
-        )
-        .map_err(|e| format!("恢复 accounts 失败: {}", e))?;
-    } else {
-        tx.execute_batch(
-            "INSERT INTO accounts (id, email, password, recovery, phone, secret, reg_year, country, group_name, remark, status, sold_status, created_at, updated_at, deleted_at)
-             SELECT id, email, password, recovery, phone, secret, reg_year, country, group_name, remark, status, sold_status, created_at, updated_at, NULL
-             FROM backup_db.accounts",
-        )
-        .map_err(|e| format!("恢复 accounts 失败: {}", e))?;
-    }
+    tx.execute_batch(&format!(
+        "INSERT INTO accounts ({}) SELECT {} FROM backup_db.accounts",
+        insert_columns.join(", "),
+        select_columns.join(", ")
+    ))
+    .map_err(|e| format!("恢复 accounts 失败: {}", e))?;
 
     let has_history_table: i64 = tx
         .query_row(
@@ -819,11 +2558,19 @@ pub fn restore_backup(conn: &Connection, backup_name: &str) -> Result<(), String
         )
         .map_err(|e| e.to_string())?;
     if has_history_table > 0 {
-        tx.execute_batch(
-            "INSERT INTO account_history (id, account_id, field_name, old_value, new_value, changed_at)
-             SELECT id, account_id, field_name, old_value, new_value, changed_at
+        // 旧版备份没有操作人列，恢复为空
+        let actor_columns =
+            if backup_table_columns(&tx, "account_history")?.contains("actor_user_id") {
+                "actor_user_id, actor_username"
+            } else {
+                "NULL, NULL"
+            };
+        tx.execute_batch(&format!(
+            "INSERT INTO account_history (id, account_id, field_name, old_value, new_value, changed_at, actor_user_id, actor_username)
+             SELECT id, account_id, field_name, old_value, new_value, changed_at, {}
              FROM backup_db.account_history",
-        )
+            actor_columns
+        ))
         .map_err(|e| format!("恢复 account_history 失败: {}", e))?;
     }
 
@@ -903,7 +2650,10 @@ pub fn init_database() -> Result<Connection> {
             sold_status TEXT DEFAULT 'unsold',
             created_at TEXT DEFAULT CURRENT_TIMESTAMP,
             updated_at TEXT DEFAULT CURRENT_TIMESTAMP,
-            deleted_at TEXT
+            deleted_at TEXT,
+            totp_algorithm TEXT DEFAULT 'SHA1',
+            totp_digits INTEGER DEFAULT 6,
+            totp_period INTEGER DEFAULT 30
         )",
         [],
     )?;
@@ -916,11 +2666,54 @@ pub fn init_database() -> Result<Connection> {
             old_value TEXT,
             new_value TEXT,
             changed_at TEXT DEFAULT CURRENT_TIMESTAMP,
+            actor_user_id INTEGER,
+            actor_username TEXT,
+            FOREIGN KEY (account_id) REFERENCES accounts(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS secret_reveals (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            account_id INTEGER NOT NULL,
+            field_name TEXT NOT NULL,
+            channel TEXT NOT NULL,
+            actor_user_id INTEGER,
+            actor_username TEXT,
+            revealed_at TEXT DEFAULT CURRENT_TIMESTAMP,
             FOREIGN KEY (account_id) REFERENCES accounts(id) ON DELETE CASCADE
         )",
         [],
     )?;
 
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS export_presets (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL UNIQUE,
+            config TEXT NOT NULL,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+            updated_at TEXT DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS export_audits (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            scope TEXT NOT NULL,
+            format TEXT NOT NULL,
+            row_count INTEGER NOT NULL,
+            target TEXT,
+            actor_user_id INTEGER,
+            actor_username TEXT,
+            exported_at TEXT DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+
+    create_auth_tables(&conn)?;
+
     // 数据库迁移：补齐字段（字段已存在时忽略）
     for col in &["phone", "reg_year", "country", "group_name", "deleted_at"] {
         let _ = conn.execute(&format!("ALTER TABLE accounts ADD COLUMN {} TEXT", col), []);
@@ -934,6 +2727,28 @@ pub fn init_database() -> Result<Connection> {
         )))
     })?;
 
+    // TOTP 参数列（需在软删除迁移之后补齐，默认值与旧版行为一致）
+    for (col, definition) in &[
+        ("totp_algorithm", "TEXT DEFAULT 'SHA1'"),
+        ("totp_digits", "INTEGER DEFAULT 6"),
+        ("totp_period", "INTEGER DEFAULT 30"),
+    ] {
+        let _ = conn.execute(
+            &format!("ALTER TABLE accounts ADD COLUMN {} {}", col, definition),
+            [],
+        );
+    }
+
+    // 查看记录与字段历史的操作人列（旧记录保持为空）
+    for table in ["secret_reveals", "account_history"] {
+        for (col, definition) in &[("actor_user_id", "INTEGER"), ("actor_username", "TEXT")] {
+            let _ = conn.execute(
+                &format!("ALTER TABLE {} ADD COLUMN {} {}", table, col, definition),
+                [],
+            );
+        }
+    }
+
     conn.execute_batch(
         "PRAGMA foreign_keys = ON;
          PRAGMA journal_mode = WAL;",
@@ -956,6 +2771,10 @@ pub fn init_database() -> Result<Connection> {
         "CREATE INDEX IF NOT EXISTS idx_account_history_account_id ON account_history(account_id)",
         [],
     )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_secret_reveals_account_id ON secret_reveals(account_id)",
+        [],
+    )?;
 
     Ok(conn)
 }
@@ -964,6 +2783,11 @@ pub fn init_database() -> Result<Connection> {
 mod tests {
     use super::*;
 
+    const TEST_ACTOR: AuditActor = AuditActor {
+        user_id: 1,
+        username: "tester",
+    };
+
     fn setup_test_db() -> Connection {
         let conn = Connection::open_in_memory().unwrap();
         conn.execute(
@@ -982,25 +2806,69 @@ mod tests {
                 sold_status TEXT DEFAULT 'unsold',
                 created_at TEXT DEFAULT CURRENT_TIMESTAMP,
                 updated_at TEXT DEFAULT CURRENT_TIMESTAMP,
-                deleted_at TEXT
+                deleted_at TEXT,
+                totp_algorithm TEXT DEFAULT 'SHA1',
+                totp_digits INTEGER DEFAULT 6,
+                totp_period INTEGER DEFAULT 30
+            )",
+            [],
+        )
+        .unwrap();
+        conn.execute(
+            "CREATE UNIQUE INDEX IF NOT EXISTS idx_accounts_email_active ON accounts(email) WHERE deleted_at IS NULL",
+            [],
+        )
+        .unwrap();
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS account_history (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                account_id INTEGER NOT NULL,
+                field_name TEXT NOT NULL,
+                old_value TEXT,
+                new_value TEXT,
+                changed_at TEXT DEFAULT CURRENT_TIMESTAMP,
+                actor_user_id INTEGER,
+                actor_username TEXT,
+                FOREIGN KEY (account_id) REFERENCES accounts(id) ON DELETE CASCADE
             )",
             [],
         )
         .unwrap();
         conn.execute(
-            "CREATE UNIQUE INDEX IF NOT EXISTS idx_accounts_email_active ON accounts(email) WHERE deleted_at IS NULL",
+            "CREATE TABLE IF NOT EXISTS secret_reveals (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                account_id INTEGER NOT NULL,
+                field_name TEXT NOT NULL,
+                channel TEXT NOT NULL,
+                actor_user_id INTEGER,
+                actor_username TEXT,
+                revealed_at TEXT DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (account_id) REFERENCES accounts(id) ON DELETE CASCADE
+            )",
             [],
         )
         .unwrap();
         conn.execute(
-            "CREATE TABLE IF NOT EXISTS account_history (
+            "CREATE TABLE IF NOT EXISTS export_presets (
                 id INTEGER PRIMARY KEY AUTOINCREMENT,
-                account_id INTEGER NOT NULL,
-                field_name TEXT NOT NULL,
-                old_value TEXT,
-                new_value TEXT,
-                changed_at TEXT DEFAULT CURRENT_TIMESTAMP,
-                FOREIGN KEY (account_id) REFERENCES accounts(id) ON DELETE CASCADE
+                name TEXT NOT NULL UNIQUE,
+                config TEXT NOT NULL,
+                created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+                updated_at TEXT DEFAULT CURRENT_TIMESTAMP
+            )",
+            [],
+        )
+        .unwrap();
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS export_audits (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                scope TEXT NOT NULL,
+                format TEXT NOT NULL,
+                row_count INTEGER NOT NULL,
+                target TEXT,
+                actor_user_id INTEGER,
+                actor_username TEXT,
+                exported_at TEXT DEFAULT CURRENT_TIMESTAMP
             )",
             [],
         )
@@ -1026,6 +2894,7 @@ mod tests {
             country: None,
             group_name: None,
             remark: Some("old-remark".to_string()),
+            totp: None,
         };
         let account = create_account(&conn, &input).unwrap();
         assert_eq!(account.email, "test@example.com");
@@ -1045,6 +2914,7 @@ mod tests {
             country: None,
             group_name: None,
             remark: None,
+            totp: None,
         };
         let account = create_account(&conn, &input).unwrap();
 
@@ -1061,8 +2931,9 @@ mod tests {
             country: None,
             group_name: None,
             remark: Some("new-remark".to_string()),
+            totp: None,
         };
-        let updated = update_account(&conn, account.id, &updated_input).unwrap();
+        let updated = update_account(&conn, account.id, &updated_input, TEST_ACTOR).unwrap();
 
         // 验证新密码已解密回原文
         assert_eq!(updated.password, "newpassword");
@@ -1071,9 +2942,45 @@ mod tests {
         let history = get_account_history(&conn, account.id).unwrap();
         assert_eq!(history.len(), 1);
         assert_eq!(history[0].field_name, "remark");
+        assert_eq!(history[0].actor_user_id, Some(1));
+        assert_eq!(history[0].actor_username.as_deref(), Some("tester"));
         assert!(history.iter().all(|h| h.field_name != "password"));
     }
 
+    #[test]
+    fn test_update_account_keeps_redacted_fields() {
+        let conn = setup_test_db();
+        let input = AccountInput {
+            email: "redacted-update@example.com".to_string(),
+            password: "password123".to_string(),
+            recovery: None,
+            phone: None,
+            secret: Some("JBSWY3DPEHPK3PXP".to_string()),
+            reg_year: None,
+            country: None,
+            group_name: None,
+            remark: None,
+            totp: None,
+        };
+        let account = create_account(&conn, &input).unwrap();
+
+        // 客户端基于脱敏列表回写：password/secret 仍是占位符，只改了备注
+        let redacted_input = AccountInput {
+            password: REDACTED_MARKER.to_string(),
+            secret: Some(REDACTED_MARKER.to_string()),
+            remark: Some("edited".to_string()),
+            ..input
+        };
+        let updated = update_account(&conn, account.id, &redacted_input, TEST_ACTOR).unwrap();
+
+        assert_eq!(updated.password, "password123");
+        assert_eq!(updated.secret.as_deref(), Some("JBSWY3DPEHPK3PXP"));
+        assert_eq!(updated.remark.as_deref(), Some("edited"));
+        let history = get_account_history(&conn, account.id).unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].field_name, "remark");
+    }
+
     #[test]
     fn test_update_secret_should_not_write_history() {
         let conn = setup_test_db();
@@ -1087,6 +2994,7 @@ mod tests {
             country: None,
             group_name: None,
             remark: None,
+            totp: None,
         };
         let account = create_account(&conn, &input).unwrap();
 
@@ -1100,8 +3008,9 @@ mod tests {
             country: None,
             group_name: None,
             remark: None,
+            totp: None,
         };
-        let _ = update_account(&conn, account.id, &updated_input).unwrap();
+        let _ = update_account(&conn, account.id, &updated_input, TEST_ACTOR).unwrap();
 
         let history = get_account_history(&conn, account.id).unwrap();
         assert!(history.iter().all(|h| h.field_name != "secret"));
@@ -1120,6 +3029,7 @@ mod tests {
             country: None,
             group_name: None,
             remark: None,
+            totp: None,
         };
         let account = create_account(&conn, &input).unwrap();
         delete_account(&conn, account.id).unwrap();
@@ -1150,6 +3060,7 @@ mod tests {
             country: None,
             group_name: None,
             remark: None,
+            totp: None,
         };
         let account = create_account(&conn, &input).unwrap();
 
@@ -1175,6 +3086,7 @@ mod tests {
             country: None,
             group_name: None,
             remark: None,
+            totp: None,
         };
         create_account(&conn, &input).unwrap();
         let result = create_account(&conn, &input);
@@ -1194,11 +3106,12 @@ mod tests {
             country: None,
             group_name: None,
             remark: None,
+            totp: None,
         };
         let account = create_account(&conn, &input).unwrap();
         assert_eq!(account.sold_status, "unsold");
 
-        let toggled = toggle_sold_status(&conn, account.id).unwrap();
+        let toggled = toggle_sold_status(&conn, account.id, TEST_ACTOR).unwrap();
         assert_eq!(toggled.sold_status, "sold");
     }
 
@@ -1215,6 +3128,7 @@ mod tests {
                 country: None,
                 group_name: None,
                 remark: Some("备注A".into()),
+                totp: None,
             },
             AccountInput {
                 email: "bob@gmail.com".into(),
@@ -1226,6 +3140,7 @@ mod tests {
                 country: Some("India".into()),
                 group_name: None,
                 remark: None,
+                totp: None,
             },
             AccountInput {
                 email: "charlie@gmail.com".into(),
@@ -1237,6 +3152,7 @@ mod tests {
                 country: None,
                 group_name: None,
                 remark: None,
+                totp: None,
             },
             AccountInput {
                 email: "david@gmail.com".into(),
@@ -1248,6 +3164,7 @@ mod tests {
                 country: None,
                 group_name: None,
                 remark: None,
+                totp: None,
             },
             AccountInput {
                 email: "echo@gmail.com".into(),
@@ -1259,6 +3176,7 @@ mod tests {
                 country: None,
                 group_name: None,
                 remark: None,
+                totp: None,
             },
             AccountInput {
                 email: "frank@gmail.com".into(),
@@ -1270,6 +3188,7 @@ mod tests {
                 country: None,
                 group_name: Some("主号".into()),
                 remark: Some("VIP".into()),
+                totp: None,
             },
             AccountInput {
                 email: "grace@gmail.com".into(),
@@ -1281,6 +3200,7 @@ mod tests {
                 country: Some("China".into()),
                 group_name: None,
                 remark: None,
+                totp: None,
             },
             AccountInput {
                 email: "min1@gmail.com".into(),
@@ -1292,6 +3212,7 @@ mod tests {
                 country: None,
                 group_name: None,
                 remark: None,
+                totp: None,
             },
         ]
     }
@@ -1302,7 +3223,8 @@ mod tests {
         let accounts = generate_test_accounts();
         let total = accounts.len() as i32;
 
-        let (success, failed) = batch_import(&conn, &accounts).unwrap();
+        let report = batch_import(&conn, &accounts, &ImportOptions::default(), TEST_ACTOR).unwrap();
+        let (success, failed) = (report.success_count, report.failed_count);
         assert_eq!(success, total);
         assert_eq!(failed, 0);
 
@@ -1321,7 +3243,7 @@ mod tests {
     fn test_batch_import_fields_correct() {
         let conn = setup_test_db();
         let accounts = generate_test_accounts();
-        batch_import(&conn, &accounts).unwrap();
+        batch_import(&conn, &accounts, &ImportOptions::default(), TEST_ACTOR).unwrap();
 
         let all = query_accounts(&conn, None, None).unwrap();
 
@@ -1363,7 +3285,7 @@ mod tests {
         let total = accounts.len() as i32;
 
         // 第一次导入
-        batch_import(&conn, &accounts).unwrap();
+        batch_import(&conn, &accounts, &ImportOptions::default(), TEST_ACTOR).unwrap();
         let count1 = query_accounts(&conn, None, None).unwrap().len();
         assert_eq!(count1, total as usize);
 
@@ -1374,7 +3296,8 @@ mod tests {
         assert_eq!(count_after_delete, 0);
 
         // 第二次导入
-        let (success2, failed2) = batch_import(&conn, &accounts).unwrap();
+        let report = batch_import(&conn, &accounts, &ImportOptions::default(), TEST_ACTOR).unwrap();
+        let (success2, failed2) = (report.success_count, report.failed_count);
         assert_eq!(success2, total);
         assert_eq!(failed2, 0);
         let count2 = query_accounts(&conn, None, None).unwrap().len();
@@ -1394,7 +3317,9 @@ mod tests {
             assert_eq!(empty.len(), 0, "第 {} 轮清空后不为空", round);
 
             // 导入
-            let (success, failed) = batch_import(&conn, &accounts).unwrap();
+            let report =
+                batch_import(&conn, &accounts, &ImportOptions::default(), TEST_ACTOR).unwrap();
+            let (success, failed) = (report.success_count, report.failed_count);
             assert_eq!(success, total, "第 {} 轮导入成功数不一致", round);
             assert_eq!(failed, 0, "第 {} 轮有失败记录", round);
 
@@ -1411,21 +3336,50 @@ mod tests {
     }
 
     #[test]
-    fn test_batch_import_duplicate_email_fails_gracefully() {
+    fn test_batch_import_duplicate_email_skipped_by_default() {
         let conn = setup_test_db();
         let accounts = generate_test_accounts();
-        batch_import(&conn, &accounts).unwrap();
-
-        // 再次导入相同数据，应该全部失败（email UNIQUE 约束）
-        let (success, failed) = batch_import(&conn, &accounts).unwrap();
-        assert_eq!(success, 0);
-        assert_eq!(failed, accounts.len() as i32);
+        batch_import(&conn, &accounts, &ImportOptions::default(), TEST_ACTOR).unwrap();
+
+        // 再次导入相同数据，默认全部跳过并逐行说明原因
+        let report = batch_import(&conn, &accounts, &ImportOptions::default(), TEST_ACTOR).unwrap();
+        assert_eq!(report.success_count, 0);
+        assert_eq!(report.failed_count, 0);
+        assert_eq!(report.skipped_count, accounts.len() as i32);
+        assert!(
+            report
+                .rows
+                .iter()
+                .all(|row| row.status == ImportRowStatus::SkippedDuplicate
+                    && row.account_id.is_some())
+        );
 
         // 原数据不受影响
         let all = query_accounts(&conn, None, None).unwrap();
         assert_eq!(all.len(), accounts.len());
     }
 
+    #[test]
+    fn test_batch_import_duplicate_email_ignores_case() {
+        let conn = setup_test_db();
+        let mut input = generate_test_accounts().remove(0);
+        input.email = "Case.Check@Gmail.com".to_string();
+        batch_import(
+            &conn,
+            std::slice::from_ref(&input),
+            &ImportOptions::default(),
+            TEST_ACTOR,
+        )
+        .unwrap();
+
+        input.email = "case.check@gmail.com".to_string();
+        let report = batch_import(&conn, &[input], &ImportOptions::default(), TEST_ACTOR).unwrap();
+        assert_eq!(report.success_count, 0);
+        assert_eq!(report.skipped_count, 1);
+        assert_eq!(report.rows[0].status, ImportRowStatus::SkippedDuplicate);
+        assert_eq!(query_accounts(&conn, None, None).unwrap().len(), 1);
+    }
+
     #[test]
     fn test_batch_import_secret_encryption() {
         let conn = setup_test_db();
@@ -1439,8 +3393,9 @@ mod tests {
             country: None,
             group_name: None,
             remark: None,
+            totp: None,
         }];
-        batch_import(&conn, &accounts).unwrap();
+        batch_import(&conn, &accounts, &ImportOptions::default(), TEST_ACTOR).unwrap();
 
         // 通过 map_row_to_account 查询（会解密）
         let all = query_accounts(&conn, None, None).unwrap();
@@ -1511,6 +3466,7 @@ mod tests {
             country: None,
             group_name: None,
             remark: Some("target remark".to_string()),
+            totp: None,
         };
         let input2 = AccountInput {
             email: "bob@example.com".to_string(),
@@ -1522,6 +3478,7 @@ mod tests {
             country: None,
             group_name: None,
             remark: Some("other".to_string()),
+            totp: None,
         };
         create_account(&conn, &input1).unwrap();
         create_account(&conn, &input2).unwrap();
@@ -1535,4 +3492,805 @@ mod tests {
         assert_eq!(found.len(), 1);
         assert_eq!(found[0].email, "alice@example.com");
     }
+
+    #[test]
+    fn test_query_accounts_page_paginates_and_counts() {
+        let conn = setup_test_db();
+        batch_import(
+            &conn,
+            &generate_test_accounts(),
+            &ImportOptions::default(),
+            TEST_ACTOR,
+        )
+        .unwrap();
+
+        let page_query = AccountPageQuery {
+            page: Some(2),
+            page_size: Some(3),
+            sort_field: Some("email".to_string()),
+            sort_direction: Some("asc".to_string()),
+        };
+        let page = query_accounts_page(&conn, None, None, None, &page_query).unwrap();
+        assert_eq!(page.total, 8);
+        assert_eq!(page.page, 2);
+        assert_eq!(page.page_size, 3);
+        let emails: Vec<&str> = page.items.iter().map(|a| a.email.as_str()).collect();
+        assert_eq!(
+            emails,
+            vec!["david@gmail.com", "echo@gmail.com", "frank@gmail.com"]
+        );
+
+        // 超出范围的页返回空列表，但总数不变
+        let beyond = AccountPageQuery {
+            page: Some(10),
+            page_size: Some(3),
+            ..Default::default()
+        };
+        let page = query_accounts_page(&conn, None, None, None, &beyond).unwrap();
+        assert!(page.items.is_empty());
+        assert_eq!(page.total, 8);
+    }
+
+    #[test]
+    fn test_query_deleted_accounts_page_paginates() {
+        let conn = setup_test_db();
+        batch_import(
+            &conn,
+            &generate_test_accounts(),
+            &ImportOptions::default(),
+            TEST_ACTOR,
+        )
+        .unwrap();
+        let ids: Vec<i64> = query_accounts(&conn, None, None)
+            .unwrap()
+            .iter()
+            .map(|a| a.id)
+            .collect();
+        for id in &ids[..5] {
+            delete_account(&conn, *id).unwrap();
+        }
+
+        let page_query = AccountPageQuery {
+            page: Some(2),
+            page_size: Some(2),
+            ..Default::default()
+        };
+        let page = query_deleted_accounts_page(&conn, &page_query).unwrap();
+        assert_eq!(page.total, 5);
+        assert_eq!(page.items.len(), 2);
+        assert!(page.items.iter().all(|a| a.deleted_at.is_some()));
+        assert_eq!(page.items[0].password, REDACTED_MARKER);
+    }
+
+    #[test]
+    fn test_query_accounts_page_filters_and_defaults() {
+        let conn = setup_test_db();
+        batch_import(
+            &conn,
+            &generate_test_accounts(),
+            &ImportOptions::default(),
+            TEST_ACTOR,
+        )
+        .unwrap();
+
+        // 未指定分页时使用默认页大小，默认按 id 倒序
+        let all =
+            query_accounts_page(&conn, None, None, None, &AccountPageQuery::default()).unwrap();
+        assert_eq!(all.total, 8);
+        assert_eq!(all.items.len(), 8);
+        assert_eq!(all.page, 1);
+        assert_eq!(all.page_size, DEFAULT_PAGE_SIZE);
+        assert_eq!(all.items[0].email, "min1@gmail.com");
+
+        // 非白名单排序字段回退到 id
+        let injected = AccountPageQuery {
+            page: Some(1),
+            page_size: Some(2),
+            sort_field: Some("email; DROP TABLE accounts".to_string()),
+            sort_direction: Some("asc".to_string()),
+        };
+        let page = query_accounts_page(&conn, Some("gmail"), None, None, &injected).unwrap();
+        assert_eq!(page.total, 8);
+        assert_eq!(page.items[0].email, "alice@gmail.com");
+        assert_eq!(page.items[1].email, "bob@gmail.com");
+
+        let filtered = query_accounts_page(
+            &conn,
+            Some("VIP"),
+            Some("unsold"),
+            None,
+            &AccountPageQuery::default(),
+        )
+        .unwrap();
+        assert_eq!(filtered.total, 1);
+        assert_eq!(filtered.items[0].email, "frank@gmail.com");
+    }
+
+    #[test]
+    fn test_query_accounts_page_never_returns_more_than_default_page_size() {
+        let conn = setup_test_db();
+        let total = DEFAULT_PAGE_SIZE as usize + 5;
+        for i in 0..total {
+            conn.execute(
+                "INSERT INTO accounts (email, password) VALUES (?1, 'x')",
+                [format!("bulk{}@example.com", i)],
+            )
+            .unwrap();
+        }
+
+        let page =
+            query_accounts_page(&conn, None, None, None, &AccountPageQuery::default()).unwrap();
+        assert_eq!(page.total, total as i64);
+        assert_eq!(page.items.len(), DEFAULT_PAGE_SIZE as usize);
+
+        let oversized = AccountPageQuery {
+            page_size: Some(MAX_PAGE_SIZE + 1),
+            ..AccountPageQuery::default()
+        };
+        let page = query_accounts_page(&conn, None, None, None, &oversized).unwrap();
+        assert_eq!(page.page_size, MAX_PAGE_SIZE);
+    }
+
+    #[test]
+    fn test_group_filter_matches_whole_tags() {
+        let conn = setup_test_db();
+        for (email, group_name) in [
+            ("a@example.com", Some("VIP,美国")),
+            ("b@example.com", Some("VIPPLUS")),
+            ("c@example.com", Some("美国， 老号")),
+            ("d@example.com", None),
+        ] {
+            conn.execute(
+                "INSERT INTO accounts (email, password, group_name) VALUES (?1, 'x', ?2)",
+                params![email, group_name],
+            )
+            .unwrap();
+        }
+        conn.execute(
+            "INSERT INTO accounts (email, password, group_name, deleted_at) VALUES ('e@example.com', 'x', '已删除', CURRENT_TIMESTAMP)",
+            [],
+        )
+        .unwrap();
+
+        assert_eq!(
+            list_account_groups(&conn).unwrap(),
+            vec!["VIP", "VIPPLUS", "美国", "老号"]
+        );
+
+        let vip = query_accounts_page(&conn, None, None, Some("VIP"), &AccountPageQuery::default())
+            .unwrap();
+        assert_eq!(vip.total, 1);
+        assert_eq!(vip.items[0].email, "a@example.com");
+
+        assert_eq!(
+            query_account_ids(&conn, None, None, Some("美国"))
+                .unwrap()
+                .len(),
+            2
+        );
+        assert_eq!(
+            query_account_ids(&conn, Some("c@"), None, Some("美国"))
+                .unwrap()
+                .len(),
+            1
+        );
+        assert_eq!(query_account_ids(&conn, None, None, None).unwrap().len(), 4);
+        assert!(query_account_ids(&conn, None, None, Some("已删除"))
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_redacted_page_does_not_decrypt() {
+        let conn = setup_test_db();
+        conn.execute(
+            "INSERT INTO accounts (email, password, secret, status, sold_status) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                "broken@example.com",
+                "not-encrypted-password",
+                "not-encrypted-secret",
+                "inactive",
+                "unsold"
+            ],
+        )
+        .unwrap();
+
+        let page =
+            query_accounts_page(&conn, None, None, None, &AccountPageQuery::default()).unwrap();
+        assert_eq!(page.items.len(), 1);
+        assert_eq!(page.items[0].password, REDACTED_MARKER);
+        assert_eq!(page.items[0].secret.as_deref(), Some(REDACTED_MARKER));
+    }
+
+    #[test]
+    fn test_reveal_account_secret_decrypts_single_field_and_records() {
+        let conn = setup_test_db();
+        let input = AccountInput {
+            email: "reveal@example.com".to_string(),
+            password: "pwd-reveal".to_string(),
+            recovery: None,
+            phone: None,
+            secret: Some("JBSWY3DPEHPK3PXP".to_string()),
+            reg_year: None,
+            country: None,
+            group_name: None,
+            remark: None,
+            totp: None,
+        };
+        let account = create_account(&conn, &input).unwrap();
+
+        let password = reveal_account_secret(&conn, account.id, "password", TEST_ACTOR).unwrap();
+        assert_eq!(password.as_deref(), Some("pwd-reveal"));
+        let secret = reveal_account_secret(&conn, account.id, "secret", TEST_ACTOR).unwrap();
+        assert_eq!(secret.as_deref(), Some("JBSWY3DPEHPK3PXP"));
+        assert!(reveal_account_secret(&conn, account.id, "email", TEST_ACTOR).is_err());
+
+        let reveals = get_secret_reveals(&conn, account.id).unwrap();
+        assert_eq!(reveals.len(), 2);
+        assert_eq!(reveals[0].field_name, "secret");
+        assert_eq!(reveals[1].field_name, "password");
+        assert!(reveals.iter().all(|r| r.channel == "reveal"));
+        assert!(reveals
+            .iter()
+            .all(|r| r.actor_user_id == Some(1) && r.actor_username.as_deref() == Some("tester")));
+
+        // 查看记录不进入字段修改历史
+        assert!(get_account_history(&conn, account.id).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_redacted_account_by_id_masks_secrets() {
+        let conn = setup_test_db();
+        let mut input = AccountInput {
+            email: "detail-redacted@example.com".to_string(),
+            password: "pwd-detail".to_string(),
+            recovery: None,
+            phone: None,
+            secret: Some("JBSWY3DPEHPK3PXP".to_string()),
+            reg_year: None,
+            country: None,
+            group_name: None,
+            remark: None,
+            totp: None,
+        };
+        let with_secret = create_account(&conn, &input).unwrap();
+        input.email = "detail-redacted-2@example.com".to_string();
+        input.secret = None;
+        let without_secret = create_account(&conn, &input).unwrap();
+
+        let redacted = get_redacted_account_by_id(&conn, with_secret.id).unwrap();
+        assert_eq!(redacted.password, REDACTED_MARKER);
+        assert_eq!(redacted.secret.as_deref(), Some(REDACTED_MARKER));
+        let redacted = get_redacted_account_by_id(&conn, without_secret.id).unwrap();
+        assert_eq!(redacted.secret, None);
+
+        // 脱敏读取不写查看记录
+        assert!(get_secret_reveals(&conn, with_secret.id)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_totp_params_persist_and_survive_update() {
+        let conn = setup_test_db();
+        let mut input = AccountInput {
+            email: "totp-params@example.com".to_string(),
+            password: "pwd".to_string(),
+            recovery: None,
+            phone: None,
+            secret: Some("JBSWY3DPEHPK3PXPJBSWY3DPEHPK3PXP".to_string()),
+            reg_year: None,
+            country: None,
+            group_name: None,
+            remark: None,
+            totp: Some(TotpParams {
+                algorithm: "sha512".to_string(),
+                digits: 8,
+                period: 60,
+            }),
+        };
+        let account = create_account(&conn, &input).unwrap();
+        assert_eq!(account.totp.algorithm, "SHA512");
+        assert_eq!(account.totp.digits, 8);
+        assert_eq!(account.totp.period, 60);
+
+        // 更新时未提供参数则保留原值
+        input.totp = None;
+        input.remark = Some("changed".to_string());
+        let updated = update_account(&conn, account.id, &input, TEST_ACTOR).unwrap();
+        assert_eq!(updated.totp, account.totp);
+
+        let stored = get_account_secret(&conn, account.id).unwrap().unwrap();
+        assert_eq!(stored.params, account.totp);
+
+        // 非法参数：创建报错，批量导入计为失败
+        input.email = "totp-invalid@example.com".to_string();
+        input.totp = Some(TotpParams {
+            digits: 10,
+            ..TotpParams::default()
+        });
+        assert!(create_account(&conn, &input).is_err());
+        let report = batch_import(
+            &conn,
+            std::slice::from_ref(&input),
+            &ImportOptions::default(),
+            TEST_ACTOR,
+        )
+        .unwrap();
+        let (success, failed) = (report.success_count, report.failed_count);
+        assert_eq!((success, failed), (0, 1));
+
+        // 未指定参数的导入使用默认值
+        let imported = generate_test_accounts();
+        batch_import(&conn, &imported, &ImportOptions::default(), TEST_ACTOR).unwrap();
+        let all = query_accounts(&conn, Some("alice"), None).unwrap();
+        assert_eq!(all[0].totp, TotpParams::default());
+    }
+
+    #[test]
+    fn test_otpauth_uri_secret_is_parsed_on_create_update_and_import() {
+        let conn = setup_test_db();
+        let mut input = AccountInput {
+            email: "otpauth@example.com".to_string(),
+            password: "pwd".to_string(),
+            recovery: None,
+            phone: None,
+            secret: Some(
+                "otpauth://totp/Google:otpauth%40example.com?secret=jbswy3dpehpk3pxp&issuer=Google&digits=8&period=60"
+                    .to_string(),
+            ),
+            reg_year: None,
+            country: None,
+            group_name: None,
+            remark: None,
+            totp: None,
+        };
+        let account = create_account(&conn, &input).unwrap();
+        assert_eq!(account.secret.as_deref(), Some("JBSWY3DPEHPK3PXP"));
+        assert_eq!(account.totp.digits, 8);
+        assert_eq!(account.totp.period, 60);
+
+        // 显式参数优先于 URI 中的参数
+        input.totp = Some(TotpParams::default());
+        let updated = update_account(&conn, account.id, &input, TEST_ACTOR).unwrap();
+        assert_eq!(updated.secret.as_deref(), Some("JBSWY3DPEHPK3PXP"));
+        assert_eq!(updated.totp, TotpParams::default());
+
+        input.email = "otpauth-import@example.com".to_string();
+        input.totp = None;
+        let mut broken = input.clone();
+        broken.email = "otpauth-broken@example.com".to_string();
+        broken.secret = Some("otpauth://hotp/x?secret=JBSWY3DPEHPK3PXP".to_string());
+        assert!(create_account(&conn, &broken).is_err());
+        let report = batch_import(
+            &conn,
+            &[input, broken],
+            &ImportOptions::default(),
+            TEST_ACTOR,
+        )
+        .unwrap();
+        let (success, failed) = (report.success_count, report.failed_count);
+        assert_eq!((success, failed), (1, 1));
+        let imported = query_accounts(&conn, Some("otpauth-import"), None).unwrap();
+        assert_eq!(imported[0].secret.as_deref(), Some("JBSWY3DPEHPK3PXP"));
+        assert_eq!(imported[0].totp.period, 60);
+    }
+
+    #[test]
+    fn test_migration_payload_export_then_import_matches_by_email() {
+        let conn = setup_test_db();
+        let mut input = AccountInput {
+            email: "Migrate@example.com".to_string(),
+            password: "pwd".to_string(),
+            recovery: None,
+            phone: None,
+            secret: Some("JBSWY3DPEHPK3PXPJBSWY3DPEHPK3PXP".to_string()),
+            reg_year: None,
+            country: None,
+            group_name: None,
+            remark: Some("keep".to_string()),
+            totp: None,
+        };
+        let account = create_account(&conn, &input).unwrap();
+        let export =
+            crate::migration::export_migration_uris(&conn, &[account.id, 9999], TEST_ACTOR)
+                .unwrap();
+        assert_eq!(export.uris.len(), 1);
+        assert_eq!(export.exported_ids, vec![account.id]);
+        assert_eq!(export.skipped[0].account_id, 9999);
+        assert_eq!(
+            get_secret_reveals(&conn, account.id).unwrap()[0].channel,
+            "migration"
+        );
+
+        input.secret = Some("GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ".to_string());
+        update_account(&conn, account.id, &input, TEST_ACTOR).unwrap();
+
+        let preview =
+            crate::migration::import_migration_uri(&conn, &export.uris[0], false, TEST_ACTOR)
+                .unwrap();
+        assert_eq!(preview[0].account_id, Some(account.id));
+        assert_eq!(preview[0].matched_by.as_deref(), Some("email"));
+        assert!(!preview[0].applied);
+
+        let applied =
+            crate::migration::import_migration_uri(&conn, &export.uris[0], true, TEST_ACTOR)
+                .unwrap();
+        assert!(applied[0].applied);
+        let restored = get_account_by_id(&conn, account.id).unwrap();
+        assert_eq!(
+            restored.secret.as_deref(),
+            Some("JBSWY3DPEHPK3PXPJBSWY3DPEHPK3PXP")
+        );
+        assert_eq!(restored.remark.as_deref(), Some("keep"));
+    }
+
+    #[test]
+    fn test_account_qr_code_records_reveal() {
+        let conn = setup_test_db();
+        let mut input = AccountInput {
+            email: "qr@example.com".to_string(),
+            password: "pwd".to_string(),
+            recovery: None,
+            phone: None,
+            secret: Some("JBSWY3DPEHPK3PXPJBSWY3DPEHPK3PXP".to_string()),
+            reg_year: None,
+            country: None,
+            group_name: None,
+            remark: None,
+            totp: None,
+        };
+        let account = create_account(&conn, &input).unwrap();
+
+        let uri = reveal_account_otpauth_uri(&conn, account.id, "qrcode", TEST_ACTOR).unwrap();
+        assert!(uri.starts_with("otpauth://totp/Google:qr%40example.com?secret=JBSWY3DP"));
+        let image =
+            crate::qr::render_account_qr_code(&conn, account.id, Some("svg"), TEST_ACTOR).unwrap();
+        assert_eq!(image.format, "svg");
+        let reveals = get_secret_reveals(&conn, account.id).unwrap();
+        assert_eq!(reveals.len(), 2);
+        assert!(reveals
+            .iter()
+            .all(|r| r.channel == "qrcode" && r.field_name == "secret"));
+
+        // 未设置密钥时报错且不记录查看
+        input.email = "qr-empty@example.com".to_string();
+        input.secret = None;
+        let empty = create_account(&conn, &input).unwrap();
+        assert!(crate::qr::render_account_qr_code(&conn, empty.id, None, TEST_ACTOR).is_err());
+        assert!(get_secret_reveals(&conn, empty.id).unwrap().is_empty());
+    }
+
+    fn import_input(email: &str, password: &str) -> AccountInput {
+        AccountInput {
+            email: email.to_string(),
+            password: password.to_string(),
+            recovery: None,
+            phone: None,
+            secret: None,
+            reg_year: None,
+            country: None,
+            group_name: None,
+            remark: None,
+            totp: None,
+        }
+    }
+
+    #[test]
+    fn test_batch_import_reports_each_row() {
+        let conn = setup_test_db();
+        let mut invalid = import_input("bad@example.com", "pwd");
+        invalid.secret = Some("otpauth://hotp/x?secret=JBSWY3DP".to_string());
+        let rows = vec![
+            import_input("new@example.com", "pwd"),
+            invalid,
+            import_input("new@example.com", "pwd2"),
+            import_input("nopass@example.com", " "),
+        ];
+
+        let report = batch_import(&conn, &rows, &ImportOptions::default(), TEST_ACTOR).unwrap();
+        let statuses: Vec<_> = report.rows.iter().map(|row| row.status).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                ImportRowStatus::Inserted,
+                ImportRowStatus::Failed,
+                ImportRowStatus::SkippedDuplicate,
+                ImportRowStatus::Failed
+            ]
+        );
+        assert_eq!(report.rows[1].index, 1);
+        assert_eq!(report.rows[1].email, "bad@example.com");
+        assert!(report.rows[1].reason.as_deref().unwrap().contains("totp"));
+        assert_eq!(report.rows[3].failure, Some(ImportFailure::Other));
+        assert_eq!(
+            (
+                report.inserted_count,
+                report.failed_count,
+                report.skipped_count
+            ),
+            (1, 2, 1)
+        );
+    }
+
+    #[test]
+    fn test_batch_import_overwrite_and_merge_existing() {
+        let conn = setup_test_db();
+        let mut original = import_input("dup@example.com", "old-pwd");
+        original.remark = Some("old remark".to_string());
+        let id = create_account(&conn, &original).unwrap().id;
+
+        let mut incoming = import_input("dup@example.com", "new-pwd");
+        incoming.remark = Some("new remark".to_string());
+        incoming.country = Some("US".to_string());
+
+        // 合并：只填充空字段
+        let merge = ImportOptions {
+            duplicate_mode: DuplicateMode::Merge,
+            ..ImportOptions::default()
+        };
+        let report =
+            batch_import(&conn, std::slice::from_ref(&incoming), &merge, TEST_ACTOR).unwrap();
+        assert_eq!(report.rows[0].status, ImportRowStatus::Updated);
+        let merged = get_account_by_id(&conn, id).unwrap();
+        assert_eq!(merged.password, "old-pwd");
+        assert_eq!(merged.remark.as_deref(), Some("old remark"));
+        assert_eq!(merged.country.as_deref(), Some("US"));
+
+        // 再次合并没有可填充的字段
+        let report =
+            batch_import(&conn, std::slice::from_ref(&incoming), &merge, TEST_ACTOR).unwrap();
+        assert_eq!(report.rows[0].status, ImportRowStatus::SkippedDuplicate);
+
+        // 覆盖：非空字段全部替换，空字段保留
+        let overwrite = ImportOptions {
+            duplicate_mode: DuplicateMode::Overwrite,
+            ..ImportOptions::default()
+        };
+        incoming.country = None;
+        let report = batch_import(&conn, &[incoming], &overwrite, TEST_ACTOR).unwrap();
+        assert_eq!(report.updated_count, 1);
+        let overwritten = get_account_by_id(&conn, id).unwrap();
+        assert_eq!(overwritten.password, "new-pwd");
+        assert_eq!(overwritten.remark.as_deref(), Some("new remark"));
+        assert_eq!(overwritten.country.as_deref(), Some("US"));
+        assert!(get_account_history(&conn, id)
+            .unwrap()
+            .iter()
+            .any(|h| h.field_name == "remark"));
+    }
+
+    #[test]
+    fn test_batch_import_recycled_email_modes() {
+        let conn = setup_test_db();
+        let mut original = import_input("recycled@example.com", "old-pwd");
+        original.remark = Some("kept".to_string());
+        let id = create_account(&conn, &original).unwrap().id;
+        delete_account(&conn, id).unwrap();
+        let mut incoming = import_input("recycled@example.com", "new-pwd");
+        incoming.country = Some("CN".to_string());
+
+        let skip = ImportOptions {
+            recycled_mode: RecycledMode::Skip,
+            ..ImportOptions::default()
+        };
+        let report =
+            batch_import(&conn, std::slice::from_ref(&incoming), &skip, TEST_ACTOR).unwrap();
+        assert_eq!(report.rows[0].status, ImportRowStatus::SkippedDeleted);
+        assert_eq!(report.rows[0].account_id, Some(id));
+
+        let restore = ImportOptions {
+            duplicate_mode: DuplicateMode::Merge,
+            recycled_mode: RecycledMode::Restore,
+            ..ImportOptions::default()
+        };
+        let report =
+            batch_import(&conn, std::slice::from_ref(&incoming), &restore, TEST_ACTOR).unwrap();
+        assert_eq!(report.rows[0].status, ImportRowStatus::Restored);
+        assert_eq!(report.restored_count, 1);
+        let restored = get_account_by_id(&conn, id).unwrap();
+        assert_eq!(restored.password, "old-pwd");
+        assert_eq!(restored.remark.as_deref(), Some("kept"));
+        assert_eq!(restored.country.as_deref(), Some("CN"));
+
+        // 默认模式：回收站中的旧记录不变，新建账号
+        delete_account(&conn, id).unwrap();
+        let report =
+            batch_import(&conn, &[incoming], &ImportOptions::default(), TEST_ACTOR).unwrap();
+        assert_eq!(report.rows[0].status, ImportRowStatus::Inserted);
+        assert_ne!(report.rows[0].account_id, Some(id));
+        let deleted = query_deleted_accounts_page(&conn, &AccountPageQuery::default()).unwrap();
+        assert_eq!(deleted.total, 1);
+    }
+
+    #[test]
+    fn test_batch_import_dry_run_reports_without_writing() {
+        let conn = setup_test_db();
+        let active_id = create_account(&conn, &import_input("active@example.com", "pwd"))
+            .unwrap()
+            .id;
+        let recycled_id = create_account(&conn, &import_input("recycled@example.com", "pwd"))
+            .unwrap()
+            .id;
+        delete_account(&conn, recycled_id).unwrap();
+
+        let mut bad_secret = import_input("bad-secret@example.com", "pwd");
+        bad_secret.secret = Some("not base32!".to_string());
+        let mut bad_phone = import_input("bad-phone@example.com", "pwd");
+        bad_phone.phone = Some("12-34".to_string());
+        let mut good_phone = import_input("new@example.com", "pwd");
+        good_phone.phone = Some("+86 138-1234-5678".to_string());
+        let rows = vec![
+            good_phone,
+            import_input("active@example.com", "pwd"),
+            import_input("recycled@example.com", "pwd"),
+            bad_secret,
+            bad_phone,
+        ];
+        let options = ImportOptions {
+            recycled_mode: RecycledMode::Restore,
+            dry_run: true,
+            ..ImportOptions::default()
+        };
+
+        let report = batch_import(&conn, &rows, &options, TEST_ACTOR).unwrap();
+        assert!(report.dry_run);
+        let summary: Vec<_> = report
+            .rows
+            .iter()
+            .map(|row| (row.status, row.conflict, row.failure))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (ImportRowStatus::Inserted, None, None),
+                (
+                    ImportRowStatus::SkippedDuplicate,
+                    Some(ImportConflict::Active),
+                    None
+                ),
+                (
+                    ImportRowStatus::Restored,
+                    Some(ImportConflict::Recycled),
+                    None
+                ),
+                (
+                    ImportRowStatus::Failed,
+                    None,
+                    Some(ImportFailure::InvalidSecret)
+                ),
+                (
+                    ImportRowStatus::Failed,
+                    None,
+                    Some(ImportFailure::InvalidPhone)
+                ),
+            ]
+        );
+        assert_eq!(report.rows[0].account_id, None);
+        assert_eq!(report.rows[1].account_id, Some(active_id));
+
+        // 预览不落库：新账号未插入，回收站账号未恢复
+        let active = query_accounts(&conn, None, None).unwrap();
+        assert_eq!(active.len(), 1);
+        let deleted = query_deleted_accounts_page(&conn, &AccountPageQuery::default()).unwrap();
+        assert_eq!(deleted.items[0].id, recycled_id);
+
+        // 同样的输入正式导入，结果与预览一致
+        let committed = batch_import(
+            &conn,
+            &rows,
+            &ImportOptions {
+                dry_run: false,
+                ..options
+            },
+            TEST_ACTOR,
+        )
+        .unwrap();
+        let committed_statuses: Vec<_> = committed.rows.iter().map(|row| row.status).collect();
+        let preview_statuses: Vec<_> = report.rows.iter().map(|row| row.status).collect();
+        assert_eq!(committed_statuses, preview_statuses);
+        assert_eq!(query_accounts(&conn, None, None).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_export_preset_crud() {
+        let conn = setup_test_db();
+        let config =
+            serde_json::json!({ "separator": "----", "fields": ["email"], "include_stats": false });
+
+        let created = create_export_preset(&conn, "  邮箱列表 ", &config).unwrap();
+        assert_eq!(created.name, "邮箱列表");
+        assert_eq!(created.config, config);
+        assert!(create_export_preset(&conn, "邮箱列表", &config)
+            .unwrap_err()
+            .contains("已存在"));
+        assert!(create_export_preset(&conn, " ", &config).is_err());
+        assert!(create_export_preset(&conn, "数组", &serde_json::json!([])).is_err());
+
+        let other = create_export_preset(&conn, "A 全字段", &config).unwrap();
+        assert!(update_export_preset(&conn, other.id, "邮箱列表", &config).is_err());
+        let renamed = serde_json::json!({ "separator": ",", "fields": ["email", "password"], "include_stats": true });
+        let updated = update_export_preset(&conn, other.id, "全字段", &renamed).unwrap();
+        assert_eq!(get_export_preset(&conn, "全字段").unwrap().config, renamed);
+        assert_eq!(updated.id, other.id);
+
+        let names: Vec<_> = list_export_presets(&conn)
+            .unwrap()
+            .into_iter()
+            .map(|preset| preset.name)
+            .collect();
+        assert_eq!(names, vec!["全字段", "邮箱列表"]);
+
+        delete_export_preset(&conn, created.id).unwrap();
+        assert!(get_export_preset(&conn, "邮箱列表").is_err());
+        assert!(delete_export_preset(&conn, created.id).is_err());
+    }
+
+    #[test]
+    fn test_record_export_audit_writes_single_row() {
+        let conn = setup_test_db();
+        record_export_audit(
+            &conn,
+            r#"{"search":"a"}"#,
+            "csv",
+            42,
+            Some("/tmp/out.csv"),
+            TEST_ACTOR,
+        )
+        .unwrap();
+
+        let rows: Vec<(String, String, i64, Option<String>, i64, String)> = conn
+            .prepare(
+                "SELECT scope, format, row_count, target, actor_user_id, actor_username FROM export_audits",
+            )
+            .unwrap()
+            .query_map([], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?, row.get(5)?))
+            })
+            .unwrap()
+            .collect::<Result<_>>()
+            .unwrap();
+        assert_eq!(
+            rows,
+            vec![(
+                r#"{"search":"a"}"#.to_string(),
+                "csv".to_string(),
+                42,
+                Some("/tmp/out.csv".to_string()),
+                1,
+                "tester".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_read_snapshot_ignores_later_writes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data.db");
+        let writer = Connection::open(&path).unwrap();
+        writer
+            .execute_batch(
+                "PRAGMA journal_mode = WAL;
+                 CREATE TABLE items (id INTEGER PRIMARY KEY);
+                 INSERT INTO items DEFAULT VALUES;",
+            )
+            .unwrap();
+
+        let snapshot = open_read_snapshot_at(&path).unwrap();
+        let count = |conn: &Connection| -> i64 {
+            conn.query_row("SELECT COUNT(*) FROM items", [], |row| row.get(0))
+                .unwrap()
+        };
+        assert_eq!(count(&snapshot), 1);
+
+        // 快照读取期间共享连接仍可写入，快照看不到新数据
+        writer
+            .execute("INSERT INTO items DEFAULT VALUES", [])
+            .unwrap();
+        assert_eq!(count(&snapshot), 1);
+        assert_eq!(count(&writer), 2);
+        assert!(snapshot
+            .execute("INSERT INTO items DEFAULT VALUES", [])
+            .is_err());
+    }
 }
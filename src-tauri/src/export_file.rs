@@ -0,0 +1,286 @@
+//! 导出直接写入磁盘文件：逐行写入临时文件并同步计算字节数与 SHA-256，完成后重命名为目标文件
+//!
+//! 目标路径必须位于数据目录（对应 `capabilities/default.json` 中的 `$APPDATA/googlemanager/**`）
+//! 或 `GOOGLE_MANAGER_EXPORT_DIR` 列出的导出目录内，且不能覆盖数据库、主密钥与备份文件。
+
+use crate::database;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+/// 额外允许的导出目录（多个目录按系统路径分隔符分隔）
+pub const EXPORT_DIR_ENV: &str = "GOOGLE_MANAGER_EXPORT_DIR";
+/// 数据目录中禁止被导出覆盖的文件 / 目录
+const PROTECTED_DATA_ENTRIES: &[&str] = &[
+    "data.db",
+    "data.db-wal",
+    "data.db-journal",
+    "data.db-shm",
+    "master.key",
+    "backups",
+];
+/// 写入过程中使用的临时文件后缀
+const PARTIAL_SUFFIX: &str = ".partial";
+
+/// 导出文件摘要
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportFileSummary {
+    pub path: String,
+    /// 写入的数据行数（账号数或 SQL INSERT 语句数）
+    pub rows: usize,
+    pub bytes: u64,
+    pub sha256: String,
+}
+
+/// 允许写入导出文件的目录范围
+#[derive(Debug, Clone)]
+pub struct ExportScope {
+    roots: Vec<PathBuf>,
+    protected: Vec<PathBuf>,
+}
+
+impl ExportScope {
+    /// 数据目录 + 环境变量配置的导出目录（不存在的目录会被忽略）
+    pub fn from_env() -> Self {
+        let data_dir = database::data_dir();
+        let mut roots = vec![data_dir.clone()];
+        if let Some(dirs) = std::env::var_os(EXPORT_DIR_ENV) {
+            roots.extend(std::env::split_paths(&dirs).filter(|dir| !dir.as_os_str().is_empty()));
+        }
+        Self::new(roots, &data_dir)
+    }
+
+    fn new(roots: Vec<PathBuf>, data_dir: &Path) -> Self {
+        let roots = roots
+            .into_iter()
+            .filter_map(|root| fs::canonicalize(root).ok())
+            .collect();
+        let data_dir = fs::canonicalize(data_dir).unwrap_or_else(|_| data_dir.to_path_buf());
+        let protected = PROTECTED_DATA_ENTRIES
+            .iter()
+            .map(|entry| data_dir.join(entry))
+            .collect();
+        Self { roots, protected }
+    }
+
+    /// 校验并规范化导出路径（父目录必须已存在，符号链接会被解析后再比对范围）
+    fn resolve(&self, path: &str) -> Result<PathBuf, String> {
+        let path = Path::new(path.trim());
+        if path.as_os_str().is_empty() {
+            return Err("导出路径不能为空".to_string());
+        }
+        if !path.is_absolute() {
+            return Err("导出路径必须是绝对路径".to_string());
+        }
+        let file_name = path
+            .file_name()
+            .ok_or_else(|| "导出路径缺少文件名".to_string())?;
+        let parent = path
+            .parent()
+            .ok_or_else(|| "导出路径缺少所在目录".to_string())?;
+        let parent =
+            fs::canonicalize(parent).map_err(|e| format!("导出目录不存在或无法访问: {}", e))?;
+        let target = parent.join(file_name);
+
+        if !self.roots.iter().any(|root| target.starts_with(root)) {
+            return Err(format!(
+                "导出路径不在允许的目录内（数据目录或 {} 指定的目录）: {}",
+                EXPORT_DIR_ENV,
+                target.display()
+            ));
+        }
+        if self
+            .protected
+            .iter()
+            .any(|protected| target.starts_with(protected))
+        {
+            return Err("不能覆盖数据库、主密钥或备份文件".to_string());
+        }
+        if let Ok(metadata) = fs::symlink_metadata(&target) {
+            if metadata.file_type().is_symlink() || metadata.is_dir() {
+                return Err("导出路径指向目录或符号链接".to_string());
+            }
+        }
+        Ok(target)
+    }
+}
+
+/// 边写边统计字节数与 SHA-256 的文件写入器
+pub struct ExportFileWriter {
+    file: BufWriter<File>,
+    hasher: Sha256,
+    bytes: u64,
+}
+
+impl Write for ExportFileWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.file.write(buf)?;
+        self.hasher.update(&buf[..written]);
+        self.bytes += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+/// 把导出内容写入 `path`；`write` 返回写入的行数。失败时删除临时文件，不影响已有的同名文件
+pub fn write_export_file(
+    scope: &ExportScope,
+    path: &str,
+    write: impl FnOnce(&mut ExportFileWriter) -> Result<usize, String>,
+) -> Result<ExportFileSummary, String> {
+    let target = scope.resolve(path)?;
+    let mut partial = target.clone().into_os_string();
+    partial.push(PARTIAL_SUFFIX);
+    let partial = PathBuf::from(partial);
+
+    // 临时文件不能跟随符号链接写到范围外；上次中断遗留的普通文件先删除，再以独占方式新建
+    if let Ok(metadata) = fs::symlink_metadata(&partial) {
+        if !metadata.is_file() {
+            return Err("导出临时文件路径指向目录或符号链接".to_string());
+        }
+        fs::remove_file(&partial).map_err(|e| format!("清理导出临时文件失败: {}", e))?;
+    }
+    let file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&partial)
+        .map_err(|e| format!("创建导出文件失败: {}", e))?;
+    let mut writer = ExportFileWriter {
+        file: BufWriter::new(file),
+        hasher: Sha256::new(),
+        bytes: 0,
+    };
+    let result = write(&mut writer).and_then(|rows| {
+        let file = writer
+            .file
+            .into_inner()
+            .map_err(|e| format!("写入导出文件失败: {}", e.error()))?;
+        file.sync_all()
+            .map_err(|e| format!("写入导出文件失败: {}", e))?;
+        fs::rename(&partial, &target).map_err(|e| format!("保存导出文件失败: {}", e))?;
+        Ok(ExportFileSummary {
+            path: target.to_string_lossy().to_string(),
+            rows,
+            bytes: writer.bytes,
+            sha256: format!("{:x}", writer.hasher.finalize()),
+        })
+    });
+    if result.is_err() {
+        if let Err(e) = fs::remove_file(&partial) {
+            if e.kind() != io::ErrorKind::NotFound {
+                log::warn!("清理导出临时文件失败 ({}): {}", partial.display(), e);
+            }
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_file_and_reports_summary() {
+        let dir = tempfile::tempdir().unwrap();
+        let scope = ExportScope::new(vec![dir.path().to_path_buf()], dir.path());
+        let path = dir.path().join("exports.txt");
+
+        let summary = write_export_file(&scope, path.to_str().unwrap(), |out| {
+            out.write_all(b"a@gmail.com\n").map_err(|e| e.to_string())?;
+            out.write_all(b"b@gmail.com\n").map_err(|e| e.to_string())?;
+            Ok(2)
+        })
+        .unwrap();
+
+        assert_eq!(fs::read(&path).unwrap(), b"a@gmail.com\nb@gmail.com\n");
+        assert_eq!(summary.rows, 2);
+        assert_eq!(summary.bytes, 24);
+        assert_eq!(
+            summary.sha256,
+            format!("{:x}", Sha256::digest(b"a@gmail.com\nb@gmail.com\n"))
+        );
+        assert!(!dir.path().join("exports.txt.partial").exists());
+    }
+
+    #[test]
+    fn failed_export_keeps_existing_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let scope = ExportScope::new(vec![dir.path().to_path_buf()], dir.path());
+        let path = dir.path().join("exports.txt");
+        fs::write(&path, "old").unwrap();
+
+        let err = write_export_file(&scope, path.to_str().unwrap(), |out| {
+            out.write_all(b"partial").map_err(|e| e.to_string())?;
+            Err("查询失败".to_string())
+        })
+        .unwrap_err();
+
+        assert_eq!(err, "查询失败");
+        assert_eq!(fs::read_to_string(&path).unwrap(), "old");
+        assert!(!dir.path().join("exports.txt.partial").exists());
+    }
+
+    #[test]
+    fn rejects_paths_outside_scope_or_protected() {
+        let allowed = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        fs::create_dir(allowed.path().join("backups")).unwrap();
+        let scope = ExportScope::new(vec![allowed.path().to_path_buf()], allowed.path());
+        let resolve = |path: PathBuf| scope.resolve(path.to_str().unwrap());
+
+        assert!(resolve(allowed.path().join("ok.csv")).is_ok());
+        assert!(resolve(outside.path().join("x.csv"))
+            .unwrap_err()
+            .contains("不在允许的目录内"));
+        assert!(resolve(allowed.path().join("../x.csv")).is_err());
+        assert!(resolve(allowed.path().join("missing/x.csv")).is_err());
+        assert!(resolve(allowed.path().join("data.db")).is_err());
+        assert!(resolve(allowed.path().join("data.db-journal")).is_err());
+        assert!(resolve(allowed.path().join("backups/x.db")).is_err());
+        assert!(scope.resolve("relative.csv").is_err());
+        assert!(scope.resolve("  ").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn refuses_symlinked_partial_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        let scope = ExportScope::new(vec![dir.path().to_path_buf()], dir.path());
+        let victim = outside.path().join("victim.txt");
+        fs::write(&victim, "keep").unwrap();
+        std::os::unix::fs::symlink(&victim, dir.path().join("exports.txt.partial")).unwrap();
+
+        let path = dir.path().join("exports.txt");
+        let err = write_export_file(&scope, path.to_str().unwrap(), |out| {
+            out.write_all(b"secret").map_err(|e| e.to_string())?;
+            Ok(1)
+        })
+        .unwrap_err();
+
+        assert!(err.contains("符号链接"));
+        assert_eq!(fs::read_to_string(&victim).unwrap(), "keep");
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn replaces_stale_partial_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let scope = ExportScope::new(vec![dir.path().to_path_buf()], dir.path());
+        fs::write(dir.path().join("exports.txt.partial"), "stale").unwrap();
+
+        let path = dir.path().join("exports.txt");
+        write_export_file(&scope, path.to_str().unwrap(), |out| {
+            out.write_all(b"fresh").map_err(|e| e.to_string())?;
+            Ok(1)
+        })
+        .unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "fresh");
+    }
+}
@@ -0,0 +1,313 @@
+//! 导出行模板：`{email}|{password}{?secret}|2FA: {secret}{/secret}`
+//!
+//! - `{name}`：占位符，可追加过滤器 `{name|upper}`、`{secret|mask:4}`、`{phone|last:4}`、
+//!   `{country|default:未知}`，多个过滤器从左到右依次应用
+//! - `{?name}...{/name}`：值非空时才输出的条件段；`{!name}...{/name}` 则在值为空时输出，
+//!   结束标签也可简写为 `{/}`
+//! - `{{` / `}}` 输出字面量花括号；过滤器参数中不能包含 `|` 与 `}`
+//!
+//! 占位符名称在解析时通过调用方提供的 `resolve` 转换为键（如导出字段），未知名称直接报错。
+
+/// `mask` 未指定参数时保留的末尾字符数
+const DEFAULT_MASK_VISIBLE: usize = 4;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Filter {
+    Upper,
+    Lower,
+    /// 只保留末尾 N 个字符，其余替换为 `*`（长度不足时全部遮盖）
+    Mask(usize),
+    /// 只保留末尾 N 个字符
+    Last(usize),
+    /// 值为空时使用的默认值
+    Default(String),
+}
+
+impl Filter {
+    fn parse(spec: &str) -> Result<Self, String> {
+        let (name, arg) = match spec.split_once(':') {
+            Some((name, arg)) => (name.trim(), Some(arg)),
+            None => (spec.trim(), None),
+        };
+        let count = |default: Option<usize>| -> Result<usize, String> {
+            match arg.map(str::trim) {
+                Some(value) => value
+                    .parse()
+                    .map_err(|_| format!("模板过滤器 {} 的参数必须是非负整数: {}", name, value)),
+                None => {
+                    default.ok_or_else(|| format!("模板过滤器 {} 需要参数，例如 {}:4", name, name))
+                }
+            }
+        };
+        match name.to_ascii_lowercase().as_str() {
+            "upper" => Ok(Self::Upper),
+            "lower" => Ok(Self::Lower),
+            "mask" => Ok(Self::Mask(count(Some(DEFAULT_MASK_VISIBLE))?)),
+            "last" => Ok(Self::Last(count(None)?)),
+            "default" => Ok(Self::Default(arg.unwrap_or_default().to_string())),
+            other => Err(format!("未知的模板过滤器: {}", other)),
+        }
+    }
+
+    fn apply(&self, value: String) -> String {
+        match self {
+            Self::Upper => value.to_uppercase(),
+            Self::Lower => value.to_lowercase(),
+            Self::Mask(visible) => {
+                let len = value.chars().count();
+                let keep = if len > *visible { *visible } else { 0 };
+                value
+                    .chars()
+                    .enumerate()
+                    .map(|(index, ch)| if index < len - keep { '*' } else { ch })
+                    .collect()
+            }
+            Self::Last(count) => {
+                let skip = value.chars().count().saturating_sub(*count);
+                value.chars().skip(skip).collect()
+            }
+            Self::Default(default) => {
+                if value.trim().is_empty() {
+                    default.clone()
+                } else {
+                    value
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Node<K> {
+    Text(String),
+    Value {
+        key: K,
+        filters: Vec<Filter>,
+    },
+    Section {
+        key: K,
+        /// true 表示 `{!name}`：值为空时输出
+        inverted: bool,
+        body: Vec<Node<K>>,
+    },
+}
+
+/// 解析后的行模板，可对每个账号重复渲染
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineTemplate<K> {
+    nodes: Vec<Node<K>>,
+}
+
+/// 解析中尚未闭合的条件段
+struct OpenSection<K> {
+    name: String,
+    key: K,
+    inverted: bool,
+    parent: Vec<Node<K>>,
+}
+
+impl<K: Copy> LineTemplate<K> {
+    /// 解析模板；`resolve` 把占位符名称转换为键，返回 `None` 表示未知占位符
+    pub fn parse(template: &str, resolve: impl Fn(&str) -> Option<K>) -> Result<Self, String> {
+        let resolve_name = |name: &str| {
+            resolve(name).ok_or_else(|| format!("模板中存在未知的占位符: {{{}}}", name))
+        };
+        let mut nodes: Vec<Node<K>> = Vec::new();
+        let mut open: Vec<OpenSection<K>> = Vec::new();
+        let mut text = String::new();
+        let mut chars = template.chars().peekable();
+
+        while let Some(ch) = chars.next() {
+            match ch {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    text.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    text.push('}');
+                }
+                '{' => {
+                    let mut tag = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(ch) => tag.push(ch),
+                            None => return Err(format!("模板中的 {{{} 缺少结束的 }}", tag)),
+                        }
+                    }
+                    if !text.is_empty() {
+                        nodes.push(Node::Text(std::mem::take(&mut text)));
+                    }
+
+                    let tag = tag.trim();
+                    if let Some(name) = tag.strip_prefix('/') {
+                        let name = name.trim();
+                        let section = open
+                            .pop()
+                            .ok_or_else(|| format!("模板中的 {{/{}}} 没有对应的条件段", name))?;
+                        if !name.is_empty() && !name.eq_ignore_ascii_case(&section.name) {
+                            return Err(format!(
+                                "模板条件段 {{?{}}} 不能用 {{/{}}} 结束",
+                                section.name, name
+                            ));
+                        }
+                        let body = std::mem::replace(&mut nodes, section.parent);
+                        nodes.push(Node::Section {
+                            key: section.key,
+                            inverted: section.inverted,
+                            body,
+                        });
+                    } else if let Some((inverted, name)) = tag
+                        .strip_prefix('?')
+                        .map(|name| (false, name))
+                        .or_else(|| tag.strip_prefix('!').map(|name| (true, name)))
+                    {
+                        let name = name.trim();
+                        open.push(OpenSection {
+                            name: name.to_string(),
+                            key: resolve_name(name)?,
+                            inverted,
+                            parent: std::mem::take(&mut nodes),
+                        });
+                    } else {
+                        let mut parts = tag.split('|');
+                        let name = parts.next().unwrap_or_default().trim();
+                        nodes.push(Node::Value {
+                            key: resolve_name(name)?,
+                            filters: parts.map(Filter::parse).collect::<Result<_, _>>()?,
+                        });
+                    }
+                }
+                _ => text.push(ch),
+            }
+        }
+
+        if let Some(section) = open.last() {
+            return Err(format!("模板条件段 {{?{}}} 缺少结束标签", section.name));
+        }
+        if !text.is_empty() {
+            nodes.push(Node::Text(text));
+        }
+        Ok(Self { nodes })
+    }
+
+    /// 渲染模板，`value` 返回键对应的原始值（空字符串视为空值）
+    pub fn render(&self, value: impl Fn(K) -> String) -> String {
+        let mut output = String::new();
+        render_nodes(&self.nodes, &value, &mut output);
+        output
+    }
+}
+
+fn render_nodes<K: Copy>(nodes: &[Node<K>], value: &impl Fn(K) -> String, output: &mut String) {
+    for node in nodes {
+        match node {
+            Node::Text(text) => output.push_str(text),
+            Node::Value { key, filters } => {
+                let rendered = filters
+                    .iter()
+                    .fold(value(*key), |current, filter| filter.apply(current));
+                output.push_str(&rendered);
+            }
+            Node::Section {
+                key,
+                inverted,
+                body,
+            } => {
+                if value(*key).trim().is_empty() == *inverted {
+                    render_nodes(body, value, output);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(template: &str) -> Result<LineTemplate<&'static str>, String> {
+        LineTemplate::parse(template, |name| {
+            ["email", "password", "secret", "phone", "country"]
+                .into_iter()
+                .find(|known| *known == name)
+        })
+    }
+
+    fn render(template: &str, values: &[(&str, &str)]) -> String {
+        parse(template).unwrap().render(|key| {
+            values
+                .iter()
+                .find(|(name, _)| *name == key)
+                .map(|(_, value)| value.to_string())
+                .unwrap_or_default()
+        })
+    }
+
+    #[test]
+    fn renders_placeholders_sections_and_escapes() {
+        let template =
+            "{email}|{password}{?secret}|2FA: {secret}{/secret}{!phone} (无手机){/} {{raw}}";
+        assert_eq!(
+            render(
+                template,
+                &[
+                    ("email", "a@gmail.com"),
+                    ("password", "pw"),
+                    ("secret", "ABC")
+                ]
+            ),
+            "a@gmail.com|pw|2FA: ABC (无手机) {raw}"
+        );
+        assert_eq!(
+            render(
+                template,
+                &[("email", "a@gmail.com"), ("password", "pw"), ("phone", "1")]
+            ),
+            "a@gmail.com|pw {raw}"
+        );
+        // 条件段可以嵌套
+        assert_eq!(
+            render(
+                "{?email}[{?phone}{phone}{/phone}]{/email}",
+                &[("email", "x")]
+            ),
+            "[]"
+        );
+    }
+
+    #[test]
+    fn applies_filters_in_order() {
+        let values = [
+            ("email", "Alice@Gmail.com"),
+            ("phone", "+8613800001234"),
+            ("secret", "abc"),
+        ];
+        assert_eq!(render("{email|lower}", &values), "alice@gmail.com");
+        assert_eq!(render("{email | upper}", &values), "ALICE@GMAIL.COM");
+        assert_eq!(render("{phone|last:4}", &values), "1234");
+        assert_eq!(render("{phone|mask}", &values), "**********1234");
+        assert_eq!(render("{phone|mask:2}", &values), "************34");
+        assert_eq!(render("{secret|mask}", &values), "***");
+        assert_eq!(render("{country|default:未知}", &values), "未知");
+        assert_eq!(render("{country|default:n/a|upper}", &values), "N/A");
+        assert_eq!(render("{secret|default:无|upper}", &values), "ABC");
+    }
+
+    #[test]
+    fn rejects_invalid_templates() {
+        assert!(parse("{unknown}").unwrap_err().contains("未知的占位符"));
+        assert!(parse("{email|reverse}")
+            .unwrap_err()
+            .contains("未知的模板过滤器"));
+        assert!(parse("{phone|last}").unwrap_err().contains("需要参数"));
+        assert!(parse("{phone|last:x}").is_err());
+        assert!(parse("{email").unwrap_err().contains("缺少结束"));
+        assert!(parse("{?secret}x").unwrap_err().contains("缺少结束标签"));
+        assert!(parse("x{/secret}").is_err());
+        assert!(parse("{?secret}x{/email}").is_err());
+        // 单独的 `}` 按字面量输出
+        assert_eq!(render("a}b", &[]), "a}b");
+    }
+}
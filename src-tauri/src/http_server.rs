@@ -1,10 +1,18 @@
-use crate::database::{self, AccountInput, Database};
+use crate::auth::{AuthPolicy, SessionClient, SessionUser};
+use crate::authenticator_backup::AuthenticatorBackupSource;
+use crate::database::{self, AccountInput, AccountPageQuery, Database, ImportOptions, UserRole};
+use crate::import_formats::CsvImportConfig;
+use crate::password_import::PasswordManagerSource;
+use crate::totp::TotpParams;
 use actix_cors::Cors;
 use actix_web::{http::header, web, App, HttpRequest, HttpResponse, HttpServer, Responder};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::sync::Arc;
 
+/// JSON 请求体上限（二维码截图以 base64 提交，默认 32KB 不够用）
+const MAX_JSON_PAYLOAD_BYTES: usize = 16 * 1024 * 1024;
+
 #[derive(Serialize)]
 pub struct ApiResponse<T> {
     pub success: bool,
@@ -38,52 +46,200 @@ fn bearer_token(req: &HttpRequest) -> Option<String> {
         .map(|v| v.to_string())
 }
 
-fn ensure_authorized(req: &HttpRequest) -> Result<(), HttpResponse> {
+/// HTTP 会话的客户端信息：设备标签默认取 User-Agent，IP 取 TCP 对端地址（不信任转发头）
+fn http_client(req: &HttpRequest, device_label: Option<&str>) -> SessionClient {
+    let user_agent = req
+        .headers()
+        .get(header::USER_AGENT)
+        .and_then(|v| v.to_str().ok());
+    SessionClient {
+        device_label: device_label.or(user_agent).map(str::to_string),
+        ip: req.peer_addr().map(|addr| addr.ip().to_string()),
+    }
+}
+
+/// 未登录或会话失效返回 401；会话有效但保险库已锁定时返回 423
+fn ensure_authorized(req: &HttpRequest, db: &Database) -> Result<SessionUser, HttpResponse> {
     let token = bearer_token(req);
-    crate::auth::require_auth(token.as_deref()).map_err(|e| unauthorized_response(&e))
+    let conn = db.0.lock().map_err(err_response)?;
+    let policy = AuthPolicy::from_env();
+    crate::auth::session_user(&conn, &policy, token.as_deref()).map_err(|e| {
+        let valid_session =
+            crate::auth::active_session_user(&conn, &policy, token.as_deref()).is_ok();
+        if valid_session && crate::key_manager::is_master_key_locked() {
+            HttpResponse::Locked().body(e)
+        } else {
+            unauthorized_response(&e)
+        }
+    })
+}
+
+/// 未登录返回 401，角色不足返回 403
+fn ensure_role(
+    req: &HttpRequest,
+    db: &Database,
+    role: UserRole,
+) -> Result<SessionUser, HttpResponse> {
+    let user = ensure_authorized(req, db)?;
+    crate::auth::ensure_role(&user, role).map_err(|e| HttpResponse::Forbidden().body(e))?;
+    Ok(user)
 }
 
 #[derive(Deserialize)]
 pub struct GetAccountsQuery {
     pub search: Option<String>,
     pub sold_status: Option<String>,
+    pub group: Option<String>,
+    pub page: Option<u32>,
+    pub page_size: Option<u32>,
+    pub sort_field: Option<String>,
+    pub sort_direction: Option<String>,
 }
 
 #[derive(Deserialize)]
 pub struct BatchImportRequest {
     pub accounts: Vec<AccountInput>,
+    #[serde(default)]
+    pub options: ImportOptions,
+}
+
+#[derive(Deserialize)]
+pub struct CsvImportRequest {
+    pub content: String,
+    #[serde(default)]
+    pub config: CsvImportConfig,
+    #[serde(default)]
+    pub options: ImportOptions,
+}
+
+#[derive(Deserialize)]
+pub struct JsonImportRequest {
+    pub content: String,
+    #[serde(default)]
+    pub options: ImportOptions,
+}
+
+#[derive(Deserialize)]
+pub struct PasswordManagerImportRequest {
+    pub source: PasswordManagerSource,
+    pub content: String,
+    #[serde(default)]
+    pub options: ImportOptions,
+}
+
+#[derive(Deserialize)]
+pub struct ImportTextRequest {
+    pub text: String,
 }
 
 #[derive(Deserialize)]
 pub struct TotpRequest {
     pub secret: String,
+    #[serde(default)]
+    pub params: Option<TotpParams>,
+}
+
+#[derive(Deserialize)]
+pub struct RevealSecretRequest {
+    pub field: String,
+}
+
+#[derive(Deserialize)]
+pub struct VerifyTotpRequest {
+    pub code: String,
+    pub window: Option<u32>,
+}
+
+#[derive(Deserialize)]
+pub struct BatchTotpRequest {
+    pub ids: Vec<i64>,
+}
+
+#[derive(Deserialize)]
+pub struct MigrationImportRequest {
+    pub uri: String,
+    #[serde(default)]
+    pub apply: bool,
+}
+
+#[derive(Deserialize)]
+pub struct AuthenticatorBackupImportRequest {
+    pub source: AuthenticatorBackupSource,
+    pub content: String,
+    pub password: Option<String>,
+    #[serde(default)]
+    pub apply: bool,
+}
+
+#[derive(Deserialize)]
+pub struct QrCodeQuery {
+    pub format: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct QrImageRequest {
+    pub image_base64: String,
 }
 
 #[derive(Deserialize)]
 pub struct LoginRequest {
+    pub username: Option<String>,
+    pub password: String,
+    pub device_label: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct RevokeAllSessionsRequest {
+    pub include_current: Option<bool>,
+}
+
+#[derive(Deserialize)]
+pub struct UnlockRequest {
     pub password: String,
 }
 
+#[derive(Deserialize)]
+pub struct ChangePasswordRequest {
+    pub current_password: String,
+    pub new_password: String,
+}
+
+#[derive(Deserialize)]
+pub struct CreateUserRequest {
+    pub username: String,
+    pub password: String,
+    pub role: UserRole,
+}
+
+#[derive(Deserialize)]
+pub struct UpdateUserRequest {
+    pub role: Option<UserRole>,
+    pub disabled: Option<bool>,
+    pub password: Option<String>,
+}
+
 #[derive(Deserialize)]
 pub struct CreateBackupRequest {
     pub reason: Option<String>,
 }
 
 #[derive(Deserialize)]
-pub struct RestoreBackupRequest {
-    pub backup_name: String,
+pub struct ExportPresetRequest {
+    pub name: String,
+    pub config: serde_json::Value,
 }
 
-#[derive(Serialize)]
-pub struct BatchImportResponse {
-    pub success_count: i32,
-    pub failed_count: i32,
+#[derive(Deserialize)]
+pub struct RestoreBackupRequest {
+    pub backup_name: String,
 }
 
 #[derive(Serialize)]
 pub struct TotpResponse {
     pub code: String,
     pub remaining: u32,
+    pub previous_code: String,
+    pub next_code: String,
 }
 
 async fn get_accounts(
@@ -91,15 +247,65 @@ async fn get_accounts(
     db: web::Data<Arc<Database>>,
     query: web::Query<GetAccountsQuery>,
 ) -> impl Responder {
-    if let Err(resp) = ensure_authorized(&req) {
+    if let Err(resp) = ensure_role(&req, &db, UserRole::Viewer) {
         return resp;
     }
     let conn = match db.0.lock() {
         Ok(c) => c,
         Err(e) => return err_response(e),
     };
-    match database::query_accounts(&conn, query.search.as_deref(), query.sold_status.as_deref()) {
-        Ok(list) => success_response(list, "操作成功"),
+    let query = query.into_inner();
+    let page_query = AccountPageQuery {
+        page: query.page,
+        page_size: query.page_size,
+        sort_field: query.sort_field,
+        sort_direction: query.sort_direction,
+    };
+    match database::query_accounts_page(
+        &conn,
+        query.search.as_deref(),
+        query.sold_status.as_deref(),
+        query.group.as_deref(),
+        &page_query,
+    ) {
+        Ok(page) => success_response(page, "操作成功"),
+        Err(e) => err_response(e),
+    }
+}
+
+async fn get_account_groups(req: HttpRequest, db: web::Data<Arc<Database>>) -> impl Responder {
+    if let Err(resp) = ensure_role(&req, &db, UserRole::Viewer) {
+        return resp;
+    }
+    let conn = match db.0.lock() {
+        Ok(c) => c,
+        Err(e) => return err_response(e),
+    };
+    match database::list_account_groups(&conn) {
+        Ok(groups) => success_response(groups, "操作成功"),
+        Err(e) => err_response(e),
+    }
+}
+
+async fn get_account_ids(
+    req: HttpRequest,
+    db: web::Data<Arc<Database>>,
+    query: web::Query<GetAccountsQuery>,
+) -> impl Responder {
+    if let Err(resp) = ensure_role(&req, &db, UserRole::Viewer) {
+        return resp;
+    }
+    let conn = match db.0.lock() {
+        Ok(c) => c,
+        Err(e) => return err_response(e),
+    };
+    match database::query_account_ids(
+        &conn,
+        query.search.as_deref(),
+        query.sold_status.as_deref(),
+        query.group.as_deref(),
+    ) {
+        Ok(ids) => success_response(ids, "操作成功"),
         Err(e) => err_response(e),
     }
 }
@@ -109,7 +315,7 @@ async fn create_account(
     db: web::Data<Arc<Database>>,
     account: web::Json<AccountInput>,
 ) -> impl Responder {
-    if let Err(resp) = ensure_authorized(&req) {
+    if let Err(resp) = ensure_role(&req, &db, UserRole::Operator) {
         return resp;
     }
     let conn = match db.0.lock() {
@@ -128,15 +334,16 @@ async fn update_account(
     path: web::Path<i64>,
     account: web::Json<AccountInput>,
 ) -> impl Responder {
-    if let Err(resp) = ensure_authorized(&req) {
-        return resp;
-    }
+    let user = match ensure_role(&req, &db, UserRole::Operator) {
+        Ok(user) => user,
+        Err(resp) => return resp,
+    };
     let id = path.into_inner();
     let conn = match db.0.lock() {
         Ok(c) => c,
         Err(e) => return err_response(e),
     };
-    match database::update_account(&conn, id, &account) {
+    match database::update_account(&conn, id, &account, user.audit_actor()) {
         Ok(acc) => success_response(acc, "账号更新成功"),
         Err(e) => HttpResponse::NotFound().body(e),
     }
@@ -147,7 +354,7 @@ async fn delete_account(
     db: web::Data<Arc<Database>>,
     path: web::Path<i64>,
 ) -> impl Responder {
-    if let Err(resp) = ensure_authorized(&req) {
+    if let Err(resp) = ensure_role(&req, &db, UserRole::Admin) {
         return resp;
     }
     let id = path.into_inner();
@@ -166,15 +373,16 @@ async fn toggle_status(
     db: web::Data<Arc<Database>>,
     path: web::Path<i64>,
 ) -> impl Responder {
-    if let Err(resp) = ensure_authorized(&req) {
-        return resp;
-    }
+    let user = match ensure_role(&req, &db, UserRole::Operator) {
+        Ok(user) => user,
+        Err(resp) => return resp,
+    };
     let id = path.into_inner();
     let conn = match db.0.lock() {
         Ok(c) => c,
         Err(e) => return err_response(e),
     };
-    match database::toggle_status(&conn, id) {
+    match database::toggle_status(&conn, id, user.audit_actor()) {
         Ok(acc) => success_response(acc, "状态已更新"),
         Err(e) => HttpResponse::NotFound().body(e),
     }
@@ -185,15 +393,16 @@ async fn toggle_sold_status(
     db: web::Data<Arc<Database>>,
     path: web::Path<i64>,
 ) -> impl Responder {
-    if let Err(resp) = ensure_authorized(&req) {
-        return resp;
-    }
+    let user = match ensure_role(&req, &db, UserRole::Operator) {
+        Ok(user) => user,
+        Err(resp) => return resp,
+    };
     let id = path.into_inner();
     let conn = match db.0.lock() {
         Ok(c) => c,
         Err(e) => return err_response(e),
     };
-    match database::toggle_sold_status(&conn, id) {
+    match database::toggle_sold_status(&conn, id, user.audit_actor()) {
         Ok(acc) => success_response(acc, "出售状态已更新"),
         Err(e) => HttpResponse::NotFound().body(e),
     }
@@ -204,34 +413,121 @@ async fn batch_import(
     db: web::Data<Arc<Database>>,
     body: web::Json<BatchImportRequest>,
 ) -> impl Responder {
-    if let Err(resp) = ensure_authorized(&req) {
-        return resp;
+    let user = match ensure_role(&req, &db, UserRole::Operator) {
+        Ok(user) => user,
+        Err(resp) => return resp,
+    };
+    let conn = match db.0.lock() {
+        Ok(c) => c,
+        Err(e) => return err_response(e),
+    };
+    match database::batch_import(&conn, &body.accounts, &body.options, user.audit_actor()) {
+        Ok(report) => success_response(report, "批量导入完成"),
+        Err(e) => err_response(e),
+    }
+}
+
+async fn batch_import_csv(
+    req: HttpRequest,
+    db: web::Data<Arc<Database>>,
+    body: web::Json<CsvImportRequest>,
+) -> impl Responder {
+    let user = match ensure_role(&req, &db, UserRole::Operator) {
+        Ok(user) => user,
+        Err(resp) => return resp,
+    };
+    let accounts = match crate::import_formats::parse_csv_accounts(&body.content, &body.config) {
+        Ok(accounts) => accounts,
+        Err(e) => return HttpResponse::BadRequest().body(e),
+    };
+    let conn = match db.0.lock() {
+        Ok(c) => c,
+        Err(e) => return err_response(e),
+    };
+    match database::batch_import(&conn, &accounts, &body.options, user.audit_actor()) {
+        Ok(report) => success_response(report, "批量导入完成"),
+        Err(e) => err_response(e),
     }
+}
+
+async fn batch_import_json(
+    req: HttpRequest,
+    db: web::Data<Arc<Database>>,
+    body: web::Json<JsonImportRequest>,
+) -> impl Responder {
+    let user = match ensure_role(&req, &db, UserRole::Operator) {
+        Ok(user) => user,
+        Err(resp) => return resp,
+    };
+    let accounts = match crate::import_formats::parse_json_accounts(&body.content) {
+        Ok(accounts) => accounts,
+        Err(e) => return HttpResponse::BadRequest().body(e),
+    };
     let conn = match db.0.lock() {
         Ok(c) => c,
         Err(e) => return err_response(e),
     };
-    match database::batch_import(&conn, &body.accounts) {
-        Ok((success_count, failed_count)) => success_response(
-            BatchImportResponse {
-                success_count,
-                failed_count,
-            },
-            "批量导入完成",
-        ),
+    match database::batch_import(&conn, &accounts, &body.options, user.audit_actor()) {
+        Ok(report) => success_response(report, "批量导入完成"),
         Err(e) => err_response(e),
     }
 }
 
-async fn generate_totp(req: HttpRequest, body: web::Json<TotpRequest>) -> impl Responder {
-    if let Err(resp) = ensure_authorized(&req) {
+async fn import_password_manager(
+    req: HttpRequest,
+    db: web::Data<Arc<Database>>,
+    body: web::Json<PasswordManagerImportRequest>,
+) -> impl Responder {
+    let user = match ensure_role(&req, &db, UserRole::Operator) {
+        Ok(user) => user,
+        Err(resp) => return resp,
+    };
+    let conn = match db.0.lock() {
+        Ok(c) => c,
+        Err(e) => return err_response(e),
+    };
+    match crate::password_import::import_password_export(
+        &conn,
+        body.source,
+        &body.content,
+        &body.options,
+        user.audit_actor(),
+    ) {
+        Ok(report) => success_response(report, "批量导入完成"),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
+
+async fn parse_import_text(
+    req: HttpRequest,
+    body: web::Json<ImportTextRequest>,
+    db: web::Data<Arc<Database>>,
+) -> impl Responder {
+    if let Err(resp) = ensure_role(&req, &db, UserRole::Operator) {
+        return resp;
+    }
+    success_response(
+        crate::import_parser::parse_import_text(&body.text),
+        "解析完成",
+    )
+}
+
+async fn generate_totp(
+    req: HttpRequest,
+    body: web::Json<TotpRequest>,
+    db: web::Data<Arc<Database>>,
+) -> impl Responder {
+    if let Err(resp) = ensure_role(&req, &db, UserRole::Operator) {
         return resp;
     }
-    match crate::totp::generate_totp(&body.secret) {
+    let params = body.params.clone().unwrap_or_default();
+    match crate::totp::generate_totp_with_params(&body.secret, &params) {
         Ok(result) => success_response(
             TotpResponse {
                 code: result.code,
                 remaining: result.remaining,
+                previous_code: result.previous_code,
+                next_code: result.next_code,
             },
             "操作成功",
         ),
@@ -239,31 +535,52 @@ async fn generate_totp(req: HttpRequest, body: web::Json<TotpRequest>) -> impl R
     }
 }
 
-async fn get_account_history(
+async fn reveal_account_secret(
+    req: HttpRequest,
+    db: web::Data<Arc<Database>>,
+    path: web::Path<i64>,
+    body: web::Json<RevealSecretRequest>,
+) -> impl Responder {
+    let user = match ensure_role(&req, &db, UserRole::Operator) {
+        Ok(user) => user,
+        Err(resp) => return resp,
+    };
+    let id = path.into_inner();
+    let conn = match db.0.lock() {
+        Ok(c) => c,
+        Err(e) => return err_response(e),
+    };
+    match database::reveal_account_secret(&conn, id, &body.field, user.audit_actor()) {
+        Ok(value) => success_response(value, "操作成功"),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
+
+async fn get_secret_reveals(
     req: HttpRequest,
     db: web::Data<Arc<Database>>,
     path: web::Path<i64>,
 ) -> impl Responder {
-    if let Err(resp) = ensure_authorized(&req) {
+    if let Err(resp) = ensure_role(&req, &db, UserRole::Admin) {
         return resp;
     }
-    let account_id = path.into_inner();
+    let id = path.into_inner();
     let conn = match db.0.lock() {
         Ok(c) => c,
         Err(e) => return err_response(e),
     };
-    match database::get_account_history(&conn, account_id) {
+    match database::get_secret_reveals(&conn, id) {
         Ok(list) => success_response(list, "操作成功"),
         Err(e) => err_response(e),
     }
 }
 
-async fn get_account_by_id_handler(
+async fn generate_totp_for_account(
     req: HttpRequest,
     db: web::Data<Arc<Database>>,
     path: web::Path<i64>,
 ) -> impl Responder {
-    if let Err(resp) = ensure_authorized(&req) {
+    if let Err(resp) = ensure_role(&req, &db, UserRole::Operator) {
         return resp;
     }
     let id = path.into_inner();
@@ -271,167 +588,697 @@ async fn get_account_by_id_handler(
         Ok(c) => c,
         Err(e) => return err_response(e),
     };
-    match database::get_account_by_id(&conn, id) {
-        Ok(acc) => success_response(acc, "操作成功"),
-        Err(e) => HttpResponse::NotFound().body(e),
+    match crate::totp::generate_totp_for_account(&conn, id) {
+        Ok(result) => success_response(
+            TotpResponse {
+                code: result.code,
+                remaining: result.remaining,
+                previous_code: result.previous_code,
+                next_code: result.next_code,
+            },
+            "操作成功",
+        ),
+        Err(e) => HttpResponse::BadRequest().body(e),
     }
 }
 
-async fn delete_all_accounts_handler(
+async fn verify_totp(
     req: HttpRequest,
     db: web::Data<Arc<Database>>,
+    path: web::Path<i64>,
+    body: web::Json<VerifyTotpRequest>,
 ) -> impl Responder {
-    if let Err(resp) = ensure_authorized(&req) {
+    if let Err(resp) = ensure_role(&req, &db, UserRole::Operator) {
         return resp;
     }
+    let id = path.into_inner();
     let conn = match db.0.lock() {
         Ok(c) => c,
         Err(e) => return err_response(e),
     };
-    match database::delete_all_accounts(&conn) {
-        Ok(count) => success_response(count, "账号已删除"),
-        Err(e) => err_response(e),
+    match crate::totp::verify_totp_for_account(&conn, id, &body.code, body.window) {
+        Ok(result) => success_response(result, "操作成功"),
+        Err(e) => HttpResponse::BadRequest().body(e),
     }
 }
 
-async fn get_deleted_accounts(req: HttpRequest, db: web::Data<Arc<Database>>) -> impl Responder {
-    if let Err(resp) = ensure_authorized(&req) {
+async fn generate_totp_for_accounts(
+    req: HttpRequest,
+    db: web::Data<Arc<Database>>,
+    body: web::Json<BatchTotpRequest>,
+) -> impl Responder {
+    if let Err(resp) = ensure_role(&req, &db, UserRole::Operator) {
         return resp;
     }
     let conn = match db.0.lock() {
         Ok(c) => c,
         Err(e) => return err_response(e),
     };
-    match database::query_deleted_accounts(&conn) {
+    match crate::totp::generate_totp_for_accounts(&conn, &body.ids) {
         Ok(list) => success_response(list, "操作成功"),
         Err(e) => err_response(e),
     }
 }
 
-async fn restore_account_handler(
+async fn import_migration_payload(
     req: HttpRequest,
     db: web::Data<Arc<Database>>,
-    path: web::Path<i64>,
+    body: web::Json<MigrationImportRequest>,
 ) -> impl Responder {
-    if let Err(resp) = ensure_authorized(&req) {
-        return resp;
-    }
-    let id = path.into_inner();
+    let user = match ensure_role(&req, &db, UserRole::Operator) {
+        Ok(user) => user,
+        Err(resp) => return resp,
+    };
     let conn = match db.0.lock() {
         Ok(c) => c,
         Err(e) => return err_response(e),
     };
-    match database::restore_account(&conn, id) {
-        Ok(acc) => success_response(acc, "账号已恢复"),
+    match crate::migration::import_migration_uri(&conn, &body.uri, body.apply, user.audit_actor()) {
+        Ok(list) => success_response(list, "操作成功"),
         Err(e) => HttpResponse::BadRequest().body(e),
     }
 }
 
-async fn purge_account_handler(
+async fn import_authenticator_backup(
     req: HttpRequest,
     db: web::Data<Arc<Database>>,
-    path: web::Path<i64>,
+    body: web::Json<AuthenticatorBackupImportRequest>,
 ) -> impl Responder {
-    if let Err(resp) = ensure_authorized(&req) {
-        return resp;
-    }
-    let id = path.into_inner();
+    let user = match ensure_role(&req, &db, UserRole::Operator) {
+        Ok(user) => user,
+        Err(resp) => return resp,
+    };
     let conn = match db.0.lock() {
         Ok(c) => c,
         Err(e) => return err_response(e),
     };
-    match database::purge_account(&conn, id) {
-        Ok(()) => success_response(json!(null), "账号已永久删除"),
+    match crate::authenticator_backup::import_authenticator_backup(
+        &conn,
+        body.source,
+        &body.content,
+        body.password.as_deref(),
+        body.apply,
+        user.audit_actor(),
+    ) {
+        Ok(list) => success_response(list, "操作成功"),
         Err(e) => HttpResponse::BadRequest().body(e),
     }
 }
 
-async fn purge_all_deleted_handler(
+async fn export_migration_payload(
     req: HttpRequest,
     db: web::Data<Arc<Database>>,
+    body: web::Json<BatchTotpRequest>,
 ) -> impl Responder {
-    if let Err(resp) = ensure_authorized(&req) {
-        return resp;
-    }
+    let user = match ensure_role(&req, &db, UserRole::Operator) {
+        Ok(user) => user,
+        Err(resp) => return resp,
+    };
     let conn = match db.0.lock() {
         Ok(c) => c,
         Err(e) => return err_response(e),
     };
-    match database::purge_all_deleted(&conn) {
-        Ok(count) => success_response(count, "回收站已清空"),
+    match crate::migration::export_migration_uris(&conn, &body.ids, user.audit_actor()) {
+        Ok(export) => success_response(export, "操作成功"),
         Err(e) => err_response(e),
     }
 }
 
-async fn create_backup_handler(
+async fn get_account_qr_code(
     req: HttpRequest,
     db: web::Data<Arc<Database>>,
-    body: web::Json<CreateBackupRequest>,
+    path: web::Path<i64>,
+    query: web::Query<QrCodeQuery>,
 ) -> impl Responder {
-    if let Err(resp) = ensure_authorized(&req) {
-        return resp;
-    }
+    let user = match ensure_role(&req, &db, UserRole::Operator) {
+        Ok(user) => user,
+        Err(resp) => return resp,
+    };
+    let id = path.into_inner();
     let conn = match db.0.lock() {
         Ok(c) => c,
         Err(e) => return err_response(e),
     };
-    match database::create_backup(&conn, body.reason.as_deref()) {
-        Ok(path) => success_response(path.to_string_lossy().to_string(), "备份创建成功"),
-        Err(e) => err_response(e),
+    match crate::qr::render_account_qr_code(&conn, id, query.format.as_deref(), user.audit_actor())
+    {
+        Ok(image) => success_response(image, "操作成功"),
+        Err(e) => HttpResponse::BadRequest().body(e),
     }
 }
 
-async fn list_backups_handler(req: HttpRequest) -> impl Responder {
-    if let Err(resp) = ensure_authorized(&req) {
+async fn decode_qr_secret(
+    req: HttpRequest,
+    body: web::Json<QrImageRequest>,
+    db: web::Data<Arc<Database>>,
+) -> impl Responder {
+    if let Err(resp) = ensure_role(&req, &db, UserRole::Operator) {
         return resp;
     }
-    match database::list_backups() {
-        Ok(list) => success_response(list, "操作成功"),
-        Err(e) => err_response(e),
+    match crate::qr::decode_qr_secret(&body.image_base64) {
+        Ok(decoded) => success_response(decoded, "操作成功"),
+        Err(e) => HttpResponse::BadRequest().body(e),
     }
 }
 
-async fn restore_backup_handler(
+async fn attach_qr_secret(
     req: HttpRequest,
     db: web::Data<Arc<Database>>,
-    body: web::Json<RestoreBackupRequest>,
+    path: web::Path<i64>,
+    body: web::Json<QrImageRequest>,
+) -> impl Responder {
+    let user = match ensure_role(&req, &db, UserRole::Operator) {
+        Ok(user) => user,
+        Err(resp) => return resp,
+    };
+    let id = path.into_inner();
+    let conn = match db.0.lock() {
+        Ok(c) => c,
+        Err(e) => return err_response(e),
+    };
+    match crate::qr::attach_qr_secret(&conn, id, &body.image_base64, user.audit_actor()) {
+        Ok(account) => success_response(account, "账号更新成功"),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
+
+async fn get_account_history(
+    req: HttpRequest,
+    db: web::Data<Arc<Database>>,
+    path: web::Path<i64>,
 ) -> impl Responder {
-    if let Err(resp) = ensure_authorized(&req) {
+    if let Err(resp) = ensure_role(&req, &db, UserRole::Viewer) {
         return resp;
     }
+    let account_id = path.into_inner();
     let conn = match db.0.lock() {
         Ok(c) => c,
         Err(e) => return err_response(e),
     };
-    match database::restore_backup(&conn, &body.backup_name) {
-        Ok(()) => success_response(json!(null), "备份恢复成功"),
+    match database::get_account_history(&conn, account_id) {
+        Ok(list) => success_response(list, "操作成功"),
         Err(e) => err_response(e),
     }
 }
 
-async fn login_handler(body: web::Json<LoginRequest>) -> impl Responder {
-    match crate::auth::login(&body.password) {
-        Ok(result) => success_response(result, "操作成功"),
+async fn get_account_by_id_handler(
+    req: HttpRequest,
+    db: web::Data<Arc<Database>>,
+    path: web::Path<i64>,
+) -> impl Responder {
+    if let Err(resp) = ensure_role(&req, &db, UserRole::Viewer) {
+        return resp;
+    }
+    let id = path.into_inner();
+    let conn = match db.0.lock() {
+        Ok(c) => c,
+        Err(e) => return err_response(e),
+    };
+    match database::get_redacted_account_by_id(&conn, id) {
+        Ok(acc) => success_response(acc, "操作成功"),
+        Err(e) => HttpResponse::NotFound().body(e),
+    }
+}
+
+async fn delete_all_accounts_handler(
+    req: HttpRequest,
+    db: web::Data<Arc<Database>>,
+) -> impl Responder {
+    if let Err(resp) = ensure_role(&req, &db, UserRole::Admin) {
+        return resp;
+    }
+    let conn = match db.0.lock() {
+        Ok(c) => c,
+        Err(e) => return err_response(e),
+    };
+    match database::delete_all_accounts(&conn) {
+        Ok(count) => success_response(count, "账号已删除"),
+        Err(e) => err_response(e),
+    }
+}
+
+async fn get_deleted_accounts(
+    req: HttpRequest,
+    db: web::Data<Arc<Database>>,
+    query: web::Query<AccountPageQuery>,
+) -> impl Responder {
+    if let Err(resp) = ensure_role(&req, &db, UserRole::Viewer) {
+        return resp;
+    }
+    let conn = match db.0.lock() {
+        Ok(c) => c,
+        Err(e) => return err_response(e),
+    };
+    match database::query_deleted_accounts_page(&conn, &query.into_inner()) {
+        Ok(page) => success_response(page, "操作成功"),
+        Err(e) => err_response(e),
+    }
+}
+
+async fn restore_account_handler(
+    req: HttpRequest,
+    db: web::Data<Arc<Database>>,
+    path: web::Path<i64>,
+) -> impl Responder {
+    if let Err(resp) = ensure_role(&req, &db, UserRole::Admin) {
+        return resp;
+    }
+    let id = path.into_inner();
+    let conn = match db.0.lock() {
+        Ok(c) => c,
+        Err(e) => return err_response(e),
+    };
+    match database::restore_account(&conn, id) {
+        Ok(acc) => success_response(acc, "账号已恢复"),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
+
+async fn purge_account_handler(
+    req: HttpRequest,
+    db: web::Data<Arc<Database>>,
+    path: web::Path<i64>,
+) -> impl Responder {
+    if let Err(resp) = ensure_role(&req, &db, UserRole::Admin) {
+        return resp;
+    }
+    let id = path.into_inner();
+    let conn = match db.0.lock() {
+        Ok(c) => c,
+        Err(e) => return err_response(e),
+    };
+    match database::purge_account(&conn, id) {
+        Ok(()) => success_response(json!(null), "账号已永久删除"),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
+
+async fn purge_all_deleted_handler(
+    req: HttpRequest,
+    db: web::Data<Arc<Database>>,
+) -> impl Responder {
+    if let Err(resp) = ensure_role(&req, &db, UserRole::Admin) {
+        return resp;
+    }
+    let conn = match db.0.lock() {
+        Ok(c) => c,
+        Err(e) => return err_response(e),
+    };
+    match database::purge_all_deleted(&conn) {
+        Ok(count) => success_response(count, "回收站已清空"),
+        Err(e) => err_response(e),
+    }
+}
+
+async fn create_backup_handler(
+    req: HttpRequest,
+    db: web::Data<Arc<Database>>,
+    body: web::Json<CreateBackupRequest>,
+) -> impl Responder {
+    if let Err(resp) = ensure_role(&req, &db, UserRole::Operator) {
+        return resp;
+    }
+    let conn = match db.0.lock() {
+        Ok(c) => c,
+        Err(e) => return err_response(e),
+    };
+    match database::create_backup(&conn, body.reason.as_deref()) {
+        Ok(path) => success_response(path.to_string_lossy().to_string(), "备份创建成功"),
+        Err(e) => err_response(e),
+    }
+}
+
+async fn list_backups_handler(req: HttpRequest, db: web::Data<Arc<Database>>) -> impl Responder {
+    if let Err(resp) = ensure_role(&req, &db, UserRole::Viewer) {
+        return resp;
+    }
+    match database::list_backups() {
+        Ok(list) => success_response(list, "操作成功"),
         Err(e) => err_response(e),
     }
 }
 
-async fn check_auth_handler(req: HttpRequest) -> impl Responder {
+async fn restore_backup_handler(
+    req: HttpRequest,
+    db: web::Data<Arc<Database>>,
+    body: web::Json<RestoreBackupRequest>,
+) -> impl Responder {
+    if let Err(resp) = ensure_role(&req, &db, UserRole::Admin) {
+        return resp;
+    }
+    let conn = match db.0.lock() {
+        Ok(c) => c,
+        Err(e) => return err_response(e),
+    };
+    match database::restore_backup(&conn, &body.backup_name) {
+        Ok(()) => success_response(json!(null), "备份恢复成功"),
+        Err(e) => err_response(e),
+    }
+}
+
+async fn list_export_presets_handler(
+    req: HttpRequest,
+    db: web::Data<Arc<Database>>,
+) -> impl Responder {
+    if let Err(resp) = ensure_role(&req, &db, UserRole::Viewer) {
+        return resp;
+    }
+    let conn = match db.0.lock() {
+        Ok(c) => c,
+        Err(e) => return err_response(e),
+    };
+    match database::list_export_presets(&conn) {
+        Ok(list) => success_response(list, "操作成功"),
+        Err(e) => err_response(e),
+    }
+}
+
+async fn create_export_preset_handler(
+    req: HttpRequest,
+    db: web::Data<Arc<Database>>,
+    body: web::Json<ExportPresetRequest>,
+) -> impl Responder {
+    if let Err(resp) = ensure_role(&req, &db, UserRole::Operator) {
+        return resp;
+    }
+    let conn = match db.0.lock() {
+        Ok(c) => c,
+        Err(e) => return err_response(e),
+    };
+    match database::create_export_preset(&conn, &body.name, &body.config) {
+        Ok(preset) => success_response(preset, "预设已保存"),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
+
+async fn update_export_preset_handler(
+    req: HttpRequest,
+    db: web::Data<Arc<Database>>,
+    path: web::Path<i64>,
+    body: web::Json<ExportPresetRequest>,
+) -> impl Responder {
+    if let Err(resp) = ensure_role(&req, &db, UserRole::Operator) {
+        return resp;
+    }
+    let id = path.into_inner();
+    let conn = match db.0.lock() {
+        Ok(c) => c,
+        Err(e) => return err_response(e),
+    };
+    match database::update_export_preset(&conn, id, &body.name, &body.config) {
+        Ok(preset) => success_response(preset, "预设已更新"),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
+
+async fn delete_export_preset_handler(
+    req: HttpRequest,
+    db: web::Data<Arc<Database>>,
+    path: web::Path<i64>,
+) -> impl Responder {
+    if let Err(resp) = ensure_role(&req, &db, UserRole::Operator) {
+        return resp;
+    }
+    let id = path.into_inner();
+    let conn = match db.0.lock() {
+        Ok(c) => c,
+        Err(e) => return err_response(e),
+    };
+    match database::delete_export_preset(&conn, id) {
+        Ok(()) => success_response(json!(null), "预设已删除"),
+        Err(e) => err_response(e),
+    }
+}
+
+async fn login_handler(
+    req: HttpRequest,
+    body: web::Json<LoginRequest>,
+    db: web::Data<Arc<Database>>,
+) -> impl Responder {
+    let conn = match db.0.lock() {
+        Ok(c) => c,
+        Err(e) => return err_response(e),
+    };
+    match crate::auth::login(
+        &conn,
+        &AuthPolicy::from_env(),
+        body.username.as_deref().unwrap_or("admin"),
+        &body.password,
+        &http_client(&req, body.device_label.as_deref()),
+    ) {
+        Ok(result) => success_response(result, "操作成功"),
+        Err(e) => err_response(e),
+    }
+}
+
+async fn check_auth_handler(req: HttpRequest, db: web::Data<Arc<Database>>) -> impl Responder {
     let token = bearer_token(&req);
-    match crate::auth::check_auth(token.as_deref()) {
+    let conn = match db.0.lock() {
+        Ok(c) => c,
+        Err(e) => return err_response(e),
+    };
+    match crate::auth::check_auth(&conn, &AuthPolicy::from_env(), token.as_deref()) {
         Ok(result) => success_response(result, "操作成功"),
         Err(e) => err_response(e),
     }
 }
 
-async fn logout_handler(req: HttpRequest) -> impl Responder {
+async fn setup_admin_password_handler(
+    req: HttpRequest,
+    body: web::Json<LoginRequest>,
+    db: web::Data<Arc<Database>>,
+) -> impl Responder {
+    let conn = match db.0.lock() {
+        Ok(c) => c,
+        Err(e) => return err_response(e),
+    };
+    match crate::auth::setup_admin_password(
+        &conn,
+        &AuthPolicy::from_env(),
+        body.username.as_deref().unwrap_or("admin"),
+        &body.password,
+        &http_client(&req, body.device_label.as_deref()),
+    ) {
+        Ok(result) => success_response(result, "管理员密码设置成功"),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
+
+async fn change_admin_password_handler(
+    req: HttpRequest,
+    body: web::Json<ChangePasswordRequest>,
+    db: web::Data<Arc<Database>>,
+) -> impl Responder {
+    if let Err(resp) = ensure_authorized(&req, &db) {
+        return resp;
+    }
+    let token = bearer_token(&req);
+    let conn = match db.0.lock() {
+        Ok(c) => c,
+        Err(e) => return err_response(e),
+    };
+    match crate::auth::change_admin_password(
+        &conn,
+        &AuthPolicy::from_env(),
+        token.as_deref(),
+        &body.current_password,
+        &body.new_password,
+    ) {
+        Ok(()) => success_response(json!(null), "管理员密码已修改"),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
+
+async fn logout_handler(req: HttpRequest, db: web::Data<Arc<Database>>) -> impl Responder {
     let token = bearer_token(&req);
-    match crate::auth::logout(token.as_deref()) {
+    let conn = match db.0.lock() {
+        Ok(c) => c,
+        Err(e) => return err_response(e),
+    };
+    match crate::auth::logout(&conn, token.as_deref()) {
         Ok(()) => success_response(json!(null), "已退出登录"),
         Err(e) => unauthorized_response(&e),
     }
 }
 
+async fn lock_handler(req: HttpRequest, db: web::Data<Arc<Database>>) -> impl Responder {
+    let token = bearer_token(&req);
+    let conn = match db.0.lock() {
+        Ok(c) => c,
+        Err(e) => return err_response(e),
+    };
+    let user =
+        match crate::auth::active_session_user(&conn, &AuthPolicy::from_env(), token.as_deref()) {
+            Ok(user) => user,
+            Err(e) => return unauthorized_response(&e),
+        };
+    match crate::auth::lock(&user) {
+        Ok(()) => success_response(json!(null), "已锁定"),
+        Err(e) => HttpResponse::Forbidden().body(e),
+    }
+}
+
+async fn unlock_handler(
+    req: HttpRequest,
+    body: web::Json<UnlockRequest>,
+    db: web::Data<Arc<Database>>,
+) -> impl Responder {
+    let token = bearer_token(&req);
+    let conn = match db.0.lock() {
+        Ok(c) => c,
+        Err(e) => return err_response(e),
+    };
+    match crate::auth::unlock(
+        &conn,
+        &AuthPolicy::from_env(),
+        token.as_deref(),
+        &body.password,
+    ) {
+        Ok(result) => success_response(result, "操作成功"),
+        Err(e) => err_response(e),
+    }
+}
+
+async fn list_sessions_handler(req: HttpRequest, db: web::Data<Arc<Database>>) -> impl Responder {
+    let actor = match ensure_role(&req, &db, UserRole::Viewer) {
+        Ok(user) => user,
+        Err(resp) => return resp,
+    };
+    let token = bearer_token(&req);
+    let conn = match db.0.lock() {
+        Ok(c) => c,
+        Err(e) => return err_response(e),
+    };
+    match crate::auth::list_sessions(&conn, &AuthPolicy::from_env(), &actor, token.as_deref()) {
+        Ok(sessions) => success_response(sessions, "操作成功"),
+        Err(e) => err_response(e),
+    }
+}
+
+async fn revoke_session_handler(
+    req: HttpRequest,
+    path: web::Path<String>,
+    db: web::Data<Arc<Database>>,
+) -> impl Responder {
+    let actor = match ensure_role(&req, &db, UserRole::Viewer) {
+        Ok(user) => user,
+        Err(resp) => return resp,
+    };
+    let conn = match db.0.lock() {
+        Ok(c) => c,
+        Err(e) => return err_response(e),
+    };
+    match crate::auth::revoke_session(&conn, &actor, &path.into_inner()) {
+        Ok(()) => success_response(json!(null), "会话已吊销"),
+        Err(e) => HttpResponse::NotFound().body(e),
+    }
+}
+
+async fn revoke_all_sessions_handler(
+    req: HttpRequest,
+    body: web::Json<RevokeAllSessionsRequest>,
+    db: web::Data<Arc<Database>>,
+) -> impl Responder {
+    let actor = match ensure_role(&req, &db, UserRole::Viewer) {
+        Ok(user) => user,
+        Err(resp) => return resp,
+    };
+    let token = bearer_token(&req);
+    let conn = match db.0.lock() {
+        Ok(c) => c,
+        Err(e) => return err_response(e),
+    };
+    match crate::auth::revoke_all_sessions(
+        &conn,
+        &actor,
+        token.as_deref(),
+        body.include_current.unwrap_or(false),
+    ) {
+        Ok(count) => success_response(count, "会话已吊销"),
+        Err(e) => err_response(e),
+    }
+}
+
+async fn list_users_handler(req: HttpRequest, db: web::Data<Arc<Database>>) -> impl Responder {
+    if let Err(resp) = ensure_role(&req, &db, UserRole::Admin) {
+        return resp;
+    }
+    let conn = match db.0.lock() {
+        Ok(c) => c,
+        Err(e) => return err_response(e),
+    };
+    match database::list_users(&conn) {
+        Ok(users) => success_response(users, "操作成功"),
+        Err(e) => err_response(e),
+    }
+}
+
+async fn create_user_handler(
+    req: HttpRequest,
+    db: web::Data<Arc<Database>>,
+    body: web::Json<CreateUserRequest>,
+) -> impl Responder {
+    if let Err(resp) = ensure_role(&req, &db, UserRole::Admin) {
+        return resp;
+    }
+    let conn = match db.0.lock() {
+        Ok(c) => c,
+        Err(e) => return err_response(e),
+    };
+    match crate::auth::create_user(&conn, &body.username, &body.password, body.role) {
+        Ok(user) => success_response(user, "用户已创建"),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
+
+async fn update_user_handler(
+    req: HttpRequest,
+    db: web::Data<Arc<Database>>,
+    path: web::Path<i64>,
+    body: web::Json<UpdateUserRequest>,
+) -> impl Responder {
+    let actor = match ensure_role(&req, &db, UserRole::Admin) {
+        Ok(user) => user,
+        Err(resp) => return resp,
+    };
+    let id = path.into_inner();
+    let conn = match db.0.lock() {
+        Ok(c) => c,
+        Err(e) => return err_response(e),
+    };
+    match crate::auth::update_user(
+        &conn,
+        &AuthPolicy::from_env(),
+        &actor,
+        id,
+        body.role,
+        body.disabled,
+        body.password.as_deref(),
+    ) {
+        Ok(user) => success_response(user, "用户已更新"),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
+
+async fn delete_user_handler(
+    req: HttpRequest,
+    db: web::Data<Arc<Database>>,
+    path: web::Path<i64>,
+) -> impl Responder {
+    let actor = match ensure_role(&req, &db, UserRole::Admin) {
+        Ok(user) => user,
+        Err(resp) => return resp,
+    };
+    let id = path.into_inner();
+    let conn = match db.0.lock() {
+        Ok(c) => c,
+        Err(e) => return err_response(e),
+    };
+    match crate::auth::delete_user(&conn, &actor, id) {
+        Ok(()) => success_response(json!(null), "用户已删除"),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
+
 pub async fn start_http_server(db: Arc<Database>, port: u16) -> std::io::Result<()> {
     println!("Starting HTTP server on port {}", port);
 
@@ -448,6 +1295,7 @@ pub async fn start_http_server(db: Arc<Database>, port: u16) -> std::io::Result<
         App::new()
             .wrap(cors)
             .app_data(web::Data::new(db.clone()))
+            .app_data(web::JsonConfig::default().limit(MAX_JSON_PAYLOAD_BYTES))
             .route("/api/accounts", web::get().to(get_accounts))
             .route("/api/accounts", web::post().to(create_account))
             .route(
@@ -455,7 +1303,22 @@ pub async fn start_http_server(db: Arc<Database>, port: u16) -> std::io::Result<
                 web::post().to(delete_all_accounts_handler),
             )
             .route("/api/accounts/deleted", web::get().to(get_deleted_accounts))
+            .route("/api/accounts/groups", web::get().to(get_account_groups))
+            .route("/api/accounts/ids", web::get().to(get_account_ids))
             .route("/api/accounts/batch-import", web::post().to(batch_import))
+            .route(
+                "/api/accounts/batch-import/csv",
+                web::post().to(batch_import_csv),
+            )
+            .route(
+                "/api/accounts/batch-import/json",
+                web::post().to(batch_import_json),
+            )
+            .route(
+                "/api/import/password-manager",
+                web::post().to(import_password_manager),
+            )
+            .route("/api/import/parse", web::post().to(parse_import_text))
             .route(
                 "/api/accounts/purge-all",
                 web::delete().to(purge_all_deleted_handler),
@@ -488,10 +1351,67 @@ pub async fn start_http_server(db: Arc<Database>, port: u16) -> std::io::Result<
                 web::post().to(toggle_sold_status),
             )
             .route("/api/totp/generate", web::post().to(generate_totp))
+            .route(
+                "/api/totp/generate-batch",
+                web::post().to(generate_totp_for_accounts),
+            )
+            .route(
+                "/api/migration/import",
+                web::post().to(import_migration_payload),
+            )
+            .route(
+                "/api/migration/export",
+                web::post().to(export_migration_payload),
+            )
+            .route(
+                "/api/import/authenticator-backup",
+                web::post().to(import_authenticator_backup),
+            )
+            .route(
+                "/api/accounts/{id}/qrcode",
+                web::get().to(get_account_qr_code),
+            )
+            .route(
+                "/api/accounts/{id}/qrcode",
+                web::post().to(attach_qr_secret),
+            )
+            .route("/api/qrcode/decode", web::post().to(decode_qr_secret))
+            .route(
+                "/api/accounts/{id}/totp",
+                web::get().to(generate_totp_for_account),
+            )
+            .route(
+                "/api/accounts/{id}/totp/verify",
+                web::post().to(verify_totp),
+            )
+            .route(
+                "/api/accounts/{id}/reveal",
+                web::post().to(reveal_account_secret),
+            )
+            .route(
+                "/api/accounts/{id}/reveals",
+                web::get().to(get_secret_reveals),
+            )
             .route(
                 "/api/accounts/{id}/history",
                 web::get().to(get_account_history),
             )
+            .route(
+                "/api/export-presets",
+                web::get().to(list_export_presets_handler),
+            )
+            .route(
+                "/api/export-presets",
+                web::post().to(create_export_preset_handler),
+            )
+            .route(
+                "/api/export-presets/{id}",
+                web::put().to(update_export_preset_handler),
+            )
+            .route(
+                "/api/export-presets/{id}",
+                web::delete().to(delete_export_preset_handler),
+            )
             .route("/api/backups", web::post().to(create_backup_handler))
             .route("/api/backups", web::get().to(list_backups_handler))
             .route(
@@ -501,6 +1421,29 @@ pub async fn start_http_server(db: Arc<Database>, port: u16) -> std::io::Result<
             .route("/api/auth/login", web::post().to(login_handler))
             .route("/api/auth/check", web::get().to(check_auth_handler))
             .route("/api/auth/logout", web::post().to(logout_handler))
+            .route("/api/auth/lock", web::post().to(lock_handler))
+            .route("/api/auth/unlock", web::post().to(unlock_handler))
+            .route(
+                "/api/auth/setup",
+                web::post().to(setup_admin_password_handler),
+            )
+            .route(
+                "/api/auth/change-password",
+                web::post().to(change_admin_password_handler),
+            )
+            .route("/api/auth/sessions", web::get().to(list_sessions_handler))
+            .route(
+                "/api/auth/sessions/revoke-all",
+                web::post().to(revoke_all_sessions_handler),
+            )
+            .route(
+                "/api/auth/sessions/{id}",
+                web::delete().to(revoke_session_handler),
+            )
+            .route("/api/users", web::get().to(list_users_handler))
+            .route("/api/users", web::post().to(create_user_handler))
+            .route("/api/users/{id}", web::put().to(update_user_handler))
+            .route("/api/users/{id}", web::delete().to(delete_user_handler))
     })
     .bind(("127.0.0.1", port))?
     .run()
@@ -0,0 +1,441 @@
+//! 结构化导入格式（CSV / JSON / JSONL）→ `AccountInput` 行，解析结果交给 `database::batch_import`
+
+use crate::database::AccountInput;
+use crate::totp;
+use serde::Deserialize;
+use serde_json::Value;
+
+/// 自动识别分隔符时的候选项（按优先级）
+const CSV_DELIMITER_CANDIDATES: [u8; 4] = [b',', b';', b'\t', b'|'];
+
+/// 可导入的账号字段（名称与导出字段保持一致）
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImportField {
+    Email,
+    Password,
+    Recovery,
+    Phone,
+    Secret,
+    RegYear,
+    Country,
+    GroupName,
+    Remark,
+    /// otpauth:// URI，写入 `secret` 后由导入流程解析参数
+    OtpauthUri,
+}
+
+impl ImportField {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "email" => Some(Self::Email),
+            "password" => Some(Self::Password),
+            "recovery" => Some(Self::Recovery),
+            "phone" => Some(Self::Phone),
+            "secret" => Some(Self::Secret),
+            "reg_year" => Some(Self::RegYear),
+            "country" => Some(Self::Country),
+            "group_name" => Some(Self::GroupName),
+            "remark" => Some(Self::Remark),
+            "otpauth_uri" => Some(Self::OtpauthUri),
+            _ => None,
+        }
+    }
+
+    /// 按表头名自动匹配字段（字段名本身及常见中英文别名）
+    pub fn from_header(header: &str) -> Option<Self> {
+        if let Some(field) = Self::from_name(header) {
+            return Some(field);
+        }
+        let normalized: String = header
+            .to_lowercase()
+            .chars()
+            .filter(|c| !c.is_whitespace() && !matches!(c, '_' | '-' | '.'))
+            .collect();
+        match normalized.as_str() {
+            "mail" | "邮箱" | "账号" | "username" | "login" => Some(Self::Email),
+            "pass" | "pwd" | "密码" => Some(Self::Password),
+            "recoveryemail" | "辅助邮箱" | "恢复邮箱" => Some(Self::Recovery),
+            "phonenumber" | "mobile" | "手机" | "手机号" => Some(Self::Phone),
+            "2fa" | "totp" | "totpsecret" | "密钥" | "2fa密钥" => Some(Self::Secret),
+            "regyear" | "year" | "注册年份" | "年份" => Some(Self::RegYear),
+            "国家" => Some(Self::Country),
+            "group" | "groupname" | "分组" | "标签" => Some(Self::GroupName),
+            "note" | "notes" | "备注" => Some(Self::Remark),
+            "otpauthuri" | "otpauth" => Some(Self::OtpauthUri),
+            _ => None,
+        }
+    }
+
+    /// 将值写入对应字段；空值忽略。otpauth URI 携带 TOTP 参数，优先于裸 secret
+    pub fn apply(self, account: &mut AccountInput, value: &str) {
+        let value = value.trim();
+        if value.is_empty() {
+            return;
+        }
+        let value = value.to_string();
+        match self {
+            Self::Email => account.email = value,
+            Self::Password => account.password = value,
+            Self::Recovery => account.recovery = Some(value),
+            Self::Phone => account.phone = Some(value),
+            Self::Secret => {
+                if !account.secret.as_deref().is_some_and(totp::is_otpauth_uri) {
+                    account.secret = Some(value);
+                }
+            }
+            Self::RegYear => account.reg_year = Some(value),
+            Self::Country => account.country = Some(value),
+            Self::GroupName => account.group_name = Some(value),
+            Self::Remark => account.remark = Some(value),
+            Self::OtpauthUri => account.secret = Some(value),
+        }
+    }
+}
+
+/// 空的导入行，由各格式逐字段填充
+pub fn empty_account_input() -> AccountInput {
+    AccountInput {
+        email: String::new(),
+        password: String::new(),
+        recovery: None,
+        phone: None,
+        secret: None,
+        reg_year: None,
+        country: None,
+        group_name: None,
+        remark: None,
+        totp: None,
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// CSV 列映射：`column` 为表头名或从 0 开始的列序号，`field` 为空表示忽略该列
+#[derive(Debug, Clone, Deserialize)]
+pub struct CsvColumnMapping {
+    pub column: String,
+    #[serde(default)]
+    pub field: String,
+}
+
+/// CSV 导入配置
+#[derive(Debug, Clone, Deserialize)]
+pub struct CsvImportConfig {
+    /// 分隔符（单个字符，`\t` / `tab` 表示制表符）；为空时自动识别 `,` `;` 制表符 `|`
+    #[serde(default)]
+    pub delimiter: Option<String>,
+    /// 首行是否为表头
+    #[serde(default = "default_true")]
+    pub has_header: bool,
+    /// 列 → 字段（`email` / `password` 等）映射；为空时按表头名自动匹配
+    #[serde(default)]
+    pub mapping: Vec<CsvColumnMapping>,
+}
+
+impl Default for CsvImportConfig {
+    fn default() -> Self {
+        Self {
+            delimiter: None,
+            has_header: true,
+            mapping: Vec::new(),
+        }
+    }
+}
+
+fn parse_delimiter(delimiter: &str) -> Result<u8, String> {
+    match delimiter {
+        "\\t" | "tab" | "\t" => Ok(b'\t'),
+        value if value.len() == 1 && value.is_ascii() => Ok(value.as_bytes()[0]),
+        value => Err(format!("CSV 分隔符必须是单个 ASCII 字符: {:?}", value)),
+    }
+}
+
+/// 根据首个非空行（忽略引号内内容）识别分隔符，默认逗号
+fn detect_delimiter(content: &str) -> u8 {
+    let Some(first_line) = content.lines().find(|line| !line.trim().is_empty()) else {
+        return b',';
+    };
+    let mut counts = [0usize; CSV_DELIMITER_CANDIDATES.len()];
+    let mut in_quotes = false;
+    for byte in first_line.bytes() {
+        if byte == b'"' {
+            in_quotes = !in_quotes;
+        } else if !in_quotes {
+            if let Some(index) = CSV_DELIMITER_CANDIDATES.iter().position(|c| *c == byte) {
+                counts[index] += 1;
+            }
+        }
+    }
+    // 次数相同时按候选顺序优先
+    let mut best = 0;
+    for (index, count) in counts.iter().enumerate() {
+        if *count > counts[best] {
+            best = index;
+        }
+    }
+    CSV_DELIMITER_CANDIDATES[best]
+}
+
+/// 根据配置计算每一列对应的字段
+fn resolve_column_fields(
+    config: &CsvImportConfig,
+    headers: Option<&csv::StringRecord>,
+) -> Result<Vec<(usize, ImportField)>, String> {
+    if config.mapping.is_empty() {
+        let Some(headers) = headers else {
+            return Err("CSV 没有表头时必须提供列映射".to_string());
+        };
+        return Ok(headers
+            .iter()
+            .enumerate()
+            .filter_map(|(index, header)| ImportField::from_header(header).map(|f| (index, f)))
+            .collect());
+    }
+
+    let mut columns = Vec::new();
+    for CsvColumnMapping { column, field } in &config.mapping {
+        if field.trim().is_empty() {
+            continue;
+        }
+        let field =
+            ImportField::from_name(field).ok_or_else(|| format!("未知的导入字段: {}", field))?;
+        let header_index = headers.and_then(|headers| {
+            headers
+                .iter()
+                .position(|header| header.trim().eq_ignore_ascii_case(column.trim()))
+        });
+        let index = match header_index {
+            Some(index) => index,
+            None => column
+                .trim()
+                .parse::<usize>()
+                .map_err(|_| format!("CSV 中不存在列: {}", column))?,
+        };
+        columns.push((index, field));
+    }
+    Ok(columns)
+}
+
+/// 解析 CSV 文本（支持 RFC 4180 引号转义、UTF-8 BOM 与自定义分隔符）
+///
+/// 每个数据行对应一个 `AccountInput`（空行除外），缺少邮箱/密码的行交由 `batch_import` 报告失败
+pub fn parse_csv_accounts(
+    content: &str,
+    config: &CsvImportConfig,
+) -> Result<Vec<AccountInput>, String> {
+    let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+    let delimiter = match config.delimiter.as_deref().filter(|d| !d.is_empty()) {
+        Some(delimiter) => parse_delimiter(delimiter)?,
+        None => detect_delimiter(content),
+    };
+
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(content.as_bytes());
+    let mut records = reader.records();
+
+    let headers = if config.has_header {
+        match records.next() {
+            Some(record) => Some(record.map_err(|e| format!("CSV 表头解析失败: {}", e))?),
+            None => return Ok(Vec::new()),
+        }
+    } else {
+        None
+    };
+
+    let columns = resolve_column_fields(config, headers.as_ref())?;
+    if !columns
+        .iter()
+        .any(|(_, field)| *field == ImportField::Email)
+    {
+        return Err("列映射中缺少 email 字段".to_string());
+    }
+
+    let mut accounts = Vec::new();
+    for record in records {
+        let record = record.map_err(|e| {
+            let line = e.position().map(|p| p.line()).unwrap_or_default();
+            format!("CSV 第 {} 行解析失败: {}", line, e)
+        })?;
+        if record.iter().all(|value| value.trim().is_empty()) {
+            continue;
+        }
+        let mut account = empty_account_input();
+        for (index, field) in &columns {
+            if let Some(value) = record.get(*index) {
+                field.apply(&mut account, value);
+            }
+        }
+        accounts.push(account);
+    }
+    Ok(accounts)
+}
+
+/// 将一个 JSON 对象转换为导入行；按 `ImportField` 名称取值，其余键（id、status 等）忽略
+fn json_object_to_account(value: &Value, position: &str) -> Result<AccountInput, String> {
+    let Value::Object(object) = value else {
+        return Err(format!("{}不是 JSON 对象", position));
+    };
+    let mut account = empty_account_input();
+    for (key, value) in object {
+        let Some(field) = ImportField::from_name(key) else {
+            continue;
+        };
+        match value {
+            Value::Null => {}
+            Value::String(text) => field.apply(&mut account, text),
+            Value::Number(number) => field.apply(&mut account, &number.to_string()),
+            Value::Bool(flag) => field.apply(&mut account, &flag.to_string()),
+            _ => return Err(format!("{}的字段 {} 必须是字符串", position, key)),
+        }
+    }
+    Ok(account)
+}
+
+/// 解析 JSON 数组或 JSON Lines（每行一个对象）；以 `[` 开头时按数组解析
+pub fn parse_json_accounts(content: &str) -> Result<Vec<AccountInput>, String> {
+    let content = content.strip_prefix('\u{feff}').unwrap_or(content).trim();
+    if content.starts_with('[') {
+        let items: Vec<Value> =
+            serde_json::from_str(content).map_err(|e| format!("JSON 解析失败: {}", e))?;
+        return items
+            .iter()
+            .enumerate()
+            .map(|(index, item)| json_object_to_account(item, &format!("第 {} 条记录", index + 1)))
+            .collect();
+    }
+
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| {
+            let value: Value = serde_json::from_str(line)
+                .map_err(|e| format!("JSONL 第 {} 行解析失败: {}", index + 1, e))?;
+            json_object_to_account(&value, &format!("JSONL 第 {} 行", index + 1))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mapping(pairs: &[(&str, &str)]) -> Vec<CsvColumnMapping> {
+        pairs
+            .iter()
+            .map(|(column, field)| CsvColumnMapping {
+                column: column.to_string(),
+                field: field.to_string(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn parses_quoted_fields_with_bom_and_auto_mapping() {
+        let content = "\u{feff}email,password,remark,unused\r\n\
+            a@example.com,\"p,w\"\"d\",\"line1\nline2\",x\r\n\
+            \r\n\
+            b@example.com,pwd2,,\r\n";
+        let accounts = parse_csv_accounts(content, &CsvImportConfig::default()).unwrap();
+
+        assert_eq!(accounts.len(), 2);
+        assert_eq!(accounts[0].email, "a@example.com");
+        assert_eq!(accounts[0].password, "p,w\"d");
+        assert_eq!(accounts[0].remark.as_deref(), Some("line1\nline2"));
+        assert_eq!(accounts[1].remark, None);
+    }
+
+    #[test]
+    fn detects_delimiter_and_applies_custom_mapping() {
+        let content = "邮箱;Pass;2FA\nuser@example.com;secret;JBSWY3DPEHPK3PXP\n";
+        let accounts = parse_csv_accounts(content, &CsvImportConfig::default()).unwrap();
+        assert_eq!(accounts[0].password, "secret");
+        assert_eq!(accounts[0].secret.as_deref(), Some("JBSWY3DPEHPK3PXP"));
+
+        let config = CsvImportConfig {
+            delimiter: Some("\\t".to_string()),
+            has_header: false,
+            mapping: mapping(&[("0", "email"), ("2", "password"), ("1", "")]),
+        };
+        let accounts = parse_csv_accounts("a@x.com\tignored\tpw\n", &config).unwrap();
+        assert_eq!(accounts[0].email, "a@x.com");
+        assert_eq!(accounts[0].password, "pw");
+
+        let config = CsvImportConfig {
+            mapping: mapping(&[("Login", "email"), ("Secret Code", "password")]),
+            ..CsvImportConfig::default()
+        };
+        let accounts = parse_csv_accounts("login|secret code\nc@x.com|pw3\n", &config).unwrap();
+        assert_eq!(accounts[0].email, "c@x.com");
+        assert_eq!(accounts[0].password, "pw3");
+    }
+
+    #[test]
+    fn rejects_invalid_config() {
+        let no_email = CsvImportConfig {
+            mapping: mapping(&[("password", "password")]),
+            ..CsvImportConfig::default()
+        };
+        assert!(parse_csv_accounts("email,password\n", &no_email).is_err());
+
+        let unknown_field = CsvImportConfig {
+            mapping: mapping(&[("email", "nickname")]),
+            ..CsvImportConfig::default()
+        };
+        assert!(parse_csv_accounts("email\n", &unknown_field).is_err());
+
+        let missing_column = CsvImportConfig {
+            mapping: mapping(&[("mail address", "email")]),
+            ..CsvImportConfig::default()
+        };
+        assert!(parse_csv_accounts("email\n", &missing_column).is_err());
+
+        let headerless = CsvImportConfig {
+            has_header: false,
+            ..CsvImportConfig::default()
+        };
+        assert!(parse_csv_accounts("a@x.com,pw\n", &headerless).is_err());
+    }
+
+    #[test]
+    fn parses_json_array_and_json_lines() {
+        let array = r#"[
+            {"id": 7, "email": "a@example.com", "password": "p----w", "reg_year": 2021,
+             "remark": "多行\n备注", "status": "pro", "country": null},
+            {"email": "b@example.com", "password": "pw",
+             "otpauth_uri": "otpauth://totp/x?secret=JBSWY3DPEHPK3PXP&digits=8",
+             "secret": "JBSWY3DPEHPK3PXP"}
+        ]"#;
+        let accounts = parse_json_accounts(array).unwrap();
+        assert_eq!(accounts.len(), 2);
+        assert_eq!(accounts[0].password, "p----w");
+        assert_eq!(accounts[0].reg_year.as_deref(), Some("2021"));
+        assert_eq!(accounts[0].remark.as_deref(), Some("多行\n备注"));
+        assert_eq!(accounts[0].country, None);
+        assert!(accounts[1]
+            .secret
+            .as_deref()
+            .unwrap()
+            .starts_with("otpauth://"));
+
+        let lines = "{\"email\":\"c@example.com\",\"password\":\"pw\"}\n\n{\"email\":\"d@example.com\",\"password\":\"pw\"}\n";
+        let accounts = parse_json_accounts(lines).unwrap();
+        assert_eq!(
+            accounts
+                .iter()
+                .map(|a| a.email.as_str())
+                .collect::<Vec<_>>(),
+            vec!["c@example.com", "d@example.com"]
+        );
+
+        assert!(parse_json_accounts("[1]").is_err());
+        assert!(parse_json_accounts("{\"email\": {\"x\": 1}}").is_err());
+        let err = parse_json_accounts("{\"email\":\"a@x.com\"}\nnot json").unwrap_err();
+        assert!(err.contains("第 2 行"));
+    }
+}
@@ -0,0 +1,965 @@
+//! 导入文本解析（与前端 `utils/importParser.js` / `utils/phoneUtils.js` 行为保持一致）
+
+use crate::database::AccountInput;
+use regex::Regex;
+use serde::Serialize;
+use std::sync::OnceLock;
+
+const COUNTRIES: &[&str] = &[
+    "india",
+    "france",
+    "brazil",
+    "laos",
+    "china",
+    "usa",
+    "germany",
+    "japan",
+    "korea",
+    "vietnam",
+    "thailand",
+    "indonesia",
+    "malaysia",
+    "singapore",
+    "philippines",
+    "russia",
+    "uk",
+    "canada",
+    "australia",
+    "mexico",
+    "spain",
+    "italy",
+    "netherlands",
+    "belgium",
+    "switzerland",
+    "austria",
+    "poland",
+    "turkey",
+    "egypt",
+    "nigeria",
+    "south africa",
+    "argentina",
+    "chile",
+    "colombia",
+    "peru",
+];
+const SECRET_MIN_LENGTH: usize = 16;
+const NO_SEPARATOR: &str = "无分隔符";
+
+const DEFAULT_COUNTRY_CODE: &str = "86";
+const MIN_LOCAL_LENGTH: usize = 6;
+const MAX_LOCAL_LENGTH: usize = 14;
+
+const COUNTRY_DIALING_CODES: &[&str] = &[
+    "1", "7", "20", "27", "30", "31", "32", "33", "34", "36", "39", "40", "41", "43", "44", "45",
+    "46", "47", "48", "49", "51", "52", "53", "54", "55", "56", "57", "58", "60", "61", "62", "63",
+    "64", "65", "66", "81", "82", "84", "86", "90", "91", "92", "93", "94", "95", "98", "212",
+    "213", "216", "218", "220", "221", "222", "223", "224", "225", "226", "227", "228", "229",
+    "230", "231", "232", "233", "234", "235", "236", "237", "238", "239", "240", "241", "242",
+    "243", "244", "245", "246", "248", "249", "250", "251", "252", "253", "254", "255", "256",
+    "257", "258", "260", "261", "262", "263", "264", "265", "266", "267", "268", "269", "290",
+    "291", "297", "298", "299", "350", "351", "352", "353", "354", "355", "356", "357", "358",
+    "359", "370", "371", "372", "373", "374", "375", "376", "377", "378", "380", "381", "382",
+    "383", "385", "386", "387", "389", "420", "421", "423", "500", "501", "502", "503", "504",
+    "505", "506", "507", "508", "509", "591", "592", "593", "594", "595", "596", "597", "598",
+    "599", "670", "672", "673", "674", "675", "676", "677", "678", "679", "680", "681", "682",
+    "683", "685", "686", "687", "688", "689", "690", "691", "692", "850", "852", "853", "855",
+    "856", "880", "886", "960", "961", "962", "963", "964", "965", "966", "967", "968", "970",
+    "971", "972", "973", "974", "975", "976", "977",
+];
+
+/// 国家提示词 → 国家码（顺序与前端保持一致，模糊匹配时按此顺序查找）
+const COUNTRY_HINT_TO_CODE: &[(&str, &str)] = &[
+    ("cn", "86"),
+    ("china", "86"),
+    ("中国", "86"),
+    ("us", "1"),
+    ("usa", "1"),
+    ("unitedstates", "1"),
+    ("美国", "1"),
+    ("uk", "44"),
+    ("gb", "44"),
+    ("britain", "44"),
+    ("england", "44"),
+    ("英国", "44"),
+    ("jp", "81"),
+    ("japan", "81"),
+    ("日本", "81"),
+    ("kr", "82"),
+    ("korea", "82"),
+    ("韩国", "82"),
+    ("vn", "84"),
+    ("vietnam", "84"),
+    ("越南", "84"),
+    ("in", "91"),
+    ("india", "91"),
+    ("印度", "91"),
+    ("ru", "7"),
+    ("russia", "7"),
+    ("俄罗斯", "7"),
+    ("de", "49"),
+    ("germany", "49"),
+    ("德国", "49"),
+    ("fr", "33"),
+    ("france", "33"),
+    ("法国", "33"),
+    ("br", "55"),
+    ("brazil", "55"),
+    ("巴西", "55"),
+    ("mx", "52"),
+    ("mexico", "52"),
+    ("墨西哥", "52"),
+    ("sg", "65"),
+    ("singapore", "65"),
+    ("新加坡", "65"),
+    ("my", "60"),
+    ("malaysia", "60"),
+    ("马来西亚", "60"),
+    ("id", "62"),
+    ("indonesia", "62"),
+    ("印度尼西亚", "62"),
+    ("ph", "63"),
+    ("philippines", "63"),
+    ("菲律宾", "63"),
+    ("th", "66"),
+    ("thailand", "66"),
+    ("泰国", "66"),
+    ("hk", "852"),
+    ("hongkong", "852"),
+    ("香港", "852"),
+    ("tw", "886"),
+    ("taiwan", "886"),
+    ("台湾", "886"),
+    ("mo", "853"),
+    ("macao", "853"),
+    ("macau", "853"),
+    ("澳门", "853"),
+    ("ca", "1"),
+    ("canada", "1"),
+    ("加拿大", "1"),
+    ("au", "61"),
+    ("australia", "61"),
+    ("澳大利亚", "61"),
+    ("nz", "64"),
+    ("newzealand", "64"),
+    ("新西兰", "64"),
+];
+
+/// 未能导入的行
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ImportLineWarning {
+    /// 行号（从 1 开始）
+    pub line: usize,
+    pub content: String,
+    pub message: String,
+}
+
+/// 导入文本解析结果；`accounts` 可直接传给 `batch_import`
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportParseResult {
+    pub accounts: Vec<AccountInput>,
+    pub warnings: Vec<ImportLineWarning>,
+    /// 格式差异提示（分隔符混用、部分行缺字段等）
+    pub detected_formats: Vec<String>,
+}
+
+#[derive(Default)]
+struct FormatStats {
+    separators: Vec<String>,
+    has_recovery: usize,
+    no_recovery: usize,
+    has_secret: usize,
+    no_secret: usize,
+    has_phone: usize,
+    no_phone: usize,
+    special_format: usize,
+    standard_format: usize,
+    invalid_lines: usize,
+}
+
+impl FormatStats {
+    fn record(&mut self, separator: &str, account: &AccountInput) {
+        if !self.separators.iter().any(|s| s == separator) {
+            self.separators.push(separator.to_string());
+        }
+        if account.recovery.is_some() {
+            self.has_recovery += 1;
+        } else {
+            self.no_recovery += 1;
+        }
+        if account.secret.is_some() {
+            self.has_secret += 1;
+        } else {
+            self.no_secret += 1;
+        }
+        if account.phone.is_some() {
+            self.has_phone += 1;
+        } else {
+            self.no_phone += 1;
+        }
+    }
+
+    fn detected_formats(&self) -> Vec<String> {
+        let mut formats = Vec::new();
+        if self.separators.len() > 1 {
+            formats.push(format!(
+                "使用了 {} 种不同的分隔符: {}",
+                self.separators.len(),
+                self.separators.join(", ")
+            ));
+        }
+        if self.special_format > 0 && self.standard_format > 0 {
+            formats.push(format!(
+                "混合使用了\"卡号:密码:\"格式 ({}行) 和标准格式 ({}行)",
+                self.special_format, self.standard_format
+            ));
+        }
+        if self.has_recovery > 0 && self.no_recovery > 0 {
+            formats.push(format!(
+                "部分行有恢复邮箱 ({}行)，部分行没有 ({}行)",
+                self.has_recovery, self.no_recovery
+            ));
+        }
+        if self.has_secret > 0 && self.no_secret > 0 {
+            formats.push(format!(
+                "部分行有2FA密钥 ({}行)，部分行没有 ({}行)",
+                self.has_secret, self.no_secret
+            ));
+        }
+        if self.has_phone > 0 && self.no_phone > 0 {
+            formats.push(format!(
+                "部分行有手机号 ({}行)，部分行没有 ({}行)",
+                self.has_phone, self.no_phone
+            ));
+        }
+        if self.invalid_lines > 0 {
+            formats.push(format!(
+                "有 {} 行未导入（缺少邮箱/密码或格式无法识别）",
+                self.invalid_lines
+            ));
+        }
+        formats
+    }
+}
+
+fn cached_regex(cell: &'static OnceLock<Regex>, pattern: &str) -> &'static Regex {
+    cell.get_or_init(|| Regex::new(pattern).expect("内置正则表达式无效"))
+}
+
+macro_rules! static_regex {
+    ($name:ident, $pattern:expr) => {
+        fn $name() -> &'static Regex {
+            static CELL: OnceLock<Regex> = OnceLock::new();
+            cached_regex(&CELL, $pattern)
+        }
+    };
+}
+
+// JS 正则中的 `\d` / `\b` 仅匹配 ASCII，这里显式写出以保持一致
+static_regex!(email_re, r"^[^\s@]+@[^\s@]+\.[^\s@]+$");
+static_regex!(e164_re, r"^\+[0-9]{8,18}$");
+static_regex!(year_re, r"(?-u:\b)20[0-9]{2}(?-u:\b)");
+static_regex!(intl_phone_re, r"\+[0-9][0-9\s\-]{6,}[0-9]");
+static_regex!(cn_phone_re, r"(?-u:\b)1[3-9][0-9]{9}(?-u:\b)");
+static_regex!(plain_phone_re, r"(?-u:\b)[0-9]{10,15}(?-u:\b)");
+static_regex!(explicit_code_re, r"(?:^|[^0-9])\+\s*[0-9]{1,4}");
+static_regex!(hint_token_re, r"[A-Za-z\x{4e00}-\x{9fff}]+");
+static_regex!(digit_token_re, r"[0-9]+");
+static_regex!(cn_mobile_re, r"^1[3-9][0-9]{9}$");
+static_regex!(two_fa_live_re, r"(?i)2fa\.live/(?:tok|ok)/([a-z2-7]{16,})");
+static_regex!(base32_token_re, r"[A-Z2-7]{16,}");
+static_regex!(group_re, r"^(主号|成员[0-9]+)[：:]\s*");
+static_regex!(tag_re, r"<([^>]+)>");
+static_regex!(
+    group_title_re,
+    r"^第(?:[0-9]+|[一二三四五六七八九十百千万]+)组"
+);
+static_regex!(account_set_re, r"^账号集[0-9]+");
+static_regex!(divider_re, r"^[-—=]+$");
+static_regex!(
+    special_line_re,
+    r"卡号[：:]\s*(.+?)\s*密码[：:]\s*(.+?)(?:\s*(----|\||——|---|--)\s*(.*))?$"
+);
+
+/// 判断是否为邮箱
+pub fn is_email(value: &str) -> bool {
+    !value.is_empty() && email_re().is_match(value)
+}
+
+/// 判断是否为手机号（规范化后符合 E.164 常见长度）
+pub fn is_phone_number(value: &str) -> bool {
+    let cleaned = normalize_phone_number(value);
+    !cleaned.is_empty() && e164_re().is_match(&cleaned)
+}
+
+/// 判断是否为年份（2015-2030）
+pub fn is_year(value: &str) -> bool {
+    let trimmed = value.trim();
+    trimmed.len() == 4
+        && trimmed.bytes().all(|b| b.is_ascii_digit())
+        && trimmed
+            .parse::<u16>()
+            .is_ok_and(|year| (2015..=2030).contains(&year))
+}
+
+/// 判断是否为国家名
+pub fn is_country(value: &str) -> bool {
+    let lowered = value.trim().to_lowercase();
+    !lowered.is_empty() && COUNTRIES.contains(&lowered.as_str())
+}
+
+/// 提取 2FA 密钥（优先 2fa.live URL，其次匹配足够长度的 Base32 token）
+pub fn extract_secret(value: &str) -> String {
+    let raw = value.trim();
+    if raw.is_empty() {
+        return String::new();
+    }
+
+    if let Some(caps) = two_fa_live_re().captures(raw) {
+        return caps[1].to_uppercase();
+    }
+
+    // 允许空格分组形式（如: XXXX XXXX XXXX）
+    let compact: String = raw
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>()
+        .to_uppercase();
+    if compact.len() >= SECRET_MIN_LENGTH
+        && compact
+            .bytes()
+            .all(|b| b.is_ascii_uppercase() || (b'2'..=b'7').contains(&b))
+    {
+        return compact;
+    }
+
+    // 处理带后缀说明的场景（如：SECRET --<备注>）
+    base32_token_re()
+        .find(&raw.to_uppercase())
+        .map(|m| m.as_str().to_string())
+        .unwrap_or_default()
+}
+
+/// 提取分组前缀（主号 / 成员N），返回 (分组, 剩余内容)
+pub fn extract_group(line: &str) -> (String, &str) {
+    match group_re().captures(line) {
+        Some(caps) => (caps[1].to_string(), &line[caps[0].len()..]),
+        None => (String::new(), line),
+    }
+}
+
+/// 提取 `<...>` 标注，以空格连接
+pub fn extract_tags(value: &str) -> String {
+    tag_re()
+        .captures_iter(value)
+        .map(|caps| caps[1].to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// 移除 `<...>` 标注
+pub fn clean_tags(value: &str) -> String {
+    tag_re().replace_all(value, "").trim().to_string()
+}
+
+/// 判断是否应跳过该行（空行、注释、URL、分组标题、分隔线等）
+pub fn should_skip_line(line: &str) -> bool {
+    let trimmed = line.trim();
+    trimmed.is_empty()
+        || trimmed.starts_with("```")
+        || trimmed.starts_with('#')
+        || trimmed.starts_with("//")
+        || trimmed.starts_with("http://")
+        || trimmed.starts_with("https://")
+        || group_title_re().is_match(trimmed)
+        || trimmed.starts_with("待加入")
+        || account_set_re().is_match(trimmed)
+        || divider_re().is_match(trimmed)
+}
+
+/// 智能识别分隔符并分割，返回 (各段, 分隔符)
+pub fn detect_and_split(line: &str) -> (Vec<&str>, &'static str) {
+    // 管道符优先；以 | 开头/结尾或出现空字段时视为无法识别
+    if line.contains('|') {
+        if line.starts_with('|') || line.ends_with('|') {
+            return (vec![line], NO_SEPARATOR);
+        }
+        let parts: Vec<&str> = line.split('|').collect();
+        if parts.iter().any(|part| part.trim().is_empty()) {
+            return (vec![line], NO_SEPARATOR);
+        }
+        if parts.len() >= 2 {
+            return (parts, "|");
+        }
+    }
+    for separator in ["----", "——", "---", "--"] {
+        if line.contains(separator) {
+            return (line.split(separator).collect(), separator);
+        }
+    }
+    (vec![line], NO_SEPARATOR)
+}
+
+fn normalize_raw_phone_input(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| match c {
+            '０'..='９' => char::from(b'0' + (c as u32 - '０' as u32) as u8),
+            '＋' => '+',
+            other => other,
+        })
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+fn normalize_country_hint_key(value: &str) -> String {
+    value
+        .to_lowercase()
+        .chars()
+        .filter(|c| !c.is_whitespace() && !matches!(c, '.' | '_' | '-'))
+        .collect()
+}
+
+fn country_code_from_hint(raw: &str) -> Option<&'static str> {
+    let lookup = |key: &str| {
+        COUNTRY_HINT_TO_CODE
+            .iter()
+            .find(|(hint, _)| *hint == key)
+            .map(|(_, code)| *code)
+    };
+    for token in hint_token_re().find_iter(raw) {
+        if let Some(code) = lookup(&normalize_country_hint_key(token.as_str())) {
+            return Some(code);
+        }
+    }
+
+    let letters: String = hint_token_re().find_iter(raw).map(|m| m.as_str()).collect();
+    let letters = normalize_country_hint_key(&letters);
+    if letters.is_empty() {
+        return None;
+    }
+    COUNTRY_HINT_TO_CODE
+        .iter()
+        .find(|(hint, _)| letters.contains(hint))
+        .map(|(_, code)| *code)
+}
+
+fn is_dialing_code(code: &str) -> bool {
+    COUNTRY_DIALING_CODES.contains(&code)
+}
+
+fn local_length_ok(len: usize) -> bool {
+    (MIN_LOCAL_LENGTH..=MAX_LOCAL_LENGTH).contains(&len)
+}
+
+/// 按最长前缀匹配数字开头的国家码（剩余本地号码长度需合理）
+fn country_code_from_leading_digits(digits: &str) -> Option<&str> {
+    (1..=3)
+        .rev()
+        .filter(|&len| digits.len() > len)
+        .map(|len| &digits[..len])
+        .find(|code| is_dialing_code(code) && local_length_ok(digits.len() - code.len()))
+}
+
+/// 分段输入：`1 2192731268` / `44-7700-900123` / `86 13812345678`
+fn normalize_with_segmented_country_code(raw: &str) -> Option<String> {
+    let tokens: Vec<&str> = digit_token_re()
+        .find_iter(raw)
+        .map(|m| m.as_str())
+        .collect();
+    if tokens.len() < 2 {
+        return None;
+    }
+
+    let mut code = tokens[0];
+    if code.starts_with("00") && code.len() > 2 {
+        code = &code[2..];
+    }
+    if code.starts_with("011") && code.len() > 3 {
+        code = &code[3..];
+    }
+    if !is_dialing_code(code) {
+        return None;
+    }
+
+    let local = tokens[1..].concat();
+    local_length_ok(local.len()).then(|| format!("+{}{}", code, local))
+}
+
+/// 将各种书写形式的手机号规范化为 `+国家码号码`；未提供国家码时默认 +86
+pub fn normalize_phone_number(value: &str) -> String {
+    let raw = normalize_raw_phone_input(value);
+    let digits: String = raw.chars().filter(char::is_ascii_digit).collect();
+    if digits.is_empty() {
+        return String::new();
+    }
+
+    // 显式国家码：+86xxxx / +1xxxx（允许在文本中出现）
+    if explicit_code_re().is_match(&raw) {
+        return format!("+{}", digits);
+    }
+
+    let compact: String = raw
+        .chars()
+        .filter(|c| c.is_ascii_digit() || *c == '+')
+        .collect();
+    // 00 前缀国际格式：0086xxxx / 001xxxx
+    if compact.starts_with("00") && digits.len() > 2 {
+        return format!("+{}", &digits[2..]);
+    }
+    // 北美常见国际前缀：011xx...
+    if compact.starts_with("011") && digits.len() > 3 {
+        return format!("+{}", &digits[3..]);
+    }
+
+    // 明确国家提示词（CN/US/中国/美国 等）+ 本地号码
+    if let Some(code) = country_code_from_hint(&raw) {
+        if digits.starts_with(code) && digits.len() - code.len() >= MIN_LOCAL_LENGTH {
+            return format!("+{}", digits);
+        }
+        return format!("+{}{}", code, digits);
+    }
+
+    if let Some(segmented) = normalize_with_segmented_country_code(&raw) {
+        return segmented;
+    }
+
+    // 中国大陆本地手机号优先视为 +86，避免误判为 +1
+    if cn_mobile_re().is_match(&digits) {
+        return format!("+{}{}", DEFAULT_COUNTRY_CODE, digits);
+    }
+
+    // 已含有效国家码（无 +）或默认国家码
+    if country_code_from_leading_digits(&digits).is_some()
+        || (digits.starts_with(DEFAULT_COUNTRY_CODE) && digits.len() >= 10)
+    {
+        return format!("+{}", digits);
+    }
+
+    format!("+{}{}", DEFAULT_COUNTRY_CODE, digits)
+}
+
+/// 从单个字段中提取年份与手机号
+fn extract_year_and_phone(value: &str) -> (Option<String>, Option<String>) {
+    let year = year_re()
+        .find_iter(value)
+        .map(|m| m.as_str())
+        .find(|candidate| is_year(candidate))
+        .map(str::to_string);
+
+    let phone = [intl_phone_re(), cn_phone_re(), plain_phone_re()]
+        .into_iter()
+        .find_map(|pattern| {
+            pattern
+                .find_iter(value)
+                .map(|m| normalize_phone_number(m.as_str()))
+                .find(|normalized| is_phone_number(normalized))
+        });
+
+    (year, phone)
+}
+
+fn parse_tail_fields(fields: &[&str], target: &mut AccountInput) {
+    for field in fields.iter().map(|f| f.trim()).filter(|f| !f.is_empty()) {
+        if target.recovery.is_none() && is_email(field) {
+            target.recovery = Some(field.to_string());
+            continue;
+        }
+
+        let (year, phone) = extract_year_and_phone(field);
+        if target.reg_year.is_none() {
+            target.reg_year = year;
+        }
+        if target.phone.is_none() {
+            target.phone = phone;
+        }
+
+        if target.secret.is_none() {
+            let secret = extract_secret(field);
+            if !secret.is_empty() {
+                target.secret = Some(secret);
+            }
+        }
+
+        if target.country.is_none() && is_country(field) {
+            target.country = Some(field.to_string());
+        }
+    }
+}
+
+fn new_account(email: &str, password: &str, group: &str, remark: &str) -> AccountInput {
+    let non_empty = |value: &str| (!value.is_empty()).then(|| value.to_string());
+    AccountInput {
+        email: email.to_string(),
+        password: password.to_string(),
+        recovery: None,
+        phone: None,
+        secret: None,
+        reg_year: None,
+        country: None,
+        group_name: non_empty(group),
+        remark: non_empty(remark),
+        totp: None,
+    }
+}
+
+fn split_non_empty(line: &str) -> (Vec<&str>, &'static str) {
+    let (parts, separator) = detect_and_split(line);
+    (
+        parts
+            .into_iter()
+            .map(str::trim)
+            .filter(|part| !part.is_empty())
+            .collect(),
+        separator,
+    )
+}
+
+/// 特殊格式：`卡号：xxx密码：xxx` + 可选尾字段；不匹配该格式时返回 None
+fn parse_special_line(
+    line: &str,
+    group: &str,
+    remark: &str,
+) -> Option<(Result<AccountInput, String>, &'static str)> {
+    let caps = special_line_re().captures(line)?;
+    let email = caps.get(1).map_or("", |m| m.as_str()).trim();
+    let password = caps.get(2).map_or("", |m| m.as_str()).trim();
+    let separator = match caps.get(3).map(|m| m.as_str()) {
+        Some("----") => "----",
+        Some("|") => "|",
+        Some("——") => "——",
+        Some("---") => "---",
+        Some("--") => "--",
+        _ => NO_SEPARATOR,
+    };
+    let rest = caps.get(4).map_or("", |m| m.as_str()).trim();
+
+    if !is_email(email) {
+        return Some((Err("卡号格式中的邮箱无效".to_string()), separator));
+    }
+    if password.is_empty() {
+        return Some((Err("卡号格式缺少密码".to_string()), separator));
+    }
+
+    let mut account = new_account(email, password, group, remark);
+    if !rest.is_empty() {
+        parse_tail_fields(&split_non_empty(rest).0, &mut account);
+    }
+    Some((Ok(account), separator))
+}
+
+/// 标准格式：邮箱 + 密码 + 任意顺序的尾字段
+fn parse_standard_line(
+    line: &str,
+    group: &str,
+    remark: &str,
+) -> (Result<AccountInput, String>, &'static str) {
+    let (parts, separator) = split_non_empty(line);
+    let Some(email_index) = parts.iter().position(|part| is_email(part)) else {
+        return (Err("未识别到邮箱".to_string()), separator);
+    };
+
+    let password = parts
+        .get(email_index + 1)
+        .copied()
+        .filter(|candidate| !is_email(candidate))
+        .unwrap_or("");
+    if password.is_empty() {
+        return (Err("邮箱后缺少密码".to_string()), separator);
+    }
+
+    let mut account = new_account(parts[email_index], password, group, remark);
+    parse_tail_fields(&parts[email_index + 2..], &mut account);
+    (Ok(account), separator)
+}
+
+/// 解析导入文本：逐行识别邮箱/密码/恢复邮箱/手机号/2FA/年份/国家，并汇总格式差异
+pub fn parse_import_text(text: &str) -> ImportParseResult {
+    let mut result = ImportParseResult {
+        accounts: Vec::new(),
+        warnings: Vec::new(),
+        detected_formats: Vec::new(),
+    };
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    if text.trim().is_empty() {
+        return result;
+    }
+
+    let mut stats = FormatStats::default();
+    for (index, line) in text.split('\n').enumerate() {
+        if should_skip_line(line) {
+            continue;
+        }
+
+        let (group, line_without_group) = extract_group(line);
+        let remark = extract_tags(line_without_group);
+        let cleaned = clean_tags(line_without_group);
+
+        let (parsed, separator) = match parse_special_line(&cleaned, &group, &remark) {
+            Some(special) => {
+                stats.special_format += 1;
+                special
+            }
+            None => {
+                stats.standard_format += 1;
+                parse_standard_line(&cleaned, &group, &remark)
+            }
+        };
+
+        match parsed {
+            Ok(account) => {
+                stats.record(separator, &account);
+                result.accounts.push(account);
+            }
+            Err(message) => {
+                stats.invalid_lines += 1;
+                result.warnings.push(ImportLineWarning {
+                    line: index + 1,
+                    content: line.trim().to_string(),
+                    message,
+                });
+            }
+        }
+    }
+
+    result.detected_formats = stats.detected_formats();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REAL_DATA: &str = include_str!("../../e2e/test-data/import-test-data.txt");
+
+    fn parse_one(line: &str) -> AccountInput {
+        let mut result = parse_import_text(line);
+        assert_eq!(result.accounts.len(), 1, "{:?}", result.warnings);
+        result.accounts.remove(0)
+    }
+
+    #[test]
+    fn field_predicates() {
+        assert!(is_email("user.name@example.co.uk"));
+        assert!(is_email("a@b.c"));
+        assert!(!is_email("@gmail.com"));
+        assert!(!is_email(""));
+
+        assert!(is_phone_number("13812345678"));
+        assert!(is_phone_number("+1234567890"));
+        assert!(!is_phone_number("12345"));
+        assert!(!is_phone_number("abcdefghijk"));
+
+        assert!(is_year("2015") && is_year("2030"));
+        assert!(!is_year("2014") && !is_year("2031") && !is_year("20"));
+
+        assert!(is_country("China") && is_country("USA"));
+        assert!(!is_country("password123") && !is_country(""));
+    }
+
+    #[test]
+    fn normalize_phone_number_matches_frontend() {
+        let cases = [
+            ("13812345678", "+8613812345678"),
+            (" 18024048401 ", "+8618024048401"),
+            ("138 1234 5678", "+8613812345678"),
+            ("+1 2192731268", "+12192731268"),
+            ("+86 138 1234 5678", "+8613812345678"),
+            ("008613812345678", "+8613812345678"),
+            ("0086 13812345678", "+8613812345678"),
+            ("1 2192731268", "+12192731268"),
+            ("44-7700-900123", "+447700900123"),
+            ("86 13812345678", "+8613812345678"),
+            ("US 2192731268", "+12192731268"),
+            ("中国 13812345678", "+8613812345678"),
+            ("CN 中国 (+86) 13812345678", "+8613812345678"),
+            ("011442070313000", "+442070313000"),
+            ("＋８６１３８１２３４５６７８", "+8613812345678"),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(normalize_phone_number(input), expected, "{}", input);
+        }
+        assert_eq!(normalize_phone_number("abc"), "");
+    }
+
+    #[test]
+    fn extract_secret_variants() {
+        assert_eq!(extract_secret("JBSWY3DPEHPK3PXP"), "JBSWY3DPEHPK3PXP");
+        assert_eq!(
+            extract_secret("https://2fa.live/ok/jbswy3dpehpk3pxp"),
+            "JBSWY3DPEHPK3PXP"
+        );
+        assert_eq!(extract_secret("JBSW Y3DP EHPK 3PXP"), "JBSWY3DPEHPK3PXP");
+        assert_eq!(extract_secret("short"), "");
+        assert_eq!(extract_secret("password123"), "");
+    }
+
+    #[test]
+    fn line_helpers() {
+        assert_eq!(
+            extract_group("成员1：test@gmail.com"),
+            ("成员1".to_string(), "test@gmail.com")
+        );
+        assert_eq!(extract_group("a----b"), (String::new(), "a----b"));
+        assert_eq!(extract_tags("test <VIP> <重要>"), "VIP 重要");
+        assert_eq!(clean_tags("test <VIP> content"), "test  content");
+
+        for line in [
+            "",
+            "   ",
+            "# 注释",
+            "// 注释",
+            "https://x.com",
+            "第二组",
+            "待加入",
+            "========",
+            "```",
+        ] {
+            assert!(should_skip_line(line), "{:?}", line);
+        }
+        assert!(!should_skip_line("test@gmail.com----password"));
+
+        assert_eq!(detect_and_split("a----b---c"), (vec!["a", "b---c"], "----"));
+        assert_eq!(detect_and_split("a——b——c"), (vec!["a", "b", "c"], "——"));
+        assert_eq!(detect_and_split("a|b|c"), (vec!["a", "b", "c"], "|"));
+        assert_eq!(detect_and_split("a--b"), (vec!["a", "b"], "--"));
+        assert_eq!(detect_and_split("a||b"), (vec!["a||b"], NO_SEPARATOR));
+        assert_eq!(
+            detect_and_split("test@gmail.com"),
+            (vec!["test@gmail.com"], NO_SEPARATOR)
+        );
+    }
+
+    #[test]
+    fn parse_standard_lines() {
+        let account =
+            parse_one("主号：test@gmail.com|pass123|recovery@example.com|JBSWY3DPEHPK3PXP <VIP>");
+        assert_eq!(account.email, "test@gmail.com");
+        assert_eq!(account.password, "pass123");
+        assert_eq!(account.recovery.as_deref(), Some("recovery@example.com"));
+        assert_eq!(account.secret.as_deref(), Some("JBSWY3DPEHPK3PXP"));
+        assert_eq!(account.group_name.as_deref(), Some("主号"));
+        assert_eq!(account.remark.as_deref(), Some("VIP"));
+
+        let account = parse_one("test@gmail.com----pass123----2021----India----13812345678");
+        assert_eq!(account.reg_year.as_deref(), Some("2021"));
+        assert_eq!(account.country.as_deref(), Some("India"));
+        assert_eq!(account.phone.as_deref(), Some("+8613812345678"));
+        assert_eq!(account.recovery, None);
+        assert_eq!(account.group_name, None);
+
+        // 密码即使是 base32 形态也不应被识别为 secret
+        let account = parse_one(
+            "DmDayouss559@gmail.com|gsygevwbm|DmDayouss55923433@merrce.site|wplvdtltztircw4k7jlafjkp6jfj6h5o",
+        );
+        assert_eq!(account.password, "gsygevwbm");
+        assert_eq!(
+            account.secret.as_deref(),
+            Some("WPLVDTLTZTIRCW4K7JLAFJKP6JFJ6H5O")
+        );
+
+        let account = parse_one(
+            "jacinthee1jd666@gmail.com----9dj3xACGDER----rpyyjmhp3nnum@disbox.org----2021   -- 18024048401",
+        );
+        assert_eq!(account.reg_year.as_deref(), Some("2021"));
+        assert_eq!(account.phone.as_deref(), Some("+8618024048401"));
+    }
+
+    #[test]
+    fn parse_special_lines() {
+        let account = parse_one(
+            "卡号：GhshdbdKaocher68@gmail.com密码：Zhh10@666888xb22 ----GhshdbdKaocher6864647@raink.site ----razpoziyf6w5m4kpgfubrsbxuiaa3spv",
+        );
+        assert_eq!(account.email, "GhshdbdKaocher68@gmail.com");
+        assert_eq!(account.password, "Zhh10@666888xb22");
+        assert_eq!(
+            account.recovery.as_deref(),
+            Some("GhshdbdKaocher6864647@raink.site")
+        );
+        assert_eq!(
+            account.secret.as_deref(),
+            Some("RAZPOZIYF6W5M4KPGFUBRSBXUIAA3SPV")
+        );
+
+        let account = parse_one("卡号：test@gmail.com密码：pass123----JBSWY3DPEHPK3PXP");
+        assert_eq!(account.password, "pass123");
+        assert_eq!(account.secret.as_deref(), Some("JBSWY3DPEHPK3PXP"));
+    }
+
+    #[test]
+    fn invalid_lines_produce_warnings_and_formats() {
+        let text = "# 注释\na@gmail.com----pass1----recovery@gmail.com\nnotanemail----password\n\nb@gmail.com|pass2\nonly-email@gmail.com";
+        let result = parse_import_text(text);
+        assert_eq!(result.accounts.len(), 2);
+        assert_eq!(
+            result.warnings,
+            vec![
+                ImportLineWarning {
+                    line: 3,
+                    content: "notanemail----password".to_string(),
+                    message: "未识别到邮箱".to_string(),
+                },
+                ImportLineWarning {
+                    line: 6,
+                    content: "only-email@gmail.com".to_string(),
+                    message: "邮箱后缺少密码".to_string(),
+                },
+            ]
+        );
+        assert_eq!(
+            result.detected_formats,
+            vec![
+                "使用了 2 种不同的分隔符: ----, |".to_string(),
+                "部分行有恢复邮箱 (1行)，部分行没有 (1行)".to_string(),
+                "有 2 行未导入（缺少邮箱/密码或格式无法识别）".to_string(),
+            ]
+        );
+
+        let empty = parse_import_text("  \n");
+        assert!(empty.accounts.is_empty() && empty.detected_formats.is_empty());
+    }
+
+    #[test]
+    fn real_data_regression() {
+        let result = parse_import_text(REAL_DATA);
+        let find = |email: &'static str| {
+            result
+                .accounts
+                .iter()
+                .filter(move |a| a.email.eq_ignore_ascii_case(email))
+        };
+
+        assert!(result.accounts.len() > 80);
+        assert!(result
+            .accounts
+            .iter()
+            .all(|a| !a.email.is_empty() && !a.password.is_empty()));
+
+        let row = find("DmDayouss559@gmail.com").next().unwrap();
+        assert_eq!(row.password, "gsygevwbm");
+        assert_eq!(
+            row.recovery.as_deref(),
+            Some("DmDayouss55923433@merrce.site")
+        );
+        assert_eq!(
+            row.secret.as_deref(),
+            Some("WPLVDTLTZTIRCW4K7JLAFJKP6JFJ6H5O")
+        );
+
+        let row = find("GhshdbdKaocher68@gmail.com").next().unwrap();
+        assert_eq!(row.password, "Zhh10@666888xb22");
+        assert_eq!(
+            row.secret.as_deref(),
+            Some("RAZPOZIYF6W5M4KPGFUBRSBXUIAA3SPV")
+        );
+
+        assert!(find("jamalanty87@gmail.com").any(|a| a.phone.as_deref() == Some("+8618576762071")));
+        assert!(find("jacinthee1jd666@gmail.com").any(|a| a.reg_year.as_deref() == Some("2021")));
+        assert!(
+            find("jacinthee1jd666@gmail.com").any(|a| a.phone.as_deref() == Some("+8618024048401"))
+        );
+
+        assert!(find("belocarla219@gmail.com").next().is_none());
+        assert!(find("940117344@qq.com").next().is_none());
+    }
+}
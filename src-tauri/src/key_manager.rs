@@ -1,12 +1,92 @@
 use std::fs::{self, OpenOptions};
 use std::io::{Read, Write};
 use std::path::PathBuf;
-use std::sync::OnceLock;
+use std::sync::Mutex;
 
 use base64::{engine::general_purpose, Engine as _};
 use rand::RngCore;
 
-static MASTER_KEY_CACHE: OnceLock<Result<[u8; 32], String>> = OnceLock::new();
+/// 锁定状态下访问主密钥时返回的错误
+pub const VAULT_LOCKED_MESSAGE: &str = "保险库已锁定，请输入密码解锁";
+
+static MASTER_KEY_VAULT: KeyVault = KeyVault::new();
+
+/// 主密钥缓存：解锁时按需加载并缓存，锁定时清零并丢弃，直到重新解锁
+struct KeyVault {
+    state: Mutex<VaultState>,
+}
+
+/// 保险库的锁定原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LockReason {
+    /// 所有会话空闲超时后自动锁定，任意用户重新登录即可恢复
+    Idle,
+    /// 用户主动锁定，只能由操作员及以上角色输入密码解锁
+    Explicit,
+}
+
+struct VaultState {
+    key: Option<[u8; 32]>,
+    locked: Option<LockReason>,
+}
+
+impl KeyVault {
+    const fn new() -> Self {
+        Self {
+            state: Mutex::new(VaultState {
+                key: None,
+                locked: None,
+            }),
+        }
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, VaultState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// 加载失败时不缓存错误，下次访问会重新尝试
+    fn get(&self, load: impl FnOnce() -> Result<[u8; 32], String>) -> Result<[u8; 32], String> {
+        let mut state = self.state();
+        if state.locked.is_some() {
+            return Err(VAULT_LOCKED_MESSAGE.to_string());
+        }
+        if let Some(key) = state.key {
+            return Ok(key);
+        }
+        let key = load()?;
+        state.key = Some(key);
+        Ok(key)
+    }
+
+    /// 已主动锁定时不会被空闲锁定覆盖为可自动恢复的状态
+    fn lock(&self, reason: LockReason) {
+        let mut state = self.state();
+        if let Some(key) = state.key.as_mut() {
+            key.fill(0);
+        }
+        state.key = None;
+        if state.locked != Some(LockReason::Explicit) {
+            state.locked = Some(reason);
+        }
+    }
+
+    fn unlock(&self) {
+        self.state().locked = None;
+    }
+
+    /// 仅解除空闲自动锁定，返回解除后是否仍处于锁定状态
+    fn resume_idle(&self) -> bool {
+        let mut state = self.state();
+        if state.locked == Some(LockReason::Idle) {
+            state.locked = None;
+        }
+        state.locked.is_some()
+    }
+
+    fn lock_reason(&self) -> Option<LockReason> {
+        self.state().locked
+    }
+}
 
 fn key_file_path() -> PathBuf {
     let mut path = dirs::data_dir().unwrap_or_else(|| PathBuf::from("."));
@@ -77,14 +157,91 @@ fn read_or_create_key_file() -> Result<[u8; 32], String> {
     Ok(key)
 }
 
+fn load_master_key() -> Result<[u8; 32], String> {
+    if let Ok(env_key) = std::env::var("GOOGLE_MANAGER_MASTER_KEY") {
+        parse_env_key(&env_key)
+    } else {
+        read_or_create_key_file()
+    }
+}
+
 pub fn get_master_key() -> Result<[u8; 32], String> {
-    MASTER_KEY_CACHE
-        .get_or_init(|| {
-            if let Ok(env_key) = std::env::var("GOOGLE_MANAGER_MASTER_KEY") {
-                parse_env_key(&env_key)
-            } else {
-                read_or_create_key_file()
-            }
-        })
-        .clone()
+    MASTER_KEY_VAULT.get(load_master_key)
+}
+
+/// 锁定保险库：清除内存中的主密钥，解锁前所有需要解密的操作都会失败
+pub fn lock_master_key(reason: LockReason) {
+    MASTER_KEY_VAULT.lock(reason);
+}
+
+/// 解锁保险库（调用方负责先校验密码与角色），主密钥在下次访问时重新加载
+pub fn unlock_master_key() {
+    MASTER_KEY_VAULT.unlock();
+}
+
+/// 登录成功时解除空闲自动锁定；主动锁定保持不变，返回是否仍处于锁定状态
+pub fn resume_idle_lock() -> bool {
+    MASTER_KEY_VAULT.resume_idle()
+}
+
+pub fn lock_reason() -> Option<LockReason> {
+    MASTER_KEY_VAULT.lock_reason()
+}
+
+pub fn is_master_key_locked() -> bool {
+    lock_reason().is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn locked_vault_drops_key_until_unlocked() {
+        let vault = KeyVault::new();
+        let mut loads = 0;
+        assert_eq!(
+            vault.get(|| {
+                loads += 1;
+                Ok([7u8; 32])
+            }),
+            Ok([7u8; 32])
+        );
+        assert_eq!(vault.get(|| Err("不应重新加载".to_string())), Ok([7u8; 32]));
+
+        vault.lock(LockReason::Explicit);
+        assert_eq!(vault.lock_reason(), Some(LockReason::Explicit));
+        assert!(vault.state().key.is_none());
+        assert_eq!(
+            vault.get(|| Ok([7u8; 32])).unwrap_err(),
+            VAULT_LOCKED_MESSAGE
+        );
+
+        vault.unlock();
+        assert_eq!(
+            vault.get(|| {
+                loads += 1;
+                Ok([7u8; 32])
+            }),
+            Ok([7u8; 32])
+        );
+        assert_eq!(loads, 2);
+    }
+
+    #[test]
+    fn login_resumes_idle_lock_but_not_explicit_lock() {
+        let vault = KeyVault::new();
+        vault.lock(LockReason::Idle);
+        assert!(!vault.resume_idle());
+        assert_eq!(vault.lock_reason(), None);
+
+        vault.lock(LockReason::Explicit);
+        // 之后的空闲锁定不会把主动锁定降级为可自动恢复
+        vault.lock(LockReason::Idle);
+        assert!(vault.resume_idle());
+        assert_eq!(vault.lock_reason(), Some(LockReason::Explicit));
+
+        vault.unlock();
+        assert_eq!(vault.lock_reason(), None);
+    }
 }
@@ -1,11 +1,19 @@
 mod auth;
+mod authenticator_backup;
 #[cfg(feature = "desktop")]
 mod commands;
 mod crypto;
 mod database;
+mod export_file;
+mod export_template;
 #[cfg(feature = "test-server")]
 mod http_server;
+mod import_formats;
+mod import_parser;
 mod key_manager;
+mod migration;
+mod password_import;
+mod qr;
 mod totp;
 
 pub use database::{init_database, Database};
@@ -15,6 +23,23 @@ pub use http_server::start_http_server;
 #[cfg(feature = "test-server")]
 use std::sync::Arc;
 use std::sync::Mutex;
+#[cfg(any(feature = "desktop", feature = "test-server"))]
+use std::time::Duration;
+
+/// 空闲锁定检查间隔
+#[cfg(any(feature = "desktop", feature = "test-server"))]
+const IDLE_LOCK_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// 后台定期清理空闲超时的会话，所有会话都空闲超时后自动锁定保险库
+#[cfg(any(feature = "desktop", feature = "test-server"))]
+fn spawn_idle_lock_watcher(check: impl Fn() -> Result<(), String> + Send + 'static) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(IDLE_LOCK_CHECK_INTERVAL);
+        if let Err(e) = check() {
+            log::warn!("空闲锁定检查失败: {}", e);
+        }
+    });
+}
 
 /// 启动 HTTP 测试服务器（不启动 Tauri GUI）
 #[cfg(feature = "test-server")]
@@ -23,10 +48,16 @@ pub async fn run_http_server(port: u16) {
     println!("Starting HTTP test server on http://localhost:{}", port);
 
     let conn = init_database().expect("Failed to initialize database");
+    auth::bootstrap_admin_credential(&conn).expect("Failed to initialize admin credential");
     if let Err(e) = database::create_backup(&conn, Some("startup_http")) {
         log::warn!("HTTP 模式启动自动备份失败: {}", e);
     }
     let db = Arc::new(Database(Mutex::new(conn)));
+    let watcher_db = db.clone();
+    spawn_idle_lock_watcher(move || {
+        let conn = watcher_db.0.lock().map_err(|e| e.to_string())?;
+        auth::enforce_idle_lock(&conn, &auth::AuthPolicy::from_env())
+    });
 
     start_http_server(db, port)
         .await
@@ -37,6 +68,7 @@ pub async fn run_http_server(port: u16) {
 #[cfg_attr(all(feature = "desktop", mobile), tauri::mobile_entry_point)]
 pub fn run() {
     let conn = init_database().expect("Failed to initialize database");
+    auth::bootstrap_admin_credential(&conn).expect("Failed to initialize admin credential");
     if let Err(e) = database::create_backup(&conn, Some("startup")) {
         log::warn!("启动自动备份失败: {}", e);
     }
@@ -54,10 +86,19 @@ pub fn run() {
                         .build(),
                 )?;
             }
+            let handle = app.handle().clone();
+            spawn_idle_lock_watcher(move || {
+                use tauri::Manager;
+                let db = handle.state::<Database>();
+                let conn = db.0.lock().map_err(|e| e.to_string())?;
+                auth::enforce_idle_lock(&conn, &auth::AuthPolicy::from_env())
+            });
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             commands::get_accounts,
+            commands::get_account_groups,
+            commands::get_account_ids,
             commands::create_account,
             commands::update_account,
             commands::delete_account,
@@ -73,13 +114,45 @@ pub fn run() {
             commands::toggle_sold_status,
             commands::get_account_history,
             commands::get_account_by_id,
+            commands::reveal_account_secret,
+            commands::get_secret_reveals,
             commands::check_auth,
             commands::login,
             commands::logout,
+            commands::lock,
+            commands::unlock,
+            commands::setup_admin_password,
+            commands::change_admin_password,
+            commands::list_sessions,
+            commands::revoke_session,
+            commands::revoke_all_sessions,
+            commands::list_users,
+            commands::create_user,
+            commands::update_user,
+            commands::delete_user,
             commands::generate_totp,
+            commands::generate_totp_for_account,
+            commands::generate_totp_for_accounts,
+            commands::verify_totp,
+            commands::import_migration_payload,
+            commands::export_migration_payload,
+            commands::import_authenticator_backup,
+            commands::get_account_qr_code,
+            commands::decode_qr_secret,
+            commands::attach_qr_secret,
+            commands::parse_import_text,
             commands::batch_import,
+            commands::batch_import_csv,
+            commands::batch_import_json,
+            commands::import_password_manager,
             commands::export_database_sql,
             commands::export_accounts_text,
+            commands::export_accounts_to_file,
+            commands::export_database_sql_to_file,
+            commands::list_export_presets,
+            commands::create_export_preset,
+            commands::update_export_preset,
+            commands::delete_export_preset,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,483 @@
+use crate::database::{self, AuditActor};
+use crate::totp::{self, TotpParams, DEFAULT_PERIOD, OTPAUTH_ISSUER};
+use base64::{
+    engine::general_purpose::{STANDARD, STANDARD_NO_PAD},
+    Engine as _,
+};
+use rand::Rng;
+use rusqlite::Connection;
+use serde::Serialize;
+use totp_rs::Secret;
+
+const MIGRATION_PREFIX: &str = "otpauth-migration://offline?";
+/// 每个迁移码包含的最大条目数（与 Google Authenticator 导出时的分批方式保持一致）
+const MIGRATION_BATCH_SIZE: usize = 10;
+const MIGRATION_VERSION: u64 = 1;
+
+/// 迁移码中的单个条目（`MigrationPayload.OtpParameters`）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MigrationEntry {
+    /// 原始标签（可能带 `issuer:` 前缀）
+    pub name: String,
+    pub issuer: String,
+    /// 从标签中提取的账号名（通常是邮箱）
+    pub email: String,
+    /// Base32 编码的密钥
+    pub secret: String,
+    /// `totp` / `hotp`
+    pub otp_type: String,
+    pub params: TotpParams,
+}
+
+/// 迁移条目与本地账号的匹配结果
+#[derive(Debug, Clone, Serialize)]
+pub struct MigrationMatch {
+    pub entry: MigrationEntry,
+    pub account_id: Option<i64>,
+    /// `email` / `label`，未匹配时为空
+    pub matched_by: Option<String>,
+    pub applied: bool,
+    pub error: Option<String>,
+}
+
+/// 导出迁移码时被跳过的账号
+#[derive(Debug, Clone, Serialize)]
+pub struct MigrationSkipped {
+    pub account_id: i64,
+    pub reason: String,
+}
+
+/// 迁移码导出结果（条目较多时拆分为多个迁移码）
+#[derive(Debug, Clone, Serialize)]
+pub struct MigrationExport {
+    pub uris: Vec<String>,
+    pub exported_ids: Vec<i64>,
+    pub skipped: Vec<MigrationSkipped>,
+}
+
+enum FieldValue<'a> {
+    Varint(u64),
+    Bytes(&'a [u8]),
+}
+
+fn read_varint(buf: &[u8], pos: &mut usize) -> Result<u64, String> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let byte = *buf.get(*pos).ok_or("迁移数据被截断")?;
+        *pos += 1;
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err("迁移数据中的整数过长".to_string())
+}
+
+/// 读取一个 protobuf 字段，未知的定长字段直接跳过
+fn read_field<'a>(buf: &'a [u8], pos: &mut usize) -> Result<(u64, Option<FieldValue<'a>>), String> {
+    let key = read_varint(buf, pos)?;
+    let field = key >> 3;
+    let value = match key & 0x07 {
+        0 => Some(FieldValue::Varint(read_varint(buf, pos)?)),
+        2 => {
+            let len = read_varint(buf, pos)? as usize;
+            let end = pos
+                .checked_add(len)
+                .filter(|end| *end <= buf.len())
+                .ok_or("迁移数据被截断")?;
+            let bytes = &buf[*pos..end];
+            *pos = end;
+            Some(FieldValue::Bytes(bytes))
+        }
+        1 => {
+            *pos += 8;
+            None
+        }
+        5 => {
+            *pos += 4;
+            None
+        }
+        wire_type => return Err(format!("迁移数据包含不支持的字段类型: {}", wire_type)),
+    };
+    if *pos > buf.len() {
+        return Err("迁移数据被截断".to_string());
+    }
+    Ok((field, value))
+}
+
+fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn write_varint_field(out: &mut Vec<u8>, field: u64, value: u64) {
+    write_varint(out, field << 3);
+    write_varint(out, value);
+}
+
+fn write_bytes_field(out: &mut Vec<u8>, field: u64, bytes: &[u8]) {
+    write_varint(out, (field << 3) | 2);
+    write_varint(out, bytes.len() as u64);
+    out.extend_from_slice(bytes);
+}
+
+fn bytes_to_string(bytes: &[u8]) -> Result<String, String> {
+    String::from_utf8(bytes.to_vec()).map_err(|e| format!("迁移数据中的文本不是 UTF-8: {}", e))
+}
+
+fn decode_otp_parameters(buf: &[u8]) -> Result<MigrationEntry, String> {
+    let mut pos = 0;
+    let mut secret = Vec::new();
+    let mut name = String::new();
+    let mut issuer = String::new();
+    let mut algorithm = 0;
+    let mut digits = 0;
+    let mut otp_type = 0;
+    while pos < buf.len() {
+        match read_field(buf, &mut pos)? {
+            (1, Some(FieldValue::Bytes(bytes))) => secret = bytes.to_vec(),
+            (2, Some(FieldValue::Bytes(bytes))) => name = bytes_to_string(bytes)?,
+            (3, Some(FieldValue::Bytes(bytes))) => issuer = bytes_to_string(bytes)?,
+            (4, Some(FieldValue::Varint(value))) => algorithm = value,
+            (5, Some(FieldValue::Varint(value))) => digits = value,
+            (6, Some(FieldValue::Varint(value))) => otp_type = value,
+            _ => {}
+        }
+    }
+    if secret.is_empty() {
+        return Err(format!("迁移条目 {} 缺少密钥", name));
+    }
+
+    let algorithm = match algorithm {
+        0 | 1 => "SHA1",
+        2 => "SHA256",
+        3 => "SHA512",
+        4 => "MD5",
+        _ => "UNKNOWN",
+    };
+    let digits = match digits {
+        2 => 8,
+        _ => 6,
+    };
+    let otp_type = match otp_type {
+        1 => "hotp",
+        _ => "totp",
+    };
+    let email = label_account(&name);
+    let secret = match Secret::Raw(secret).to_encoded() {
+        Secret::Encoded(encoded) => encoded,
+        Secret::Raw(_) => unreachable!("to_encoded 总是返回 Encoded"),
+    };
+
+    Ok(MigrationEntry {
+        name,
+        issuer,
+        email,
+        secret,
+        otp_type: otp_type.to_string(),
+        params: TotpParams {
+            algorithm: algorithm.to_string(),
+            digits,
+            period: DEFAULT_PERIOD,
+        },
+    })
+}
+
+/// 解析 `otpauth-migration://offline?data=...` 迁移码
+pub fn decode_migration_uri(uri: &str) -> Result<Vec<MigrationEntry>, String> {
+    let trimmed = uri.trim();
+    let query = trimmed
+        .get(..MIGRATION_PREFIX.len())
+        .filter(|prefix| prefix.eq_ignore_ascii_case(MIGRATION_PREFIX))
+        .map(|_| &trimmed[MIGRATION_PREFIX.len()..])
+        .ok_or_else(|| "不是 otpauth-migration://offline 迁移码".to_string())?;
+    let data = query
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("data="))
+        .ok_or_else(|| "迁移码缺少 data 参数".to_string())?;
+    // 部分扫码工具会把未编码的 `+` 还原成空格
+    let data = totp::percent_decode(data, false)?.replace(' ', "+");
+    let payload = STANDARD_NO_PAD
+        .decode(data.trim_end_matches('='))
+        .map_err(|e| format!("迁移码 Base64 解码失败: {}", e))?;
+
+    let mut pos = 0;
+    let mut entries = Vec::new();
+    while pos < payload.len() {
+        if let (1, Some(FieldValue::Bytes(bytes))) = read_field(&payload, &mut pos)? {
+            entries.push(decode_otp_parameters(bytes)?);
+        }
+    }
+    if entries.is_empty() {
+        return Err("迁移码中没有任何条目".to_string());
+    }
+    Ok(entries)
+}
+
+/// 从 `issuer:account` 形式的标签中取出账号名
+pub fn label_account(label: &str) -> String {
+    match label.split_once(':') {
+        Some((_, account)) => account.trim().to_string(),
+        None => label.trim().to_string(),
+    }
+}
+
+/// 按邮箱（或完整标签）把迁移条目匹配到本地活跃账号
+pub fn match_migration_entries(
+    conn: &Connection,
+    entries: Vec<MigrationEntry>,
+) -> Result<Vec<MigrationMatch>, String> {
+    let index = database::query_active_email_index(conn)?;
+    Ok(entries
+        .into_iter()
+        .map(|entry| {
+            let by_email = index
+                .get(&entry.email.to_lowercase())
+                .map(|id| (*id, "email"));
+            let by_label = || {
+                index
+                    .get(&entry.name.trim().to_lowercase())
+                    .map(|id| (*id, "label"))
+            };
+            let matched = by_email.or_else(by_label);
+            MigrationMatch {
+                account_id: matched.map(|(id, _)| id),
+                matched_by: matched.map(|(_, by)| by.to_string()),
+                applied: false,
+                error: None,
+                entry,
+            }
+        })
+        .collect())
+}
+
+/// 解析迁移码并匹配账号；`apply` 为 true 时把密钥与参数写入匹配到的账号
+pub fn import_migration_uri(
+    conn: &Connection,
+    uri: &str,
+    apply: bool,
+    actor: AuditActor,
+) -> Result<Vec<MigrationMatch>, String> {
+    let mut matches = match_migration_entries(conn, decode_migration_uri(uri)?)?;
+    if apply {
+        apply_migration_matches(conn, &mut matches, actor);
+    }
+    Ok(matches)
+}
+
+/// 把匹配到账号的条目密钥与参数写入数据库，逐条记录写入结果
+pub fn apply_migration_matches(
+    conn: &Connection,
+    matches: &mut [MigrationMatch],
+    actor: AuditActor,
+) {
+    for item in matches.iter_mut() {
+        let Some(account_id) = item.account_id else {
+            continue;
+        };
+        if item.entry.otp_type != "totp" {
+            item.error = Some("仅支持导入 TOTP 类型的条目".to_string());
+            continue;
+        }
+        match database::update_account_secret(
+            conn,
+            account_id,
+            &item.entry.secret,
+            &item.entry.params,
+            actor,
+        ) {
+            Ok(_) => item.applied = true,
+            Err(e) => {
+                log::warn!("迁移条目写入失败 (account_id={}): {}", account_id, e);
+                item.error = Some(e);
+            }
+        }
+    }
+}
+
+fn encode_otp_parameters(
+    email: &str,
+    secret: &str,
+    params: &TotpParams,
+) -> Result<Vec<u8>, String> {
+    let secret = Secret::Encoded(secret.replace(' ', "").to_uppercase())
+        .to_bytes()
+        .map_err(|_| "2FA 密钥不是合法的 Base32".to_string())?;
+    let algorithm = match params.algorithm.as_str() {
+        "SHA256" => 2,
+        "SHA512" => 3,
+        _ => 1,
+    };
+    let digits = match params.digits {
+        6 => 1,
+        8 => 2,
+        other => {
+            return Err(format!(
+                "Google Authenticator 仅支持 6 或 8 位验证码，当前为 {}",
+                other
+            ))
+        }
+    };
+    if params.period != DEFAULT_PERIOD {
+        return Err(format!(
+            "Google Authenticator 迁移码仅支持 {} 秒周期，当前为 {}",
+            DEFAULT_PERIOD, params.period
+        ));
+    }
+
+    let mut out = Vec::new();
+    write_bytes_field(&mut out, 1, &secret);
+    write_bytes_field(&mut out, 2, email.as_bytes());
+    write_bytes_field(&mut out, 3, OTPAUTH_ISSUER.as_bytes());
+    write_varint_field(&mut out, 4, algorithm);
+    write_varint_field(&mut out, 5, digits);
+    write_varint_field(&mut out, 6, 2);
+    Ok(out)
+}
+
+fn encode_migration_batch(
+    entries: &[Vec<u8>],
+    batch_index: usize,
+    batch_size: usize,
+    batch_id: i32,
+) -> String {
+    let mut payload = Vec::new();
+    for entry in entries {
+        write_bytes_field(&mut payload, 1, entry);
+    }
+    write_varint_field(&mut payload, 2, MIGRATION_VERSION);
+    write_varint_field(&mut payload, 3, batch_size as u64);
+    write_varint_field(&mut payload, 4, batch_index as u64);
+    write_varint_field(&mut payload, 5, batch_id as u32 as u64);
+    format!(
+        "otpauth-migration://offline?data={}",
+        totp::percent_encode(&STANDARD.encode(payload))
+    )
+}
+
+/// 为选中账号生成迁移码（解密密钥并记录查看，每个迁移码最多包含 10 个条目）
+pub fn export_migration_uris(
+    conn: &Connection,
+    ids: &[i64],
+    actor: AuditActor,
+) -> Result<MigrationExport, String> {
+    let emails: std::collections::HashMap<i64, String> =
+        database::query_accounts_by_ids(conn, ids)?
+            .into_iter()
+            .map(|account| (account.id, account.email))
+            .collect();
+    let mut encoded = Vec::new();
+    let mut exported_ids = Vec::new();
+    let mut skipped = Vec::new();
+    for (account_id, secret) in database::reveal_account_secrets(conn, ids, "migration", actor)? {
+        let result = secret.and_then(|secret| {
+            let secret = secret.ok_or_else(|| "该账号未设置 2FA 密钥".to_string())?;
+            let email = emails
+                .get(&account_id)
+                .ok_or_else(|| "账号不存在或已删除".to_string())?;
+            encode_otp_parameters(email, &secret.secret, &secret.params)
+        });
+        match result {
+            Ok(entry) => {
+                encoded.push(entry);
+                exported_ids.push(account_id);
+            }
+            Err(reason) => skipped.push(MigrationSkipped { account_id, reason }),
+        }
+    }
+
+    let batch_id: i32 = rand::thread_rng().gen();
+    let batch_size = encoded.len().div_ceil(MIGRATION_BATCH_SIZE);
+    let uris = encoded
+        .chunks(MIGRATION_BATCH_SIZE)
+        .enumerate()
+        .map(|(index, chunk)| encode_migration_batch(chunk, index, batch_size, batch_id))
+        .collect();
+    Ok(MigrationExport {
+        uris,
+        exported_ids,
+        skipped,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECRET: &str = "JBSWY3DPEHPK3PXPJBSWY3DPEHPK3PXP";
+
+    #[test]
+    fn migration_payload_round_trips() {
+        let params = TotpParams {
+            algorithm: "SHA256".to_string(),
+            digits: 8,
+            period: DEFAULT_PERIOD,
+        };
+        let entries = vec![
+            encode_otp_parameters("alice@gmail.com", SECRET, &params).unwrap(),
+            encode_otp_parameters("bob@gmail.com", SECRET, &TotpParams::default()).unwrap(),
+        ];
+        let uri = encode_migration_batch(&entries, 0, 1, -7);
+        assert!(uri.starts_with("otpauth-migration://offline?data="));
+
+        let decoded = decode_migration_uri(&uri).unwrap();
+        assert_eq!(decoded.len(), 2);
+        assert_eq!(decoded[0].email, "alice@gmail.com");
+        assert_eq!(decoded[0].issuer, "Google");
+        assert_eq!(decoded[0].secret, SECRET);
+        assert_eq!(decoded[0].otp_type, "totp");
+        assert_eq!(decoded[0].params, params);
+        assert_eq!(decoded[1].params, TotpParams::default());
+    }
+
+    #[test]
+    fn decode_reads_labels_and_unsupported_types() {
+        let mut entry = Vec::new();
+        write_bytes_field(&mut entry, 1, b"hello-world-1234");
+        write_bytes_field(&mut entry, 2, "Google:carol@gmail.com".as_bytes());
+        write_varint_field(&mut entry, 4, 4);
+        write_varint_field(&mut entry, 6, 1);
+        write_varint_field(&mut entry, 7, 3);
+        let mut payload = Vec::new();
+        write_bytes_field(&mut payload, 1, &entry);
+        let uri = format!(
+            "otpauth-migration://offline?data={}",
+            STANDARD_NO_PAD.encode(payload)
+        );
+
+        let decoded = decode_migration_uri(&uri).unwrap();
+        assert_eq!(decoded[0].name, "Google:carol@gmail.com");
+        assert_eq!(decoded[0].email, "carol@gmail.com");
+        assert_eq!(decoded[0].otp_type, "hotp");
+        assert_eq!(decoded[0].params.algorithm, "MD5");
+        assert_eq!(decoded[0].params.digits, 6);
+    }
+
+    #[test]
+    fn decode_rejects_invalid_payloads() {
+        assert!(decode_migration_uri("otpauth://totp/x?secret=JBSWY3DP").is_err());
+        assert!(decode_migration_uri("otpauth-migration://offline?foo=bar").is_err());
+        assert!(decode_migration_uri("otpauth-migration://offline?data=%%%").is_err());
+        assert!(decode_migration_uri("otpauth-migration://offline?data=CgQ").is_err());
+    }
+
+    #[test]
+    fn encode_rejects_params_unsupported_by_authenticator() {
+        let seven_digits = TotpParams {
+            digits: 7,
+            ..TotpParams::default()
+        };
+        let long_period = TotpParams {
+            period: 60,
+            ..TotpParams::default()
+        };
+        assert!(encode_otp_parameters("a@gmail.com", SECRET, &seven_digits).is_err());
+        assert!(encode_otp_parameters("a@gmail.com", SECRET, &long_period).is_err());
+        assert!(
+            encode_otp_parameters("a@gmail.com", "not base32!", &TotpParams::default()).is_err()
+        );
+    }
+}
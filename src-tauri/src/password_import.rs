@@ -0,0 +1,440 @@
+//! 密码管理器导出导入：Bitwarden JSON、KeePass / KeePassXC CSV、Chrome 密码 CSV
+//!
+//! 只保留 Google 域名的登录项，TOTP 写入 `secret`、备注写入 `remark`，最终统一走 `batch_import`。
+
+use crate::database::{self, AccountInput, AuditActor, BatchImportReport, ImportOptions};
+use crate::import_formats::{empty_account_input, ImportField};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+
+/// 视为 Google 登录的域名（含子域名，如 accounts.google.com）
+const GOOGLE_DOMAINS: &[&str] = &["google.com", "gmail.com", "googlemail.com"];
+/// 用户名不含 `@` 时补全的邮箱域名
+const DEFAULT_EMAIL_DOMAIN: &str = "gmail.com";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PasswordManagerSource {
+    /// Bitwarden 未加密 JSON 导出
+    Bitwarden,
+    /// KeePass 2 / KeePassXC CSV 导出
+    Keepass,
+    /// Chrome / Chromium 密码 CSV 导出
+    Chrome,
+}
+
+impl PasswordManagerSource {
+    fn display_name(self) -> &'static str {
+        match self {
+            Self::Bitwarden => "Bitwarden",
+            Self::Keepass => "KeePass",
+            Self::Chrome => "Chrome",
+        }
+    }
+}
+
+/// 密码管理器导入结果（展开 `BatchImportReport` 的逐行结果）
+#[derive(Debug, Serialize)]
+pub struct PasswordImportReport {
+    pub source: PasswordManagerSource,
+    /// 导出文件中的登录项总数
+    pub total_entries: usize,
+    /// 非 Google 域名、被忽略的登录项数
+    pub ignored_count: usize,
+    #[serde(flatten)]
+    pub import: BatchImportReport,
+}
+
+/// 各来源统一后的登录项
+#[derive(Debug, Default)]
+struct LoginEntry {
+    username: String,
+    password: String,
+    urls: Vec<String>,
+    totp: Option<String>,
+    notes: Option<String>,
+    group: Option<String>,
+    /// 自定义字段（名称, 值），按表头别名匹配恢复邮箱 / 手机号等
+    extra_fields: Vec<(String, String)>,
+}
+
+/// 从 URL 中取出小写主机名（允许省略协议）
+fn url_host(url: &str) -> Option<String> {
+    let rest = url.trim();
+    let rest = rest.split_once("://").map_or(rest, |(_, rest)| rest);
+    let authority = rest.split(['/', '?', '#']).next()?;
+    let host = authority.rsplit('@').next()?;
+    let host = host.split(':').next()?.trim_end_matches('.');
+    (!host.is_empty()).then(|| host.to_ascii_lowercase())
+}
+
+fn is_google_domain(host: &str) -> bool {
+    GOOGLE_DOMAINS.iter().any(|domain| {
+        host == *domain
+            || host
+                .strip_suffix(domain)
+                .is_some_and(|prefix| prefix.ends_with('.'))
+    })
+}
+
+impl LoginEntry {
+    /// 有网址时按网址判断；没有网址时按用户名的邮箱域名判断
+    fn is_google_login(&self) -> bool {
+        if self.urls.iter().any(|url| !url.trim().is_empty()) {
+            return self
+                .urls
+                .iter()
+                .filter_map(|url| url_host(url))
+                .any(|host| is_google_domain(&host));
+        }
+        self.username
+            .rsplit_once('@')
+            .is_some_and(|(_, domain)| is_google_domain(&domain.trim().to_ascii_lowercase()))
+    }
+
+    fn into_account_input(self) -> AccountInput {
+        let mut account = empty_account_input();
+        let username = self.username.trim();
+        account.email = if username.is_empty() || username.contains('@') {
+            username.to_string()
+        } else {
+            format!("{}@{}", username, DEFAULT_EMAIL_DOMAIN)
+        };
+        account.password = self.password;
+        // Steam 令牌不是标准 TOTP，直接忽略
+        if let Some(totp) = self
+            .totp
+            .filter(|totp| !totp.trim().starts_with("steam://"))
+        {
+            ImportField::Secret.apply(&mut account, &totp);
+        }
+        if let Some(notes) = self.notes {
+            ImportField::Remark.apply(&mut account, &notes);
+        }
+        if let Some(group) = self.group {
+            ImportField::GroupName.apply(&mut account, &group);
+        }
+        for (name, value) in &self.extra_fields {
+            match ImportField::from_header(name) {
+                Some(ImportField::Email | ImportField::Password) | None => {}
+                Some(ImportField::Secret) if account.secret.is_some() => {}
+                Some(field) => field.apply(&mut account, value),
+            }
+        }
+        account
+    }
+}
+
+#[derive(Deserialize)]
+struct BitwardenExport {
+    #[serde(default)]
+    encrypted: bool,
+    #[serde(default)]
+    folders: Option<Vec<BitwardenFolder>>,
+    #[serde(default)]
+    items: Option<Vec<BitwardenItem>>,
+}
+
+#[derive(Deserialize)]
+struct BitwardenFolder {
+    id: String,
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct BitwardenItem {
+    #[serde(rename = "type", default)]
+    item_type: i64,
+    #[serde(default)]
+    notes: Option<String>,
+    #[serde(rename = "folderId", default)]
+    folder_id: Option<String>,
+    #[serde(default)]
+    login: Option<BitwardenLogin>,
+    #[serde(default)]
+    fields: Option<Vec<BitwardenField>>,
+}
+
+#[derive(Deserialize)]
+struct BitwardenLogin {
+    #[serde(default)]
+    username: Option<String>,
+    #[serde(default)]
+    password: Option<String>,
+    #[serde(default)]
+    totp: Option<String>,
+    #[serde(default)]
+    uris: Option<Vec<BitwardenUri>>,
+}
+
+#[derive(Deserialize)]
+struct BitwardenUri {
+    #[serde(default)]
+    uri: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct BitwardenField {
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    value: Option<String>,
+}
+
+/// Bitwarden 登录项类型
+const BITWARDEN_LOGIN_TYPE: i64 = 1;
+
+fn parse_bitwarden(content: &str) -> Result<Vec<LoginEntry>, String> {
+    let export: BitwardenExport =
+        serde_json::from_str(content).map_err(|e| format!("Bitwarden JSON 解析失败: {}", e))?;
+    if export.encrypted {
+        return Err("暂不支持加密的 Bitwarden 导出，请导出为未加密的 JSON".to_string());
+    }
+
+    let folders = export.folders.unwrap_or_default();
+    let entries = export
+        .items
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|item| item.item_type == BITWARDEN_LOGIN_TYPE)
+        .filter_map(|item| {
+            let login = item.login?;
+            let group = item.folder_id.and_then(|id| {
+                folders
+                    .iter()
+                    .find(|folder| folder.id == id)
+                    .map(|folder| folder.name.clone())
+            });
+            Some(LoginEntry {
+                username: login.username.unwrap_or_default(),
+                password: login.password.unwrap_or_default(),
+                urls: login
+                    .uris
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter_map(|uri| uri.uri)
+                    .collect(),
+                totp: login.totp,
+                notes: item.notes,
+                group,
+                extra_fields: item
+                    .fields
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter_map(|field| Some((field.name?, field.value?)))
+                    .collect(),
+            })
+        })
+        .collect();
+    Ok(entries)
+}
+
+/// 按表头别名查找列（不区分大小写）
+fn find_column(headers: &csv::StringRecord, aliases: &[&str]) -> Option<usize> {
+    headers.iter().position(|header| {
+        let header = header.trim().trim_start_matches('\u{feff}');
+        aliases
+            .iter()
+            .any(|alias| header.eq_ignore_ascii_case(alias))
+    })
+}
+
+/// KeePass / KeePassXC / Chrome CSV（均带表头，列名不同）
+fn parse_password_csv(
+    content: &str,
+    source: PasswordManagerSource,
+) -> Result<Vec<LoginEntry>, String> {
+    let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .from_reader(content.as_bytes());
+    let headers = reader
+        .headers()
+        .map_err(|e| format!("{} CSV 表头解析失败: {}", source.display_name(), e))?
+        .clone();
+
+    let (Some(username_col), Some(password_col)) = (
+        find_column(&headers, &["username", "user name", "login name"]),
+        find_column(&headers, &["password"]),
+    ) else {
+        return Err(format!(
+            "不是有效的 {} CSV 导出：缺少用户名或密码列",
+            source.display_name()
+        ));
+    };
+    let url_col = find_column(&headers, &["url", "web site", "website", "origin"]);
+    let notes_col = find_column(&headers, &["notes", "note", "comments"]);
+    let totp_col = find_column(&headers, &["totp", "otp", "one-time password"]);
+    let group_col = find_column(&headers, &["group"]);
+
+    let mut entries = Vec::new();
+    for record in reader.records() {
+        let record = record.map_err(|e| {
+            let line = e.position().map(|p| p.line()).unwrap_or_default();
+            format!(
+                "{} CSV 第 {} 行解析失败: {}",
+                source.display_name(),
+                line,
+                e
+            )
+        })?;
+        let cell = |col: Option<usize>| {
+            col.and_then(|col| record.get(col))
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(str::to_string)
+        };
+        entries.push(LoginEntry {
+            username: cell(Some(username_col)).unwrap_or_default(),
+            password: record.get(password_col).unwrap_or_default().to_string(),
+            urls: cell(url_col).into_iter().collect(),
+            totp: cell(totp_col),
+            notes: cell(notes_col),
+            group: cell(group_col),
+            extra_fields: Vec::new(),
+        });
+    }
+    Ok(entries)
+}
+
+/// 解析导出文件，返回 (Google 登录项对应的导入行, 登录项总数)
+pub fn parse_password_export(
+    source: PasswordManagerSource,
+    content: &str,
+) -> Result<(Vec<AccountInput>, usize), String> {
+    let entries = match source {
+        PasswordManagerSource::Bitwarden => parse_bitwarden(content)?,
+        PasswordManagerSource::Keepass | PasswordManagerSource::Chrome => {
+            parse_password_csv(content, source)?
+        }
+    };
+    let total = entries.len();
+    let accounts = entries
+        .into_iter()
+        .filter(LoginEntry::is_google_login)
+        .map(LoginEntry::into_account_input)
+        .collect();
+    Ok((accounts, total))
+}
+
+/// 解析密码管理器导出并通过 `batch_import` 导入（secret 加密落库）
+pub fn import_password_export(
+    conn: &Connection,
+    source: PasswordManagerSource,
+    content: &str,
+    options: &ImportOptions,
+    actor: AuditActor,
+) -> Result<PasswordImportReport, String> {
+    let (accounts, total_entries) = parse_password_export(source, content)?;
+    let import = database::batch_import(conn, &accounts, options, actor)?;
+    Ok(PasswordImportReport {
+        source,
+        total_entries,
+        ignored_count: total_entries - accounts.len(),
+        import,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn google_domain_detection() {
+        assert_eq!(
+            url_host("https://user@Accounts.Google.com:443/signin?x=1"),
+            Some("accounts.google.com".to_string())
+        );
+        assert_eq!(
+            url_host("mail.google.com/mail"),
+            Some("mail.google.com".to_string())
+        );
+        assert!(is_google_domain("google.com"));
+        assert!(is_google_domain("accounts.google.com"));
+        assert!(!is_google_domain("notgoogle.com"));
+        assert!(!is_google_domain("google.com.evil.io"));
+
+        let mut entry = LoginEntry {
+            username: "someone@gmail.com".to_string(),
+            urls: vec!["https://facebook.com".to_string()],
+            ..LoginEntry::default()
+        };
+        assert!(!entry.is_google_login());
+        entry.urls.clear();
+        assert!(entry.is_google_login());
+    }
+
+    #[test]
+    fn parses_bitwarden_export() {
+        let content = r#"{
+            "encrypted": false,
+            "folders": [{"id": "f1", "name": "主号"}],
+            "items": [
+                {"type": 1, "name": "Google", "folderId": "f1", "notes": "备注",
+                 "login": {"username": "alice@gmail.com", "password": "pw1",
+                           "totp": "otpauth://totp/Google:alice?secret=JBSWY3DPEHPK3PXP",
+                           "uris": [{"match": null, "uri": "https://accounts.google.com/"}]},
+                 "fields": [{"name": "Recovery email", "value": "r@example.com", "type": 0}]},
+                {"type": 1, "name": "Bare", "login": {"username": "bob", "password": "pw2",
+                 "totp": "JBSW Y3DP EHPK 3PXP", "uris": [{"uri": "google.com"}]}},
+                {"type": 1, "name": "Other", "login": {"username": "carol@gmail.com",
+                 "password": "pw3", "uris": [{"uri": "https://github.com"}]}},
+                {"type": 2, "name": "Secure note", "notes": "x"}
+            ]
+        }"#;
+        let (accounts, total) =
+            parse_password_export(PasswordManagerSource::Bitwarden, content).unwrap();
+
+        assert_eq!(total, 3);
+        assert_eq!(accounts.len(), 2);
+        assert_eq!(accounts[0].email, "alice@gmail.com");
+        assert!(accounts[0]
+            .secret
+            .as_deref()
+            .unwrap()
+            .starts_with("otpauth://"));
+        assert_eq!(accounts[0].remark.as_deref(), Some("备注"));
+        assert_eq!(accounts[0].group_name.as_deref(), Some("主号"));
+        assert_eq!(accounts[0].recovery.as_deref(), Some("r@example.com"));
+        assert_eq!(accounts[1].email, "bob@gmail.com");
+        assert_eq!(accounts[1].secret.as_deref(), Some("JBSW Y3DP EHPK 3PXP"));
+
+        assert!(parse_password_export(
+            PasswordManagerSource::Bitwarden,
+            r#"{"encrypted": true, "data": "..."}"#
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn parses_keepass_and_chrome_csv() {
+        let keepassxc = "\u{feff}\"Group\",\"Title\",\"Username\",\"Password\",\"URL\",\"Notes\",\"TOTP\"\n\
+            \"Root/Google\",\"Gmail\",\"dave@gmail.com\",\"p,w\",\"https://accounts.google.com\",\"多行\n备注\",\"otpauth://totp/x?secret=JBSWY3DPEHPK3PXP\"\n\
+            \"Root\",\"Bank\",\"dave@gmail.com\",\"pw\",\"https://bank.example\",\"\",\"\"\n";
+        let (accounts, total) =
+            parse_password_export(PasswordManagerSource::Keepass, keepassxc).unwrap();
+        assert_eq!((accounts.len(), total), (1, 2));
+        assert_eq!(accounts[0].password, "p,w");
+        assert_eq!(accounts[0].remark.as_deref(), Some("多行\n备注"));
+        assert_eq!(accounts[0].group_name.as_deref(), Some("Root/Google"));
+        assert!(accounts[0].secret.is_some());
+
+        let keepass2 = "\"Account\",\"Login Name\",\"Password\",\"Web Site\",\"Comments\"\n\
+            \"Google\",\"erin@googlemail.com\",\"pw\",\"\",\"note\"\n";
+        let (accounts, _) =
+            parse_password_export(PasswordManagerSource::Keepass, keepass2).unwrap();
+        assert_eq!(accounts[0].email, "erin@googlemail.com");
+        assert_eq!(accounts[0].remark.as_deref(), Some("note"));
+
+        let chrome = "name,url,username,password,note\n\
+            accounts.google.com,https://accounts.google.com/signin,frank@gmail.com,pw,n1\n\
+            example.com,https://example.com/,frank@gmail.com,pw,\n";
+        let (accounts, total) =
+            parse_password_export(PasswordManagerSource::Chrome, chrome).unwrap();
+        assert_eq!((accounts.len(), total), (1, 2));
+        assert_eq!(accounts[0].remark.as_deref(), Some("n1"));
+        assert_eq!(accounts[0].secret, None);
+
+        assert!(parse_password_export(PasswordManagerSource::Chrome, "name,url\n").is_err());
+    }
+}
@@ -0,0 +1,220 @@
+use crate::database::{self, Account, AuditActor};
+use crate::totp::{self, OtpauthUri};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use image::{ImageFormat, Luma};
+use qrcode::{render::svg, QrCode};
+use rusqlite::Connection;
+use serde::Serialize;
+use std::io::Cursor;
+
+const QR_MIN_DIMENSION: u32 = 256;
+/// 待识别图片的大小上限（base64 解码后）
+const MAX_QR_IMAGE_BYTES: usize = 10 * 1024 * 1024;
+
+/// 二维码图片（`data_url` 可直接用作 `<img src>`）
+#[derive(Debug, Clone, Serialize)]
+pub struct QrCodeImage {
+    pub format: String,
+    pub mime_type: String,
+    pub data_url: String,
+}
+
+/// 将文本渲染为 PNG / SVG 二维码（`format` 为空时默认 PNG）
+pub fn render_qr_code(content: &str, format: Option<&str>) -> Result<QrCodeImage, String> {
+    let code = QrCode::new(content.as_bytes()).map_err(|e| format!("生成二维码失败: {}", e))?;
+    let format = format
+        .map(|value| value.trim().to_ascii_lowercase())
+        .filter(|value| !value.is_empty())
+        .unwrap_or_else(|| "png".to_string());
+
+    let (mime_type, bytes) = match format.as_str() {
+        "png" => {
+            let image = code
+                .render::<Luma<u8>>()
+                .min_dimensions(QR_MIN_DIMENSION, QR_MIN_DIMENSION)
+                .build();
+            let mut bytes = Vec::new();
+            image
+                .write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)
+                .map_err(|e| format!("PNG 编码失败: {}", e))?;
+            ("image/png", bytes)
+        }
+        "svg" => {
+            let markup = code
+                .render::<svg::Color>()
+                .min_dimensions(QR_MIN_DIMENSION, QR_MIN_DIMENSION)
+                .build();
+            ("image/svg+xml", markup.into_bytes())
+        }
+        other => return Err(format!("不支持的二维码格式: {}", other)),
+    };
+
+    Ok(QrCodeImage {
+        data_url: format!("data:{};base64,{}", mime_type, STANDARD.encode(bytes)),
+        mime_type: mime_type.to_string(),
+        format,
+    })
+}
+
+/// 渲染账号 otpauth URI 的二维码（解密 secret 并记录一次查看）
+pub fn render_account_qr_code(
+    conn: &Connection,
+    id: i64,
+    format: Option<&str>,
+    actor: AuditActor,
+) -> Result<QrCodeImage, String> {
+    let uri = database::reveal_account_otpauth_uri(conn, id, "qrcode", actor)?;
+    render_qr_code(&uri, format)
+}
+
+/// 从二维码图片中识别出的 2FA 密钥；`uri` 可直接作为 `batch_import` 行的 `secret`
+#[derive(Debug, Clone, Serialize)]
+pub struct DecodedQrSecret {
+    pub uri: String,
+    #[serde(flatten)]
+    pub otpauth: OtpauthUri,
+}
+
+/// 解码图片参数（允许带 `data:image/...;base64,` 前缀）
+fn decode_image_base64(image_base64: &str) -> Result<Vec<u8>, String> {
+    let trimmed = image_base64.trim();
+    let payload = match trimmed.split_once(";base64,") {
+        Some((prefix, data)) if prefix.starts_with("data:") => data,
+        _ => trimmed,
+    };
+    let bytes = STANDARD
+        .decode(payload)
+        .map_err(|e| format!("图片 Base64 解码失败: {}", e))?;
+    if bytes.len() > MAX_QR_IMAGE_BYTES {
+        return Err(format!(
+            "图片过大（{} 字节），上限为 {} 字节",
+            bytes.len(),
+            MAX_QR_IMAGE_BYTES
+        ));
+    }
+    Ok(bytes)
+}
+
+/// 在本地识别 PNG / JPEG 图片中的全部二维码，返回各二维码的文本内容
+pub fn decode_qr_image(bytes: &[u8]) -> Result<Vec<String>, String> {
+    let image = image::load_from_memory(bytes)
+        .map_err(|e| format!("无法读取图片（仅支持 PNG / JPEG）: {}", e))?
+        .to_luma8();
+    let mut prepared = rqrr::PreparedImage::prepare_from_greyscale(
+        image.width() as usize,
+        image.height() as usize,
+        |x, y| image.get_pixel(x as u32, y as u32).0[0],
+    );
+    let grids = prepared.detect_grids();
+    if grids.is_empty() {
+        return Err("图片中未识别到二维码".to_string());
+    }
+
+    let mut contents = Vec::new();
+    let mut last_error = None;
+    for grid in grids {
+        match grid.decode() {
+            Ok((_, content)) => contents.push(content),
+            Err(e) => last_error = Some(e.to_string()),
+        }
+    }
+    if contents.is_empty() {
+        return Err(format!(
+            "二维码解码失败: {}",
+            last_error.unwrap_or_default()
+        ));
+    }
+    Ok(contents)
+}
+
+/// 识别截图中的 otpauth:// 二维码并解析出密钥与参数
+pub fn decode_qr_secret(image_base64: &str) -> Result<DecodedQrSecret, String> {
+    let contents = decode_qr_image(&decode_image_base64(image_base64)?)?;
+    let Some(uri) = contents
+        .iter()
+        .find(|content| totp::is_otpauth_uri(content))
+    else {
+        if contents
+            .iter()
+            .any(|content| content.trim_start().starts_with("otpauth-migration://"))
+        {
+            return Err("该二维码是 Google Authenticator 迁移码，请使用迁移码导入".to_string());
+        }
+        return Err("二维码内容不是 otpauth:// 链接".to_string());
+    };
+    Ok(DecodedQrSecret {
+        otpauth: totp::parse_otpauth_uri(uri)?,
+        uri: uri.trim().to_string(),
+    })
+}
+
+/// 识别截图中的二维码并把密钥写入指定账号
+pub fn attach_qr_secret(
+    conn: &Connection,
+    id: i64,
+    image_base64: &str,
+    actor: AuditActor,
+) -> Result<Account, String> {
+    let decoded = decode_qr_secret(image_base64)?;
+    database::update_account_secret(
+        conn,
+        id,
+        &decoded.otpauth.secret,
+        &decoded.otpauth.params,
+        actor,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_png_and_svg() {
+        let uri = "otpauth://totp/Google:a%40example.com?secret=JBSWY3DPEHPK3PXP&issuer=Google";
+
+        let png = render_qr_code(uri, None).unwrap();
+        assert_eq!(png.format, "png");
+        let encoded = png.data_url.strip_prefix("data:image/png;base64,").unwrap();
+        let bytes = STANDARD.decode(encoded).unwrap();
+        assert_eq!(&bytes[..8], b"\x89PNG\r\n\x1a\n");
+
+        let svg = render_qr_code(uri, Some("SVG")).unwrap();
+        assert_eq!(svg.mime_type, "image/svg+xml");
+        let encoded = svg
+            .data_url
+            .strip_prefix("data:image/svg+xml;base64,")
+            .unwrap();
+        let markup = String::from_utf8(STANDARD.decode(encoded).unwrap()).unwrap();
+        assert!(markup.contains("<svg"));
+    }
+
+    #[test]
+    fn render_rejects_unknown_format() {
+        assert!(render_qr_code("hello", Some("gif")).is_err());
+    }
+
+    #[test]
+    fn decode_round_trips_rendered_png() {
+        let uri =
+            "otpauth://totp/Google:a%40example.com?secret=JBSWY3DPEHPK3PXP&issuer=Google&digits=8";
+        let png = render_qr_code(uri, Some("png")).unwrap();
+
+        let decoded = decode_qr_secret(&png.data_url).unwrap();
+        assert_eq!(decoded.uri, uri);
+        assert_eq!(decoded.otpauth.secret, "JBSWY3DPEHPK3PXP");
+        assert_eq!(decoded.otpauth.params.digits, 8);
+        assert_eq!(
+            decoded.otpauth.account_name.as_deref(),
+            Some("a@example.com")
+        );
+    }
+
+    #[test]
+    fn decode_rejects_non_otpauth_content() {
+        let png = render_qr_code("https://example.com", None).unwrap();
+        assert!(decode_qr_secret(&png.data_url).is_err());
+        assert!(decode_qr_secret("not base64!").is_err());
+        assert!(decode_qr_secret(&STANDARD.encode(b"plain text")).is_err());
+    }
+}
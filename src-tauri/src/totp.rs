@@ -1,32 +1,689 @@
+use crate::database;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
 use std::time::{SystemTime, UNIX_EPOCH};
+use subtle::ConstantTimeEq;
 use totp_rs::{Algorithm, Secret, TOTP};
 
 pub struct TotpResult {
     pub code: String,
     pub remaining: u32,
+    /// 上一个周期的验证码（周期切换时对方可能仍在使用）
+    pub previous_code: String,
+    /// 下一个周期的验证码（临近切换时提前展示）
+    pub next_code: String,
+}
+
+/// 验证码校验结果；`offset` 为匹配到的周期偏移（0 为当前周期，-1 为上一周期）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TotpVerification {
+    pub valid: bool,
+    pub offset: Option<i64>,
+}
+
+/// 默认容忍 ±1 个周期的时钟偏差
+pub const DEFAULT_VERIFY_WINDOW: u32 = 1;
+const MAX_VERIFY_WINDOW: u32 = 10;
+
+/// 导出 otpauth URI / 迁移码时使用的发行方名称
+pub const OTPAUTH_ISSUER: &str = "Google";
+pub const DEFAULT_ALGORITHM: &str = "SHA1";
+pub const DEFAULT_DIGITS: u32 = 6;
+pub const DEFAULT_PERIOD: u32 = 30;
+const MAX_PERIOD: u32 = 300;
+
+/// 每个账号的 TOTP 参数（默认 SHA1 / 6 位 / 30 秒，与旧版行为一致）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TotpParams {
+    pub algorithm: String,
+    pub digits: u32,
+    pub period: u32,
+}
+
+impl Default for TotpParams {
+    fn default() -> Self {
+        Self {
+            algorithm: DEFAULT_ALGORITHM.to_string(),
+            digits: DEFAULT_DIGITS,
+            period: DEFAULT_PERIOD,
+        }
+    }
+}
+
+impl TotpParams {
+    /// 校验并规范化参数（算法名统一为 SHA1/SHA256/SHA512）
+    pub fn normalized(&self) -> Result<Self, String> {
+        let algorithm = match self
+            .algorithm
+            .trim()
+            .to_ascii_uppercase()
+            .replace('-', "")
+            .as_str()
+        {
+            "" | "SHA1" => "SHA1",
+            "SHA256" => "SHA256",
+            "SHA512" => "SHA512",
+            other => return Err(format!("不支持的 TOTP 算法: {}", other)),
+        };
+        if !(6..=8).contains(&self.digits) {
+            return Err(format!("TOTP 位数必须为 6~8，当前为 {}", self.digits));
+        }
+        if self.period == 0 || self.period > MAX_PERIOD {
+            return Err(format!(
+                "TOTP 周期必须为 1~{} 秒，当前为 {}",
+                MAX_PERIOD, self.period
+            ));
+        }
+        Ok(Self {
+            algorithm: algorithm.to_string(),
+            digits: self.digits,
+            period: self.period,
+        })
+    }
+
+    fn totp_algorithm(&self) -> Algorithm {
+        match self.algorithm.as_str() {
+            "SHA256" => Algorithm::SHA256,
+            "SHA512" => Algorithm::SHA512,
+            _ => Algorithm::SHA1,
+        }
+    }
+}
+
+/// 解析后的 otpauth:// URI（仅支持 totp 类型）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct OtpauthUri {
+    pub secret: String,
+    pub params: TotpParams,
+    pub issuer: Option<String>,
+    pub account_name: Option<String>,
+}
+
+/// 批量生成时的单账号结果（失败时 code/remaining 为空并附带原因）
+#[derive(Debug, Serialize)]
+pub struct AccountTotpResult {
+    pub account_id: i64,
+    pub code: Option<String>,
+    pub remaining: Option<u32>,
+    pub previous_code: Option<String>,
+    pub next_code: Option<String>,
+    pub error: Option<String>,
+}
+
+/// 百分号解码（`plus_as_space` 为 true 时按查询参数规则把 `+` 视为空格）
+pub fn percent_decode(value: &str, plus_as_space: bool) -> Result<String, String> {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        match bytes[index] {
+            b'%' => {
+                let hex = value
+                    .get(index + 1..index + 3)
+                    .ok_or_else(|| format!("otpauth URI 编码非法: {}", value))?;
+                let byte = u8::from_str_radix(hex, 16)
+                    .map_err(|_| format!("otpauth URI 编码非法: {}", value))?;
+                decoded.push(byte);
+                index += 3;
+            }
+            b'+' if plus_as_space => {
+                decoded.push(b' ');
+                index += 1;
+            }
+            byte => {
+                decoded.push(byte);
+                index += 1;
+            }
+        }
+    }
+    String::from_utf8(decoded).map_err(|e| format!("otpauth URI UTF-8 解码失败: {}", e))
+}
+
+/// 百分号编码（仅保留 RFC 3986 非保留字符）
+pub fn percent_encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
+
+/// 判断输入是否为 otpauth:// URI
+pub fn is_otpauth_uri(value: &str) -> bool {
+    value
+        .trim()
+        .get(..10)
+        .map(|prefix| prefix.eq_ignore_ascii_case("otpauth://"))
+        .unwrap_or(false)
+}
+
+/// 解析 `otpauth://totp/Label?secret=...&issuer=...&algorithm=...&digits=...&period=...`
+pub fn parse_otpauth_uri(uri: &str) -> Result<OtpauthUri, String> {
+    let trimmed = uri.trim();
+    if !is_otpauth_uri(trimmed) {
+        return Err("不是 otpauth:// URI".to_string());
+    }
+    let rest = &trimmed[10..];
+    let (kind, rest) = rest
+        .split_once('/')
+        .ok_or_else(|| "otpauth URI 缺少类型或标签".to_string())?;
+    if !kind.eq_ignore_ascii_case("totp") {
+        return Err(format!("仅支持 totp 类型的 otpauth URI，当前为 {}", kind));
+    }
+    let (raw_label, query) = rest.split_once('?').unwrap_or((rest, ""));
+    let label = percent_decode(raw_label, false)?;
+    let (label_issuer, account_name) = match label.split_once(':') {
+        Some((issuer, account)) => (Some(issuer.trim().to_string()), account.trim().to_string()),
+        None => (None, label.trim().to_string()),
+    };
+
+    let mut secret = None;
+    let mut issuer = None;
+    let mut params = TotpParams::default();
+    for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        let value = percent_decode(value, true)?;
+        match key.to_ascii_lowercase().as_str() {
+            "secret" => secret = Some(value.replace(' ', "").to_uppercase()),
+            "issuer" => issuer = Some(value.trim().to_string()),
+            "algorithm" => params.algorithm = value,
+            "digits" => {
+                params.digits = value
+                    .trim()
+                    .parse()
+                    .map_err(|_| format!("otpauth URI digits 非法: {}", value))?
+            }
+            "period" => {
+                params.period = value
+                    .trim()
+                    .parse()
+                    .map_err(|_| format!("otpauth URI period 非法: {}", value))?
+            }
+            _ => {}
+        }
+    }
+
+    let secret = secret
+        .filter(|value| !value.is_empty())
+        .ok_or_else(|| "otpauth URI 缺少 secret 参数".to_string())?;
+    Secret::Encoded(secret.clone())
+        .to_bytes()
+        .map_err(|_| "otpauth URI secret 不是合法的 Base32".to_string())?;
+
+    Ok(OtpauthUri {
+        secret,
+        params: params.normalized()?,
+        issuer: issuer.or(label_issuer).filter(|value| !value.is_empty()),
+        account_name: Some(account_name).filter(|value| !value.is_empty()),
+    })
+}
+
+/// 生成 otpauth:// URI（标签格式为 `Issuer:account`）
+pub fn build_otpauth_uri(
+    account_name: &str,
+    issuer: Option<&str>,
+    secret: &str,
+    params: &TotpParams,
+) -> String {
+    let secret = secret.replace(' ', "").to_uppercase();
+    let issuer = issuer.map(str::trim).filter(|value| !value.is_empty());
+    let label = match issuer {
+        Some(issuer) => format!(
+            "{}:{}",
+            percent_encode(issuer),
+            percent_encode(account_name)
+        ),
+        None => percent_encode(account_name),
+    };
+    let mut uri = format!(
+        "otpauth://totp/{}?secret={}",
+        label,
+        percent_encode(&secret)
+    );
+    if let Some(issuer) = issuer {
+        uri.push_str(&format!("&issuer={}", percent_encode(issuer)));
+    }
+    uri.push_str(&format!(
+        "&algorithm={}&digits={}&period={}",
+        params.algorithm, params.digits, params.period
+    ));
+    uri
 }
 
 /// 共享 TOTP 生成逻辑（供 commands 和 http_server 共用）
 pub fn generate_totp(secret: &str) -> Result<TotpResult, String> {
+    generate_totp_with_params(secret, &TotpParams::default())
+}
+
+fn build_totp(secret: &str, params: &TotpParams) -> Result<(TOTP, TotpParams), String> {
+    let params = params.normalized()?;
     let secret_clean = secret.replace(" ", "").to_uppercase();
 
     let totp = TOTP::new(
-        Algorithm::SHA1,
-        6,
+        params.totp_algorithm(),
+        params.digits as usize,
         1,
-        30,
+        params.period as u64,
         Secret::Encoded(secret_clean)
             .to_bytes()
             .map_err(|e| e.to_string())?,
     )
     .map_err(|e| e.to_string())?;
+    Ok((totp, params))
+}
 
-    let code = totp.generate_current().map_err(|e| e.to_string())?;
-    let now = SystemTime::now()
+fn now_epoch_secs() -> u64 {
+    SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .unwrap_or_default()
-        .as_secs();
-    let remaining = 30 - (now % 30) as u32;
+        .as_secs()
+}
+
+/// 按指定算法/位数/周期生成 TOTP
+pub fn generate_totp_with_params(secret: &str, params: &TotpParams) -> Result<TotpResult, String> {
+    generate_totp_at(secret, params, now_epoch_secs())
+}
+
+fn generate_totp_at(secret: &str, params: &TotpParams, now: u64) -> Result<TotpResult, String> {
+    let (totp, params) = build_totp(secret, params)?;
+    let period = params.period as u64;
+    let remaining = params.period - (now % period) as u32;
+
+    Ok(TotpResult {
+        code: totp.generate(now),
+        remaining,
+        previous_code: totp.generate(now.saturating_sub(period)),
+        next_code: totp.generate(now + period),
+    })
+}
+
+/// 校验验证码，容忍 ±`window` 个周期的时钟偏差（优先匹配离当前最近的周期）
+pub fn verify_totp_with_params(
+    secret: &str,
+    params: &TotpParams,
+    code: &str,
+    window: Option<u32>,
+) -> Result<TotpVerification, String> {
+    verify_totp_at(secret, params, code, window, now_epoch_secs())
+}
+
+fn verify_totp_at(
+    secret: &str,
+    params: &TotpParams,
+    code: &str,
+    window: Option<u32>,
+    now: u64,
+) -> Result<TotpVerification, String> {
+    let window = window.unwrap_or(DEFAULT_VERIFY_WINDOW);
+    if window > MAX_VERIFY_WINDOW {
+        return Err(format!(
+            "校验窗口不能超过 ±{} 个周期，当前为 {}",
+            MAX_VERIFY_WINDOW, window
+        ));
+    }
+    let (totp, params) = build_totp(secret, params)?;
+    let code: String = code
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '-')
+        .collect();
+    if code.len() != params.digits as usize || !code.chars().all(|c| c.is_ascii_digit()) {
+        return Ok(TotpVerification {
+            valid: false,
+            offset: None,
+        });
+    }
+
+    let period = params.period as i64;
+    let offsets = std::iter::once(0).chain((1..=window as i64).flat_map(|step| [-step, step]));
+    for offset in offsets {
+        let Some(time) = (now as i64)
+            .checked_add(offset * period)
+            .filter(|t| *t >= 0)
+        else {
+            continue;
+        };
+        if bool::from(totp.generate(time as u64).as_bytes().ct_eq(code.as_bytes())) {
+            return Ok(TotpVerification {
+                valid: true,
+                offset: Some(offset),
+            });
+        }
+    }
+    Ok(TotpVerification {
+        valid: false,
+        offset: None,
+    })
+}
+
+/// 按账号 ID 校验对方输入的验证码
+pub fn verify_totp_for_account(
+    conn: &Connection,
+    id: i64,
+    code: &str,
+    window: Option<u32>,
+) -> Result<TotpVerification, String> {
+    let secret = database::get_account_secret(conn, id)?
+        .ok_or_else(|| "该账号未设置 2FA 密钥".to_string())?;
+    verify_totp_with_params(&secret.secret, &secret.params, code, window)
+}
+
+/// 按账号 ID 在服务端解密 secret 并生成验证码（客户端无需持有明文密钥）
+pub fn generate_totp_for_account(conn: &Connection, id: i64) -> Result<TotpResult, String> {
+    generate_totp_for_account_at(conn, id, now_epoch_secs())
+}
+
+fn generate_totp_for_account_at(
+    conn: &Connection,
+    id: i64,
+    now: u64,
+) -> Result<TotpResult, String> {
+    let secret = database::get_account_secret(conn, id)?
+        .ok_or_else(|| "该账号未设置 2FA 密钥".to_string())?;
+    generate_totp_at(&secret.secret, &secret.params, now)
+}
+
+/// 批量按账号 ID 生成验证码，单个账号失败不影响其他账号
+pub fn generate_totp_for_accounts(
+    conn: &Connection,
+    ids: &[i64],
+) -> Result<Vec<AccountTotpResult>, String> {
+    let secrets = database::get_account_secrets(conn, ids)?;
+    Ok(secrets
+        .into_iter()
+        .map(|(account_id, secret)| {
+            let generated = secret.and_then(|secret| {
+                let secret = secret.ok_or_else(|| "该账号未设置 2FA 密钥".to_string())?;
+                generate_totp_with_params(&secret.secret, &secret.params)
+            });
+            match generated {
+                Ok(result) => AccountTotpResult {
+                    account_id,
+                    code: Some(result.code),
+                    remaining: Some(result.remaining),
+                    previous_code: Some(result.previous_code),
+                    next_code: Some(result.next_code),
+                    error: None,
+                },
+                Err(e) => AccountTotpResult {
+                    account_id,
+                    code: None,
+                    remaining: None,
+                    previous_code: None,
+                    next_code: None,
+                    error: Some(e),
+                },
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_otpauth_uri_reads_secret_and_params() {
+        let parsed = parse_otpauth_uri(
+            "otpauth://totp/Google%3Aalice%40gmail.com?secret=jbswy3dpehpk3pxp&issuer=Google&algorithm=SHA256&digits=8&period=60",
+        )
+        .unwrap();
+        assert_eq!(parsed.secret, "JBSWY3DPEHPK3PXP");
+        assert_eq!(parsed.issuer.as_deref(), Some("Google"));
+        assert_eq!(parsed.account_name.as_deref(), Some("alice@gmail.com"));
+        assert_eq!(
+            parsed.params,
+            TotpParams {
+                algorithm: "SHA256".to_string(),
+                digits: 8,
+                period: 60,
+            }
+        );
+    }
+
+    #[test]
+    fn parse_otpauth_uri_uses_defaults_and_label_issuer() {
+        let parsed =
+            parse_otpauth_uri("OTPAUTH://TOTP/Example:bob@gmail.com?secret=JBSWY3DPEHPK3PXP")
+                .unwrap();
+        assert_eq!(parsed.params, TotpParams::default());
+        assert_eq!(parsed.issuer.as_deref(), Some("Example"));
+        assert_eq!(parsed.account_name.as_deref(), Some("bob@gmail.com"));
+    }
+
+    #[test]
+    fn parse_otpauth_uri_rejects_invalid_input() {
+        assert!(parse_otpauth_uri("JBSWY3DPEHPK3PXP").is_err());
+        assert!(parse_otpauth_uri("otpauth://hotp/x?secret=JBSWY3DPEHPK3PXP&counter=1").is_err());
+        assert!(parse_otpauth_uri("otpauth://totp/x?issuer=Google").is_err());
+        assert!(parse_otpauth_uri("otpauth://totp/x?secret=not-base32!").is_err());
+        assert!(parse_otpauth_uri("otpauth://totp/x?secret=JBSWY3DPEHPK3PXP&digits=12").is_err());
+    }
+
+    #[test]
+    fn build_otpauth_uri_round_trips() {
+        let params = TotpParams {
+            algorithm: "SHA512".to_string(),
+            digits: 8,
+            period: 60,
+        };
+        let uri = build_otpauth_uri("alice@gmail.com", Some("Google"), "jbsw y3dp", &params);
+        assert_eq!(
+            uri,
+            "otpauth://totp/Google:alice%40gmail.com?secret=JBSWY3DP&issuer=Google&algorithm=SHA512&digits=8&period=60"
+        );
+        let parsed = parse_otpauth_uri(&uri).unwrap();
+        assert_eq!(parsed.secret, "JBSWY3DP");
+        assert_eq!(parsed.params, params);
+        assert_eq!(parsed.account_name.as_deref(), Some("alice@gmail.com"));
+    }
+
+    const SECRET: &str = "JBSWY3DPEHPK3PXPJBSWY3DPEHPK3PXP";
+
+    /// RFC 6238 附录 B 的测试密钥（ASCII "1234567890" 循环，按算法取 20 / 32 / 64 字节）
+    const RFC_SHA1_SECRET: &str = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ";
+    const RFC_SHA256_SECRET: &str = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQGEZA";
+    const RFC_SHA512_SECRET: &str = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQGEZDGNA";
+
+    fn rfc_params(algorithm: &str) -> TotpParams {
+        TotpParams {
+            algorithm: algorithm.to_string(),
+            digits: 8,
+            period: 30,
+        }
+    }
+
+    #[test]
+    fn generate_matches_rfc6238_vectors() {
+        let vectors = [
+            (59, "94287082", "46119246", "90693936"),
+            (1_111_111_109, "07081804", "68084774", "25091201"),
+            (1_111_111_111, "14050471", "67062674", "99943326"),
+            (1_234_567_890, "89005924", "91819424", "93441116"),
+            (2_000_000_000, "69279037", "90698825", "38618901"),
+            (20_000_000_000, "65353130", "77737706", "47863826"),
+        ];
+        for (time, sha1, sha256, sha512) in vectors {
+            let generate = |secret, algorithm| {
+                generate_totp_at(secret, &rfc_params(algorithm), time)
+                    .unwrap()
+                    .code
+            };
+            assert_eq!(generate(RFC_SHA1_SECRET, "SHA1"), sha1, "SHA1 @ {}", time);
+            assert_eq!(
+                generate(RFC_SHA256_SECRET, "SHA256"),
+                sha256,
+                "SHA256 @ {}",
+                time
+            );
+            assert_eq!(
+                generate(RFC_SHA512_SECRET, "SHA512"),
+                sha512,
+                "SHA512 @ {}",
+                time
+            );
+        }
+    }
+
+    #[test]
+    fn generate_normalizes_secret_and_params() {
+        // 默认参数取 RFC 向量的低 6 位
+        let default = generate_totp_at(RFC_SHA1_SECRET, &TotpParams::default(), 59).unwrap();
+        assert_eq!(default.code, "287082");
+        assert_eq!(default.remaining, 1);
+
+        let spaced = "gezd gnbv gy3t qojq gezd gnbv gy3t qojq";
+        assert_eq!(
+            generate_totp_at(spaced, &TotpParams::default(), 59)
+                .unwrap()
+                .code,
+            "287082"
+        );
+
+        let aliased = TotpParams {
+            algorithm: "sha-256".to_string(),
+            digits: 8,
+            period: 60,
+        };
+        assert_eq!(aliased.normalized().unwrap().algorithm, "SHA256");
+        // 60 秒周期下 t=59 与 30 秒周期的 t=29 同属第 0 个计数器
+        let result = generate_totp_at(RFC_SHA256_SECRET, &aliased, 59).unwrap();
+        assert_eq!(
+            result.code,
+            generate_totp_at(RFC_SHA256_SECRET, &rfc_params("SHA256"), 29)
+                .unwrap()
+                .code
+        );
+        assert_eq!(result.remaining, 1);
+    }
+
+    #[test]
+    fn generate_rejects_invalid_secret_and_params() {
+        assert!(generate_totp("invalid!@#").is_err());
+        let invalid_digits = TotpParams {
+            digits: 4,
+            ..TotpParams::default()
+        };
+        assert!(generate_totp_with_params(SECRET, &invalid_digits).is_err());
+        let invalid_algorithm = TotpParams {
+            algorithm: "MD5".to_string(),
+            ..TotpParams::default()
+        };
+        assert!(invalid_algorithm.normalized().is_err());
+    }
+
+    #[test]
+    fn generate_for_account_uses_stored_secret_and_params() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute(
+            "CREATE TABLE accounts (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                secret TEXT,
+                totp_algorithm TEXT DEFAULT 'SHA1',
+                totp_digits INTEGER DEFAULT 6,
+                totp_period INTEGER DEFAULT 30,
+                deleted_at TEXT
+            )",
+            [],
+        )
+        .unwrap();
+        let key = crate::key_manager::get_master_key().unwrap();
+        let encrypted = crate::crypto::encrypt_secret(RFC_SHA512_SECRET, &key).unwrap();
+        conn.execute(
+            "INSERT INTO accounts (secret, totp_algorithm, totp_digits) VALUES (?1, 'SHA512', 8)",
+            [&encrypted],
+        )
+        .unwrap();
+        let with_secret_id = conn.last_insert_rowid();
+        conn.execute("INSERT INTO accounts (secret) VALUES (NULL)", [])
+            .unwrap();
+        let no_secret_id = conn.last_insert_rowid();
+
+        let single = generate_totp_for_account_at(&conn, with_secret_id, 1_234_567_890).unwrap();
+        assert_eq!(single.code, "93441116");
+        assert!(generate_totp_for_account(&conn, no_secret_id).is_err());
+
+        let results =
+            generate_totp_for_accounts(&conn, &[with_secret_id, no_secret_id, 9999]).unwrap();
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].account_id, with_secret_id);
+        assert_eq!(results[0].code.as_deref().map(str::len), Some(8));
+        assert!(results[0].error.is_none());
+        assert!(results[1].code.is_none());
+        assert!(results[1].error.is_some());
+        assert!(results[2].error.as_deref().unwrap().contains("不存在"));
+    }
+
+    #[test]
+    fn generate_returns_previous_and_next_codes() {
+        let params = TotpParams::default();
+        let now = 1_700_000_015;
+        let result = generate_totp_at(SECRET, &params, now).unwrap();
+        assert_eq!(result.remaining, 30 - (now % 30) as u32);
+        assert_eq!(
+            result.previous_code,
+            generate_totp_at(SECRET, &params, now - 30).unwrap().code
+        );
+        assert_eq!(
+            result.next_code,
+            generate_totp_at(SECRET, &params, now + 30).unwrap().code
+        );
+        assert_ne!(result.code, result.next_code);
+    }
+
+    #[test]
+    fn verify_accepts_codes_within_window() {
+        let params = TotpParams::default();
+        let now = 1_700_000_015;
+        let current = generate_totp_at(SECRET, &params, now).unwrap();
+        let two_steps_ago = generate_totp_at(SECRET, &params, now - 60).unwrap().code;
+
+        let verified = verify_totp_at(SECRET, &params, &current.code, None, now).unwrap();
+        assert_eq!(verified.offset, Some(0));
+        let spaced = format!(
+            "{} {}",
+            &current.previous_code[..3],
+            &current.previous_code[3..]
+        );
+        let previous = verify_totp_at(SECRET, &params, &spaced, None, now).unwrap();
+        assert_eq!(previous.offset, Some(-1));
+        let next = verify_totp_at(SECRET, &params, &current.next_code, None, now).unwrap();
+        assert_eq!(next.offset, Some(1));
+
+        assert!(
+            !verify_totp_at(SECRET, &params, &two_steps_ago, None, now)
+                .unwrap()
+                .valid
+        );
+        assert_eq!(
+            verify_totp_at(SECRET, &params, &two_steps_ago, Some(2), now)
+                .unwrap()
+                .offset,
+            Some(-2)
+        );
+        assert!(
+            !verify_totp_at(SECRET, &params, &current.code, Some(0), now + 30)
+                .unwrap()
+                .valid
+        );
+    }
 
-    Ok(TotpResult { code, remaining })
+    #[test]
+    fn verify_rejects_malformed_codes_and_windows() {
+        let params = TotpParams::default();
+        assert!(
+            !verify_totp_at(SECRET, &params, "12345", None, 0)
+                .unwrap()
+                .valid
+        );
+        assert!(
+            !verify_totp_at(SECRET, &params, "abcdef", None, 0)
+                .unwrap()
+                .valid
+        );
+        assert!(verify_totp_at(SECRET, &params, "123456", Some(11), 0).is_err());
+    }
 }